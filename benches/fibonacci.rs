@@ -0,0 +1,60 @@
+//! Keygen, synthesis, proving and verification times of the two Fibonacci
+//! layouts: three advice columns with one region per step (example1) against
+//! one advice column with a single region (example2).
+//!
+//! Both prove the same term `f(N)` at several sizes `2^k`, so the numbers show
+//! what the extra columns cost and what the taller column saves. The sizes start
+//! at the smallest `k` each circuit fits in.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use halo2_proofs::{dev::MockProver, pasta::Fp, plonk::Circuit};
+use halo2examples::{
+    common::min_k_for_circuit,
+    fibonacci::{example1, example2, FibIndex, FibonacciInstances},
+    prover,
+};
+
+/// The term both circuits prove. It fits in a `u64`.
+const N: usize = 20;
+
+/// How far above the smallest workable `k` each run goes.
+const K_STEPS: [u32; 3] = [0, 2, 4];
+
+fn bench_circuit<C: Circuit<Fp>>(c: &mut Criterion, name: &str, circuit: impl Fn() -> C) {
+    let columns = FibonacciInstances::from_seeds(1, 1, FibIndex(N))
+        .unwrap()
+        .to_columns();
+    let instance = columns[0].as_slice();
+
+    let min_k = min_k_for_circuit(&circuit(), columns.clone()).unwrap();
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    for k in K_STEPS.map(|step| min_k + step) {
+        group.bench_with_input(BenchmarkId::new("keygen", k), &k, |b, &k| {
+            b.iter(|| prover::Keys::generate(k, &circuit()).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("synthesis", k), &k, |b, &k| {
+            b.iter(|| MockProver::run(k, &circuit(), columns.clone()).unwrap())
+        });
+
+        let keys = prover::Keys::generate(k, &circuit()).unwrap();
+        group.bench_with_input(BenchmarkId::new("prove", k), &k, |b, _| {
+            b.iter(|| prover::prove(&keys, circuit(), &[instance]).unwrap())
+        });
+
+        let proof = prover::prove(&keys, circuit(), &[instance]).unwrap();
+        group.bench_with_input(BenchmarkId::new("verify", k), &k, |b, _| {
+            b.iter(|| prover::verify(&keys, &proof, &[instance]).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn fibonacci(c: &mut Criterion) {
+    bench_circuit(c, "example1", || example1::MyCircuit::<Fp>::new(N));
+    bench_circuit(c, "example2", || example2::MyCircuit::<Fp>::new(N));
+}
+
+criterion_group!(benches, fibonacci);
+criterion_main!(benches);
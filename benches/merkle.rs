@@ -0,0 +1,44 @@
+//! Keygen, proving and verification times of a depth-20 Merkle membership
+//! proof with Poseidon as the node hash. Needs the `poseidon` feature.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use halo2_proofs::pasta::Fp;
+use halo2examples::{
+    merkle::{PoseidonMerkleCircuit, PoseidonMerkleTree},
+    prover,
+};
+
+const DEPTH: usize = 20;
+
+/// Twenty Poseidon permutations and the index bits fit in `2^11` rows.
+const K: u32 = 11;
+
+fn merkle(c: &mut Criterion) {
+    let index = 123_456;
+    let mut tree = PoseidonMerkleTree::new(DEPTH);
+    tree.insert(index, Fp::from(42));
+    let path = tree.path(index);
+    let circuit = || PoseidonMerkleCircuit::<DEPTH>::new(Fp::from(42), &path);
+    let columns = PoseidonMerkleCircuit::<DEPTH>::instances(index, tree.root());
+    let instance = columns[0].as_slice();
+
+    let mut group = c.benchmark_group("poseidon merkle depth 20");
+    group.sample_size(10);
+    group.bench_function("keygen", |b| {
+        b.iter(|| prover::Keys::generate(K, &circuit()).unwrap())
+    });
+
+    let keys = prover::Keys::generate(K, &circuit()).unwrap();
+    group.bench_function("prove", |b| {
+        b.iter(|| prover::prove(&keys, circuit(), &[instance]).unwrap())
+    });
+
+    let proof = prover::prove(&keys, circuit(), &[instance]).unwrap();
+    group.bench_function("verify", |b| {
+        b.iter(|| prover::verify(&keys, &proof, &[instance]).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, merkle);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+//! Witness generation for long Fibonacci sequences: computing the terms, and
+//! synthesizing example2 with `MockProver` at `k = 18` and `k = 20`, where
+//! synthesis rather than the gate checks dominates.
+//!
+//! The `parallel` feature changes how the terms are computed, so compare a run
+//! with it against one without:
+//!
+//! ```text
+//! cargo bench --bench witness -- --save-baseline serial
+//! cargo bench --bench witness --features parallel -- --baseline serial
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use halo2_proofs::{dev::MockProver, pasta::Fp};
+use halo2examples::{
+    common::usable_rows,
+    fibonacci::{example2, fib_sequence, FibIndex, FibonacciInstances},
+};
+
+const KS: [u32; 2] = [18, 20];
+
+fn witness(c: &mut Criterion) {
+    let mut group = c.benchmark_group("witness");
+    group.sample_size(10);
+    for k in KS {
+        // The longest sequence that fits: one row per term, f(0) to f(n).
+        let n = usable_rows::<Fp, example2::MyCircuit<Fp>>(k) - 1;
+
+        group.bench_with_input(BenchmarkId::new("sequence", k), &n, |b, &n| {
+            b.iter(|| fib_sequence(Fp::one(), Fp::one(), n + 1))
+        });
+
+        let instances = FibonacciInstances::new(Fp::one(), Fp::one(), FibIndex(n)).to_columns();
+        group.bench_with_input(BenchmarkId::new("synthesis", k), &n, |b, &n| {
+            b.iter(|| {
+                MockProver::run(k, &example2::MyCircuit::<Fp>::new(n), instances.clone()).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, witness);
+criterion_main!(benches);
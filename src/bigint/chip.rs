@@ -0,0 +1,691 @@
+use super::native::{self, Limbs, LIMBS, LIMB_BITS};
+use crate::{
+    decompose::{DecomposeChip, DecomposeConfig},
+    prelude::*,
+};
+
+/// Bits per lookup in the range checks.
+const CHUNK_BITS: usize = 8;
+/// A limb is checked as `64 / 8` chunks.
+const LIMB_CHUNKS: usize = LIMB_BITS / CHUNK_BITS;
+/// Carries of [`BigIntChip::mul_add`] are checked as `72 / 8` chunks, after
+/// adding [`CARRY_OFFSET`].
+const CARRY_CHUNKS: usize = 9;
+/// `2^71`. Every carry of the multiplication lies in `[-2^71, 2^71)`, so the
+/// shifted carry fits in 72 bits.
+fn carry_offset<F: PrimeField>() -> F {
+    F::from(2).pow([71])
+}
+
+/// A 256-bit integer as four assigned limbs, least significant first, each
+/// range-checked to 64 bits.
+#[derive(Debug, Clone)]
+pub struct AssignedBigInt<F: Field> {
+    pub limbs: [AssignedCell<F, F>; LIMBS],
+}
+
+impl<F: PrimeField> AssignedBigInt<F> {
+    pub fn value(&self) -> Value<Limbs> {
+        let mut value = Value::known([0; LIMBS]);
+        for (i, limb) in self.limbs.iter().enumerate() {
+            value = value.zip(limb.value()).map(|(mut value, limb)| {
+                value[i] = crate::decompose::chunks(limb, LIMB_BITS, 1)[0];
+                value
+            });
+        }
+        value
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BigIntConfig<F: PrimeField> {
+    /// `[a, b, c, q, r, carry]`, one limb per row.
+    pub advice: [Column<Advice>; 6],
+    pub q_add: Selector,
+    pub q_mul: Selector,
+    pub constant: Column<Fixed>,
+    pub limb_range: DecomposeConfig<F, CHUNK_BITS, LIMB_CHUNKS>,
+    pub carry_range: DecomposeConfig<F, CHUNK_BITS, CARRY_CHUNKS>,
+    /// The modulus of [`BigIntChip::mul_mod`] and [`BigIntChip::add_mod`].
+    pub modulus: Limbs,
+}
+
+/// Arithmetic on 256-bit integers modulo a fixed `m`, in any field of more
+/// than 140 bits. Limbs are 64 bits; every limb a gate outputs is
+/// range-checked with 8-bit lookups, so no gate equation can wrap around the
+/// native modulus.
+///
+/// `add` lays the limbs of `a`, `b` and the sum down rows `0..4`, with one
+/// boolean carry per limb:
+///
+/// ```text
+/// a_i + b_i + carry_{i-1} = sum_i + 2^64 * carry_i
+/// ```
+///
+/// `mul_add` proves `a * b + c = q * m + r` limb by limb. Row `k` of the carry
+/// column holds `carry_k + 2^71`, and for every coefficient `k` in `0..7`
+///
+/// ```text
+/// sum_{i+j=k} a_i b_j + c_k - sum_{i+j=k} q_i m_j - r_k + carry_{k-1} = 2^64 * carry_k
+/// ```
+///
+/// with `carry_6 = 0`. Since `r` is checked to be below `m`, it is the
+/// remainder. Inputs are expected below `m` too, which [`BigIntChip::load`]
+/// checks, so that `q` fits in four limbs.
+#[derive(Debug, Clone)]
+pub struct BigIntChip<F: PrimeField> {
+    config: BigIntConfig<F>,
+}
+
+impl<F: PrimeField> BigIntChip<F> {
+    pub fn construct(config: BigIntConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 6],
+        modulus: Limbs,
+    ) -> BigIntConfig<F> {
+        assert!(F::NUM_BITS > 140, "the field is too small for 64-bit limbs");
+        let [a, b, c, q, r, carry] = advice;
+        let q_add = meta.selector();
+        let q_mul = meta.selector();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let two_pow_64 = || Expression::Constant(F::from_u128(1 << LIMB_BITS));
+
+        meta.create_gate("bigint add", |meta| {
+            let s = meta.query_selector(q_add);
+            let mut constraints = vec![];
+            let mut carry_in = Expression::Constant(F::ZERO);
+            for i in 0..LIMBS {
+                let at = Rotation(i as i32);
+                let a = meta.query_advice(a, at);
+                let b = meta.query_advice(b, at);
+                let sum = meta.query_advice(r, at);
+                let carry = meta.query_advice(carry, at);
+                constraints
+                    .push(s.clone() * (a + b + carry_in - sum - two_pow_64() * carry.clone()));
+                constraints.push(
+                    s.clone() * carry.clone() * (Expression::Constant(F::ONE) - carry.clone()),
+                );
+                carry_in = carry;
+            }
+            constraints
+        });
+
+        meta.create_gate("bigint mul", |meta| {
+            let s = meta.query_selector(q_mul);
+            let limbs = |meta: &mut VirtualCells<'_, F>, column| {
+                (0..LIMBS)
+                    .map(|i| meta.query_advice(column, Rotation(i as i32)))
+                    .collect::<Vec<_>>()
+            };
+            let (a, b, c, q, r) = (
+                limbs(meta, a),
+                limbs(meta, b),
+                limbs(meta, c),
+                limbs(meta, q),
+                limbs(meta, r),
+            );
+            let carries = (0..2 * LIMBS - 2)
+                .map(|k| {
+                    meta.query_advice(carry, Rotation(k as i32))
+                        - Expression::Constant(carry_offset())
+                })
+                .collect::<Vec<_>>();
+            let m = modulus.map(|limb| F::from(limb));
+
+            (0..2 * LIMBS - 1)
+                .map(|k| {
+                    let mut poly = Expression::Constant(F::ZERO);
+                    for i in k.saturating_sub(LIMBS - 1)..=k.min(LIMBS - 1) {
+                        let j = k - i;
+                        poly = poly + a[i].clone() * b[j].clone()
+                            - q[i].clone() * Expression::Constant(m[j]);
+                    }
+                    if k < LIMBS {
+                        poly = poly + c[k].clone() - r[k].clone();
+                    }
+                    if k > 0 {
+                        poly = poly + carries[k - 1].clone();
+                    }
+                    if k < 2 * LIMBS - 2 {
+                        poly = poly - two_pow_64() * carries[k].clone();
+                    }
+                    s.clone() * poly
+                })
+                .collect::<Vec<_>>()
+        });
+
+        BigIntConfig {
+            advice,
+            q_add,
+            q_mul,
+            constant,
+            limb_range: DecomposeChip::configure(meta, a, b),
+            carry_range: DecomposeChip::configure(meta, a, b),
+            modulus,
+        }
+    }
+
+    /// Loads the lookup tables of the range checks. Call once per circuit.
+    pub fn load_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        DecomposeChip::construct(self.config.limb_range.clone()).load_table(layouter)?;
+        DecomposeChip::construct(self.config.carry_range.clone()).load_table(layouter)
+    }
+
+    /// Witnesses `value`, range-checks its limbs and checks that it is below
+    /// the modulus.
+    pub fn load(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Limbs>,
+    ) -> Result<AssignedBigInt<F>, Error> {
+        let x = self.witness(
+            layouter.namespace(|| "witness"),
+            self.config.advice[4],
+            value,
+        )?;
+        self.assert_reduced(layouter.namespace(|| "reduced"), &x)?;
+        Ok(x)
+    }
+
+    /// Loads the constant `value`, which needs no range check.
+    pub fn constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Limbs,
+    ) -> Result<AssignedBigInt<F>, Error> {
+        layouter.assign_region(
+            || "bigint constant",
+            |mut region| {
+                let limbs = (0..LIMBS)
+                    .map(|i| {
+                        let limb = F::from(value[i]);
+                        region.assign_advice_from_constant(
+                            || "limb",
+                            self.config.advice[0],
+                            i,
+                            limb,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(AssignedBigInt {
+                    limbs: limbs.try_into().unwrap(),
+                })
+            },
+        )
+    }
+
+    /// Returns `a + b` over the integers, as 256 bits and a carry bit.
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedBigInt<F>,
+        b: &AssignedBigInt<F>,
+    ) -> Result<(AssignedBigInt<F>, AssignedCell<F, F>), Error> {
+        let value = a.value().zip(b.value()).map(|(a, b)| native::add(&a, &b));
+        let (sum, carry) = self.assign_add(
+            layouter.namespace(|| "add"),
+            a,
+            b,
+            value.map(|(sum, _)| sum),
+        )?;
+        for (i, limb) in sum.limbs.iter().enumerate() {
+            self.range_check_limb(layouter.namespace(|| format!("sum limb {}", i)), limb)?;
+        }
+        Ok((sum, carry))
+    }
+
+    /// Returns `a * b mod m`.
+    pub fn mul_mod(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedBigInt<F>,
+        b: &AssignedBigInt<F>,
+    ) -> Result<AssignedBigInt<F>, Error> {
+        let zero = self.constant(layouter.namespace(|| "zero"), [0; LIMBS])?;
+        self.mul_add(layouter, a, b, &zero)
+    }
+
+    /// Returns `a + b mod m`, as `a * 1 + b`.
+    pub fn add_mod(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedBigInt<F>,
+        b: &AssignedBigInt<F>,
+    ) -> Result<AssignedBigInt<F>, Error> {
+        let one = self.constant(layouter.namespace(|| "one"), native::one())?;
+        self.mul_add(layouter, a, &one, b)
+    }
+
+    /// Returns `a * b + c mod m`.
+    pub fn mul_add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedBigInt<F>,
+        b: &AssignedBigInt<F>,
+        c: &AssignedBigInt<F>,
+    ) -> Result<AssignedBigInt<F>, Error> {
+        let m = self.config.modulus;
+        let qr = a
+            .value()
+            .zip(b.value())
+            .zip(c.value())
+            .map(|((a, b), c)| native::mul_add_div_rem(&a, &b, &c, &m));
+        self.assign_mul_add(layouter, a, b, c, qr)
+    }
+
+    /// Checks `x < m`, by witnessing `d = m - 1 - x` with limbs in range and
+    /// checking that `x + d = m - 1` does not carry out.
+    pub fn assert_reduced(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedBigInt<F>,
+    ) -> Result<(), Error> {
+        let m_minus_one = native::sub(&self.config.modulus, &native::one()).0;
+        let d = x.value().map(|x| native::sub(&m_minus_one, &x).0);
+        let d = self.witness(layouter.namespace(|| "m - 1 - x"), self.config.advice[1], d)?;
+        let (sum, carry) = self.assign_add(
+            layouter.namespace(|| "x + d"),
+            x,
+            &d,
+            Value::known(m_minus_one),
+        )?;
+        layouter.assign_region(
+            || "x + d == m - 1",
+            |mut region| {
+                for (i, limb) in sum.limbs.iter().enumerate() {
+                    region.constrain_constant(limb.cell(), F::from(m_minus_one[i]))?;
+                }
+                region.constrain_constant(carry.cell(), F::ZERO)
+            },
+        )
+    }
+
+    /// Constrains the limbs of `x` to instance rows `row..row + 4`.
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedBigInt<F>,
+        instance: Column<Instance>,
+        row: usize,
+    ) -> Result<(), Error> {
+        for (i, limb) in x.limbs.iter().enumerate() {
+            layouter.constrain_instance(limb.cell(), instance, row + i)?;
+        }
+        Ok(())
+    }
+
+    /// Witnesses `value` down `column` and range-checks its limbs.
+    fn witness(
+        &self,
+        mut layouter: impl Layouter<F>,
+        column: Column<Advice>,
+        value: Value<Limbs>,
+    ) -> Result<AssignedBigInt<F>, Error> {
+        let limbs = layouter.assign_region(
+            || "bigint",
+            |mut region| {
+                (0..LIMBS)
+                    .map(|i| {
+                        let limb = value.map(|value| F::from(value[i]));
+                        region.assign_advice(|| "limb", column, i, || limb)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+        for (i, limb) in limbs.iter().enumerate() {
+            self.range_check_limb(layouter.namespace(|| format!("limb {}", i)), limb)?;
+        }
+        Ok(AssignedBigInt {
+            limbs: limbs.try_into().unwrap(),
+        })
+    }
+
+    fn range_check_limb(
+        &self,
+        layouter: impl Layouter<F>,
+        limb: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        DecomposeChip::construct(self.config.limb_range.clone()).decompose(layouter, limb)?;
+        Ok(())
+    }
+
+    /// Lays out `a + b` with `sum` as the result; the sum limbs are not range
+    /// checked here.
+    fn assign_add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedBigInt<F>,
+        b: &AssignedBigInt<F>,
+        sum: Value<Limbs>,
+    ) -> Result<(AssignedBigInt<F>, AssignedCell<F, F>), Error> {
+        let [col_a, col_b, _, _, col_sum, col_carry] = self.config.advice;
+        let two_pow_64_inv = F::from_u128(1 << LIMB_BITS).invert().unwrap();
+        layouter.assign_region(
+            || "bigint add",
+            |mut region| {
+                self.config.q_add.enable(&mut region, 0)?;
+                let mut carry = Value::known(F::ZERO);
+                let mut carry_cell = None;
+                let mut limbs = vec![];
+                for i in 0..LIMBS {
+                    let a = a.limbs[i].copy_advice(|| "a", &mut region, col_a, i)?;
+                    let b = b.limbs[i].copy_advice(|| "b", &mut region, col_b, i)?;
+                    let s = sum.map(|sum| F::from(sum[i]));
+                    limbs.push(region.assign_advice(|| "sum", col_sum, i, || s)?);
+                    carry =
+                        (a.value().copied() + b.value() + carry - s).map(|t| t * two_pow_64_inv);
+                    carry_cell = Some(region.assign_advice(|| "carry", col_carry, i, || carry)?);
+                }
+                Ok((
+                    AssignedBigInt {
+                        limbs: limbs.try_into().unwrap(),
+                    },
+                    carry_cell.unwrap(),
+                ))
+            },
+        )
+    }
+
+    /// Lays out `a * b + c = q * m + r` with the given quotient and remainder,
+    /// and returns `r`.
+    fn assign_mul_add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedBigInt<F>,
+        b: &AssignedBigInt<F>,
+        c: &AssignedBigInt<F>,
+        qr: Value<(Limbs, Limbs)>,
+    ) -> Result<AssignedBigInt<F>, Error> {
+        let [col_a, col_b, col_c, col_q, col_r, col_carry] = self.config.advice;
+        let m = self.config.modulus.map(F::from);
+        let two_pow_64_inv = F::from_u128(1 << LIMB_BITS).invert().unwrap();
+
+        let (q, r, carries) = layouter.assign_region(
+            || "bigint mul",
+            |mut region| {
+                self.config.q_mul.enable(&mut region, 0)?;
+                let mut cells = [vec![], vec![], vec![], vec![], vec![]];
+                for i in 0..LIMBS {
+                    let limb = |qr: &(Limbs, Limbs), which: usize| {
+                        F::from(if which == 0 { qr.0[i] } else { qr.1[i] })
+                    };
+                    cells[0].push(a.limbs[i].copy_advice(|| "a", &mut region, col_a, i)?);
+                    cells[1].push(b.limbs[i].copy_advice(|| "b", &mut region, col_b, i)?);
+                    cells[2].push(c.limbs[i].copy_advice(|| "c", &mut region, col_c, i)?);
+                    cells[3].push(region.assign_advice(
+                        || "q",
+                        col_q,
+                        i,
+                        || qr.map(|qr| limb(&qr, 0)),
+                    )?);
+                    cells[4].push(region.assign_advice(
+                        || "r",
+                        col_r,
+                        i,
+                        || qr.map(|qr| limb(&qr, 1)),
+                    )?);
+                }
+                let value = |cells: &Vec<AssignedCell<F, F>>| {
+                    cells.iter().fold(Value::known(vec![]), |acc, cell| {
+                        acc.zip(cell.value()).map(|(mut acc, v)| {
+                            acc.push(*v);
+                            acc
+                        })
+                    })
+                };
+                let [a, b, c, q, r] = [0, 1, 2, 3, 4].map(|i| value(&cells[i]));
+
+                // Carries in the field: each division by 2^64 is exact.
+                let carries = a.zip(b).zip(c).zip(q).zip(r).map(|((((a, b), c), q), r)| {
+                    let mut carry = F::ZERO;
+                    (0..2 * LIMBS - 2)
+                        .map(|k| {
+                            let mut t = carry;
+                            for i in k.saturating_sub(LIMBS - 1)..=k.min(LIMBS - 1) {
+                                t += a[i] * b[k - i] - q[i] * m[k - i];
+                            }
+                            if k < LIMBS {
+                                t += c[k] - r[k];
+                            }
+                            carry = t * two_pow_64_inv;
+                            carry + carry_offset::<F>()
+                        })
+                        .collect::<Vec<_>>()
+                });
+                let carries = (0..2 * LIMBS - 2)
+                    .map(|k| {
+                        let carry = carries.as_ref().map(|carries| carries[k]);
+                        region.assign_advice(|| "carry", col_carry, k, || carry)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let [_, _, _, q, r] = cells;
+                Ok((q, r, carries))
+            },
+        )?;
+
+        for (i, limb) in q.iter().chain(&r).enumerate() {
+            self.range_check_limb(layouter.namespace(|| format!("q, r limb {}", i)), limb)?;
+        }
+        let carry_range = DecomposeChip::construct(self.config.carry_range.clone());
+        for (k, carry) in carries.iter().enumerate() {
+            carry_range.decompose(layouter.namespace(|| format!("carry {}", k)), carry)?;
+        }
+
+        let r = AssignedBigInt {
+            limbs: r.try_into().unwrap(),
+        };
+        self.assert_reduced(layouter.namespace(|| "r < m"), &r)?;
+        Ok(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigint::native::{PALLAS_Q, SECP256K1_P};
+    use ff::Field;
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::{pallas, Fp},
+    };
+    use rand_core::OsRng;
+
+    trait Modulus {
+        const MODULUS: Limbs;
+    }
+
+    struct PallasQ;
+
+    impl Modulus for PallasQ {
+        const MODULUS: Limbs = PALLAS_Q;
+    }
+
+    struct Secp256k1;
+
+    impl Modulus for Secp256k1 {
+        const MODULUS: Limbs = SECP256K1_P;
+    }
+
+    /// Loads `a`, `b` and `c` and exposes `a * b + c`, `a + b` and `a * b`
+    /// modulo `M`, then `a + b` over the integers with its carry.
+    ///
+    /// `forged` replaces the quotient and remainder of `a * b + c`.
+    struct BigIntCircuit<M> {
+        a: Limbs,
+        b: Limbs,
+        c: Limbs,
+        forged: Option<(Limbs, Limbs)>,
+        _marker: PhantomData<M>,
+    }
+
+    impl<M: Modulus> BigIntCircuit<M> {
+        fn new(a: Limbs, b: Limbs, c: Limbs) -> Self {
+            Self {
+                a,
+                b,
+                c,
+                forged: None,
+                _marker: PhantomData,
+            }
+        }
+
+        fn instances(&self) -> Vec<Vec<Fp>> {
+            let m = M::MODULUS;
+            let (sum, carry) = native::add(&self.a, &self.b);
+            let limbs = [
+                native::mul_add_div_rem(&self.a, &self.b, &self.c, &m).1,
+                native::add_mod(&self.a, &self.b, &m),
+                native::mul_mod(&self.a, &self.b, &m),
+                sum,
+            ];
+            let mut instances: Vec<_> = limbs.iter().flatten().map(|&l| Fp::from(l)).collect();
+            instances.push(Fp::from(u64::from(carry)));
+            vec![instances]
+        }
+    }
+
+    impl<M: Modulus> Circuit<Fp> for BigIntCircuit<M> {
+        type Config = (BigIntConfig<Fp>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                forged: None,
+                ..Self::new(self.a, self.b, self.c)
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 6].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (BigIntChip::configure(meta, advice, M::MODULUS), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = BigIntChip::construct(config);
+            chip.load_tables(&mut layouter)?;
+
+            let a = chip.load(layouter.namespace(|| "a"), Value::known(self.a))?;
+            let b = chip.load(layouter.namespace(|| "b"), Value::known(self.b))?;
+            let c = chip.load(layouter.namespace(|| "c"), Value::known(self.c))?;
+
+            let mul_add = match self.forged {
+                Some(qr) => chip.assign_mul_add(
+                    layouter.namespace(|| "forged a * b + c"),
+                    &a,
+                    &b,
+                    &c,
+                    Value::known(qr),
+                )?,
+                None => chip.mul_add(layouter.namespace(|| "a * b + c"), &a, &b, &c)?,
+            };
+            let add_mod = chip.add_mod(layouter.namespace(|| "a + b"), &a, &b)?;
+            let mul_mod = chip.mul_mod(layouter.namespace(|| "a * b"), &a, &b)?;
+            let (sum, carry) = chip.add(layouter.namespace(|| "a + b wide"), &a, &b)?;
+
+            for (i, x) in [mul_add, add_mod, mul_mod, sum].iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "expose"), x, instance, LIMBS * i)?;
+            }
+            layouter.constrain_instance(carry.cell(), instance, 4 * LIMBS)
+        }
+    }
+
+    const K: u32 = 11;
+
+    fn run<M: Modulus>(circuit: &BigIntCircuit<M>) -> MockProver<Fp> {
+        MockProver::run(K, circuit, circuit.instances()).unwrap()
+    }
+
+    fn to_limbs(x: pallas::Scalar) -> Limbs {
+        let repr = x.to_repr();
+        std::array::from_fn(|i| u64::from_le_bytes(repr[8 * i..8 * i + 8].try_into().unwrap()))
+    }
+
+    #[test]
+    fn bigint_matches_pallas_scalars() {
+        let [a, b, c] = [(); 3].map(|_| pallas::Scalar::random(OsRng));
+        let circuit = BigIntCircuit::<PallasQ>::new(to_limbs(a), to_limbs(b), to_limbs(c));
+
+        let instances = circuit.instances();
+        assert_eq!(instances[0][..LIMBS], to_limbs(a * b + c).map(Fp::from));
+        assert_eq!(
+            instances[0][LIMBS..2 * LIMBS],
+            to_limbs(a + b).map(Fp::from)
+        );
+        MockProver::run(K, &circuit, instances)
+            .unwrap()
+            .assert_satisfied();
+    }
+
+    #[test]
+    fn bigint_secp256k1_carries() {
+        // Every limb of p - 1 is nearly all ones, so every carry is large and
+        // the integer addition carries out of the top limb.
+        let p_minus = |n: u64| native::sub(&SECP256K1_P, &[n, 0, 0, 0]).0;
+        let circuit = BigIntCircuit::<Secp256k1>::new(p_minus(1), p_minus(2), p_minus(1));
+        assert_eq!(circuit.instances()[0][4 * LIMBS], Fp::ONE);
+        run(&circuit).assert_satisfied();
+
+        let circuit = BigIntCircuit::<Secp256k1>::new([0; LIMBS], native::one(), [0; LIMBS]);
+        run(&circuit).assert_satisfied();
+    }
+
+    #[test]
+    fn bigint_rejects_wrong_result() {
+        let circuit = BigIntCircuit::<Secp256k1>::new([3, 0, 0, 0], [5, 0, 0, 0], [7, 0, 0, 0]);
+        let mut instances = circuit.instances();
+        assert_eq!(instances[0][0], Fp::from(22));
+        instances[0][0] = Fp::from(23);
+
+        let prover = MockProver::run(K, &circuit, instances).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn bigint_rejects_unreduced_remainder() {
+        let circuit = BigIntCircuit::<PallasQ>::new(
+            to_limbs(-pallas::Scalar::ONE),
+            to_limbs(pallas::Scalar::from(3)),
+            [0; LIMBS],
+        );
+        let (q, r) = native::mul_add_div_rem(&circuit.a, &circuit.b, &circuit.c, &PALLAS_Q);
+        // q * m + r == (q - 1) * m + (r + m), and r + m still fits in 256
+        // bits, so only the range check on r catches it.
+        let forged = (
+            native::sub(&q, &native::one()).0,
+            native::add(&r, &PALLAS_Q).0,
+        );
+        let mut instances = circuit.instances();
+        for (i, &limb) in forged.1.iter().enumerate() {
+            instances[0][i] = Fp::from(limb);
+        }
+
+        let circuit = BigIntCircuit {
+            forged: Some(forged),
+            ..circuit
+        };
+        let prover = MockProver::run(K, &circuit, instances).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn bigint_rejects_unreduced_input() {
+        let circuit = BigIntCircuit::<PallasQ>::new(PALLAS_Q, native::one(), [0; LIMBS]);
+        assert!(run(&circuit).verify().is_err());
+    }
+}
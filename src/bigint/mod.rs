@@ -0,0 +1,8 @@
+//! Non-native arithmetic: 256-bit integers as 64-bit limbs, added and
+//! multiplied modulo a modulus other than the circuit field's, such as the
+//! base field of secp256k1.
+
+mod chip;
+pub mod native;
+
+pub use chip::{AssignedBigInt, BigIntChip, BigIntConfig};
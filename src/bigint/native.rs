@@ -0,0 +1,182 @@
+//! Plain-Rust arithmetic on little-endian 64-bit limbs, to compute the
+//! witnesses of [`super::BigIntChip`] and to check it against.
+
+/// Limbs of a 256-bit integer.
+pub const LIMBS: usize = 4;
+/// Bits per limb.
+pub const LIMB_BITS: usize = 64;
+
+/// A 256-bit integer as little-endian limbs.
+pub type Limbs = [u64; LIMBS];
+
+/// The base field of secp256k1, `2^256 - 2^32 - 977`.
+pub const SECP256K1_P: Limbs = [
+    0xffff_fffe_ffff_fc2f,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+];
+
+/// The scalar field of Pallas, the order of its group.
+pub const PALLAS_Q: Limbs = [
+    0x8c46_eb21_0000_0001,
+    0x2246_98fc_0994_a8dd,
+    0,
+    0x4000_0000_0000_0000,
+];
+
+/// `a + b` and the carry out of the top limb.
+pub fn add(a: &Limbs, b: &Limbs) -> (Limbs, bool) {
+    let mut sum = [0; LIMBS];
+    let mut carry = false;
+    for i in 0..LIMBS {
+        let (s, c1) = a[i].overflowing_add(b[i]);
+        let (s, c2) = s.overflowing_add(u64::from(carry));
+        sum[i] = s;
+        carry = c1 || c2;
+    }
+    (sum, carry)
+}
+
+/// `a - b` and whether it borrowed, i.e. whether `a < b`.
+pub fn sub(a: &Limbs, b: &Limbs) -> (Limbs, bool) {
+    let mut diff = [0; LIMBS];
+    let mut borrow = false;
+    for i in 0..LIMBS {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(u64::from(borrow));
+        diff[i] = d;
+        borrow = b1 || b2;
+    }
+    (diff, borrow)
+}
+
+/// The full 512-bit product `a * b`.
+pub fn mul_wide(a: &Limbs, b: &Limbs) -> [u64; 2 * LIMBS] {
+    let mut product = [0u64; 2 * LIMBS];
+    for i in 0..LIMBS {
+        let mut carry = 0u128;
+        for j in 0..LIMBS {
+            let t = u128::from(a[i]) * u128::from(b[j]) + u128::from(product[i + j]) + carry;
+            product[i + j] = t as u64;
+            carry = t >> LIMB_BITS;
+        }
+        product[i + LIMBS] = carry as u64;
+    }
+    product
+}
+
+/// The quotient and remainder of the 512-bit `n` by `m`, by shift and
+/// subtract. The quotient must fit in 256 bits, which holds whenever
+/// `n < m^2`.
+pub fn div_rem(n: &[u64; 2 * LIMBS], m: &Limbs) -> (Limbs, Limbs) {
+    assert!(*m != [0; LIMBS], "division by zero");
+    let mut q = [0u64; 2 * LIMBS];
+    // One spare bit, since the running remainder is doubled before subtracting.
+    let mut r = [0u64; LIMBS + 1];
+    let m_wide = [m[0], m[1], m[2], m[3], 0];
+    for bit in (0..2 * LIMBS * LIMB_BITS).rev() {
+        for i in (1..=LIMBS).rev() {
+            r[i] = r[i] << 1 | r[i - 1] >> 63;
+        }
+        r[0] = r[0] << 1 | (n[bit / 64] >> (bit % 64)) & 1;
+        if !lt_wide(&r, &m_wide) {
+            let mut borrow = false;
+            for i in 0..=LIMBS {
+                let (d, b1) = r[i].overflowing_sub(m_wide[i]);
+                let (d, b2) = d.overflowing_sub(u64::from(borrow));
+                r[i] = d;
+                borrow = b1 || b2;
+            }
+            q[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+    assert!(
+        q[LIMBS..].iter().all(|&limb| limb == 0),
+        "quotient does not fit"
+    );
+    ([q[0], q[1], q[2], q[3]], [r[0], r[1], r[2], r[3]])
+}
+
+fn lt_wide(a: &[u64; LIMBS + 1], b: &[u64; LIMBS + 1]) -> bool {
+    a.iter().rev().cmp(b.iter().rev()).is_lt()
+}
+
+/// `(a * b + c) / m` and `(a * b + c) % m`.
+pub fn mul_add_div_rem(a: &Limbs, b: &Limbs, c: &Limbs, m: &Limbs) -> (Limbs, Limbs) {
+    let mut n = mul_wide(a, b);
+    let mut carry = false;
+    for i in 0..2 * LIMBS {
+        let c = if i < LIMBS { c[i] } else { 0 };
+        let (s, c1) = n[i].overflowing_add(c);
+        let (s, c2) = s.overflowing_add(u64::from(carry));
+        n[i] = s;
+        carry = c1 || c2;
+    }
+    div_rem(&n, m)
+}
+
+/// `a * b mod m`.
+pub fn mul_mod(a: &Limbs, b: &Limbs, m: &Limbs) -> Limbs {
+    mul_add_div_rem(a, b, &[0; LIMBS], m).1
+}
+
+/// `a + b mod m`.
+pub fn add_mod(a: &Limbs, b: &Limbs, m: &Limbs) -> Limbs {
+    mul_add_div_rem(a, &one(), b, m).1
+}
+
+/// Whether `a < b`.
+pub fn lt(a: &Limbs, b: &Limbs) -> bool {
+    sub(a, b).1
+}
+
+/// The integer 1.
+pub fn one() -> Limbs {
+    let mut one = [0; LIMBS];
+    one[0] = 1;
+    one
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::{Field, PrimeField};
+    use halo2_proofs::pasta::pallas;
+    use rand_core::OsRng;
+
+    fn to_limbs(x: pallas::Scalar) -> Limbs {
+        let repr = x.to_repr();
+        std::array::from_fn(|i| u64::from_le_bytes(repr[8 * i..8 * i + 8].try_into().unwrap()))
+    }
+
+    #[test]
+    fn bigint_native_matches_pallas_scalars() {
+        for _ in 0..20 {
+            let (a, b) = (pallas::Scalar::random(OsRng), pallas::Scalar::random(OsRng));
+            let (la, lb) = (to_limbs(a), to_limbs(b));
+            assert_eq!(mul_mod(&la, &lb, &PALLAS_Q), to_limbs(a * b));
+            assert_eq!(add_mod(&la, &lb, &PALLAS_Q), to_limbs(a + b));
+        }
+        assert_eq!(to_limbs(-pallas::Scalar::ONE), sub(&PALLAS_Q, &one()).0);
+    }
+
+    #[test]
+    fn bigint_native_small_values() {
+        let m = [1_000_003, 0, 0, 0];
+        let (q, r) = mul_add_div_rem(
+            &[123_456_789, 0, 0, 0],
+            &[987_654, 0, 0, 0],
+            &[5, 0, 0, 0],
+            &m,
+        );
+        let n = 123_456_789u128 * 987_654 + 5;
+        assert_eq!(q, [(n / 1_000_003) as u64, 0, 0, 0]);
+        assert_eq!(r, [(n % 1_000_003) as u64, 0, 0, 0]);
+
+        let max = [u64::MAX; LIMBS];
+        assert_eq!(add(&max, &one()), ([0; LIMBS], true));
+        assert_eq!(sub(&[0; LIMBS], &one()), (max, true));
+        assert!(lt(&SECP256K1_P, &max) && !lt(&max, &SECP256K1_P));
+    }
+}
@@ -0,0 +1,272 @@
+//! Runs the Fibonacci examples from the command line.
+//!
+//! ```text
+//! fib mock   --example 1 --n 9 --a 1 --b 1
+//! fib mock   --example 2 --inputs fib.toml
+//! fib prove  --example 2 --n 20 --k 5 --proof fib.proof
+//! fib verify --example 2 --n 20 --k 5 --proof fib.proof --key-dir keys
+//! fib layout --example 1 --output fib-1-layout.png   (needs `dev-graph`)
+//! fib cost
+//! ```
+//!
+//! `prove` writes a JSON [`ProofEnvelope`] holding the proof, `k` and the public
+//! inputs. `verify` reads `k` and the public inputs back from it and regenerates
+//! the keys from the circuit, since the IPA parameters only depend on `k`; it
+//! needs the same `--example` and `--n` as `prove`. Without `--k`, the other
+//! commands use the smallest `k` the circuit verifies at. See
+//! [`halo2examples::io`] for the `--inputs` file format.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use halo2_proofs::{pasta::Fp, plonk::Circuit};
+use halo2examples::{
+    common::{check_rows, min_k_for_circuit, usable_rows, MAX_K},
+    cost,
+    fibonacci::{example1, example2, FibIndex, FibonacciInstances},
+    io::{FibonacciInputs, InputError},
+    prover::{self, ParamsStore},
+    serialize::ProofEnvelope,
+};
+
+#[derive(Parser)]
+#[command(about = "Prove Fibonacci terms with the halo2 examples")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check the circuit with `MockProver`.
+    Mock(CircuitArgs),
+    /// Write a real proof and its public inputs to `--proof`, as JSON.
+    Prove {
+        #[command(flatten)]
+        circuit: CircuitArgs,
+        #[arg(long)]
+        proof: PathBuf,
+    },
+    /// Verify the proof in `--proof` against the public inputs stored with it.
+    Verify {
+        #[command(flatten)]
+        circuit: CircuitArgs,
+        #[arg(long)]
+        proof: PathBuf,
+    },
+    /// Render the circuit layout to a PNG file.
+    Layout {
+        #[command(flatten)]
+        circuit: CircuitArgs,
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Print the rows, columns, degree and proof size of every example circuit.
+    Cost,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Example {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+}
+
+impl Example {
+    /// The example's name in the registry, and in proof files.
+    fn name(self) -> &'static str {
+        match self {
+            Example::One => "fibonacci::example1",
+            Example::Two => "fibonacci::example2",
+        }
+    }
+}
+
+#[derive(Args)]
+struct CircuitArgs {
+    /// Which Fibonacci example circuit to use.
+    #[arg(long, value_enum, default_value = "1")]
+    example: Example,
+    /// Index of the term to prove, `f(n)`.
+    #[arg(long, default_value_t = 9)]
+    n: usize,
+    /// The circuit has `2^k` rows. Defaults to the smallest `k` the circuit
+    /// verifies at.
+    #[arg(long)]
+    k: Option<u32>,
+    /// The seed `f(0)`.
+    #[arg(long, default_value_t = 1)]
+    a: u64,
+    /// The seed `f(1)`.
+    #[arg(long, default_value_t = 1)]
+    b: u64,
+    /// Read `a`, `b`, `n` and optionally `out` from this JSON or TOML file
+    /// instead of the flags above.
+    #[arg(long, conflicts_with_all = ["n", "a", "b"])]
+    inputs: Option<PathBuf>,
+    /// Cache parameters and verifying key fingerprints here across runs. Keys
+    /// are still generated on every run.
+    #[arg(long)]
+    key_dir: Option<PathBuf>,
+}
+
+/// The term to prove and the public inputs that go with it.
+struct Term {
+    n: usize,
+    instances: Vec<Vec<Fp>>,
+}
+
+impl CircuitArgs {
+    /// Keys for `circuit`, the circuit for `f(n)`, at size `2^k`, on parameters
+    /// from `--key-dir` if given.
+    fn keys<C: Circuit<Fp>>(&self, k: u32, n: usize, circuit: &C) -> Result<prover::Keys, String> {
+        match &self.key_dir {
+            // The layout depends on `n`, so entries for different terms must not
+            // share a name.
+            Some(dir) => ParamsStore::new(dir)
+                .map_err(|e| format!("{}: {}", dir.display(), e))?
+                .load_or_generate(&format!("{}-n{}", self.example.name(), n), k, circuit),
+            None => prover::Keys::generate(k, circuit),
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    /// `--k` if given and large enough, otherwise the smallest `k` that works.
+    fn k<C: Circuit<Fp>>(&self, circuit: &C, rows: usize, term: &Term) -> Result<u32, String> {
+        match self.k {
+            Some(k) => check_rows::<Fp, C>(k, rows).map(|()| k).map_err(|_| {
+                format!(
+                    "not enough rows: the circuit needs {} rows, but k = {} leaves {} usable rows",
+                    rows,
+                    k,
+                    usable_rows::<Fp, C>(k)
+                )
+            }),
+            None => min_k_for_circuit(circuit, term.instances.clone())
+                .ok_or_else(|| format!("f({}) does not verify at any k up to {}", term.n, MAX_K)),
+        }
+    }
+
+    /// The term from `--inputs` if given, otherwise from `--n`, `--a` and `--b`.
+    fn term(&self) -> Result<Term, String> {
+        let Some(path) = &self.inputs else {
+            let instances = FibonacciInstances::from_seeds(self.a, self.b, FibIndex(self.n))
+                .ok_or_else(|| format!("f({}) does not fit in a u64", self.n))?;
+            return Ok(Term {
+                n: self.n,
+                instances: instances.to_columns(),
+            });
+        };
+        let error = |e: InputError| format!("{}: {}", path.display(), e);
+        let inputs = FibonacciInputs::read(path).map_err(error)?;
+        Ok(Term {
+            n: inputs.n.0,
+            instances: inputs.instances().map_err(error)?.to_columns(),
+        })
+    }
+}
+
+/// Runs `command` on the circuit `args` selects, once its type is known.
+fn run(command: &Command, args: &CircuitArgs) -> Result<(), String> {
+    let term = args.term()?;
+    match args.example {
+        Example::One => {
+            let circuit = example1::MyCircuit::<Fp>::new(term.n);
+            let k = args.k(&circuit, circuit.rows(), &term)?;
+            run_circuit(command, args, &term, k, circuit)
+        }
+        Example::Two => {
+            let circuit = example2::MyCircuit::<Fp>::new(term.n);
+            let k = args.k(&circuit, circuit.rows(), &term)?;
+            run_circuit(command, args, &term, k, circuit)
+        }
+    }
+}
+
+fn run_circuit<C: Circuit<Fp>>(
+    command: &Command,
+    args: &CircuitArgs,
+    term: &Term,
+    k: u32,
+    circuit: C,
+) -> Result<(), String> {
+    match command {
+        Command::Mock(_) => {
+            halo2examples::registry::mock_verify(k, &circuit, term.instances.clone())?;
+            println!("f({}) verified with MockProver", term.n);
+        }
+        Command::Prove { proof, .. } => {
+            let instances = term.instances.clone();
+            let keys = args.keys(k, term.n, &circuit)?;
+            let bytes =
+                prover::prove(&keys, circuit, &[&instances[0]]).map_err(|e| e.to_string())?;
+            let envelope = ProofEnvelope {
+                circuit: args.example.name().to_string(),
+                k,
+                instances,
+                proof: bytes,
+            };
+            envelope
+                .write(proof)
+                .map_err(|e| format!("{}: {}", proof.display(), e))?;
+            println!(
+                "wrote a {}-byte proof to {}",
+                envelope.proof.len(),
+                proof.display()
+            );
+        }
+        Command::Verify { proof, .. } => {
+            let envelope =
+                ProofEnvelope::read(proof).map_err(|e| format!("{}: {}", proof.display(), e))?;
+            if envelope.circuit != args.example.name() {
+                return Err(format!(
+                    "{} holds a proof for {}, not {}",
+                    proof.display(),
+                    envelope.circuit,
+                    args.example.name()
+                ));
+            }
+            let keys = args.keys(envelope.k, term.n, &circuit)?;
+            prover::verify(&keys, &envelope.proof, &envelope.instance_slices())
+                .map_err(|e| format!("proof rejected: {}", e))?;
+            println!("proof of f({}) verified", term.n);
+        }
+        Command::Cost => unreachable!("cost does not take a circuit"),
+        Command::Layout { output, .. } => {
+            #[cfg(feature = "dev-graph")]
+            {
+                let path = output.to_str().ok_or("output path is not UTF-8")?;
+                halo2examples::plot::plot_png(path, k, &circuit).map_err(|e| e.to_string())?;
+                println!("wrote {}", output.display());
+            }
+            #[cfg(not(feature = "dev-graph"))]
+            {
+                let _ = (output, circuit);
+                return Err("layout needs the dev-graph feature".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let args = match &cli.command {
+        Command::Mock(circuit)
+        | Command::Prove { circuit, .. }
+        | Command::Verify { circuit, .. }
+        | Command::Layout { circuit, .. } => circuit,
+        Command::Cost => {
+            print!("{}", cost::format_table(&cost::example_costs()));
+            return ExitCode::SUCCESS;
+        }
+    };
+    match run(&cli.command, args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Renders the layout of every registered example circuit to PNG and SVG.
+//!
+//! ```text
+//! render_layouts --output-dir layouts
+//! render_layouts --output-dir layouts --labels --width 2048 --height 4096
+//! render_layouts --example fibonacci
+//! ```
+//!
+//! Each example is drawn at the `k` it verifies with, to
+//! `<output-dir>/<name>.png` and `.svg`, with `::` in names replaced by `-`.
+
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+use halo2examples::plot::{self, LayoutOptions, LAYOUT_SIZE};
+
+#[derive(Parser)]
+#[command(about = "Render the layouts of the halo2 example circuits")]
+struct Cli {
+    /// Directory to write the diagrams to. Created if missing.
+    #[arg(long, default_value = "layouts")]
+    output_dir: PathBuf,
+    /// Print region names on the diagrams.
+    #[arg(long)]
+    labels: bool,
+    /// Canvas width, in pixels.
+    #[arg(long, default_value_t = LAYOUT_SIZE.0)]
+    width: u32,
+    /// Canvas height, in pixels.
+    #[arg(long, default_value_t = LAYOUT_SIZE.1)]
+    height: u32,
+    /// Only render examples whose name contains this.
+    #[arg(long)]
+    example: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(e) = fs::create_dir_all(&cli.output_dir) {
+        eprintln!("error: {}: {}", cli.output_dir.display(), e);
+        return ExitCode::FAILURE;
+    }
+
+    let options = LayoutOptions {
+        size: (cli.width, cli.height),
+        labels: cli.labels,
+    };
+    let filter = |name: &str| {
+        cli.example
+            .as_deref()
+            .is_none_or(|part| name.contains(part))
+    };
+    let rendered = plot::render_examples(&cli.output_dir, &options, filter);
+
+    let mut failed = false;
+    for (name, outcome) in rendered {
+        match outcome {
+            Ok(paths) => {
+                for path in paths {
+                    println!("{}: wrote {}", name, path.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}", name, e);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,319 @@
+use crate::{
+    common::{trace_region, BooleanChip, BooleanConfig},
+    names,
+    prelude::*,
+    range_check::table::RangeTableConfig,
+    registry::ExampleCircuit,
+};
+use halo2_proofs::pasta::Fp;
+
+/// Proves that a private Collatz trajectory from a public start reaches 1.
+///
+/// Each row holds one value `n` with a parity hint `p` and its half `h`:
+///
+/// ```text
+/// n == 2*h + p
+/// n_next == p * (3n + 1) + (1 - p) * h
+/// ```
+///
+/// `p` is a boolean from a [`BooleanChip`], and `h` is looked up in a
+/// `0..RANGE` table. Without that range check any `n` would be "even": `h` could
+/// be `(n - p) / 2` computed in the field. The last row must hold 1.
+#[derive(Debug, Clone)]
+struct CollatzConfig<F: PrimeField, const RANGE: usize> {
+    pub advice: [Column<Advice>; 3],
+    pub boolean: BooleanConfig,
+    pub selector_step: Selector,
+    pub selector_last: Selector,
+    pub instance: Column<Instance>,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+#[derive(Debug, Clone)]
+struct CollatzChip<F: PrimeField, const RANGE: usize> {
+    config: CollatzConfig<F, RANGE>,
+}
+
+impl<F: PrimeField, const RANGE: usize> CollatzChip<F, RANGE> {
+    pub fn construct(config: CollatzConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        bool_column: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> CollatzConfig<F, RANGE> {
+        let [col_n, col_p, col_h] = advice;
+        let boolean = BooleanChip::configure(meta, bool_column);
+        // Lookups cannot use simple selectors.
+        let selector_step = meta.complex_selector();
+        let selector_last = meta.selector();
+        let table = RangeTableConfig::configure(meta);
+        meta.enable_equality(col_n);
+        meta.enable_equality(col_p);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::STEP, |meta| {
+            //
+            // col_n  | col_p | col_h | selector
+            //   n        p       h        s
+            //   n_next
+            //
+            let s = meta.query_selector(selector_step);
+            let n = meta.query_advice(col_n, Rotation::cur());
+            let p = meta.query_advice(col_p, Rotation::cur());
+            let h = meta.query_advice(col_h, Rotation::cur());
+            let n_next = meta.query_advice(col_n, Rotation::next());
+            let one = Expression::Constant(F::ONE);
+            let three = Expression::Constant(F::from(3));
+            vec![
+                s.clone() * (n.clone() - h.clone() - h.clone() - p.clone()),
+                s * (n_next - p.clone() * (three * n + one.clone()) - (one - p) * h),
+            ]
+        });
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector_step);
+            let h = meta.query_advice(col_h, Rotation::cur());
+            vec![(s * h, table.value)]
+        });
+
+        meta.create_gate(names::LAST, |meta| {
+            let s = meta.query_selector(selector_last);
+            let n = meta.query_advice(col_n, Rotation::cur());
+            vec![s * (n - Expression::Constant(F::ONE))]
+        });
+
+        CollatzConfig {
+            advice,
+            boolean,
+            selector_step,
+            selector_last,
+            instance,
+            table,
+        }
+    }
+
+    /// Assigns `trajectory`, which starts at the public value and should end at 1,
+    /// with a parity hint and half for every step.
+    pub fn assign_trajectory(
+        &self,
+        mut layouter: impl Layouter<F>,
+        trajectory: &[Value<F>],
+    ) -> Result<(), Error> {
+        let [col_n, col_p, col_h] = self.config.advice;
+        let steps = trajectory.len().checked_sub(1).ok_or(Error::Synthesis)?;
+
+        let boolean = BooleanChip::construct(self.config.boolean.clone());
+        let parities = trajectory[..steps]
+            .iter()
+            .map(|n| {
+                let p = n.map(|n| F::from(to_u64(&n) & 1));
+                boolean.assign_bool(layouter.namespace(|| "parity"), p)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        layouter.assign_region(
+            || "trajectory",
+            |mut region| {
+                load_instances(&mut region, self.config.instance, &[(col_n, 0)])?;
+
+                for (row, (n, p)) in trajectory.iter().zip(&parities).enumerate() {
+                    self.config.selector_step.enable(&mut region, row)?;
+
+                    p.copy_advice(|| "p", &mut region, col_p, row)?;
+                    let h = n.map(|n| F::from(to_u64(&n) >> 1));
+                    region.assign_advice(|| "h", col_h, row, || h)?;
+
+                    region.assign_advice(|| "n", col_n, row + 1, || trajectory[row + 1])?;
+                }
+
+                self.config.selector_last.enable(&mut region, steps)?;
+                trace_region!("trajectory", steps + 1);
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Plain-Rust reference: the Collatz trajectory from `start` down to 1, or `None`
+/// if a value overflows a `u64` first. `start` must be positive.
+pub fn reference(start: u64) -> Option<Vec<u64>> {
+    let mut trajectory = vec![start];
+    let mut n = start;
+    while n != 1 {
+        n = if n.is_multiple_of(2) {
+            n / 2
+        } else {
+            n.checked_mul(3)?.checked_add(1)?
+        };
+        trajectory.push(n);
+    }
+    Some(trajectory)
+}
+
+#[derive(Default)]
+struct MyCircuit<F, const RANGE: usize> {
+    trajectory: Vec<Value<F>>,
+}
+
+impl<F: PrimeField, const RANGE: usize> MyCircuit<F, RANGE> {
+    pub fn new(trajectory: &[u64]) -> Self {
+        Self {
+            trajectory: trajectory
+                .iter()
+                .map(|n| Value::known(F::from(*n)))
+                .collect(),
+        }
+    }
+}
+
+impl<F: PrimeField, const RANGE: usize> Circuit<F> for MyCircuit<F, RANGE> {
+    type Config = CollatzConfig<F, RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    // The trajectory length fixes the layout, so it survives without witnesses.
+    fn without_witnesses(&self) -> Self {
+        Self {
+            trajectory: vec![Value::unknown(); self.trajectory.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let bool_column = meta.advice_column();
+        let instance = meta.instance_column();
+        CollatzChip::configure(meta, advice, bool_column, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let chip = CollatzChip::construct(config);
+
+        chip.assign_trajectory(layouter.namespace(|| "trajectory"), &self.trajectory)
+    }
+}
+
+/// The circuit on the trajectory starting at 7, which climbs to 52 before
+/// reaching 1.
+pub fn example_circuit() -> ExampleCircuit {
+    let trajectory = reference(7).expect("7 reaches 1 well within a u64");
+    let circuit = MyCircuit::<Fp, 256>::new(&trajectory);
+    ExampleCircuit::new(9, circuit, vec![vec![Fp::from(7)]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    const RANGE: usize = 256;
+
+    fn run(trajectory: &[u64], start: u64) -> MockProver<Fp> {
+        let k = 9;
+
+        let circuit = MyCircuit::<Fp, RANGE>::new(trajectory);
+        MockProver::run(k, &circuit, vec![vec![Fp::from(start)]]).unwrap()
+    }
+
+    #[test]
+    fn collatz_from_six() {
+        let trajectory = reference(6).unwrap();
+        assert_eq!(trajectory, vec![6, 3, 10, 5, 16, 8, 4, 2, 1]);
+        run(&trajectory, 6).assert_satisfied();
+    }
+
+    #[test]
+    fn collatz_from_one() {
+        run(&[1], 1).assert_satisfied();
+    }
+
+    #[test]
+    fn gate_degree() {
+        assert_eq!(
+            max_gate_degree(|meta| {
+                MyCircuit::<Fp, RANGE>::configure(meta);
+            }),
+            3
+        );
+    }
+
+    #[test]
+    fn collatz_rejects_invalid_step() {
+        // 16 -> 4 skips the halving to 8.
+        let prover = run(&[6, 3, 10, 5, 16, 4, 2, 1], 6);
+        assert_constraint_fails(&prover, names::STEP);
+    }
+
+    #[test]
+    fn collatz_rejects_unfinished_trajectory() {
+        let prover = run(&[6, 3, 10, 5, 16], 6);
+        assert_constraint_fails(&prover, names::LAST);
+    }
+
+    #[test]
+    fn collatz_rejects_wrong_start() {
+        let trajectory = reference(6).unwrap();
+        assert!(run(&trajectory, 7).verify().is_err());
+    }
+
+    /// Claims 3 is even by using its field half `3 / 2`, which satisfies both gates
+    /// but is far outside the range table.
+    struct FakeParityCircuit;
+
+    impl Circuit<Fp> for FakeParityCircuit {
+        type Config = CollatzConfig<Fp, RANGE>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MyCircuit::<Fp, RANGE>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.table.load(&mut layouter)?;
+            let [col_n, col_p, col_h] = config.advice;
+            let n = Fp::from(3);
+            let h = n * Fp::from(2).invert().unwrap();
+
+            layouter.assign_region(
+                || "fake parity",
+                |mut region| {
+                    config.selector_step.enable(&mut region, 0)?;
+                    load_instances(&mut region, config.instance, &[(col_n, 0)])?;
+                    region.assign_advice(|| "p", col_p, 0, || Value::known(Fp::ZERO))?;
+                    region.assign_advice(|| "h", col_h, 0, || Value::known(h))?;
+                    region.assign_advice(|| "n", col_n, 1, || Value::known(h))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn collatz_rejects_fake_parity() {
+        let prover = MockProver::run(9, &FakeParityCircuit, vec![vec![Fp::from(3)]]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, halo2_proofs::dev::VerifyFailure::Lookup { .. })));
+    }
+}
@@ -0,0 +1,206 @@
+use group::{prime::PrimeCurveAffine, Curve};
+use halo2_gadgets::ecc::Point;
+use halo2_proofs::{
+    arithmetic::CurveExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    pasta::{pallas, Fp},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::{
+    curve::{self, base_to_scalar, CurveChip, CurveConfig, Ecc},
+    names,
+    registry::ExampleCircuit,
+};
+
+/// Domain separator for hashing to the second Pedersen generator.
+const PERSONALIZATION: &str = "halo2examples:pedersen";
+
+/// The Pedersen generators `(G, H)`: the Pallas generator, and a point hashed to
+/// the curve so that nobody knows its discrete log relative to `G`.
+pub fn generators() -> (pallas::Affine, pallas::Affine) {
+    let h = pallas::Point::hash_to_curve(PERSONALIZATION)(b"H").to_affine();
+    (pallas::Affine::generator(), h)
+}
+
+/// Plain-Rust reference: the commitment `v*G + r*H`, with `v` and `r` read as
+/// scalars.
+pub fn reference(v: Fp, r: Fp) -> pallas::Affine {
+    let (g, h) = generators();
+    (g * base_to_scalar(v) + h * base_to_scalar(r)).to_affine()
+}
+
+/// Proves that a public point `C` opens to a private value `v` with a private
+/// blinding factor `r`:
+///
+/// ```text
+/// C == v*G + r*H
+/// ```
+///
+/// The scalar multiplications and the addition come from the `halo2_gadgets`
+/// ECC chip. `v` and `r` are base field elements used as scalars, which is sound
+/// because the Pallas base field is smaller than its scalar field. `G` and `H`
+/// are witnessed and then constrained to the constants from [`generators`].
+/// `C`'s coordinates are public, `x` then `y`.
+#[derive(Debug, Clone)]
+struct PedersenConfig {
+    pub curve: CurveConfig,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct PedersenChip {
+    config: PedersenConfig,
+}
+
+impl PedersenChip {
+    pub fn construct(config: PedersenConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<Fp>,
+        advice: [Column<Advice>; 10],
+        instance: Column<Instance>,
+    ) -> PedersenConfig {
+        meta.enable_equality(instance);
+
+        PedersenConfig {
+            curve: CurveChip::configure(meta, advice),
+            instance,
+        }
+    }
+
+    fn curve_chip(&self) -> CurveChip {
+        CurveChip::construct(self.config.curve.clone())
+    }
+
+    /// Computes `v*G + r*H` from private `v` and `r`.
+    pub fn assign_commitment(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        v: Value<Fp>,
+        r: Value<Fp>,
+    ) -> Result<Point<pallas::Affine, Ecc>, Error> {
+        let chip = self.curve_chip();
+        let (g, h) = generators();
+        let g = chip.load_constant(layouter.namespace(|| "G"), g)?;
+        let h = chip.load_constant(layouter.namespace(|| "H"), h)?;
+
+        let v = chip.load_private(layouter.namespace(|| "v"), v)?;
+        let r = chip.load_private(layouter.namespace(|| "r"), r)?;
+        let v = chip.scalar(layouter.namespace(|| "v scalar"), &v)?;
+        let r = chip.scalar(layouter.namespace(|| "r scalar"), &r)?;
+
+        let (vg, _) = g.mul(layouter.namespace(|| "v*G"), v)?;
+        let (rh, _) = h.mul(layouter.namespace(|| "r*H"), r)?;
+        vg.add(layouter.namespace(|| "v*G + r*H"), &rh)
+    }
+
+    /// Constrains the coordinates of `point` to instance rows `row` and `row + 1`.
+    pub fn expose_public(
+        &self,
+        layouter: impl Layouter<Fp>,
+        point: &Point<pallas::Affine, Ecc>,
+        row: usize,
+    ) -> Result<(), Error> {
+        self.curve_chip()
+            .expose_point(layouter, point, self.config.instance, row)
+    }
+}
+
+/// The public inputs for a commitment: its affine coordinates.
+pub fn to_instance(commitment: pallas::Affine) -> Vec<Fp> {
+    curve::coordinates(commitment).to_vec()
+}
+
+#[derive(Default)]
+struct MyCircuit {
+    v: Value<Fp>,
+    r: Value<Fp>,
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = PedersenConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 10].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        PedersenChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = PedersenChip::construct(config);
+        chip.curve_chip().load(&mut layouter)?;
+
+        let commitment =
+            chip.assign_commitment(layouter.namespace(|| "commitment"), self.v, self.r)?;
+
+        chip.expose_public(layouter.namespace(|| names::OUT), &commitment, 0)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on the opening `v = 42`, `r = 7`.
+pub fn example_circuit() -> ExampleCircuit {
+    let (v, r) = (Fp::from(42), Fp::from(7));
+    let circuit = MyCircuit {
+        v: Value::known(v),
+        r: Value::known(r),
+    };
+    ExampleCircuit::new(11, circuit, vec![to_instance(reference(v, r))])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::Field;
+    use halo2_proofs::dev::MockProver;
+    use rand_core::OsRng;
+
+    fn run(v: Fp, r: Fp, commitment: pallas::Affine) -> MockProver<Fp> {
+        let k = 11;
+
+        let circuit = MyCircuit {
+            v: Value::known(v),
+            r: Value::known(r),
+        };
+        MockProver::run(k, &circuit, vec![to_instance(commitment)]).unwrap()
+    }
+
+    #[test]
+    fn commitment_opens() {
+        let (v, r) = (Fp::from(42), Fp::random(OsRng));
+        run(v, r, reference(v, r)).assert_satisfied();
+    }
+
+    #[test]
+    fn commitment_rejects_mismatch() {
+        let (v, r) = (Fp::from(42), Fp::random(OsRng));
+        let commitment = reference(v, r);
+
+        // Another value, another blinding factor, or another point entirely.
+        assert!(run(Fp::from(43), r, commitment).verify().is_err());
+        assert!(run(v, r + Fp::ONE, commitment).verify().is_err());
+        let (g, _) = generators();
+        assert!(run(v, r, g).verify().is_err());
+    }
+
+    #[test]
+    fn commitment_generators() {
+        let (g, h) = generators();
+        assert_ne!(g, h);
+        assert_eq!(reference(Fp::ONE, Fp::ZERO), g);
+        assert_eq!(reference(Fp::ZERO, Fp::ONE), h);
+    }
+}
@@ -0,0 +1,233 @@
+use halo2_proofs::{arithmetic::Field, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+use crate::names;
+
+/// Which operation an [`AddSubChip`] row performs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+}
+
+impl Op {
+    /// The `sign` the row is assigned with: `+1` for [`Op::Add`], `-1` for
+    /// [`Op::Sub`].
+    pub fn sign<F: Field>(self) -> F {
+        match self {
+            Op::Add => F::ONE,
+            Op::Sub => -F::ONE,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AddSubConfig {
+    pub advice: [Column<Advice>; 3],
+    pub sign: Column<Fixed>,
+    pub selector: Selector,
+}
+
+/// The Fibonacci add gate with a sign: `s * (a + sign*b - c) == 0`.
+///
+/// `sign` lives in a fixed column, so the operation of each row is part of the
+/// circuit rather than the witness. A second gate, `s * (sign - 1) * (sign + 1)`,
+/// keeps it to `+1` or `-1`. Both gates have degree 3.
+#[derive(Debug, Clone)]
+pub struct AddSubChip<F: Field> {
+    config: AddSubConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> AddSubChip<F> {
+    pub fn construct(config: AddSubConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        sign: Column<Fixed>,
+    ) -> AddSubConfig {
+        let [col_a, col_b, col_c] = advice;
+        let selector = meta.selector();
+        meta.enable_equality(col_a);
+        meta.enable_equality(col_b);
+        meta.enable_equality(col_c);
+
+        meta.create_gate(names::ADD_SUB, |meta| {
+            //
+            // col_a | col_b | col_c | sign | selector
+            //   a      b        c     sign      s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            let sign = meta.query_fixed(sign);
+            vec![s * (a + sign * b - c)]
+        });
+
+        meta.create_gate(names::SIGN, |meta| {
+            let s = meta.query_selector(selector);
+            let sign = meta.query_fixed(sign);
+            let one = Expression::Constant(F::ONE);
+            vec![s * (sign.clone() - one.clone()) * (sign + one)]
+        });
+
+        AddSubConfig {
+            advice,
+            sign,
+            selector,
+        }
+    }
+
+    /// Assigns `a op b` in a single row and returns the cell holding the result.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: Op,
+        a: Value<F>,
+        b: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_a, col_b, col_c] = self.config.advice;
+        layouter.assign_region(
+            || names::ADD_SUB,
+            |mut region| {
+                let sign = op.sign::<F>();
+                self.config.selector.enable(&mut region, 0)?;
+                region.assign_fixed(|| "sign", self.config.sign, 0, || Value::known(sign))?;
+
+                region.assign_advice(|| "a", col_a, 0, || a)?;
+                region.assign_advice(|| "b", col_b, 0, || b)?;
+                let c = a + b.map(|b| sign * b);
+                region.assign_advice(|| "c", col_c, 0, || c)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    /// Computes `a op b` and exposes the result.
+    struct AddSubCircuit {
+        op: Op,
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for AddSubCircuit {
+        type Config = (AddSubConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                op: self.op,
+                a: Value::unknown(),
+                b: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            let sign = meta.fixed_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (AddSubChip::configure(meta, advice, sign), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = AddSubChip::construct(config);
+            let c = chip.assign(layouter.namespace(|| "c"), self.op, self.a, self.b)?;
+            layouter.constrain_instance(c.cell(), instance, 0)
+        }
+    }
+
+    fn run(op: Op, a: u64, b: u64, c: Fp) -> MockProver<Fp> {
+        let circuit = AddSubCircuit {
+            op,
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        };
+        MockProver::run(4, &circuit, vec![vec![c]]).unwrap()
+    }
+
+    #[test]
+    fn add_sub_adds() {
+        run(Op::Add, 5, 3, Fp::from(8)).assert_satisfied();
+        assert!(run(Op::Add, 5, 3, Fp::from(2)).verify().is_err());
+    }
+
+    #[test]
+    fn add_sub_subtracts() {
+        run(Op::Sub, 5, 3, Fp::from(2)).assert_satisfied();
+        run(Op::Sub, 3, 5, -Fp::from(2)).assert_satisfied();
+        assert!(run(Op::Sub, 5, 3, Fp::from(8)).verify().is_err());
+    }
+
+    /// Assigns `2 + 2*3 = 8` by hand, with a sign of 2.
+    struct BadSignCircuit;
+
+    impl Circuit<Fp> for BadSignCircuit {
+        type Config = AddSubConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            AddSubCircuit::configure(meta).0
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "bad sign",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    let value = |v: u64| Value::known(Fp::from(v));
+                    region.assign_fixed(|| "sign", config.sign, 0, || value(2))?;
+                    region.assign_advice(|| "a", config.advice[0], 0, || value(2))?;
+                    region.assign_advice(|| "b", config.advice[1], 0, || value(3))?;
+                    region.assign_advice(|| "c", config.advice[2], 0, || value(8))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn add_sub_rejects_other_signs() {
+        let prover = MockProver::run(4, &BadSignCircuit, vec![vec![]]).unwrap();
+        assert_constraint_fails(&prover, names::SIGN);
+    }
+
+    #[test]
+    fn add_sub_gate_degree() {
+        assert_eq!(
+            max_gate_degree(|meta| {
+                AddSubCircuit::configure(meta);
+            }),
+            3
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use halo2_proofs::{arithmetic::Field, circuit::*, plonk::*, poly::Rotation};
+use std::marker::PhantomData;
+
+use crate::names;
+
+/// An advice column whose cells are constrained to be 0 or 1.
+#[derive(Debug, Clone)]
+pub struct BooleanConfig {
+    pub advice: Column<Advice>,
+    pub selector: Selector,
+}
+
+/// Witnesses booleans, each in its own single-row region, with the gate
+/// `b * (1 - b) == 0`. The returned cells can be copied wherever a bit is needed.
+#[derive(Debug, Clone)]
+pub struct BooleanChip<F: Field> {
+    config: BooleanConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> BooleanChip<F> {
+    pub fn construct(config: BooleanConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: Column<Advice>) -> BooleanConfig {
+        let selector = meta.selector();
+        meta.enable_equality(advice);
+
+        meta.create_gate(names::BOOL, |meta| {
+            let s = meta.query_selector(selector);
+            let b = meta.query_advice(advice, Rotation::cur());
+            vec![s * b.clone() * (Expression::Constant(F::ONE) - b)]
+        });
+
+        BooleanConfig { advice, selector }
+    }
+
+    pub fn assign_bool(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || names::BOOL,
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "bit", self.config.advice, 0, || value)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    /// Assigns one boolean and copies it into a second, unconstrained column.
+    #[derive(Default)]
+    struct BoolCircuit(Value<Fp>);
+
+    impl Circuit<Fp> for BoolCircuit {
+        type Config = (BooleanConfig, Column<Advice>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = meta.advice_column();
+            let other = meta.advice_column();
+            meta.enable_equality(other);
+            (BooleanChip::configure(meta, advice), other)
+        }
+
+        fn synthesize(
+            &self,
+            (config, other): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = BooleanChip::construct(config);
+            let bit = chip.assign_bool(layouter.namespace(|| "bit"), self.0)?;
+            layouter.assign_region(
+                || "copy",
+                |mut region| bit.copy_advice(|| "copy", &mut region, other, 0),
+            )?;
+            Ok(())
+        }
+    }
+
+    fn run(value: u64) -> MockProver<Fp> {
+        MockProver::run(4, &BoolCircuit(Value::known(Fp::from(value))), vec![]).unwrap()
+    }
+
+    #[test]
+    fn boolean_accepts_bits() {
+        run(0).assert_satisfied();
+        run(1).assert_satisfied();
+    }
+
+    #[test]
+    fn boolean_rejects_two() {
+        assert_constraint_fails(&run(2), names::BOOL);
+    }
+}
@@ -0,0 +1,51 @@
+//! A per-thread log of witness values, for inspecting a circuit after
+//! `MockProver::run`.
+//!
+//! Chips call [`record_value!`] on the cells worth looking at. With the
+//! `debug-values` feature every known value is appended to a log belonging to
+//! the current thread, which [`take_recorded_values`] drains. `MockProver`
+//! synthesizes on the calling thread, so tests running in parallel keep
+//! separate logs.
+
+#[cfg(feature = "debug-values")]
+use std::{any::Any, cell::RefCell};
+
+#[cfg(feature = "debug-values")]
+use halo2_proofs::{arithmetic::Field, circuit::AssignedCell};
+
+#[cfg(feature = "debug-values")]
+thread_local! {
+    // Holds the circuit's field type, which the log itself does not know.
+    static VALUES: RefCell<Vec<Box<dyn Any>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Appends the value of an assigned cell to the current thread's log. Without
+/// the `debug-values` feature it expands to nothing, and its argument is not
+/// evaluated.
+macro_rules! record_value {
+    ($cell:expr) => {
+        #[cfg(feature = "debug-values")]
+        $crate::common::debug::record($cell);
+    };
+}
+
+pub(crate) use record_value;
+
+#[cfg(feature = "debug-values")]
+pub(crate) fn record<F: Field>(cell: &AssignedCell<F, F>) {
+    cell.value().map(|value| {
+        VALUES.with(|values| values.borrow_mut().push(Box::new(*value)));
+    });
+}
+
+/// Drains the values recorded on this thread so far, in assignment order.
+///
+/// Values recorded with another field type than `F` are dropped.
+#[cfg(feature = "debug-values")]
+pub fn take_recorded_values<F: Field>() -> Vec<F> {
+    VALUES
+        .with(|values| values.take())
+        .into_iter()
+        .filter_map(|value| value.downcast::<F>().ok().map(|value| *value))
+        .collect()
+}
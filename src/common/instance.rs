@@ -0,0 +1,32 @@
+use halo2_proofs::{arithmetic::Field, circuit::*, plonk::*};
+
+/// Copies the first `targets.len()` public inputs of `instance` into the given
+/// `(column, offset)` advice positions of `region`, in order.
+///
+/// Fails with [`Error::NotEnoughRowsAvailable`] if the instance rows do not fit in
+/// the circuit. A short public input cannot be caught here: both provers zero-pad
+/// instance columns, so synthesis reads the missing values as zeroes. Circuits
+/// using this helper therefore read their public inputs through a parser that
+/// calls [`check_instance_len`], such as `FibonacciInstances::from_columns`.
+pub fn load_instances<F: Field>(
+    region: &mut Region<'_, F>,
+    instance: Column<Instance>,
+    targets: &[(Column<Advice>, usize)],
+) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    targets
+        .iter()
+        .enumerate()
+        .map(|(row, &(advice, offset))| {
+            region.assign_advice_from_instance(|| "instance", instance, row, advice, offset)
+        })
+        .collect()
+}
+
+/// Checks that a public input vector holds at least the `count` values a circuit
+/// reads from it, returning [`Error::InvalidInstances`] otherwise.
+pub fn check_instance_len<F>(instance: &[F], count: usize) -> Result<(), Error> {
+    if instance.len() < count {
+        return Err(Error::InvalidInstances);
+    }
+    Ok(())
+}
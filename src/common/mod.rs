@@ -0,0 +1,32 @@
+//! Helpers shared by several examples.
+
+use ff::PrimeField;
+
+mod add_sub;
+mod boolean;
+pub(crate) mod debug;
+mod instance;
+mod rows;
+mod sorting;
+mod trace;
+
+pub use add_sub::{AddSubChip, AddSubConfig, Op};
+pub use boolean::{BooleanChip, BooleanConfig};
+pub(crate) use debug::record_value;
+#[cfg(feature = "debug-values")]
+pub use debug::take_recorded_values;
+pub use instance::{check_instance_len, load_instances};
+pub use rows::{check_rows, min_k_for_circuit, usable_rows, MAX_K};
+pub use sorting::{sorting_switches, SortingNetworkChip, SortingNetworkConfig};
+pub(crate) use trace::trace_region;
+
+/// Reads a field element that is known to be smaller than `2^64` back as a `u64`.
+///
+/// Only the low 64 bits of the little-endian representation are kept, so this is
+/// meant for witness generation on small values, never for constraints.
+pub fn to_u64<F: PrimeField>(value: &F) -> u64 {
+    let repr = value.to_repr();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&repr.as_ref()[..8]);
+    u64::from_le_bytes(bytes)
+}
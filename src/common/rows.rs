@@ -0,0 +1,105 @@
+use ff::PrimeField;
+use halo2_proofs::{arithmetic::Field, dev::MockProver, plonk::*};
+
+/// Rows of a `2^k`-row circuit that regions can be assigned to: all but the
+/// blinding rows `C` asks for and the one row the prover reserves above them.
+pub fn usable_rows<F: Field, C: Circuit<F>>(k: u32) -> usize {
+    let mut meta = ConstraintSystem::default();
+    C::configure(&mut meta);
+    (1usize << k).saturating_sub(meta.blinding_factors() + 1)
+}
+
+/// Checks up front that `required` rows of `C` fit at size `2^k`, failing with
+/// the same [`Error::NotEnoughRowsAvailable`] synthesis would run into.
+///
+/// Synthesis never sees `k`, so it can only fail once a region runs past the last
+/// usable row. Call this before generating keys to fail without assigning
+/// anything.
+pub fn check_rows<F: Field, C: Circuit<F>>(k: u32, required: usize) -> Result<(), Error> {
+    if required > usable_rows::<F, C>(k) {
+        return Err(Error::NotEnoughRowsAvailable { current_k: k });
+    }
+    Ok(())
+}
+
+/// Largest size [`min_k_for_circuit`] tries before giving up.
+pub const MAX_K: u32 = 20;
+
+/// The smallest `k` at which `circuit` synthesizes and verifies with `MockProver`
+/// against `instances`.
+///
+/// Starts from the fewest rows the constraint system itself needs and moves up one
+/// `k` at a time, so every size below the answer is tried once; each try costs at
+/// most half the next one, so the whole scan is within twice the final run. Only
+/// running out of rows moves on to a larger `k`. Returns `None` if any other
+/// synthesis error comes up, if the circuit does not verify at the first size it
+/// fits in, or if it needs more than `2^MAX_K` rows.
+pub fn min_k_for_circuit<F, C>(circuit: &C, instances: Vec<Vec<F>>) -> Option<u32>
+where
+    F: PrimeField + Ord,
+    C: Circuit<F>,
+{
+    let mut meta = ConstraintSystem::<F>::default();
+    C::configure(&mut meta);
+    let start = meta.minimum_rows().next_power_of_two().trailing_zeros();
+    for k in start..=MAX_K {
+        match MockProver::run(k, circuit, instances.clone()) {
+            Ok(prover) => return prover.verify().ok().map(|()| k),
+            Err(Error::NotEnoughRowsAvailable { .. }) | Err(Error::InstanceTooLarge) => continue,
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pasta::Fp;
+
+    use super::*;
+    use crate::fibonacci::{example1, example2, FibIndex, FibonacciInstances};
+
+    fn instances(n: usize) -> Vec<Vec<Fp>> {
+        FibonacciInstances::from_seeds(1, 1, FibIndex(n))
+            .unwrap()
+            .to_columns()
+    }
+
+    /// `circuit` verifies at `min_k` but not one size below it.
+    fn assert_min_k<C: Circuit<Fp>>(circuit: &C, instances: Vec<Vec<Fp>>) {
+        let k = min_k_for_circuit(circuit, instances.clone()).unwrap();
+        let verifies = |k| {
+            MockProver::run(k, circuit, instances.clone())
+                .map(|prover| prover.verify().is_ok())
+                .unwrap_or(false)
+        };
+        assert!(verifies(k));
+        assert!(!verifies(k - 1));
+    }
+
+    #[test]
+    fn min_k_is_the_first_size_that_verifies() {
+        for n in [2, 9, 20, 40] {
+            assert_min_k(&example1::MyCircuit::<Fp>::new(n), instances(n));
+            assert_min_k(&example2::MyCircuit::<Fp>::new(n), instances(n));
+        }
+    }
+
+    #[test]
+    fn min_k_counts_instance_rows() {
+        // One region fits in 2^3 rows, but the three public inputs do not.
+        let circuit = example1::MyCircuit::<Fp>::new(2);
+        assert!(check_rows::<Fp, example1::MyCircuit<Fp>>(3, circuit.rows()).is_ok());
+        assert_eq!(min_k_for_circuit(&circuit, instances(2)), Some(4));
+    }
+
+    #[test]
+    fn min_k_rejects_a_circuit_that_never_verifies() {
+        let mut wrong = instances(9);
+        wrong[0][2] += Fp::one();
+        assert_eq!(
+            min_k_for_circuit(&example1::MyCircuit::<Fp>::new(9), wrong),
+            None
+        );
+    }
+}
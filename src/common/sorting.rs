@@ -0,0 +1,228 @@
+//! An odd-even transposition network: `n` rounds that each compare
+//! neighbouring rows, alternately starting from row 0 and row 1, and swap them
+//! if the prover's switch bit says so.
+//!
+//! Every output of the network is a rearrangement of its input by
+//! construction, and `n` rounds are enough to sort any `n` rows, so every
+//! permutation is reachable. halo2_proofs 0.3 has no shuffle argument and no
+//! verifier challenges, and copy constraints are fixed at keygen, so this is
+//! how the examples let a prover pick a permutation at proving time.
+
+use crate::prelude::*;
+
+/// The `W` fields of a row before a round, the same after it, and the switch
+/// bit of the pair starting at that row.
+#[derive(Debug, Clone)]
+pub struct SortingNetworkConfig<const W: usize> {
+    pub input: [Column<Advice>; W],
+    pub output: [Column<Advice>; W],
+    pub switch: Column<Advice>,
+    pub q_swap: Selector,
+    pub q_pass: Selector,
+}
+
+/// Lays out the network one region per round, with each round's inputs copied
+/// from the previous round's outputs:
+///
+/// ```text
+/// input | output | switch | q_swap | q_pass
+/// x_0   | y_0    | s      |   1    |   0
+/// x_1   | y_1    |        |   0    |   0
+/// x_2   | y_2    |        |   0    |   1
+/// ```
+///
+/// A swap at row `i`, with switch bit `s`, outputs
+///
+/// ```text
+/// out_i     = in_i + s * (in_{i+1} - in_i)
+/// out_{i+1} = in_{i+1} + s * (in_i - in_{i+1})
+/// ```
+///
+/// for every field of the row, and rows without a neighbour pass through.
+#[derive(Debug, Clone)]
+pub struct SortingNetworkChip<F: Field, const W: usize> {
+    config: SortingNetworkConfig<W>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField, const W: usize> SortingNetworkChip<F, W> {
+    pub fn construct(config: SortingNetworkConfig<W>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        input: [Column<Advice>; W],
+        output: [Column<Advice>; W],
+        switch: Column<Advice>,
+    ) -> SortingNetworkConfig<W> {
+        let q_swap = meta.selector();
+        let q_pass = meta.selector();
+        for column in input.into_iter().chain(output) {
+            meta.enable_equality(column);
+        }
+        let one = || Expression::Constant(F::ONE);
+
+        meta.create_gate("network swap", |meta| {
+            let q = meta.query_selector(q_swap);
+            let s = meta.query_advice(switch, Rotation::cur());
+            let mut constraints = vec![q.clone() * s.clone() * (one() - s.clone())];
+            for (input, output) in input.into_iter().zip(output) {
+                let [in_0, in_1] = [0, 1].map(|at| meta.query_advice(input, Rotation(at)));
+                let [out_0, out_1] = [0, 1].map(|at| meta.query_advice(output, Rotation(at)));
+                constraints.push(
+                    q.clone() * (in_0.clone() + s.clone() * (in_1.clone() - in_0.clone()) - out_0),
+                );
+                constraints.push(q.clone() * (in_1.clone() + s.clone() * (in_0 - in_1) - out_1));
+            }
+            constraints
+        });
+
+        meta.create_gate("network pass", |meta| {
+            let q = meta.query_selector(q_pass);
+            input
+                .into_iter()
+                .zip(output)
+                .map(|(input, output)| {
+                    let input = meta.query_advice(input, Rotation::cur());
+                    let output = meta.query_advice(output, Rotation::cur());
+                    q.clone() * (input - output)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        SortingNetworkConfig {
+            input,
+            output,
+            switch,
+            q_swap,
+            q_pass,
+        }
+    }
+
+    /// Runs `rows` through the network with the given switch bits, round by
+    /// round, and returns the output rows.
+    pub fn permute(
+        &self,
+        mut layouter: impl Layouter<F>,
+        rows: &[[AssignedCell<F, F>; W]],
+        switches: &Value<Vec<Vec<bool>>>,
+    ) -> Result<Vec<[AssignedCell<F, F>; W]>, Error> {
+        let mut rows = rows.to_vec();
+        for round in 0..rows.len() {
+            let bits = switches.as_ref().map(|switches| switches[round].clone());
+            rows = layouter.assign_region(
+                || format!("network round {}", round),
+                |mut region| self.assign_round(&mut region, round, &rows, &bits),
+            )?;
+        }
+        Ok(rows)
+    }
+
+    /// Copies the rows into the inputs of one round and returns its outputs.
+    fn assign_round(
+        &self,
+        region: &mut Region<'_, F>,
+        round: usize,
+        rows: &[[AssignedCell<F, F>; W]],
+        bits: &Value<Vec<bool>>,
+    ) -> Result<Vec<[AssignedCell<F, F>; W]>, Error> {
+        let config = &self.config;
+        let n = rows.len();
+
+        for (i, row) in rows.iter().enumerate() {
+            for (cell, &column) in row.iter().zip(&config.input) {
+                cell.copy_advice(|| "in", region, column, i)?;
+            }
+        }
+
+        let mut outputs: Vec<[Value<F>; W]> = rows
+            .iter()
+            .map(|row| row.clone().map(|cell| cell.value().copied()))
+            .collect();
+        let mut paired = vec![false; n];
+        for (pair, i) in pairs(round, n).enumerate() {
+            config.q_swap.enable(region, i)?;
+            let bit = bits.as_ref().map(|bits| bits[pair]);
+            region.assign_advice(
+                || "switch",
+                config.switch,
+                i,
+                || bit.map(|bit| F::from(u64::from(bit))),
+            )?;
+            let (a, b) = (outputs[i], outputs[i + 1]);
+            for j in 0..W {
+                outputs[i][j] = bit
+                    .zip(a[j])
+                    .zip(b[j])
+                    .map(|((s, a), b)| if s { b } else { a });
+                outputs[i + 1][j] = bit
+                    .zip(a[j])
+                    .zip(b[j])
+                    .map(|((s, a), b)| if s { a } else { b });
+            }
+            paired[i] = true;
+            paired[i + 1] = true;
+        }
+        for (i, _) in paired.iter().enumerate().filter(|(_, &paired)| !paired) {
+            config.q_pass.enable(region, i)?;
+        }
+
+        outputs
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let cells = (0..W)
+                    .map(|j| region.assign_advice(|| "out", config.output[j], i, || row[j]))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(cells.try_into().unwrap())
+            })
+            .collect()
+    }
+}
+
+/// The first rows of the neighbouring pairs a round compares: even rows in
+/// even rounds, odd rows in odd ones.
+pub fn pairs(round: usize, n: usize) -> impl Iterator<Item = usize> {
+    (round % 2..n.saturating_sub(1)).step_by(2)
+}
+
+/// The switch bits, round by round, with which the network sorts `keys`.
+pub fn sorting_switches<K: Ord + Clone>(keys: &[K]) -> Vec<Vec<bool>> {
+    let mut keys = keys.to_vec();
+    (0..keys.len())
+        .map(|round| {
+            pairs(round, keys.len())
+                .map(|i| {
+                    let swap = keys[i] > keys[i + 1];
+                    if swap {
+                        keys.swap(i, i + 1);
+                    }
+                    swap
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorting_switches_sort_keys() {
+        let keys = [3, 0, 4, 1, 2];
+        let mut sorted = keys.to_vec();
+        for (round, bits) in sorting_switches(&keys).into_iter().enumerate() {
+            for (i, swap) in pairs(round, keys.len()).zip(bits) {
+                if swap {
+                    sorted.swap(i, i + 1);
+                }
+            }
+        }
+        assert_eq!(sorted, [0, 1, 2, 3, 4]);
+    }
+}
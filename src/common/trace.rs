@@ -0,0 +1,11 @@
+/// Emits a `tracing` debug event naming an assigned region and the number of
+/// rows it used. Without the `trace` feature it expands to nothing, and its
+/// arguments are not evaluated.
+macro_rules! trace_region {
+    ($region:expr, $rows:expr) => {
+        #[cfg(feature = "trace")]
+        tracing::debug!(region = $region, rows = $rows, "assigned region");
+    };
+}
+
+pub(crate) use trace_region;
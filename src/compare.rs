@@ -0,0 +1,425 @@
+//! Comparisons of two values below `RANGE`, each returning a boolean cell.
+//!
+//! `a < b + offset` holds exactly when `diff = a - b - offset + out * RANGE` is
+//! in `0..RANGE` for `out = 1`: with both inputs below `RANGE`, the other choice
+//! of `out` pushes `diff` out of the table. [`LtChip`] uses `offset = 0`,
+//! [`LeqChip`] uses `offset = 1`, and [`GeqChip`] is `LeqChip` with its inputs
+//! swapped. The inputs and `diff` are looked up in a shared
+//! [`RangeTableConfig`], which the circuit loads once.
+//!
+//! The offset is part of the config's type, through the [`Lt`] and [`Leq`]
+//! markers, so a chip cannot be built from a config for another comparison.
+
+use std::fmt::Debug;
+
+use halo2_proofs::pasta::Fp;
+
+use crate::{
+    common::to_u64,
+    prelude::*,
+    range_check::table::RangeTableConfig,
+    registry::{Example, ExampleCircuit},
+};
+
+/// The comparison a [`CompareConfig`] checks: `a < b + OFFSET`.
+pub trait Comparison: Debug + Clone {
+    /// Name of the gate.
+    const NAME: &'static str;
+    const OFFSET: u64;
+}
+
+/// `a < b`.
+#[derive(Debug, Clone)]
+pub struct Lt;
+
+impl Comparison for Lt {
+    const NAME: &'static str = "lt";
+    const OFFSET: u64 = 0;
+}
+
+/// `a <= b`, that is `a < b + 1`.
+#[derive(Debug, Clone)]
+pub struct Leq;
+
+impl Comparison for Leq {
+    const NAME: &'static str = "leq";
+    const OFFSET: u64 = 1;
+}
+
+/// Columns `[a, b, out, diff]` of one comparison `C`, and its selector.
+#[derive(Debug, Clone)]
+pub struct CompareConfig<F: PrimeField, const RANGE: usize, C: Comparison> {
+    pub advice: [Column<Advice>; 4],
+    pub selector: Selector,
+    pub table: RangeTableConfig<F, RANGE>,
+    _marker: PhantomData<C>,
+}
+
+impl<F: PrimeField, const RANGE: usize, C: Comparison> CompareConfig<F, RANGE, C> {
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        table: &RangeTableConfig<F, RANGE>,
+    ) -> Self {
+        let [col_a, col_b, col_out, col_diff] = advice;
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        for column in [col_a, col_b, col_out] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate(C::NAME, |meta| {
+            //
+            // col_a | col_b | col_out | col_diff                      | selector
+            //   a      b       out      a - b - offset + out * RANGE      s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let out = meta.query_advice(col_out, Rotation::cur());
+            let diff = meta.query_advice(col_diff, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+            let offset = Expression::Constant(F::from(C::OFFSET));
+            let range = Expression::Constant(F::from(RANGE as u64));
+
+            vec![
+                s.clone() * out.clone() * (one - out.clone()),
+                s * (a - b - offset + out * range - diff),
+            ]
+        });
+
+        // Inactive rows look up 0, which is always in the table.
+        for column in [col_a, col_b, col_diff] {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(selector);
+                let value = meta.query_advice(column, Rotation::cur());
+                vec![(s * value, table.value)]
+            });
+        }
+
+        Self {
+            advice,
+            selector,
+            table: table.clone(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Copies `a` and `b` into a new row and returns `a < b + offset` as 0 or 1.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_a, col_b, col_out, col_diff] = self.advice;
+        let offset = F::from(C::OFFSET);
+        let range = F::from(RANGE as u64);
+
+        layouter.assign_region(
+            || "compare",
+            |mut region| {
+                self.selector.enable(&mut region, 0)?;
+
+                let a = a.copy_advice(|| "a", &mut region, col_a, 0)?;
+                let b = b.copy_advice(|| "b", &mut region, col_b, 0)?;
+                let values = a.value().copied().zip(b.value().copied());
+
+                let out =
+                    values.map(|(a, b)| F::from(u64::from(to_u64(&a) < to_u64(&b) + C::OFFSET)));
+                let diff = values
+                    .zip(out)
+                    .map(|((a, b), out)| a - b - offset + out * range);
+
+                region.assign_advice(|| "diff", col_diff, 0, || diff)?;
+                region.assign_advice(|| "out", col_out, 0, || out)
+            },
+        )
+    }
+}
+
+/// Returns `a < b`.
+#[derive(Debug, Clone)]
+pub struct LtChip<F: PrimeField, const RANGE: usize> {
+    config: CompareConfig<F, RANGE, Lt>,
+}
+
+impl<F: PrimeField, const RANGE: usize> LtChip<F, RANGE> {
+    pub fn construct(config: CompareConfig<F, RANGE, Lt>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        table: &RangeTableConfig<F, RANGE>,
+    ) -> CompareConfig<F, RANGE, Lt> {
+        CompareConfig::configure(meta, advice, table)
+    }
+
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.assign(layouter, a, b)
+    }
+}
+
+/// Returns `a <= b`.
+#[derive(Debug, Clone)]
+pub struct LeqChip<F: PrimeField, const RANGE: usize> {
+    config: CompareConfig<F, RANGE, Leq>,
+}
+
+impl<F: PrimeField, const RANGE: usize> LeqChip<F, RANGE> {
+    pub fn construct(config: CompareConfig<F, RANGE, Leq>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        table: &RangeTableConfig<F, RANGE>,
+    ) -> CompareConfig<F, RANGE, Leq> {
+        CompareConfig::configure(meta, advice, table)
+    }
+
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.config.assign(layouter, a, b)
+    }
+}
+
+/// Returns `a >= b`, as `b <= a`. Takes a [`LeqChip`] config, so one `LeqChip`
+/// configuration can serve both.
+#[derive(Debug, Clone)]
+pub struct GeqChip<F: PrimeField, const RANGE: usize> {
+    leq: LeqChip<F, RANGE>,
+}
+
+impl<F: PrimeField, const RANGE: usize> GeqChip<F, RANGE> {
+    pub fn construct(config: CompareConfig<F, RANGE, Leq>) -> Self {
+        Self {
+            leq: LeqChip::construct(config),
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        table: &RangeTableConfig<F, RANGE>,
+    ) -> CompareConfig<F, RANGE, Leq> {
+        LeqChip::configure(meta, advice, table)
+    }
+
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.leq.assign(layouter, b, a)
+    }
+}
+
+/// Inputs of the example are below this.
+const RANGE: usize = 16;
+
+#[derive(Debug, Clone)]
+struct MyConfig {
+    input: Column<Advice>,
+    instance: Column<Instance>,
+    lt: CompareConfig<Fp, RANGE, Lt>,
+    leq: CompareConfig<Fp, RANGE, Leq>,
+    table: RangeTableConfig<Fp, RANGE>,
+}
+
+/// Compares each pair with all three chips and exposes `[a < b, a <= b,
+/// a >= b]` per pair.
+#[derive(Default)]
+struct MyCircuit {
+    pairs: Vec<(u64, u64)>,
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            pairs: self.pairs.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let input = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(input);
+        meta.enable_equality(instance);
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let table = RangeTableConfig::configure(meta);
+
+        MyConfig {
+            input,
+            instance,
+            lt: LtChip::configure(meta, advice, &table),
+            leq: LeqChip::configure(meta, advice, &table),
+            table,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let lt = LtChip::construct(config.lt.clone());
+        let leq = LeqChip::construct(config.leq.clone());
+        let geq = GeqChip::construct(config.leq.clone());
+
+        for (i, &(a, b)) in self.pairs.iter().enumerate() {
+            let (a, b) = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    let mut assign = |row, value| {
+                        region.assign_advice(
+                            || "input",
+                            config.input,
+                            row,
+                            || Value::known(Fp::from(value)),
+                        )
+                    };
+                    Ok((assign(0, a)?, assign(1, b)?))
+                },
+            )?;
+            let outs = [
+                lt.assign(layouter.namespace(|| "lt"), &a, &b)?,
+                leq.assign(layouter.namespace(|| "leq"), &a, &b)?,
+                geq.assign(layouter.namespace(|| "geq"), &a, &b)?,
+            ];
+            for (j, out) in outs.iter().enumerate() {
+                layouter.constrain_instance(out.cell(), config.instance, 3 * i + j)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The public inputs for `pairs`: `[a < b, a <= b, a >= b]` per pair.
+fn expected(pairs: &[(u64, u64)]) -> Vec<Fp> {
+    pairs
+        .iter()
+        .flat_map(|&(a, b)| [a < b, a <= b, a >= b].map(Fp::from))
+        .collect()
+}
+
+/// The circuit on pairs below, equal to and above each other.
+pub fn example_circuit() -> ExampleCircuit {
+    let pairs = vec![(3, 9), (9, 3), (5, 5), (0, 15)];
+    let outs = expected(&pairs);
+    ExampleCircuit::new(9, MyCircuit { pairs }, vec![outs])
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "compare",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+
+    fn run(pairs: &[(u64, u64)], outs: Vec<Fp>) -> MockProver<Fp> {
+        let k = 9;
+
+        let circuit = MyCircuit {
+            pairs: pairs.to_vec(),
+        };
+        MockProver::run(k, &circuit, vec![outs]).unwrap()
+    }
+
+    #[test]
+    fn compare_matches_rust() {
+        let edges = [0, 1, 7, 8, 14, 15];
+        let pairs: Vec<_> = edges
+            .iter()
+            .flat_map(|&a| edges.iter().map(move |&b| (a, b)))
+            .collect();
+        run(&pairs, expected(&pairs)).assert_satisfied();
+    }
+
+    #[test]
+    fn compare_rejects_wrong_answer() {
+        for pair in [(3, 9), (9, 3), (5, 5)] {
+            let mut outs = expected(&[pair]);
+            outs[0] = Fp::ONE - outs[0];
+            assert!(run(&[pair], outs).verify().is_err());
+        }
+    }
+
+    #[test]
+    fn compare_rejects_out_of_range_input() {
+        let pair = (RANGE as u64, 3);
+        let failures = run(&[pair], expected(&[pair])).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+
+    /// Claims `9 < 3` directly, bypassing the chip's witness generation.
+    struct ForgedCircuit;
+
+    impl Circuit<Fp> for ForgedCircuit {
+        type Config = (CompareConfig<Fp, RANGE, Lt>, RangeTableConfig<Fp, RANGE>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let table = RangeTableConfig::configure(meta);
+            (LtChip::configure(meta, advice, &table), table)
+        }
+
+        fn synthesize(
+            &self,
+            (config, table): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            table.load(&mut layouter)?;
+            layouter.assign_region(
+                || "forged",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    // diff = 9 - 3 + 16 = 22 satisfies the gate.
+                    for (column, value) in config.advice.into_iter().zip([9, 3, 1, 22]) {
+                        region.assign_advice(|| "", column, 0, || Value::known(Fp::from(value)))?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn compare_rejects_forged_result() {
+        let k = 9;
+
+        let failures = MockProver::run(k, &ForgedCircuit, vec![])
+            .unwrap()
+            .verify()
+            .unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(failures[0], VerifyFailure::Lookup { .. }));
+    }
+}
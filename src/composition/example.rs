@@ -0,0 +1,329 @@
+use crate::{
+    fibonacci::{fib_term, FibIndex},
+    names,
+    prelude::*,
+    registry::ExampleCircuit,
+};
+use halo2_proofs::pasta::Fp;
+
+/// Number of Fibonacci terms the circuit computes: `f(0)` to `f(9)`.
+const NUM_TERMS: usize = 10;
+
+/// Multiplies two cells: `a * b == c`.
+///
+/// Only the selector belongs to the chip; the advice columns are handed in, so
+/// the same columns can back other chips' gates on other rows.
+#[derive(Debug, Clone)]
+struct MulConfig {
+    pub advice: [Column<Advice>; 3],
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+struct MulChip<F: Field> {
+    config: MulConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MulChip<F> {
+    pub fn construct(config: MulConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> MulConfig {
+        let [col_a, col_b, col_c] = advice;
+        let selector = meta.selector();
+
+        meta.create_gate("mul", |meta| {
+            //
+            // col_a | col_b | col_c | selector
+            //   a      b        c       s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a * b - c)]
+        });
+
+        MulConfig { advice, selector }
+    }
+
+    /// Assigns `a`, `b` and their product, and returns all three cells.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Value<F>,
+        b: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let [col_a, col_b, col_c] = self.config.advice;
+        layouter.assign_region(
+            || "mul",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                let a = region.assign_advice(|| "a", col_a, 0, || a)?;
+                let b = region.assign_advice(|| "b", col_b, 0, || b)?;
+                let c = region.assign_advice(
+                    || "a * b",
+                    col_c,
+                    0,
+                    || a.value().copied() * b.value(),
+                )?;
+                Ok((a, b, c))
+            },
+        )
+    }
+}
+
+/// The Fibonacci step `a + b == c`, over the same kind of borrowed columns as
+/// [`MulConfig`]. The seeds are copied in from cells assigned elsewhere.
+#[derive(Debug, Clone)]
+struct FiboConfig {
+    pub advice: [Column<Advice>; 3],
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+struct FiboChip<F: Field> {
+    config: FiboConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> FiboChip<F> {
+    pub fn construct(config: FiboConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> FiboConfig {
+        let [col_a, col_b, col_c] = advice;
+        let selector = meta.selector();
+
+        meta.create_gate(names::ADD, |meta| {
+            //
+            // col_a | col_b | col_c | selector
+            //   a      b        c       s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a + b - c)]
+        });
+
+        FiboConfig { advice, selector }
+    }
+
+    /// Computes `num_terms` terms seeded with copies of `seed_a` and `seed_b`,
+    /// one row per term from `f(2)` on, and returns the last one.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        seed_a: &AssignedCell<F, F>,
+        seed_b: &AssignedCell<F, F>,
+        num_terms: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_a, col_b, col_c] = self.config.advice;
+        let mut prev_a = seed_a.clone();
+        let mut prev_b = seed_b.clone();
+
+        for _i in 2..num_terms {
+            let c_cell = layouter.assign_region(
+                || names::NEXT_ROW,
+                |mut region| {
+                    self.config.selector.enable(&mut region, 0)?;
+                    let a = prev_a.copy_advice(|| "a", &mut region, col_a, 0)?;
+                    let b = prev_b.copy_advice(|| "b", &mut region, col_b, 0)?;
+                    region.assign_advice(|| "c", col_c, 0, || a.value().copied() + b.value())
+                },
+            )?;
+            prev_a = prev_b;
+            prev_b = c_cell;
+        }
+
+        Ok(prev_b)
+    }
+}
+
+/// Both chips over one set of columns.
+#[derive(Debug, Clone)]
+struct ComposedConfig {
+    pub mul: MulConfig,
+    pub fibo: FiboConfig,
+    pub instance: Column<Instance>,
+}
+
+impl ComposedConfig {
+    /// Allocates the three shared advice columns once and configures both chips
+    /// on them. Each chip gets its own selector, and every region enables
+    /// exactly one, so a row is only ever checked by the gate that assigned it.
+    pub fn configure<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let instance = meta.instance_column();
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        Self {
+            mul: MulChip::configure(meta, advice),
+            fibo: FiboChip::configure(meta, advice),
+            instance,
+        }
+    }
+}
+
+/// [`fib_term`] for `f(n)` on the seeds `x` and `x * y`.
+pub fn reference(x: u64, y: u64, n: usize) -> Fp {
+    let x = Fp::from(x);
+    fib_term(x, x * Fp::from(y), FibIndex(n))
+}
+
+/// Multiplies private `x` and `y`, then runs the Fibonacci recurrence seeded with
+/// `x` and `x * y`, and exposes `f(9)`.
+#[derive(Default)]
+struct MyCircuit<F> {
+    x: Value<F>,
+    y: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = ComposedConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        ComposedConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let mul = MulChip::construct(config.mul);
+        let fibo = FiboChip::construct(config.fibo);
+
+        let (x, _, product) = mul.assign(layouter.namespace(|| "mul"), self.x, self.y)?;
+        let out = fibo.assign(layouter.namespace(|| "fibonacci"), &x, &product, NUM_TERMS)?;
+        layouter.constrain_instance(out.cell(), config.instance, 0)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on `x = 2`, `y = 3`.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit {
+        x: Value::known(Fp::from(2)),
+        y: Value::known(Fp::from(3)),
+    };
+    let out = reference(2, 3, NUM_TERMS - 1);
+    ExampleCircuit::new(4, circuit, vec![vec![out]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn circuit(x: u64, y: u64) -> MyCircuit<Fp> {
+        MyCircuit {
+            x: Value::known(Fp::from(x)),
+            y: Value::known(Fp::from(y)),
+        }
+    }
+
+    #[test]
+    fn composition_example() {
+        let k = 4;
+
+        // Seeds 2 and 6: 2, 6, 8, 14, 22, 36, 58, 94, 152, 246.
+        assert_eq!(reference(2, 3, NUM_TERMS - 1), Fp::from(246));
+        let prover = MockProver::run(k, &circuit(2, 3), vec![vec![Fp::from(246)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn composition_rejects_wrong_output() {
+        let k = 4;
+
+        let prover = MockProver::run(k, &circuit(2, 3), vec![vec![Fp::from(245)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn composition_shares_columns() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let config = MyCircuit::<Fp>::configure(&mut meta);
+        assert_eq!(config.mul.advice, config.fibo.advice);
+        assert_ne!(config.mul.selector, config.fibo.selector);
+    }
+
+    /// Assigns a wrong product in the `mul` row, then continues honestly.
+    struct BadMulCircuit;
+
+    impl Circuit<Fp> for BadMulCircuit {
+        type Config = ComposedConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            ComposedConfig::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let [col_a, col_b, col_c] = config.mul.advice;
+            let (x, product) = layouter.assign_region(
+                || "mul",
+                |mut region| {
+                    config.mul.selector.enable(&mut region, 0)?;
+                    let x = region.assign_advice(|| "a", col_a, 0, || Value::known(Fp::from(2)))?;
+                    region.assign_advice(|| "b", col_b, 0, || Value::known(Fp::from(3)))?;
+                    let c = region.assign_advice(|| "c", col_c, 0, || Value::known(Fp::from(7)))?;
+                    Ok((x, c))
+                },
+            )?;
+            let fibo = FiboChip::construct(config.fibo);
+            let out = fibo.assign(layouter.namespace(|| "fibonacci"), &x, &product, NUM_TERMS)?;
+            layouter.constrain_instance(out.cell(), config.instance, 0)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn composition_rejects_wrong_product() {
+        let k = 4;
+
+        // The output matches seeds 2 and 7, so only the mul gate can object.
+        let out = crate::fibonacci::fib_reference(2, 7, NUM_TERMS - 1).unwrap();
+        let prover = MockProver::run(k, &BadMulCircuit, vec![vec![Fp::from(out)]]).unwrap();
+        assert_constraint_fails(&prover, "mul");
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_composition() {
+        crate::plot::plot_png("composition-layout.png", 4, &circuit(2, 3)).unwrap();
+    }
+}
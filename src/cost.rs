@@ -0,0 +1,179 @@
+//! Cost of every example circuit, as measured by `halo2_proofs::dev::CircuitCost`.
+//!
+//! [`example_costs`] measures the circuit each registered example builds, at
+//! the `k` it runs at.
+
+use std::fmt::{self, Write};
+
+use halo2_proofs::{
+    circuit::Layouter,
+    dev::CircuitCost,
+    pasta::{vesta, Fp},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+/// The numbers [`CircuitCost`] reports for one circuit at size `2^k`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cost {
+    pub k: u32,
+    /// Rows the floor planner used, out of `2^k`.
+    pub rows: usize,
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub lookups: usize,
+    /// Maximum degree of the constraint system, including the permutation and
+    /// lookup arguments.
+    pub degree: usize,
+    /// Bytes of a proof of one instance of the circuit.
+    pub proof_size: usize,
+    /// Bytes each further instance adds to a proof of several.
+    pub marginal_proof_size: usize,
+}
+
+impl Cost {
+    /// Measures `circuit` at size `2^k`, or returns `None` if a count is
+    /// missing from [`CircuitCost`]'s `Debug` output. Panics if `k` is too
+    /// small for the circuit.
+    pub fn measure<C: Circuit<Fp>>(k: u32, circuit: &C) -> Option<Self> {
+        let cost = CircuitCost::<vesta::Point, _>::measure(k, &Opaque(circuit.without_witnesses()));
+        // halo2_proofs 0.3 keeps the counts private, so they are read back from
+        // the `Debug` output.
+        let debug = format!("{:?}", cost);
+        let field = |name: &str| {
+            let start = debug.find(&format!(" {}: ", name))? + name.len() + 3;
+            debug[start..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect::<String>()
+                .parse()
+                .ok()
+        };
+        Some(Self {
+            k,
+            rows: field("max_rows")?,
+            advice_columns: field("num_advice_columns")?,
+            fixed_columns: field("num_fixed_columns")?,
+            instance_columns: field("num_instance_columns")?,
+            lookups: field("lookups")?,
+            degree: field("max_deg")?,
+            proof_size: cost.proof_size(1).into(),
+            marginal_proof_size: cost.marginal_proof_size().into(),
+        })
+    }
+}
+
+/// Delegates to `C`, with a `Debug` impl whether or not `C` has one, so that
+/// `CircuitCost<_, Opaque<C>>` can be formatted. The layout, and so the cost,
+/// does not depend on witness values.
+struct Opaque<C>(C);
+
+impl<C> fmt::Debug for Opaque<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Opaque")
+    }
+}
+
+impl<C: Circuit<Fp>> Circuit<Fp> for Opaque<C> {
+    type Config = C::Config;
+    type FloorPlanner = C::FloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self(self.0.without_witnesses())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        C::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        self.0.synthesize(config, layouter)
+    }
+}
+
+/// Measures the circuit of every registered example and returns it with the
+/// example's name, or `None` if it could not be measured.
+pub fn example_costs() -> Vec<(String, Option<Cost>)> {
+    crate::registry::examples()
+        .map(|example| {
+            let built = (example.circuit)();
+            (example.name.to_string(), built.circuit.cost(built.k))
+        })
+        .collect()
+}
+
+/// Formats costs as a table, one circuit per line.
+pub fn format_table(costs: &[(String, Option<Cost>)]) -> String {
+    let width = costs
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+    let mut table = format!(
+        "{:<width$} {:>3} {:>6} {:>6} {:>5} {:>8} {:>7} {:>6} {:>6} {:>8}\n",
+        "example",
+        "k",
+        "rows",
+        "advice",
+        "fixed",
+        "instance",
+        "lookups",
+        "degree",
+        "proof",
+        "marginal",
+    );
+    for (name, cost) in costs {
+        match cost {
+            Some(c) => writeln!(
+                table,
+                "{:<width$} {:>3} {:>6} {:>6} {:>5} {:>8} {:>7} {:>6} {:>6} {:>8}",
+                name,
+                c.k,
+                c.rows,
+                c.advice_columns,
+                c.fixed_columns,
+                c.instance_columns,
+                c.lookups,
+                c.degree,
+                c.proof_size,
+                c.marginal_proof_size,
+            ),
+            None => writeln!(table, "{:<width$} (not measured)", name),
+        }
+        .unwrap();
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fibonacci::{example1, example2};
+
+    #[test]
+    fn cost_of_fibonacci_layouts() {
+        let one = Cost::measure(4, &example1::MyCircuit::<Fp>::default()).unwrap();
+        let two = Cost::measure(4, &example2::MyCircuit::<Fp>::default()).unwrap();
+
+        // Three columns and a row per step, against one column and a row per term.
+        assert_eq!((one.advice_columns, one.rows), (3, 8));
+        assert_eq!((two.advice_columns, two.rows), (1, 10));
+        assert_eq!((one.instance_columns, one.lookups), (1, 0));
+        // The extra advice columns cost commitments in every proof.
+        assert!(one.marginal_proof_size > two.marginal_proof_size);
+        assert!(one.proof_size > one.marginal_proof_size);
+    }
+
+    #[test]
+    fn cost_of_every_example() {
+        let costs = example_costs();
+        let table = format_table(&costs);
+
+        for (name, cost) in &costs {
+            assert!(cost.is_some(), "{} was not measured", name);
+            assert!(table.contains(name.as_str()));
+        }
+        assert_eq!(table.lines().count(), costs.len() + 1);
+    }
+}
@@ -0,0 +1,202 @@
+//! Pallas point arithmetic from the `halo2_gadgets` ECC chip, shared by the
+//! examples that commit or sign with curve points.
+//!
+//! The examples only multiply variable bases, so the chip is configured
+//! without fixed bases; the generators they need are witnessed and pinned to
+//! constants instead. Scalars are base field cells, which is sound because the
+//! Pallas base field is smaller than its scalar field.
+
+use ff::PrimeField;
+use group::{prime::PrimeCurveAffine, Curve};
+use halo2_gadgets::{
+    ecc::{
+        chip::{
+            BaseFieldElem, CircuitVersion, EccChip, EccConfig, FixedPoint, FullScalar, ShortScalar,
+            H,
+        },
+        FixedPoints, NonIdentityPoint, Point, ScalarVar,
+    },
+    sinsemilla::primitives as sinsemilla,
+    utilities::{
+        lookup_range_check::{LookupRangeCheck, PallasLookupRangeCheckConfig},
+        UtilitiesInstructions,
+    },
+};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::{AssignedCell, Layouter, Value},
+    pasta::{pallas, Fp},
+    plonk::{Advice, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::range_check::table::RangeTableConfig;
+
+/// The circuits multiply by variable bases only, so they have no fixed bases.
+/// The uninhabited types below satisfy the `EccChip` bounds.
+macro_rules! no_fixed_point {
+    ($name:ident, $kind:ty) => {
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum $name {}
+
+        impl FixedPoint<pallas::Affine> for $name {
+            type FixedScalarKind = $kind;
+
+            fn generator(&self) -> pallas::Affine {
+                match *self {}
+            }
+
+            fn u(&self) -> Vec<[[u8; 32]; H]> {
+                match *self {}
+            }
+
+            fn z(&self) -> Vec<u64> {
+                match *self {}
+            }
+        }
+    };
+}
+
+no_fixed_point!(NoFullScalar, FullScalar);
+no_fixed_point!(NoShortScalar, ShortScalar);
+no_fixed_point!(NoBaseFieldElem, BaseFieldElem);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoFixedBases;
+
+impl FixedPoints<pallas::Affine> for NoFixedBases {
+    type FullScalar = NoFullScalar;
+    type ShortScalar = NoShortScalar;
+    type Base = NoBaseFieldElem;
+}
+
+/// The ECC chip without fixed bases.
+pub type Ecc = EccChip<NoFixedBases>;
+
+/// The affine coordinates of `point`, as public inputs.
+pub fn coordinates(point: pallas::Affine) -> [Fp; 2] {
+    let coordinates = point.coordinates().unwrap();
+    [*coordinates.x(), *coordinates.y()]
+}
+
+#[derive(Debug, Clone)]
+pub struct CurveConfig {
+    pub ecc: EccConfig<NoFixedBases>,
+    /// Where scalars are loaded.
+    pub advice: Column<Advice>,
+    /// The `0..2^10` table behind the scalar multiplication's range checks.
+    pub(crate) table: RangeTableConfig<Fp, { 1 << sinsemilla::K }>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CurveChip {
+    config: CurveConfig,
+}
+
+impl CurveChip {
+    pub fn construct(config: CurveConfig) -> Self {
+        Self { config }
+    }
+
+    /// Takes the ten advice columns of the ECC chip, and allocates its fixed
+    /// columns and lookup table.
+    pub fn configure(meta: &mut ConstraintSystem<Fp>, advice: [Column<Advice>; 10]) -> CurveConfig {
+        let table = RangeTableConfig::configure(meta);
+        let lagrange_coeffs = [(); 8].map(|_| meta.fixed_column());
+        // The chip loads constant points through this column.
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let range_check = PallasLookupRangeCheckConfig::configure(meta, advice[9], table.value);
+        let ecc = EccChip::<NoFixedBases>::configure(meta, advice, lagrange_coeffs, range_check);
+
+        CurveConfig {
+            ecc,
+            advice: advice[0],
+            table,
+        }
+    }
+
+    pub fn ecc_chip(&self) -> Ecc {
+        EccChip::construct(self.config.ecc.clone(), CircuitVersion::AnchoredBase)
+    }
+
+    /// Loads [`CurveConfig::table`].
+    pub fn load(&self, layouter: &mut impl Layouter<Fp>) -> Result<(), Error> {
+        self.config.table.load(layouter)
+    }
+
+    /// Witnesses a point and pins it to the constant `value`, for use as a
+    /// variable base.
+    pub fn load_constant(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        value: pallas::Affine,
+    ) -> Result<NonIdentityPoint<pallas::Affine, Ecc>, Error> {
+        let chip = self.ecc_chip();
+        let point = NonIdentityPoint::new(
+            chip.clone(),
+            layouter.namespace(|| "witness"),
+            Value::known(value),
+        )?;
+        let constant = Point::new_from_constant(chip, layouter.namespace(|| "constant"), value)?;
+        point.constrain_equal(layouter.namespace(|| "pin"), &constant)?;
+        Ok(point)
+    }
+
+    /// The Pallas generator, pinned as by [`Self::load_constant`].
+    pub fn load_generator(
+        &self,
+        layouter: impl Layouter<Fp>,
+    ) -> Result<NonIdentityPoint<pallas::Affine, Ecc>, Error> {
+        self.load_constant(layouter, pallas::Affine::generator())
+    }
+
+    /// Witnesses `value` in the scalar column.
+    pub fn load_private(
+        &self,
+        layouter: impl Layouter<Fp>,
+        value: Value<Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        self.ecc_chip()
+            .load_private(layouter, self.config.advice, value)
+    }
+
+    /// Uses the base field cell `value` as a scalar.
+    pub fn scalar(
+        &self,
+        layouter: impl Layouter<Fp>,
+        value: &AssignedCell<Fp, Fp>,
+    ) -> Result<ScalarVar<pallas::Affine, Ecc>, Error> {
+        ScalarVar::from_base(self.ecc_chip(), layouter, value)
+    }
+
+    /// Constrains the coordinates of `point` to rows `row` and `row + 1` of
+    /// `instance`.
+    pub fn expose_point(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        point: &Point<pallas::Affine, Ecc>,
+        instance: Column<Instance>,
+        row: usize,
+    ) -> Result<(), Error> {
+        let point = point.inner();
+        layouter.constrain_instance(point.x().cell(), instance, row)?;
+        layouter.constrain_instance(point.y().cell(), instance, row + 1)
+    }
+}
+
+/// Reads a scalar as a base field element. `None` for the few scalars at or
+/// above the base field's modulus.
+pub fn scalar_to_base(scalar: pallas::Scalar) -> Option<Fp> {
+    Fp::from_repr(scalar.to_repr()).into()
+}
+
+/// Reads a base field element as a scalar, which always fits.
+pub fn base_to_scalar(base: Fp) -> pallas::Scalar {
+    pallas::Scalar::from_repr(base.to_repr()).unwrap()
+}
+
+/// The point `scalar * G` on the Pallas curve.
+pub fn mul_generator(scalar: pallas::Scalar) -> pallas::Affine {
+    (pallas::Affine::generator() * scalar).to_affine()
+}
@@ -0,0 +1,306 @@
+//! Running-sum decomposition of a field element into `K`-bit chunks.
+//!
+//! A value `z_0` is split as `z_0 = k_0 + 2^K * z_1`, `z_1 = k_1 + 2^K * z_2`,
+//! and so on, with every chunk `k_i` looked up in a table of `0..2^K` and the
+//! final running sum `z_NUM_CHUNKS` constrained to zero. The value therefore
+//! fits in `K * NUM_CHUNKS` bits, and its chunks are assigned cells that other
+//! gadgets can copy.
+
+use crate::{names, prelude::*};
+
+/// Columns and selectors of a [`DecomposeChip`].
+///
+/// `table` holds `0..2^K` once [`DecomposeChip::load_table`] has run.
+#[derive(Debug, Clone)]
+pub struct DecomposeConfig<F, const K: usize, const NUM_CHUNKS: usize> {
+    pub z: Column<Advice>,
+    pub chunk: Column<Advice>,
+    pub q_step: Selector,
+    pub q_last: Selector,
+    pub table: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+/// Decomposes a value into `NUM_CHUNKS` little-endian chunks of `K` bits.
+///
+/// Region layout, one row per running sum:
+///
+/// ```text
+/// z      | chunk | q_step | q_last
+/// z_0    | k_0   |   1    |   0
+/// z_1    | k_1   |   1    |   0
+/// ...    | ...   |  ...   |  ...
+/// z_N    |       |   0    |   1
+/// ```
+#[derive(Debug, Clone)]
+pub struct DecomposeChip<F: PrimeField, const K: usize, const NUM_CHUNKS: usize> {
+    config: DecomposeConfig<F, K, NUM_CHUNKS>,
+}
+
+impl<F: PrimeField, const K: usize, const NUM_CHUNKS: usize> DecomposeChip<F, K, NUM_CHUNKS> {
+    pub fn construct(config: DecomposeConfig<F, K, NUM_CHUNKS>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        z: Column<Advice>,
+        chunk: Column<Advice>,
+    ) -> DecomposeConfig<F, K, NUM_CHUNKS> {
+        // Lookups cannot use simple selectors.
+        let q_step = meta.complex_selector();
+        let q_last = meta.selector();
+        let table = meta.lookup_table_column();
+        meta.enable_equality(z);
+        meta.enable_equality(chunk);
+
+        meta.create_gate("running sum", |meta| {
+            let q = meta.query_selector(q_step);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let chunk = meta.query_advice(chunk, Rotation::cur());
+            let two_pow_k = Expression::Constant(F::from(1 << K));
+            vec![q * (z_cur - chunk - z_next * two_pow_k)]
+        });
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_step);
+            let chunk = meta.query_advice(chunk, Rotation::cur());
+            vec![(q * chunk, table)]
+        });
+
+        meta.create_gate(names::LAST, |meta| {
+            let q = meta.query_selector(q_last);
+            let z = meta.query_advice(z, Rotation::cur());
+            vec![q * z]
+        });
+
+        DecomposeConfig {
+            z,
+            chunk,
+            q_step,
+            q_last,
+            table,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Loads `0..2^K` into the chunk table. Call once per circuit.
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load chunk table",
+            |mut table| {
+                for value in 0..1 << K {
+                    table.assign_cell(
+                        || "chunk",
+                        self.config.table,
+                        value,
+                        || Value::known(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Copies `value` into a new region and decomposes it, returning the chunks
+    /// from least to most significant.
+    pub fn decompose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let two_pow_k_inv = F::from(1 << K).invert().unwrap();
+
+        layouter.assign_region(
+            || "decompose",
+            |mut region| {
+                let mut z = value.copy_advice(|| "z_0", &mut region, self.config.z, 0)?;
+                let chunks = value.value().map(|v| chunks::<F>(v, K, NUM_CHUNKS));
+
+                let mut cells = Vec::with_capacity(NUM_CHUNKS);
+                for i in 0..NUM_CHUNKS {
+                    self.config.q_step.enable(&mut region, i)?;
+
+                    let chunk = chunks.as_ref().map(|chunks| F::from(chunks[i]));
+                    let cell = region.assign_advice(|| "chunk", self.config.chunk, i, || chunk)?;
+
+                    let z_next = (z.value().copied() - chunk).map(|z| z * two_pow_k_inv);
+                    z = region.assign_advice(|| "z", self.config.z, i + 1, || z_next)?;
+                    cells.push(cell);
+                }
+                self.config.q_last.enable(&mut region, NUM_CHUNKS)?;
+
+                Ok(cells)
+            },
+        )
+    }
+}
+
+/// The low `k * count` bits of `value`, as `count` little-endian `k`-bit chunks.
+pub fn chunks<F: PrimeField>(value: &F, k: usize, count: usize) -> Vec<u64> {
+    let repr = value.to_repr();
+    let bit = |i: usize| {
+        repr.as_ref()
+            .get(i / 8)
+            .map_or(0, |byte| u64::from((byte >> (i % 8)) & 1))
+    };
+    (0..count)
+        .map(|c| (0..k).map(|j| bit(c * k + j) << j).sum())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    const K: usize = 4;
+    const NUM_CHUNKS: usize = 4;
+
+    /// Witnesses `value`, decomposes it and copies the chunks into `out`.
+    #[derive(Default)]
+    struct DecomposeCircuit {
+        value: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for DecomposeCircuit {
+        type Config = (DecomposeConfig<Fp, K, NUM_CHUNKS>, Column<Advice>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let z = meta.advice_column();
+            let chunk = meta.advice_column();
+            let out = meta.advice_column();
+            meta.enable_equality(out);
+            (DecomposeChip::configure(meta, z, chunk), out)
+        }
+
+        fn synthesize(
+            &self,
+            (config, out): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = DecomposeChip::construct(config);
+            chip.load_table(&mut layouter)?;
+
+            let value = layouter.assign_region(
+                || "value",
+                |mut region| region.assign_advice(|| "value", out, 0, || self.value),
+            )?;
+            let chunks = chip.decompose(layouter.namespace(|| "decompose"), &value)?;
+
+            // Reuse the chunks elsewhere, as another gadget would.
+            layouter.assign_region(
+                || "copy chunks",
+                |mut region| {
+                    for (row, chunk) in chunks.iter().enumerate() {
+                        chunk.copy_advice(|| "chunk", &mut region, out, row)?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn run(value: Fp) -> MockProver<Fp> {
+        let k = 5;
+
+        MockProver::run(
+            k,
+            &DecomposeCircuit {
+                value: Value::known(value),
+            },
+            vec![],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn chunks_are_little_endian() {
+        assert_eq!(chunks(&Fp::from(0xabcd), 4, 4), vec![0xd, 0xc, 0xb, 0xa]);
+        assert_eq!(chunks(&Fp::from(0xabcd), 8, 3), vec![0xcd, 0xab, 0]);
+        assert_eq!(chunks(&Fp::from(5), 1, 3), vec![1, 0, 1]);
+    }
+
+    #[test]
+    fn decompose_accepts_values_that_fit() {
+        for value in [0, 1, 0xabcd, 0xffff] {
+            run(Fp::from(value)).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn decompose_rejects_values_that_do_not_fit() {
+        // The chunks cover the low 16 bits, leaving z_4 = 1.
+        assert_constraint_fails(&run(Fp::from(0x1_0000)), names::LAST);
+        assert_constraint_fails(&run(-Fp::ONE), names::LAST);
+    }
+
+    /// Decomposes 16 as a single out-of-range chunk, which satisfies the
+    /// running-sum gate but not the lookup.
+    struct ForgedChunkCircuit;
+
+    impl Circuit<Fp> for ForgedChunkCircuit {
+        type Config = DecomposeConfig<Fp, K, NUM_CHUNKS>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let z = meta.advice_column();
+            let chunk = meta.advice_column();
+            DecomposeChip::configure(meta, z, chunk)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            DecomposeChip::construct(config.clone()).load_table(&mut layouter)?;
+            layouter.assign_region(
+                || "forged",
+                |mut region| {
+                    let mut assign = |column, row, value: u64| {
+                        region.assign_advice(|| "", column, row, || Value::known(Fp::from(value)))
+                    };
+                    assign(config.z, 0, 16)?;
+                    assign(config.chunk, 0, 16)?;
+                    for row in 1..=NUM_CHUNKS {
+                        assign(config.z, row, 0)?;
+                    }
+                    for row in 1..NUM_CHUNKS {
+                        assign(config.chunk, row, 0)?;
+                    }
+                    for row in 0..NUM_CHUNKS {
+                        config.q_step.enable(&mut region, row)?;
+                    }
+                    config.q_last.enable(&mut region, NUM_CHUNKS)
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn decompose_rejects_oversized_chunk() {
+        let k = 5;
+
+        let failures = MockProver::run(k, &ForgedChunkCircuit, vec![])
+            .unwrap()
+            .verify()
+            .unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(failures[0], VerifyFailure::Lookup { .. }));
+    }
+}
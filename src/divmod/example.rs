@@ -0,0 +1,277 @@
+use crate::{prelude::*, range_check::table::RangeTableConfig, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Proves integer division with remainder of a public `dividend` by a public
+/// `divisor`, with a private quotient `q` and remainder `r`:
+///
+/// ```text
+/// dividend == divisor * q + r
+/// gap      == divisor - r - 1
+/// ```
+///
+/// `divisor`, `q`, `r` and `gap` are all looked up in a `0..RANGE` table. A
+/// non-negative `gap` is what makes `r < divisor`, and it also rules out a zero
+/// divisor, whose `gap` would be `-1`. With every factor below `RANGE`, the
+/// right-hand side cannot wrap around the field modulus.
+#[derive(Debug, Clone)]
+struct DivModConfig<F: PrimeField, const RANGE: usize> {
+    pub advice: [Column<Advice>; 5],
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+#[derive(Debug, Clone)]
+struct DivModChip<F: PrimeField, const RANGE: usize> {
+    config: DivModConfig<F, RANGE>,
+}
+
+impl<F: PrimeField, const RANGE: usize> DivModChip<F, RANGE> {
+    pub fn construct(config: DivModConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        instance: Column<Instance>,
+    ) -> DivModConfig<F, RANGE> {
+        let [col_dividend, col_divisor, col_q, col_r, col_gap] = advice;
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        let table = RangeTableConfig::configure(meta);
+        meta.enable_equality(col_dividend);
+        meta.enable_equality(col_divisor);
+        meta.enable_equality(instance);
+
+        meta.create_gate("divmod", |meta| {
+            //
+            // col_dividend | col_divisor | col_q | col_r | col_gap | selector
+            //   dividend       divisor      q       r       gap        s
+            //
+            let s = meta.query_selector(selector);
+            let dividend = meta.query_advice(col_dividend, Rotation::cur());
+            let divisor = meta.query_advice(col_divisor, Rotation::cur());
+            let q = meta.query_advice(col_q, Rotation::cur());
+            let r = meta.query_advice(col_r, Rotation::cur());
+            let gap = meta.query_advice(col_gap, Rotation::cur());
+            vec![
+                s.clone() * (divisor.clone() * q + r.clone() - dividend),
+                s * (divisor - r - Expression::Constant(F::ONE) - gap),
+            ]
+        });
+
+        // Inactive rows look up 0, which is always in the table.
+        for column in [col_divisor, col_q, col_r, col_gap] {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(selector);
+                let value = meta.query_advice(column, Rotation::cur());
+                vec![(s * value, table.value)]
+            });
+        }
+
+        DivModConfig {
+            advice,
+            selector,
+            instance,
+            table,
+        }
+    }
+
+    /// Assigns one division and returns the cells holding the dividend and the
+    /// divisor.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        dividend: Value<F>,
+        divisor: Value<F>,
+        q: Value<F>,
+        r: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let [col_dividend, col_divisor, col_q, col_r, col_gap] = self.config.advice;
+        layouter.assign_region(
+            || "divmod",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                let dividend = region.assign_advice(|| "dividend", col_dividend, 0, || dividend)?;
+                let divisor = region.assign_advice(|| "divisor", col_divisor, 0, || divisor)?;
+                region.assign_advice(|| "q", col_q, 0, || q)?;
+                region.assign_advice(|| "r", col_r, 0, || r)?;
+                let gap = divisor.value().copied() - r - Value::known(F::ONE);
+                region.assign_advice(|| "gap", col_gap, 0, || gap)?;
+                Ok((dividend, divisor))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference: the quotient and remainder of `dividend / divisor`, or
+/// `None` for a zero divisor.
+pub fn reference(dividend: u64, divisor: u64) -> Option<(u64, u64)> {
+    Some((
+        dividend.checked_div(divisor)?,
+        dividend.checked_rem(divisor)?,
+    ))
+}
+
+#[derive(Default)]
+struct MyCircuit<F, const RANGE: usize> {
+    dividend: Value<F>,
+    divisor: Value<F>,
+    q: Value<F>,
+    r: Value<F>,
+}
+
+impl<F: PrimeField, const RANGE: usize> MyCircuit<F, RANGE> {
+    /// The circuit for `dividend / divisor` with the honest quotient and
+    /// remainder. A zero divisor gets a zero quotient and remainder, which the
+    /// circuit then rejects.
+    pub fn new(dividend: u64, divisor: u64) -> Self {
+        let (q, r) = reference(dividend, divisor).unwrap_or((0, 0));
+        Self {
+            dividend: Value::known(F::from(dividend)),
+            divisor: Value::known(F::from(divisor)),
+            q: Value::known(F::from(q)),
+            r: Value::known(F::from(r)),
+        }
+    }
+}
+
+impl<F: PrimeField, const RANGE: usize> Circuit<F> for MyCircuit<F, RANGE> {
+    type Config = DivModConfig<F, RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let instance = meta.instance_column();
+        DivModChip::<F, RANGE>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let chip = DivModChip::<F, RANGE>::construct(config);
+
+        let (dividend, divisor) = chip.assign(
+            layouter.namespace(|| "divmod"),
+            self.dividend,
+            self.divisor,
+            self.q,
+            self.r,
+        )?;
+        chip.expose_public(layouter.namespace(|| "dividend"), &dividend, DIVIDEND_ROW)?;
+        chip.expose_public(layouter.namespace(|| "divisor"), &divisor, DIVISOR_ROW)?;
+
+        Ok(())
+    }
+}
+
+const DIVIDEND_ROW: usize = 0;
+const DIVISOR_ROW: usize = 1;
+
+/// Places the public inputs by name, like [`crate::fibonacci::FibonacciInstances`].
+pub struct DivModInstances<F> {
+    pub dividend: F,
+    pub divisor: F,
+}
+
+impl<F: Field> DivModInstances<F> {
+    pub fn new(dividend: F, divisor: F) -> Self {
+        Self { dividend, divisor }
+    }
+
+    pub fn build(self) -> Vec<Vec<F>> {
+        let mut column = vec![F::ZERO; 2];
+        column[DIVIDEND_ROW] = self.dividend;
+        column[DIVISOR_ROW] = self.divisor;
+        vec![column]
+    }
+}
+
+/// The circuit on `17 = 5 * 3 + 2`.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp, 256>::new(17, 5);
+    let instances = DivModInstances::new(Fp::from(17), Fp::from(5)).build();
+    ExampleCircuit::new(9, circuit, instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    const RANGE: usize = 256;
+
+    fn run(circuit: &MyCircuit<Fp, RANGE>, dividend: u64, divisor: u64) -> MockProver<Fp> {
+        let k = 9;
+
+        let instances = DivModInstances::new(Fp::from(dividend), Fp::from(divisor)).build();
+        MockProver::run(k, circuit, instances).unwrap()
+    }
+
+    fn circuit(dividend: u64, divisor: u64, q: u64, r: u64) -> MyCircuit<Fp, RANGE> {
+        MyCircuit {
+            dividend: Value::known(Fp::from(dividend)),
+            divisor: Value::known(Fp::from(divisor)),
+            q: Value::known(Fp::from(q)),
+            r: Value::known(Fp::from(r)),
+        }
+    }
+
+    #[test]
+    fn divmod_example() {
+        assert_eq!(reference(17, 5), Some((3, 2)));
+        run(&MyCircuit::new(17, 5), 17, 5).assert_satisfied();
+        run(&circuit(17, 5, 3, 2), 17, 5).assert_satisfied();
+        run(&MyCircuit::new(0, 1), 0, 1).assert_satisfied();
+    }
+
+    #[test]
+    fn divmod_rejects_large_remainder() {
+        // 17 = 5 * 2 + 7 holds, but 7 is not a remainder modulo 5.
+        let failures = run(&circuit(17, 5, 2, 7), 17, 5).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn divmod_rejects_wrong_equation() {
+        // 5 * 3 + 7 is 22, not 17.
+        assert_constraint_fails(&run(&circuit(17, 5, 3, 7), 17, 5), "divmod");
+    }
+
+    #[test]
+    fn divmod_rejects_zero_divisor() {
+        assert_eq!(reference(17, 0), None);
+        assert!(run(&MyCircuit::new(17, 0), 17, 0).verify().is_err());
+        // Even with the remainder carrying the whole dividend.
+        assert!(run(&circuit(17, 0, 0, 17), 17, 0).verify().is_err());
+    }
+}
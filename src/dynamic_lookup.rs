@@ -0,0 +1,231 @@
+//! Dynamic lookup: constrains witnessed values to a table that is itself
+//! advice, filled in per proof.
+//!
+//! The lookups in `range_check` and `set_membership` go into `TableColumn`s,
+//! which are fixed columns: their contents are part of the verifying key, so
+//! changing the allowed set means a new keygen. Newer halo2 versions can look
+//! up into advice columns directly; halo2_proofs 0.3 cannot, so here a query
+//! `q` is checked against a table `t` with a vanishing product instead:
+//!
+//! ```text
+//! (q - t_0) * (q - t_1) * ... * (q - t_{m-1}) == 0
+//! ```
+//!
+//! which holds exactly when `q` equals some `t_j`, since a field has no zero
+//! divisors. Unlike a real lookup argument it costs a row per table entry per
+//! query, so it only suits small tables.
+
+use halo2_proofs::pasta::Fp;
+
+use crate::{
+    prelude::*,
+    registry::{Example, ExampleCircuit},
+};
+
+/// `[table, query, product]` columns.
+#[derive(Debug, Clone)]
+pub struct DynamicLookupConfig {
+    pub advice: [Column<Advice>; 3],
+    pub selector: Selector,
+}
+
+/// One region per query, with the table and the query copied in:
+///
+/// ```text
+/// table   | query | product | selector
+/// t_0     | q     | 1       |    1
+/// t_1     | q     | z_1     |    1
+/// ...     | ...   | ...     |   ...
+/// t_{m-1} | q     | z_{m-1} |    1
+///         |       | 0       |    0
+/// ```
+///
+/// with `z_{j+1} = z_j * (q - t_j)`, starting from the constant 1 and ending
+/// at the constant 0.
+#[derive(Debug, Clone)]
+pub struct DynamicLookupChip<F: Field> {
+    config: DynamicLookupConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> DynamicLookupChip<F> {
+    pub fn construct(config: DynamicLookupConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+    ) -> DynamicLookupConfig {
+        let [table, query, product] = advice;
+        let selector = meta.selector();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("dynamic lookup", |meta| {
+            let s = meta.query_selector(selector);
+            let t = meta.query_advice(table, Rotation::cur());
+            let q = meta.query_advice(query, Rotation::cur());
+            let z = meta.query_advice(product, Rotation::cur());
+            let z_next = meta.query_advice(product, Rotation::next());
+            vec![s * (z * (q - t) - z_next)]
+        });
+
+        DynamicLookupConfig { advice, selector }
+    }
+
+    /// Constrains `query` to equal one of the `table` cells. Nothing is a
+    /// member of an empty table, so that is a synthesis error.
+    pub fn lookup(
+        &self,
+        mut layouter: impl Layouter<F>,
+        table: &[AssignedCell<F, F>],
+        query: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let [table_col, query_col, product] = self.config.advice;
+        if table.is_empty() {
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "dynamic lookup",
+            |mut region| {
+                let mut z = region
+                    .assign_advice_from_constant(|| "z", product, 0, F::ONE)?
+                    .value()
+                    .copied();
+                for (row, entry) in table.iter().enumerate() {
+                    self.config.selector.enable(&mut region, row)?;
+                    let t = entry.copy_advice(|| "table", &mut region, table_col, row)?;
+                    let q = query.copy_advice(|| "query", &mut region, query_col, row)?;
+                    z = z * (q.value().copied() - t.value().copied());
+                    let cell = region.assign_advice(|| "z", product, row + 1, || z)?;
+                    if row + 1 == table.len() {
+                        region.constrain_constant(cell.cell(), F::ZERO)?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// The allowed set is public and can change from proof to proof; the values
+/// checked against it are private.
+#[derive(Debug, Clone)]
+struct MyConfig {
+    lookup: DynamicLookupConfig,
+    instance: Column<Instance>,
+}
+
+#[derive(Default)]
+struct MyCircuit<const M: usize> {
+    values: Vec<Value<Fp>>,
+}
+
+impl<const M: usize> Circuit<Fp> for MyCircuit<M> {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        MyConfig {
+            lookup: DynamicLookupChip::configure(meta, advice),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let [table_col, query_col, _] = config.lookup.advice;
+        let table = layouter.assign_region(
+            || "table",
+            |mut region| {
+                let targets: Vec<_> = (0..M).map(|row| (table_col, row)).collect();
+                load_instances(&mut region, config.instance, &targets)
+            },
+        )?;
+
+        let chip = DynamicLookupChip::construct(config.lookup.clone());
+        for value in &self.values {
+            let query = layouter.assign_region(
+                || "query",
+                |mut region| region.assign_advice(|| "query", query_col, 0, || *value),
+            )?;
+            chip.lookup(layouter.namespace(|| "member"), &table, &query)?;
+        }
+        Ok(())
+    }
+}
+
+/// The circuit on three private values, all in a public set of four.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<4> {
+        values: [17, 2, 17].map(|v| Value::known(Fp::from(v))).to_vec(),
+    };
+    let table = [2, 3, 5, 17].map(Fp::from).to_vec();
+    ExampleCircuit::new(6, circuit, vec![table])
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "dynamic_lookup",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+
+    fn run(values: &[u64], table: [u64; 4]) -> MockProver<Fp> {
+        let k = 6;
+        let circuit = MyCircuit::<4> {
+            values: values.iter().map(|&v| Value::known(Fp::from(v))).collect(),
+        };
+
+        MockProver::run(k, &circuit, vec![table.map(Fp::from).to_vec()]).unwrap()
+    }
+
+    #[test]
+    fn dynamic_lookup_accepts_members() {
+        run(&[2, 3, 5, 17], [2, 3, 5, 17]).assert_satisfied();
+        run(&[17, 17, 17], [17, 0, 0, 0]).assert_satisfied();
+        run(&[], [1, 2, 3, 4]).assert_satisfied();
+    }
+
+    #[test]
+    fn dynamic_lookup_rejects_non_members() {
+        // The honest product ends at 4 * 1 * 1 * 13, not the constant 0.
+        let failures = run(&[2, 4], [2, 3, 5, 17]).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+        // 0 is not special, unlike in a `TableColumn` lookup.
+        assert!(run(&[0], [1, 2, 3, 4]).verify().is_err());
+    }
+
+    #[test]
+    fn dynamic_lookup_table_changes_without_keygen() {
+        // The same circuit shape, so the same keys, accepts 4 under one table
+        // and rejects it under another.
+        run(&[4], [1, 2, 3, 4]).assert_satisfied();
+        assert!(run(&[4], [1, 2, 3, 5]).verify().is_err());
+    }
+}
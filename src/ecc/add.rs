@@ -0,0 +1,376 @@
+//! Complete addition of Pallas points in affine coordinates.
+
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    pasta::{group::prime::PrimeCurveAffine, pallas, Fp},
+};
+
+use super::xy;
+use crate::prelude::*;
+
+/// The coefficient `b` of the curve `y^2 = x^3 + b`.
+const B: u64 = 5;
+
+/// A point as two assigned coordinates, `(0, 0)` for the identity.
+#[derive(Debug, Clone)]
+pub struct AssignedPoint {
+    pub x: AssignedCell<Fp, Fp>,
+    pub y: AssignedCell<Fp, Fp>,
+}
+
+impl AssignedPoint {
+    pub fn value(&self) -> Value<(Fp, Fp)> {
+        self.x.value().copied().zip(self.y.value().copied())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AddConfig {
+    pub x_p: Column<Advice>,
+    pub y_p: Column<Advice>,
+    pub x_q: Column<Advice>,
+    pub y_q: Column<Advice>,
+    pub lambda: Column<Advice>,
+    pub alpha: Column<Advice>,
+    pub beta: Column<Advice>,
+    pub gamma: Column<Advice>,
+    pub delta: Column<Advice>,
+    pub q_add: Selector,
+    pub q_point: Selector,
+}
+
+/// Adds two points with one gate that covers every case: distinct points,
+/// doubling, a point and its negation, and either input being the identity.
+///
+/// ```text
+/// x_p | y_p | x_q | y_q | lambda | alpha | beta | gamma | delta
+/// x_p   y_p   x_q   y_q   lambda   alpha   beta   gamma   delta   <- q_add
+/// x_r   y_r
+/// ```
+///
+/// `lambda` is the slope, and `alpha`, `beta`, `gamma` and `delta` are the
+/// inverses of `x_q - x_p`, `x_p`, `x_q` and `y_q + y_p` (0 where those are 0),
+/// which let each constraint switch off for the cases it does not cover. The
+/// constraints are those of the complete addition in the halo2 book.
+///
+/// [`AddChip::witness`] loads a point and checks that it is on the curve or
+/// the identity.
+#[derive(Debug, Clone)]
+pub struct AddChip {
+    config: AddConfig,
+}
+
+impl AddChip {
+    pub fn construct(config: AddConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<Fp>, advice: [Column<Advice>; 9]) -> AddConfig {
+        let [x_p, y_p, x_q, y_q, lambda, alpha, beta, gamma, delta] = advice;
+        let q_add = meta.selector();
+        let q_point = meta.selector();
+        for column in [x_p, y_p, x_q, y_q] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("on curve", |meta| {
+            // Either `x` or `y` is non-zero and the point is on the curve, or
+            // both are zero. No point has `x = 0`, as 5 is not a square.
+            let s = meta.query_selector(q_point);
+            let x = meta.query_advice(x_p, Rotation::cur());
+            let y = meta.query_advice(y_p, Rotation::cur());
+            let curve = y.clone() * y.clone()
+                - x.clone() * x.clone() * x.clone()
+                - Expression::Constant(Fp::from(B));
+            vec![s.clone() * x * curve.clone(), s * y * curve]
+        });
+
+        meta.create_gate("add", |meta| {
+            let s = meta.query_selector(q_add);
+            let x_p = meta.query_advice(x_p, Rotation::cur());
+            let y_p = meta.query_advice(y_p, Rotation::cur());
+            let x_q = meta.query_advice(x_q, Rotation::cur());
+            let y_q = meta.query_advice(y_q, Rotation::cur());
+            let x_r = meta.query_advice(advice[0], Rotation::next());
+            let y_r = meta.query_advice(advice[1], Rotation::next());
+            let lambda = meta.query_advice(lambda, Rotation::cur());
+            let alpha = meta.query_advice(alpha, Rotation::cur());
+            let beta = meta.query_advice(beta, Rotation::cur());
+            let gamma = meta.query_advice(gamma, Rotation::cur());
+            let delta = meta.query_advice(delta, Rotation::cur());
+            let one = || Expression::Constant(Fp::ONE);
+
+            let dx = x_q.clone() - x_p.clone();
+            let sum_y = y_q.clone() + y_p.clone();
+            // Non-zero when the sum is a regular addition or doubling.
+            let x_rx = lambda.clone() * lambda.clone() - x_p.clone() - x_q.clone() - x_r.clone();
+            let y_rx = lambda.clone() * (x_p.clone() - x_r.clone()) - y_p.clone() - y_r.clone();
+            let if_alpha = one() - dx.clone() * alpha.clone();
+            let if_beta = one() - x_p.clone() * beta;
+            let if_gamma = one() - x_q.clone() * gamma;
+            let if_delta = one() - dx.clone() * alpha - sum_y.clone() * delta;
+            let both = x_p.clone() * x_q.clone();
+
+            vec![
+                // x_q != x_p: lambda is the slope of the chord.
+                dx.clone() * (dx.clone() * lambda.clone() - (y_q.clone() - y_p.clone())),
+                // x_q == x_p: lambda is the slope of the tangent (if y_p != 0).
+                if_alpha
+                    * (Expression::Constant(Fp::from(2)) * y_p.clone() * lambda
+                        - Expression::Constant(Fp::from(3)) * x_p.clone() * x_p.clone()),
+                // Neither is the identity, and x_q != x_p or y_q != -y_p.
+                both.clone() * dx.clone() * x_rx.clone(),
+                both.clone() * dx * y_rx.clone(),
+                both.clone() * sum_y.clone() * x_rx,
+                both * sum_y * y_rx,
+                // P is the identity: R = Q.
+                if_beta.clone() * (x_r.clone() - x_q),
+                if_beta * (y_r.clone() - y_q),
+                // Q is the identity: R = P.
+                if_gamma.clone() * (x_r.clone() - x_p),
+                if_gamma * (y_r.clone() - y_p),
+                // Q = -P: R is the identity.
+                if_delta.clone() * x_r,
+                if_delta * y_r,
+            ]
+            .into_iter()
+            .map(|poly| s.clone() * poly)
+            .collect::<Vec<_>>()
+        });
+
+        AddConfig {
+            x_p,
+            y_p,
+            x_q,
+            y_q,
+            lambda,
+            alpha,
+            beta,
+            gamma,
+            delta,
+            q_add,
+            q_point,
+        }
+    }
+
+    /// Witnesses `point` and checks that it is on the curve or the identity.
+    pub fn witness(
+        &self,
+        layouter: impl Layouter<Fp>,
+        point: Value<pallas::Affine>,
+    ) -> Result<AssignedPoint, Error> {
+        self.witness_xy(layouter, point.map(xy))
+    }
+
+    /// Witnesses raw coordinates, which the gate then checks.
+    fn witness_xy(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        xy: Value<(Fp, Fp)>,
+    ) -> Result<AssignedPoint, Error> {
+        layouter.assign_region(
+            || "witness point",
+            |mut region| {
+                self.config.q_point.enable(&mut region, 0)?;
+                let x = region.assign_advice(|| "x", self.config.x_p, 0, || xy.map(|(x, _)| x))?;
+                let y = region.assign_advice(|| "y", self.config.y_p, 0, || xy.map(|(_, y)| y))?;
+                Ok(AssignedPoint { x, y })
+            },
+        )
+    }
+
+    /// Returns `p + q`.
+    pub fn add(
+        &self,
+        layouter: impl Layouter<Fp>,
+        p: &AssignedPoint,
+        q: &AssignedPoint,
+    ) -> Result<AssignedPoint, Error> {
+        let sum = p
+            .value()
+            .zip(q.value())
+            .map(|(p, q)| xy((to_affine(p) + to_affine(q)).into()));
+        self.assign_add(layouter, p, q, sum)
+    }
+
+    /// Lays out the addition of `p` and `q` with `sum` as the result.
+    fn assign_add(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        p: &AssignedPoint,
+        q: &AssignedPoint,
+        sum: Value<(Fp, Fp)>,
+    ) -> Result<AssignedPoint, Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "add",
+            |mut region| {
+                config.q_add.enable(&mut region, 0)?;
+                p.x.copy_advice(|| "x_p", &mut region, config.x_p, 0)?;
+                p.y.copy_advice(|| "y_p", &mut region, config.y_p, 0)?;
+                q.x.copy_advice(|| "x_q", &mut region, config.x_q, 0)?;
+                q.y.copy_advice(|| "y_q", &mut region, config.y_q, 0)?;
+
+                let inputs = p.value().zip(q.value());
+                let hints = inputs.map(|((x_p, y_p), (x_q, y_q))| hints(x_p, y_p, x_q, y_q));
+                for (i, (column, name)) in [
+                    (config.lambda, "lambda"),
+                    (config.alpha, "alpha"),
+                    (config.beta, "beta"),
+                    (config.gamma, "gamma"),
+                    (config.delta, "delta"),
+                ]
+                .into_iter()
+                .enumerate()
+                {
+                    region.assign_advice(|| name, column, 0, || hints.map(|hints| hints[i]))?;
+                }
+
+                let x = region.assign_advice(|| "x_r", config.x_p, 1, || sum.map(|(x, _)| x))?;
+                let y = region.assign_advice(|| "y_r", config.y_p, 1, || sum.map(|(_, y)| y))?;
+                Ok(AssignedPoint { x, y })
+            },
+        )
+    }
+}
+
+/// `[lambda, alpha, beta, gamma, delta]` for adding `(x_p, y_p)` and
+/// `(x_q, y_q)`.
+fn hints(x_p: Fp, y_p: Fp, x_q: Fp, y_q: Fp) -> [Fp; 5] {
+    let inv0 = |value: Fp| value.invert().unwrap_or(Fp::ZERO);
+    let lambda = if x_q != x_p {
+        (y_q - y_p) * inv0(x_q - x_p)
+    } else {
+        Fp::from(3) * x_p.square() * inv0(y_p.double())
+    };
+    let delta = if x_q == x_p {
+        inv0(y_q + y_p)
+    } else {
+        Fp::ZERO
+    };
+    [lambda, inv0(x_q - x_p), inv0(x_p), inv0(x_q), delta]
+}
+
+/// The point with coordinates `(x, y)`, or the identity for `(0, 0)`.
+pub fn to_affine((x, y): (Fp, Fp)) -> pallas::Affine {
+    if x.is_zero_vartime() && y.is_zero_vartime() {
+        pallas::Affine::identity()
+    } else {
+        pallas::Affine::from_xy(x, y).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::group::Group};
+    use rand_core::OsRng;
+
+    /// Witnesses `p` and `q` by their coordinates, adds them, and exposes the
+    /// sum. With `forged` set, the sum is replaced by that point.
+    #[derive(Default)]
+    struct MyCircuit {
+        p: Value<(Fp, Fp)>,
+        q: Value<(Fp, Fp)>,
+        forged: Option<pallas::Affine>,
+    }
+
+    impl Circuit<Fp> for MyCircuit {
+        type Config = (AddConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 9].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (AddChip::configure(meta, advice), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = AddChip::construct(config);
+            let p = chip.witness_xy(layouter.namespace(|| "p"), self.p)?;
+            let q = chip.witness_xy(layouter.namespace(|| "q"), self.q)?;
+            let r = match self.forged {
+                Some(r) => {
+                    chip.assign_add(layouter.namespace(|| "p + q"), &p, &q, Value::known(xy(r)))?
+                }
+                None => chip.add(layouter.namespace(|| "p + q"), &p, &q)?,
+            };
+            layouter.constrain_instance(r.x.cell(), instance, 0)?;
+            layouter.constrain_instance(r.y.cell(), instance, 1)
+        }
+    }
+
+    fn run(p: pallas::Affine, q: pallas::Affine, forged: Option<pallas::Affine>) -> MockProver<Fp> {
+        let k = 4;
+
+        let circuit = MyCircuit {
+            p: Value::known(xy(p)),
+            q: Value::known(xy(q)),
+            forged,
+        };
+        let (x, y) = xy(forged.unwrap_or((p + q).into()));
+        MockProver::run(k, &circuit, vec![vec![x, y]]).unwrap()
+    }
+
+    fn random_point() -> pallas::Affine {
+        pallas::Point::random(OsRng).into()
+    }
+
+    #[test]
+    fn ecc_add_matches_pasta() {
+        let (p, q) = (random_point(), random_point());
+        let o = pallas::Affine::identity();
+        for (p, q) in [(p, q), (p, p), (p, -p), (p, o), (o, q), (o, o)] {
+            run(p, q, None).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn ecc_add_rejects_wrong_sum() {
+        let (p, q) = (random_point(), random_point());
+        let o = pallas::Affine::identity();
+        for (p, q, forged) in [
+            (p, q, (p + p).into()),
+            (p, p, q),
+            (p, -p, p),
+            (p, o, o),
+            (o, q, p),
+            (p, q, (-(p + q)).into()),
+        ] {
+            assert_constraint_fails(&run(p, q, Some(forged)), "add");
+        }
+    }
+
+    #[test]
+    fn ecc_rejects_point_off_curve() {
+        // `(1, 1)` is neither on the curve nor the identity.
+        let p = random_point();
+        let circuit = MyCircuit {
+            p: Value::known(xy(p)),
+            q: Value::known((Fp::ONE, Fp::ONE)),
+            forged: Some(p),
+        };
+        let (x, y) = xy(p);
+        let k = 4;
+
+        let prover = MockProver::run(k, &circuit, vec![vec![x, y]]).unwrap();
+        assert_constraint_fails(&prover, "on curve");
+    }
+
+    #[test]
+    fn ecc_add_gate_degree() {
+        let degree = max_gate_degree(|meta| {
+            MyCircuit::configure(meta);
+        });
+        assert_eq!(degree, 6);
+    }
+}
@@ -0,0 +1,35 @@
+//! Pallas curve arithmetic built from scratch, in a circuit over the curve's
+//! base field.
+//!
+//! These chips need no feature: unlike the `halo2_gadgets` ECC chip behind the
+//! `ecc` feature, they are plain gates over `halo2_proofs` alone, written to be
+//! read. Points are affine, with the identity encoded as `(0, 0)`, which is not
+//! on the curve `y^2 = x^3 + 5`.
+//!
+//! [`AddChip`] adds points completely, and [`ScalarMulChip`] builds
+//! double-and-add scalar multiplication on top of it.
+
+pub mod add;
+pub mod scalar_mul;
+
+pub use add::{AddChip, AddConfig, AssignedPoint};
+pub use scalar_mul::{ScalarMulChip, ScalarMulConfig};
+
+use ff::Field;
+use halo2_proofs::{
+    arithmetic::{Coordinates, CurveAffine},
+    pasta::{pallas, Fp},
+};
+
+use crate::registry::Example;
+
+/// The affine coordinates of `point`, with `(0, 0)` for the identity.
+pub fn xy(point: pallas::Affine) -> (Fp, Fp) {
+    let coordinates: Option<Coordinates<_>> = point.coordinates().into();
+    coordinates.map_or((Fp::ZERO, Fp::ZERO), |c| (*c.x(), *c.y()))
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "ecc::scalar_mul",
+    circuit: scalar_mul::example_circuit,
+}];
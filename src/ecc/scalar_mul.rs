@@ -0,0 +1,262 @@
+//! Variable-base scalar multiplication by double-and-add.
+
+use halo2_proofs::pasta::{group::prime::PrimeCurveAffine, pallas, Fp};
+
+use super::{xy, AddChip, AddConfig, AssignedPoint};
+use crate::{
+    gadgets::{
+        bits::{BitsChip, BitsConfig},
+        mux::{MuxChip, MuxConfig},
+    },
+    prelude::*,
+    registry::ExampleCircuit,
+};
+
+#[derive(Debug, Clone)]
+pub struct ScalarMulConfig {
+    pub add: AddConfig,
+    pub mux: MuxConfig,
+    pub bits: BitsConfig,
+    pub constant: Column<Fixed>,
+}
+
+/// Multiplies a point by a scalar given as bits, most significant first:
+///
+/// ```text
+/// acc = O
+/// for each bit: acc = (acc + acc) + (bit ? P : O)
+/// ```
+///
+/// Each step is two [`AddChip`] additions, since complete addition also
+/// doubles, and two [`MuxChip`] rows picking the coordinates of `P` or of the
+/// identity `(0, 0)`. The identity and any constant points are loaded from a
+/// fixed column.
+#[derive(Debug, Clone)]
+pub struct ScalarMulChip {
+    config: ScalarMulConfig,
+}
+
+impl ScalarMulChip {
+    pub fn construct(config: ScalarMulConfig) -> Self {
+        Self { config }
+    }
+
+    /// The addition takes all nine columns; the multiplexer and the bit
+    /// decomposition reuse the first four and two.
+    pub fn configure(
+        meta: &mut ConstraintSystem<Fp>,
+        advice: [Column<Advice>; 9],
+    ) -> ScalarMulConfig {
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        ScalarMulConfig {
+            add: AddChip::configure(meta, advice),
+            mux: MuxChip::configure(meta, [advice[0], advice[1], advice[2], advice[3]]),
+            bits: BitsChip::configure(meta, advice[0], advice[1]),
+            constant,
+        }
+    }
+
+    /// Loads the constant `point`.
+    pub fn constant(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        point: pallas::Affine,
+    ) -> Result<AssignedPoint, Error> {
+        let (x, y) = xy(point);
+        let add = &self.config.add;
+        layouter.assign_region(
+            || "constant point",
+            |mut region| {
+                Ok(AssignedPoint {
+                    x: region.assign_advice_from_constant(|| "x", add.x_p, 0, x)?,
+                    y: region.assign_advice_from_constant(|| "y", add.y_p, 0, y)?,
+                })
+            },
+        )
+    }
+
+    /// Returns `k * base`, where `bits` are the bits of `k`, least significant
+    /// first, as [`BitsChip::to_bits`] returns them. The bits are only checked
+    /// to be boolean.
+    pub fn mul(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        bits: &[AssignedCell<Fp, Fp>],
+        base: &AssignedPoint,
+    ) -> Result<AssignedPoint, Error> {
+        let add = AddChip::construct(self.config.add.clone());
+        let mux = MuxChip::construct(self.config.mux.clone());
+        let identity = self.constant(
+            layouter.namespace(|| "identity"),
+            pallas::Affine::identity(),
+        )?;
+
+        let mut acc = identity.clone();
+        for (i, bit) in bits.iter().enumerate().rev() {
+            let mut layouter = layouter.namespace(|| format!("bit {}", i));
+            acc = add.add(layouter.namespace(|| "double"), &acc, &acc)?;
+            let term = AssignedPoint {
+                x: mux.mux(layouter.namespace(|| "x"), bit, &identity.x, &base.x)?,
+                y: mux.mux(layouter.namespace(|| "y"), bit, &identity.y, &base.y)?,
+            };
+            acc = add.add(layouter.namespace(|| "add"), &acc, &term)?;
+        }
+        Ok(acc)
+    }
+
+    /// Returns `scalar * base`, splitting the base field cell `scalar` into
+    /// `num_bits` bits.
+    ///
+    /// `num_bits` is at most 254, below the field's 255 bits, so that the
+    /// decomposition is unique; the proof fails if `scalar` does not fit.
+    pub fn mul_scalar(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        scalar: &AssignedCell<Fp, Fp>,
+        num_bits: usize,
+        base: &AssignedPoint,
+    ) -> Result<AssignedPoint, Error> {
+        assert!(
+            num_bits < Fp::NUM_BITS as usize,
+            "{} bits is too many",
+            num_bits
+        );
+        let bits = BitsChip::construct(self.config.bits.clone()).to_bits(
+            layouter.namespace(|| "bits"),
+            scalar,
+            num_bits,
+        )?;
+        self.mul(layouter.namespace(|| "double and add"), &bits, base)
+    }
+}
+
+/// Bits of the scalars in [`MyCircuit`].
+const SCALAR_BITS: usize = 254;
+
+/// Proves knowledge of the secret key behind a public key: a private `sk` with
+/// `sk * G` equal to the public point. The instance column holds `[x, y]`, and
+/// `G` is a constant, the Pallas generator unless set otherwise.
+#[derive(Debug, Clone)]
+struct MyCircuit {
+    sk: Value<Fp>,
+    base: pallas::Affine,
+}
+
+impl MyCircuit {
+    pub fn new(sk: Fp) -> Self {
+        Self {
+            sk: Value::known(sk),
+            base: pallas::Affine::generator(),
+        }
+    }
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = (ScalarMulConfig, Column<Advice>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            sk: Value::unknown(),
+            base: self.base,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 9].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (ScalarMulChip::configure(meta, advice), advice[1], instance)
+    }
+
+    fn synthesize(
+        &self,
+        (config, scalar, instance): Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = ScalarMulChip::construct(config);
+        let base = chip.constant(layouter.namespace(|| "G"), self.base)?;
+        let sk = layouter.assign_region(
+            || "sk",
+            |mut region| region.assign_advice(|| "sk", scalar, 0, || self.sk),
+        )?;
+
+        let pk = chip.mul_scalar(layouter.namespace(|| "sk*G"), &sk, SCALAR_BITS, &base)?;
+
+        layouter.constrain_instance(pk.x.cell(), instance, 0)?;
+        layouter.constrain_instance(pk.y.cell(), instance, 1)
+    }
+}
+
+/// The public inputs for `point`.
+pub fn instances(point: pallas::Affine) -> Vec<Vec<Fp>> {
+    let (x, y) = xy(point);
+    vec![vec![x, y]]
+}
+
+/// Plain-Rust reference: `scalar * base`, with the base field element read as
+/// a scalar.
+pub fn reference(scalar: Fp, base: pallas::Affine) -> pallas::Affine {
+    let scalar = pallas::Scalar::from_repr(scalar.to_repr()).unwrap();
+    (base * scalar).into()
+}
+
+/// The circuit on the secret key 42.
+pub fn example_circuit() -> ExampleCircuit {
+    let sk = Fp::from(42);
+    let pk = reference(sk, pallas::Affine::generator());
+    ExampleCircuit::new(11, MyCircuit::new(sk), instances(pk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::group::Group};
+    use rand_core::OsRng;
+
+    fn run(circuit: &MyCircuit, pk: pallas::Affine) -> MockProver<Fp> {
+        let k = 11;
+
+        MockProver::run(k, circuit, instances(pk)).unwrap()
+    }
+
+    #[test]
+    fn scalar_mul_matches_pasta() {
+        let g = pallas::Affine::generator();
+        let top = Fp::from(2).pow([SCALAR_BITS as u64]) - Fp::ONE;
+        for sk in [Fp::ZERO, Fp::ONE, Fp::from(2), Fp::from(u64::MAX), top] {
+            run(&MyCircuit::new(sk), reference(sk, g)).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn scalar_mul_other_base() {
+        let base = pallas::Point::random(OsRng).into();
+        let sk = Fp::from(0xdead_beef);
+        let circuit = MyCircuit {
+            base,
+            ..MyCircuit::new(sk)
+        };
+        run(&circuit, reference(sk, base)).assert_satisfied();
+    }
+
+    #[test]
+    fn scalar_mul_rejects_wrong_key() {
+        let g = pallas::Affine::generator();
+        let sk = Fp::from(42);
+        let circuit = MyCircuit::new(sk);
+        for pk in [reference(sk + Fp::ONE, g), -reference(sk, g), g] {
+            assert!(run(&circuit, pk).verify().is_err());
+        }
+    }
+
+    #[test]
+    fn scalar_mul_rejects_wide_scalar() {
+        // `2^254` does not fit in the 254 bits the circuit decomposes.
+        let sk = Fp::from(2).pow([SCALAR_BITS as u64]);
+        let g = pallas::Affine::generator();
+        assert!(run(&MyCircuit::new(sk), reference(sk, g)).verify().is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use group::prime::PrimeCurveAffine;
+use halo2_gadgets::ecc::NonIdentityPoint;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    pasta::{pallas, Fp},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use super::{native::recover_r, Signature};
+use crate::{
+    curve::{self, CurveChip, CurveConfig},
+    names,
+    registry::ExampleCircuit,
+};
+
+#[derive(Debug, Clone)]
+struct EcdsaConfig {
+    pub curve: CurveConfig,
+    pub instance: Column<Instance>,
+}
+
+/// Proves knowledge of a valid Pallas-ECDSA signature on the public message
+/// hash `z` under the public key `P`. The instance column holds
+/// `[x(P), y(P), z]`.
+///
+/// The witness is the signature's point `R` and `s`. The circuit checks
+///
+/// ```text
+/// s*R == z*G + x(R)*P
+/// ```
+///
+/// which is the usual verification `R == s^-1 * (z*G + r*P)` with both sides
+/// multiplied by `s`, so no scalar inversion is needed in the circuit. `R` is
+/// a non-identity point, and `r` is read off it rather than witnessed. `G` is
+/// pinned to the Pallas generator. All three scalars are base field cells.
+#[derive(Default)]
+struct MyCircuit {
+    r: Value<pallas::Affine>,
+    s: Value<Fp>,
+    pk: Value<pallas::Affine>,
+    z: Value<Fp>,
+}
+
+impl MyCircuit {
+    /// Fills the witness from a signature on `z` under `pk`. An invalid
+    /// signature still gives a witness, which the circuit then rejects.
+    pub fn new(pk: pallas::Affine, z: Fp, signature: &Signature) -> Self {
+        let r = recover_r(pk, z, signature).unwrap_or_else(pallas::Affine::generator);
+        Self {
+            r: Value::known(r),
+            s: Value::known(signature.s),
+            pk: Value::known(pk),
+            z: Value::known(z),
+        }
+    }
+}
+
+/// The public inputs for a signature on `z` under `pk`.
+pub fn instances(pk: pallas::Affine, z: Fp) -> Vec<Vec<Fp>> {
+    let [x, y] = curve::coordinates(pk);
+    vec![vec![x, y, z]]
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = EcdsaConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 10].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        EcdsaConfig {
+            curve: CurveChip::configure(meta, advice),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = CurveChip::construct(config.curve);
+        chip.load(&mut layouter)?;
+        let ecc = chip.ecc_chip();
+
+        let g = chip.load_generator(layouter.namespace(|| "G"))?;
+        let pk = NonIdentityPoint::new(ecc.clone(), layouter.namespace(|| "P"), self.pk)?;
+        let r = NonIdentityPoint::new(ecc, layouter.namespace(|| "R"), self.r)?;
+
+        let s = chip.load_private(layouter.namespace(|| "s"), self.s)?;
+        let z = chip.load_private(layouter.namespace(|| "z"), self.z)?;
+        let s_scalar = chip.scalar(layouter.namespace(|| "s scalar"), &s)?;
+        let z_scalar = chip.scalar(layouter.namespace(|| "z scalar"), &z)?;
+        let r_x = chip.scalar(layouter.namespace(|| "r scalar"), &r.inner().x())?;
+
+        let (lhs, _) = r.mul(layouter.namespace(|| "s*R"), s_scalar)?;
+        let (zg, _) = g.mul(layouter.namespace(|| "z*G"), z_scalar)?;
+        let (rp, _) = pk.mul(layouter.namespace(|| "r*P"), r_x)?;
+        let rhs = zg.add(layouter.namespace(|| "z*G + r*P"), &rp)?;
+        lhs.constrain_equal(layouter.namespace(|| "verify"), &rhs)?;
+
+        let mut layouter = layouter.namespace(|| names::OUT);
+        let pk = pk.inner();
+        layouter.constrain_instance(pk.x().cell(), config.instance, 0)?;
+        layouter.constrain_instance(pk.y().cell(), config.instance, 1)?;
+        layouter.constrain_instance(z.cell(), config.instance, 2)
+    }
+}
+
+/// The circuit on a signature of `"hello"` under the key `sk = 42`.
+pub fn example_circuit() -> ExampleCircuit {
+    let sk = pallas::Scalar::from(42);
+    let z = super::message_hash(b"hello");
+    let signature = super::sign(sk, z, rand_core::OsRng);
+    let pk = curve::mul_generator(sk);
+    ExampleCircuit::new(11, MyCircuit::new(pk, z, &signature), instances(pk, z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ecdsa::{message_hash, native::verify, sign},
+        prover,
+    };
+    use ff::Field;
+    use halo2_proofs::dev::MockProver;
+    use rand_core::OsRng;
+
+    const K: u32 = 11;
+
+    fn keypair() -> (pallas::Scalar, pallas::Affine) {
+        let sk = pallas::Scalar::random(OsRng);
+        (sk, curve::mul_generator(sk))
+    }
+
+    fn run(pk: pallas::Affine, z: Fp, signature: &Signature) -> MockProver<Fp> {
+        MockProver::run(K, &MyCircuit::new(pk, z, signature), instances(pk, z)).unwrap()
+    }
+
+    #[test]
+    fn ecdsa_verifies() {
+        let (sk, pk) = keypair();
+        let z = message_hash(b"transfer 10 to bob");
+        let signature = sign(sk, z, OsRng);
+        assert!(verify(pk, z, &signature));
+        run(pk, z, &signature).assert_satisfied();
+    }
+
+    #[test]
+    fn ecdsa_rejects_bad_signatures() {
+        let (sk, pk) = keypair();
+        let z = message_hash(b"transfer 10 to bob");
+        let signature = sign(sk, z, OsRng);
+
+        // Another message, another key, or a tampered `s`.
+        let other = message_hash(b"transfer 99 to bob");
+        assert!(run(pk, other, &signature).verify().is_err());
+        let (_, other_pk) = keypair();
+        assert!(run(other_pk, z, &signature).verify().is_err());
+        let s = signature.s.double();
+        assert!(run(pk, z, &Signature { s, ..signature }).verify().is_err());
+    }
+
+    #[test]
+    fn ecdsa_witness_must_match_the_public_inputs() {
+        // A valid signature for one message, checked against another.
+        let (sk, pk) = keypair();
+        let z = message_hash(b"a");
+        let signature = sign(sk, z, OsRng);
+        let circuit = MyCircuit::new(pk, z, &signature);
+        let prover = MockProver::run(K, &circuit, instances(pk, message_hash(b"b"))).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn ecdsa_real_proof() {
+        let (sk, pk) = keypair();
+        let z = message_hash(b"hello");
+        let signature = sign(sk, z, OsRng);
+
+        let keys = prover::Keys::generate(K, &MyCircuit::default()).unwrap();
+        let public = instances(pk, z);
+        let proof = prover::prove(&keys, MyCircuit::new(pk, z, &signature), &[&public[0]]).unwrap();
+        prover::verify(&keys, &proof, &[&public[0]]).unwrap();
+
+        let wrong = instances(pk, message_hash(b"hullo"));
+        assert!(prover::verify(&keys, &proof, &[&wrong[0]]).is_err());
+    }
+}
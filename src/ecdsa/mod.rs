@@ -0,0 +1,26 @@
+//! Pallas-ECDSA: the ECDSA scheme over the Pallas curve, verified inside a
+//! circuit over its base field.
+//!
+//! This is not secp256k1 ECDSA, and signatures from Bitcoin or Ethereum keys
+//! cannot be checked with it. Signatures follow the usual scheme with Pallas
+//! scalars: `R = k*G`, `r = x(R)`, `s = k^-1 * (z + r*sk)`. Pallas keeps every
+//! coordinate native to the circuit's field, so verification is the
+//! `halo2_gadgets` ECC chip without any big-integer arithmetic. A secp256k1
+//! verifier would need the same equation over emulated field elements.
+//!
+//! The circuit holds `r` and `s` in base field cells. The Pallas base modulus is
+//! below the scalar modulus, so a scalar at or above it has no such cell:
+//! [`Signature::from_scalars`] rejects those signatures, and [`sign`] never
+//! makes one.
+
+mod example;
+mod native;
+
+pub(crate) use native::{message_hash, sign, Signature};
+
+use crate::registry::Example;
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "ecdsa::pallas",
+    circuit: example::example_circuit,
+}];
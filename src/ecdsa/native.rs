@@ -0,0 +1,104 @@
+use ff::{Field, FromUniformBytes};
+use group::{prime::PrimeCurveAffine, Curve};
+use halo2_proofs::pasta::{pallas, Fp};
+use rand_core::RngCore;
+use sha3::{Digest, Keccak512};
+
+use crate::curve::{base_to_scalar, coordinates, mul_generator, scalar_to_base};
+
+/// An ECDSA signature. Both halves are kept in the base field: `r` is an
+/// x-coordinate, and [`sign`] only returns an `s` below the base field's
+/// modulus, so that the circuit can load it as a base field cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub r: Fp,
+    pub s: Fp,
+}
+
+impl Signature {
+    /// A signature whose halves are given as scalars, or `None` if either is at
+    /// or above the base field's modulus and so cannot be loaded.
+    pub fn from_scalars(r: pallas::Scalar, s: pallas::Scalar) -> Option<Self> {
+        Some(Self {
+            r: scalar_to_base(r)?,
+            s: scalar_to_base(s)?,
+        })
+    }
+}
+
+/// The message hash `z`, a base field element derived from Keccak-512.
+pub fn message_hash(message: &[u8]) -> Fp {
+    Fp::from_uniform_bytes(&Keccak512::digest(message).into())
+}
+
+/// Signs the hash `z` with the secret key `sk`.
+pub fn sign(sk: pallas::Scalar, z: Fp, mut rng: impl RngCore) -> Signature {
+    loop {
+        let k = pallas::Scalar::random(&mut rng);
+        let [r, _] = coordinates(mul_generator(k));
+        let s = k.invert().unwrap_or(pallas::Scalar::ZERO)
+            * (base_to_scalar(z) + base_to_scalar(r) * sk);
+        // An `s` at or above the base modulus has probability about 2^-126.
+        match Signature::from_scalars(base_to_scalar(r), s) {
+            Some(signature) if !bool::from(r.is_zero()) && !bool::from(s.is_zero()) => {
+                return signature
+            }
+            _ => continue,
+        }
+    }
+}
+
+/// The point `R` a valid signature was made with, `s^-1 * (z*G + r*P)`, or
+/// `None` if `s` is zero or the sum is the identity.
+pub fn recover_r(pk: pallas::Affine, z: Fp, signature: &Signature) -> Option<pallas::Affine> {
+    let w = Option::<pallas::Scalar>::from(base_to_scalar(signature.s).invert())?;
+    let point = (pallas::Affine::generator() * (base_to_scalar(z) * w)
+        + pk * (base_to_scalar(signature.r) * w))
+        .to_affine();
+    (!bool::from(point.is_identity())).then_some(point)
+}
+
+/// Plain-Rust reference: whether `signature` is valid for `z` under `pk`.
+#[cfg(test)]
+pub fn verify(pk: pallas::Affine, z: Fp, signature: &Signature) -> bool {
+    recover_r(pk, z, signature).is_some_and(|point| coordinates(point)[0] == signature.r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::OsRng;
+
+    #[test]
+    fn ecdsa_native_round_trip() {
+        let sk = pallas::Scalar::random(OsRng);
+        let pk = mul_generator(sk);
+        let z = message_hash(b"hello");
+        let signature = sign(sk, z, OsRng);
+
+        assert!(verify(pk, z, &signature));
+        assert!(!verify(pk, message_hash(b"hullo"), &signature));
+        assert!(!verify(
+            mul_generator(sk + pallas::Scalar::ONE),
+            z,
+            &signature
+        ));
+        let s = signature.s + Fp::ONE;
+        assert!(!verify(pk, z, &Signature { s, ..signature }));
+    }
+
+    #[test]
+    fn ecdsa_rejects_scalars_above_the_base_modulus() {
+        let signature = sign(pallas::Scalar::from(42), message_hash(b"hello"), OsRng);
+        let r = base_to_scalar(signature.r);
+        let s = base_to_scalar(signature.s);
+        assert_eq!(Signature::from_scalars(r, s), Some(signature));
+
+        // The largest base field element fits; the base modulus itself and the
+        // largest scalar do not.
+        let max = base_to_scalar(-Fp::ONE);
+        assert!(Signature::from_scalars(r, max).is_some());
+        assert_eq!(Signature::from_scalars(r, max + pallas::Scalar::ONE), None);
+        assert_eq!(Signature::from_scalars(-pallas::Scalar::ONE, s), None);
+    }
+}
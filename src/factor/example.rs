@@ -0,0 +1,207 @@
+use crate::{
+    is_zero::{IsZeroChip, IsZeroConfig},
+    prelude::*,
+    registry::ExampleCircuit,
+};
+use halo2_proofs::pasta::Fp;
+
+/// Proves knowledge of a factorization `a * b = c` of a public `c`, keeping `a`
+/// and `b` private.
+///
+/// `a - 1` and `b - 1` go through the is-zero gadget, and the "nontrivial" gate
+/// requires both to be non-zero, so `1 * c` and `c * 1` are rejected. Over a
+/// prime field every `c` has many other factorizations (any non-zero `a` with
+/// `b = c / a`); ruling those out as well would take range checks on `a` and `b`.
+#[derive(Debug, Clone)]
+struct FactorConfig<F> {
+    pub advice: [Column<Advice>; 3],
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub a_is_one: IsZeroConfig<F>,
+    pub b_is_one: IsZeroConfig<F>,
+}
+
+#[derive(Debug, Clone)]
+struct FactorChip<F: Field> {
+    config: FactorConfig<F>,
+}
+
+impl<F: Field> FactorChip<F> {
+    pub fn construct(config: FactorConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        inverses: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> FactorConfig<F> {
+        let [col_a, col_b, col_c] = advice;
+        let selector = meta.selector();
+        meta.enable_equality(col_c);
+        meta.enable_equality(instance);
+
+        let one = Expression::Constant(F::ONE);
+        let a_is_one = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(col_a, Rotation::cur()) - one.clone(),
+            inverses[0],
+        );
+        let b_is_one = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(col_b, Rotation::cur()) - one.clone(),
+            inverses[1],
+        );
+
+        meta.create_gate("mul", |meta| {
+            //
+            // col_a | col_b | col_c | a_inv | b_inv | selector
+            //   a      b       c     (a-1)^-1 (b-1)^-1    s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a * b - c)]
+        });
+
+        meta.create_gate("nontrivial", |meta| {
+            let s = meta.query_selector(selector);
+            vec![s.clone() * a_is_one.expr(), s * b_is_one.expr()]
+        });
+
+        FactorConfig {
+            advice,
+            selector,
+            instance,
+            a_is_one,
+            b_is_one,
+        }
+    }
+
+    /// Assigns `a`, `b` and their product, and returns the product.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Value<F>,
+        b: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_a, col_b, col_c] = self.config.advice;
+        let a_is_one = IsZeroChip::construct(self.config.a_is_one.clone());
+        let b_is_one = IsZeroChip::construct(self.config.b_is_one.clone());
+
+        layouter.assign_region(
+            || "factor",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "a", col_a, 0, || a)?;
+                region.assign_advice(|| "b", col_b, 0, || b)?;
+                a_is_one.assign(&mut region, 0, a.map(|a| a - F::ONE))?;
+                b_is_one.assign(&mut region, 0, b.map(|b| b - F::ONE))?;
+
+                region.assign_advice(|| "c", col_c, 0, || a * b)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    a: Value<F>,
+    b: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = FactorConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let inverses = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        FactorChip::configure(meta, advice, inverses, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FactorChip::construct(config);
+
+        let c_cell = chip.assign(layouter.namespace(|| "factor"), self.a, self.b)?;
+
+        chip.expose_public(layouter.namespace(|| "c"), &c_cell, 0)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on 7 * 11 = 77.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit {
+        a: Value::known(Fp::from(7)),
+        b: Value::known(Fp::from(11)),
+    };
+    ExampleCircuit::new(4, circuit, vec![vec![Fp::from(77)]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn circuit(a: u64, b: u64) -> MyCircuit<Fp> {
+        MyCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        }
+    }
+
+    #[test]
+    fn factor_example() {
+        let k = 4;
+
+        let prover = MockProver::run(k, &circuit(7, 11), vec![vec![Fp::from(77)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn factor_rejects_trivial_factorization() {
+        let k = 4;
+
+        for (a, b) in [(1, 77), (77, 1)] {
+            let prover = MockProver::run(k, &circuit(a, b), vec![vec![Fp::from(77)]]).unwrap();
+            assert_constraint_fails(&prover, "nontrivial");
+        }
+    }
+
+    #[test]
+    fn factor_rejects_wrong_product() {
+        let k = 4;
+
+        let prover = MockProver::run(k, &circuit(7, 12), vec![vec![Fp::from(77)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
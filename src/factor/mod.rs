@@ -0,0 +1,8 @@
+mod example;
+
+use crate::registry::Example;
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "factor",
+    circuit: example::example_circuit,
+}];
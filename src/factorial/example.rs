@@ -0,0 +1,208 @@
+use crate::{names, prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Computes `n!` one factor per row:
+///
+/// ```text
+/// acc_0 = 1                    (0! = 1, enforced by the "first" gate)
+/// acc_{r+1} = acc_r * i_r      (i_r = r + 1, in a fixed column)
+/// ```
+///
+/// so row `r` holds `r!` and row `n` holds the public output. The factors live
+/// in a fixed column, so they are part of the circuit, not the witness.
+#[derive(Debug, Clone)]
+struct FactorialConfig {
+    pub acc: Column<Advice>,
+    pub factor: Column<Fixed>,
+    pub selector_first: Selector,
+    pub selector_step: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct FactorialChip<F: PrimeField> {
+    config: FactorialConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> FactorialChip<F> {
+    pub fn construct(config: FactorialConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        acc: Column<Advice>,
+        factor: Column<Fixed>,
+        instance: Column<Instance>,
+    ) -> FactorialConfig {
+        let selector_first = meta.selector();
+        let selector_step = meta.selector();
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::FIRST, |meta| {
+            let s = meta.query_selector(selector_first);
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![s * (acc - Expression::Constant(F::ONE))]
+        });
+
+        meta.create_gate(names::STEP, |meta| {
+            //
+            // acc      | factor | selector
+            //   acc        i         s
+            //   acc_next
+            //
+            let s = meta.query_selector(selector_step);
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+            let i = meta.query_fixed(factor);
+            vec![s * (acc_cur * i - acc_next)]
+        });
+
+        FactorialConfig {
+            acc,
+            factor,
+            selector_first,
+            selector_step,
+            instance,
+        }
+    }
+
+    /// Assigns `0!` to `n!` and returns the cell holding `n!`.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "factorial",
+            |mut region| {
+                self.config.selector_first.enable(&mut region, 0)?;
+
+                let mut acc = Value::known(F::ONE);
+                let mut acc_cell = region.assign_advice(|| "acc", self.config.acc, 0, || acc)?;
+
+                for row in 0..n {
+                    self.config.selector_step.enable(&mut region, row)?;
+
+                    let i = F::from((row + 1) as u64);
+                    region.assign_fixed(|| "i", self.config.factor, row, || Value::known(i))?;
+
+                    acc = acc.map(|acc| acc * i);
+                    acc_cell = region.assign_advice(|| "acc", self.config.acc, row + 1, || acc)?;
+                }
+
+                Ok(acc_cell)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference for `n!`.
+pub fn reference(n: u64) -> u64 {
+    (1..=n).product()
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> MyCircuit<F> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
+    type Config = FactorialConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let acc = meta.advice_column();
+        let factor = meta.fixed_column();
+        let instance = meta.instance_column();
+        FactorialChip::configure(meta, acc, factor, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FactorialChip::construct(config);
+
+        let out = chip.assign(layouter.namespace(|| "factorial"), self.n)?;
+
+        chip.expose_public(layouter.namespace(|| names::OUT), &out, 0)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on `5! = 120`.
+pub fn example_circuit() -> ExampleCircuit {
+    let out = Fp::from(reference(5));
+    ExampleCircuit::new(4, MyCircuit::<Fp>::new(5), vec![vec![out]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    #[test]
+    fn factorial_example() {
+        let k = 4;
+
+        assert_eq!(reference(5), 120);
+        let prover =
+            MockProver::run(k, &MyCircuit::<Fp>::new(5), vec![vec![Fp::from(120)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn factorial_small_n() {
+        let k = 4;
+
+        // 0! and 1! are both 1: the accumulator starts at 1, not at 0 or at i.
+        for n in [0, 1, 2] {
+            let out = Fp::from(reference(n as u64));
+            let prover = MockProver::run(k, &MyCircuit::<Fp>::new(n), vec![vec![out]]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn factorial_rejects_wrong_result() {
+        let k = 4;
+
+        // 4! and 6! are the off-by-one neighbours of 5!.
+        for out in [24, 119, 720] {
+            let prover =
+                MockProver::run(k, &MyCircuit::<Fp>::new(5), vec![vec![Fp::from(out)]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::{names, prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// A segment of the Fibonacci sequence in the example2 layout: one advice column
+/// holding `nrows` consecutive terms, with the add gate on every window of three.
+///
+/// The instance column holds the two seeds (rows 0 and 1) followed by the last
+/// two terms (rows 2 and 3). Those last two terms are all a follow-up segment
+/// needs, so a long sequence can be proven piecewise, each proof picking up from
+/// the public boundary of the previous one (see [`FibContinuation`]).
+#[derive(Debug, Clone)]
+struct SegmentConfig {
+    advice: Column<Advice>,
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct SegmentChip<F: Field> {
+    config: SegmentConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SegmentChip<F> {
+    pub fn construct(config: SegmentConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> SegmentConfig {
+        let selector = meta.selector();
+
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::ADD, |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(advice, Rotation::cur());
+            let b = meta.query_advice(advice, Rotation::next());
+            let c = meta.query_advice(advice, Rotation(2));
+            vec![s * (a + b - c)]
+        });
+
+        SegmentConfig {
+            advice,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns `nrows` terms starting from the public seeds and returns the last
+    /// two cells.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        nrows: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "segment",
+            |mut region| {
+                let seeds = load_instances(
+                    &mut region,
+                    self.config.instance,
+                    &[(self.config.advice, 0), (self.config.advice, 1)],
+                )?;
+                let (mut a_cell, mut b_cell) = (seeds[0].clone(), seeds[1].clone());
+
+                for row in 2..nrows {
+                    self.config.selector.enable(&mut region, row - 2)?;
+                    let c_cell = region.assign_advice(
+                        || "advice",
+                        self.config.advice,
+                        row,
+                        || a_cell.value().copied() + b_cell.value(),
+                    )?;
+                    a_cell = b_cell;
+                    b_cell = c_cell;
+                }
+
+                Ok((a_cell, b_cell))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// The public boundary between two proven segments: the last two terms of the
+/// earlier one, which must be the seeds of the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FibContinuation<F> {
+    pub a: F,
+    pub b: F,
+}
+
+impl<F: Field> FibContinuation<F> {
+    /// Starts a sequence from its first two terms.
+    pub fn new(a: F, b: F) -> Self {
+        Self { a, b }
+    }
+
+    /// Reads the boundary a proven segment ends on from its public inputs,
+    /// failing with `Error::InvalidInstances` if they are too short.
+    #[cfg(test)]
+    pub fn from_instance(instance: &[F]) -> Result<Self, Error> {
+        check_instance_len(instance, 4)?;
+        Ok(Self::new(instance[2], instance[3]))
+    }
+
+    /// Public inputs of the `nrows`-term segment seeded from this boundary,
+    /// computed natively.
+    pub fn segment_instance(&self, nrows: usize) -> Vec<F> {
+        let (mut a, mut b) = (self.a, self.b);
+        for _ in 2..nrows {
+            (a, b) = (b, a + b);
+        }
+        vec![self.a, self.b, a, b]
+    }
+
+    /// Whether the segment with public inputs `next` picks up exactly where this
+    /// boundary left off.
+    #[cfg(test)]
+    pub fn continues(&self, next: &[F]) -> bool {
+        check_instance_len(next, 2).is_ok() && next[0] == self.a && next[1] == self.b
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    nrows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> MyCircuit<F> {
+    pub fn new(nrows: usize) -> Self {
+        Self {
+            nrows,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = SegmentConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.nrows)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        SegmentChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SegmentChip::construct(config);
+
+        let (a_cell, b_cell) = chip.assign(layouter.namespace(|| "segment"), self.nrows)?;
+
+        chip.expose_public(layouter.namespace(|| "last but one"), &a_cell, 2)?;
+        chip.expose_public(layouter.namespace(|| "last"), &b_cell, 3)?;
+
+        Ok(())
+    }
+}
+
+/// A 10-term segment from seeds 0, 1.
+pub fn example_circuit() -> ExampleCircuit {
+    let instance = FibContinuation::new(Fp::from(0), Fp::from(1)).segment_instance(10);
+    ExampleCircuit::new(5, MyCircuit::new(10), vec![instance])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fibonacci::fib_reference;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    #[test]
+    fn fibonacci_two_linked_segments() {
+        let k = 5;
+
+        // f(0)..f(9): ten terms, two of which are the seeds.
+        let start = FibContinuation::new(Fp::from(0), Fp::from(1));
+        let first = start.segment_instance(10);
+        MockProver::run(k, &MyCircuit::new(10), vec![first.clone()])
+            .unwrap()
+            .assert_satisfied();
+
+        // f(8), f(9) as seeds, then f(10)..f(19).
+        let boundary = FibContinuation::from_instance(&first).unwrap();
+        let second = boundary.segment_instance(12);
+        MockProver::run(k, &MyCircuit::new(12), vec![second.clone()])
+            .unwrap()
+            .assert_satisfied();
+
+        assert!(boundary.continues(&second));
+        assert_eq!(boundary.b, Fp::from(fib_reference(0, 1, 9).unwrap()));
+        assert_eq!(second[3], Fp::from(fib_reference(0, 1, 19).unwrap()));
+    }
+
+    #[test]
+    fn fibonacci_rejects_unlinked_continuation() {
+        let k = 5;
+
+        let first = FibContinuation::new(Fp::from(0), Fp::from(1)).segment_instance(10);
+        let boundary = FibContinuation::from_instance(&first).unwrap();
+
+        // A perfectly valid segment that does not start where the first one ended.
+        let detached = FibContinuation::new(Fp::from(21), Fp::from(35)).segment_instance(12);
+        MockProver::run(k, &MyCircuit::new(12), vec![detached.clone()])
+            .unwrap()
+            .assert_satisfied();
+        assert!(!boundary.continues(&detached));
+
+        // Claiming the right seeds while keeping the detached outputs does not
+        // verify either.
+        let mut forged = detached;
+        forged[0] = boundary.a;
+        forged[1] = boundary.b;
+        assert!(boundary.continues(&forged));
+        assert!(!boundary.continues(&forged[..1]));
+        assert!(matches!(
+            FibContinuation::from_instance(&forged[..3]),
+            Err(Error::InvalidInstances)
+        ));
+        let prover = MockProver::run(k, &MyCircuit::new(12), vec![forged]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
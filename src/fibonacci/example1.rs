@@ -0,0 +1,689 @@
+use super::{FibIndex, FibonacciInstances};
+use crate::{
+    common::{record_value, trace_region},
+    names,
+    prelude::*,
+    range_check::table::RangeTableConfig,
+    registry::ExampleCircuit,
+};
+use halo2_proofs::pasta::Fp;
+
+/// The term exposed as the output by default.
+const OUTPUT: FibIndex = FibIndex(9);
+
+/// Three advice columns `a | b | c`, one Fibonacci step per row.
+#[derive(Debug, Clone)]
+pub struct FiboConfig {
+    pub advice: [Column<Advice>; 3],
+    pub selector: Selector,
+    /// Where the seeds `f(0)`, `f(1)` are read from (rows 0 and 1).
+    pub seed_instance: Column<Instance>,
+    /// Where the output is exposed. May be the same column as `seed_instance`.
+    pub output_instance: Column<Instance>,
+    /// Set by [`FiboChip::configure_mod`].
+    pub modulus: Option<ModConfig>,
+}
+
+/// The quotient column [`FiboChip::configure_mod`] adds.
+///
+/// Each row checks `c == a + b - M*q` instead of `c == a + b`. Since `a` and
+/// `b` are already reduced, `q` can only be 0 or 1, and lookups of `a`, `b` and
+/// `c` into a `0..M` range table force `0 <= c < M`, so the prover
+/// cannot pick a quotient that leaves `c` unreduced or wraps it around the
+/// field.
+#[derive(Debug, Clone)]
+pub struct ModConfig {
+    /// `M`.
+    pub modulus: u64,
+    pub quotient: Column<Advice>,
+}
+
+/// Assigns the sequence row by row, copying `b` and `c` of each row into `a`
+/// and `b` of the next.
+#[derive(Debug, Clone)]
+pub struct FiboChip<F: PrimeField> {
+    config: FiboConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> FiboChip<F> {
+    pub fn construct(config: FiboConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        seed_instance: Column<Instance>,
+        output_instance: Column<Instance>,
+    ) -> FiboConfig {
+        let col_a = advice[0];
+        let col_b = advice[1];
+        let col_c = advice[2];
+        let selector = meta.selector();
+        meta.enable_equality(col_a);
+        meta.enable_equality(col_b);
+        meta.enable_equality(col_c);
+        meta.enable_equality(seed_instance);
+        meta.enable_equality(output_instance);
+
+        meta.create_gate(names::ADD, |meta| {
+            //
+            // col_a | col_b | col_c | selector
+            //   a      b        c       s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a + b - c)]
+        });
+
+        FiboConfig {
+            advice: [col_a, col_b, col_c],
+            selector,
+            seed_instance,
+            output_instance,
+            modulus: None,
+        }
+    }
+
+    /// Like [`Self::configure`], but computes the sequence modulo `M`, with the
+    /// quotient of each reduction in `quotient` and `table` holding `0..M`. See
+    /// [`ModConfig`]. The caller loads `table` once per circuit.
+    pub(crate) fn configure_mod<const M: usize>(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        quotient: Column<Advice>,
+        seed_instance: Column<Instance>,
+        output_instance: Column<Instance>,
+        table: &RangeTableConfig<F, M>,
+    ) -> FiboConfig {
+        let [col_a, col_b, col_c] = advice;
+        let modulus = M as u64;
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        meta.enable_equality(col_a);
+        meta.enable_equality(col_b);
+        meta.enable_equality(col_c);
+        meta.enable_equality(seed_instance);
+        meta.enable_equality(output_instance);
+
+        meta.create_gate(names::ADD_MOD, |meta| {
+            //
+            // col_a | col_b | col_c | quotient | selector
+            //   a      b        c        q          s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            let q = meta.query_advice(quotient, Rotation::cur());
+            let m = Expression::Constant(F::from(modulus));
+            let one = Expression::Constant(F::ONE);
+            vec![
+                s.clone() * (a + b - c - m * q.clone()),
+                s * q.clone() * (one - q),
+            ]
+        });
+
+        // Every operand on an active row must already be reduced. Inactive rows
+        // look up 0, which is always in the table.
+        for column in advice {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(selector);
+                let value = meta.query_advice(column, Rotation::cur());
+                vec![(s * value, table.value)]
+            });
+        }
+
+        FiboConfig {
+            advice,
+            selector,
+            seed_instance,
+            output_instance,
+            modulus: Some(ModConfig { modulus, quotient }),
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn assign_first_row(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || names::FIRST_ROW,
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                let seeds = load_instances(
+                    &mut region,
+                    self.config.seed_instance,
+                    &[(self.config.advice[0], 0), (self.config.advice[1], 0)],
+                )?;
+                let (a_cell, b_cell) = (seeds[0].clone(), seeds[1].clone());
+
+                let c_cell = self.assign_sum(&mut region, &a_cell, &b_cell, None)?;
+
+                record_value!(&a_cell);
+                record_value!(&b_cell);
+                record_value!(&c_cell);
+                trace_region!(names::FIRST_ROW, 1);
+                Ok((a_cell, b_cell, c_cell))
+            },
+        )
+    }
+
+    pub fn assign_row(
+        &self,
+        layouter: impl Layouter<F>,
+        prev_b: &AssignedCell<F, F>,
+        prev_c: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.assign_row_with_quotient(layouter, prev_b, prev_c, None)
+    }
+
+    /// Like [`Self::assign_row`], but lets the caller override the witnessed
+    /// quotient of a chip from [`Self::configure_mod`]. `c` is then derived
+    /// from that quotient, so a wrong one produces an unreduced remainder.
+    pub fn assign_row_with_quotient(
+        &self,
+        mut layouter: impl Layouter<F>,
+        prev_b: &AssignedCell<F, F>,
+        prev_c: &AssignedCell<F, F>,
+        quotient: Option<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || names::NEXT_ROW,
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                // Copy the value from b & c in previous row to a & b in current row
+                let a_cell = prev_b.copy_advice(|| "a", &mut region, self.config.advice[0], 0)?;
+                let b_cell = prev_c.copy_advice(|| "b", &mut region, self.config.advice[1], 0)?;
+
+                let c_cell = self.assign_sum(&mut region, &a_cell, &b_cell, quotient)?;
+
+                record_value!(&c_cell);
+                trace_region!(names::NEXT_ROW, 1);
+                Ok(c_cell)
+            },
+        )
+    }
+
+    /// Witnesses `c = a + b` on row 0 of `region`, or with a modulus, `q` and
+    /// `c = a + b - M*q`, computing the honest quotient unless one is given.
+    fn assign_sum(
+        &self,
+        region: &mut Region<'_, F>,
+        a_cell: &AssignedCell<F, F>,
+        b_cell: &AssignedCell<F, F>,
+        quotient: Option<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let sum = a_cell.value().copied() + b_cell.value();
+        let Some(config) = &self.config.modulus else {
+            return region.assign_advice(|| "a + b", self.config.advice[2], 0, || sum);
+        };
+        let m = config.modulus;
+        let q = quotient.unwrap_or_else(|| sum.map(|sum| F::from(to_u64(&sum) / m)));
+        region.assign_advice(|| "q", config.quotient, 0, || q)?;
+
+        let c = sum.zip(q).map(|(sum, q)| sum - F::from(m) * q);
+        region.assign_advice(|| "c", self.config.advice[2], 0, || c)
+    }
+
+    /// Assigns `f(0)` to `f(nrows - 1)`, the first three in one row and every
+    /// later one in its own row, and returns all of them. At least `f(0)` to
+    /// `f(2)` are always assigned.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        nrows: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let (a_cell, mut prev_b, mut prev_c) =
+            self.assign_first_row(layouter.namespace(|| names::FIRST_ROW))?;
+        let mut terms = vec![a_cell, prev_b.clone(), prev_c.clone()];
+
+        for _i in 3..nrows {
+            let c_cell =
+                self.assign_row(layouter.namespace(|| names::NEXT_ROW), &prev_b, &prev_c)?;
+            terms.push(c_cell.clone());
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        Ok(terms)
+    }
+
+    /// Constrains `f(index)`, taken from `terms` (all assigned terms in order), to
+    /// `row` of `instance`. Fails if `index` is past the last assigned term.
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[AssignedCell<F, F>],
+        index: FibIndex,
+        instance: Column<Instance>,
+        row: usize,
+    ) -> Result<(), Error> {
+        let cell = &terms[index.position(terms.len())?];
+        layouter.constrain_instance(cell.cell(), instance, row)
+    }
+}
+
+/// [`super::fib_term`] for `f(n)` on the seeds `a0` and `a1`.
+pub fn reference(a0: u64, a1: u64, n: usize) -> Fp {
+    super::fib_term(Fp::from(a0), Fp::from(a1), FibIndex(n))
+}
+
+/// Computes `f(0)` to `f(n)` and exposes `f(n)`.
+pub struct MyCircuit<F> {
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> MyCircuit<F> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Rows the circuit assigns: one for `f(0)` to `f(2)`, then one per term.
+    pub fn rows(&self) -> usize {
+        self.n.max(2) - 1
+    }
+}
+
+impl<F> Default for MyCircuit<F> {
+    fn default() -> Self {
+        Self::new(OUTPUT.0)
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
+    type Config = FiboConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let instance = meta.instance_column();
+        FiboChip::configure(meta, [col_a, col_b, col_c], instance, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let output = config.output_instance;
+        // With a dedicated output column the output is its only value.
+        let output_row = if output == config.seed_instance {
+            super::OUTPUT_ROW
+        } else {
+            0
+        };
+        let chip = FiboChip::construct(config);
+
+        let terms = chip.assign(layouter.namespace(|| "fibonacci"), self.n + 1)?;
+
+        chip.expose_public(
+            layouter.namespace(|| names::OUT),
+            &terms,
+            FibIndex(self.n),
+            output,
+            output_row,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Proves `f(n)` at size `2^k` with `MockProver` on seeds 1, 1, after checking
+/// that `f(n)` fits in a `u64`. If the rows do not fit, synthesis fails with
+/// [`Error::NotEnoughRowsAvailable`].
+pub fn run(k: u32, n: usize) -> Result<(), String> {
+    let circuit = MyCircuit::<Fp>::new(n);
+    let instances = FibonacciInstances::from_seeds(1, 1, FibIndex(n))
+        .ok_or_else(|| format!("f({}) does not fit in a u64", n))?;
+    crate::registry::mock_verify(k, &circuit, instances.to_columns())
+}
+
+/// The circuit on seeds 1, 1.
+pub fn example_circuit() -> ExampleCircuit {
+    let instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).expect("f(9) fits in a u64");
+    ExampleCircuit::new(4, MyCircuit::<Fp>::new(OUTPUT.0), instances.to_columns())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        reference, run, FibIndex, FiboChip, FiboConfig, FibonacciInstances, MyCircuit, OUTPUT,
+    };
+    use crate::{names, prelude::*};
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    #[test]
+    fn fibonacci_example1() {
+        let k = 4;
+
+        // F[0], F[1] and F[9]
+        let mut instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).unwrap();
+
+        let circuit = MyCircuit::default();
+
+        let prover = MockProver::run(k, &circuit, instances.to_columns()).unwrap();
+        prover.assert_satisfied();
+
+        // The last term still holds 55, so only its copy into the instance fails.
+        instances.output += Fp::ONE;
+        assert_verify_fails_with(
+            k,
+            &circuit,
+            instances.to_columns(),
+            &[VerifyFailureKind::Permutation],
+        );
+    }
+
+    #[test]
+    fn fibonacci_example1_tampered_public_inputs() {
+        let k = 4;
+
+        let circuit = MyCircuit::default();
+        for i in 0..3 {
+            let mut columns = FibonacciInstances::from_seeds(1, 1, OUTPUT)
+                .unwrap()
+                .to_columns();
+            columns[0][i] += Fp::ONE;
+            // A changed seed is copied in and changes every later term, so the
+            // sequence stays valid and only the output no longer matches.
+            assert_verify_fails_with(k, &circuit, columns, &[VerifyFailureKind::Permutation]);
+        }
+    }
+
+    /// `MyCircuit`, but the last row adds one to its `c`, to reach an output
+    /// one too large.
+    #[derive(Default)]
+    struct ForgedCircuit;
+
+    impl Circuit<Fp> for ForgedCircuit {
+        type Config = FiboConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MyCircuit::<Fp>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = FiboChip::construct(config.clone());
+            let (_, mut prev_b, mut prev_c) =
+                chip.assign_first_row(layouter.namespace(|| names::FIRST_ROW))?;
+            for _ in 3..OUTPUT.0 {
+                let c =
+                    chip.assign_row(layouter.namespace(|| names::NEXT_ROW), &prev_b, &prev_c)?;
+                prev_b = prev_c;
+                prev_c = c;
+            }
+
+            let forged = layouter.assign_region(
+                || "forged row",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    prev_b.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                    prev_c.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                    let c = prev_b.value().copied() + prev_c.value() + Value::known(Fp::ONE);
+                    region.assign_advice(|| "c", config.advice[2], 0, || c)
+                },
+            )?;
+            layouter.constrain_instance(forged.cell(), config.output_instance, 2)
+        }
+    }
+
+    #[test]
+    fn fibonacci_example1_forged_witness() {
+        let k = 4;
+
+        // The output matches the forged last term, but its row breaks the gate.
+        let mut instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).unwrap();
+        instances.output += Fp::ONE;
+        assert_verify_fails_with(
+            k,
+            &ForgedCircuit,
+            instances.to_columns(),
+            &[VerifyFailureKind::Constraint(names::ADD)],
+        );
+    }
+
+    #[test]
+    fn fibonacci_example1_gate_degree() {
+        // s * (a + b - c)
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                MyCircuit::<Fp>::configure(meta);
+            }),
+            2
+        );
+    }
+
+    #[test]
+    fn fibonacci_example1_other_seeds() {
+        let k = 4;
+
+        let instances = FibonacciInstances::from_seeds(2, 5, OUTPUT).unwrap();
+        let circuit = MyCircuit::default();
+
+        let prover = MockProver::run(k, &circuit, instances.to_columns()).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn fibonacci_example1_short_public_input() {
+        let k = 4;
+
+        let public_input = vec![vec![Fp::from(1), Fp::from(1)]];
+        assert!(matches!(
+            FibonacciInstances::from_columns(&public_input),
+            Err(Error::InvalidInstances)
+        ));
+
+        // Read unchecked, the missing output is zero-padded: synthesis still
+        // succeeds and verification reports the mismatch instead of panicking.
+        let circuit = MyCircuit::default();
+        assert_verify_fails_with(k, &circuit, public_input, &[VerifyFailureKind::Permutation]);
+    }
+
+    #[cfg(feature = "trace")]
+    #[tracing_test::traced_test]
+    #[test]
+    fn fibonacci_example1_traces_regions() {
+        let k = 4;
+
+        let instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).unwrap();
+        MockProver::run(k, &MyCircuit::default(), instances.to_columns())
+            .unwrap()
+            .assert_satisfied();
+
+        assert!(logs_contain("region=\"first row\" rows=1"));
+        assert!(logs_contain("region=\"next row\" rows=1"));
+    }
+
+    #[cfg(feature = "debug-values")]
+    #[test]
+    fn fibonacci_example1_records_sequence() {
+        let k = 4;
+
+        let instances = FibonacciInstances::from_seeds(0, 1, OUTPUT).unwrap();
+        MockProver::run(k, &MyCircuit::default(), instances.to_columns())
+            .unwrap()
+            .assert_satisfied();
+
+        let sequence = crate::common::take_recorded_values::<Fp>();
+        let expected = [0, 1, 1, 2, 3, 5, 8, 13, 21, 34].map(Fp::from);
+        assert_eq!(sequence, expected, "{:#?}", sequence);
+        assert!(crate::common::take_recorded_values::<Fp>().is_empty());
+    }
+
+    #[test]
+    fn fibonacci_example1_lengths() {
+        let k = 5;
+
+        for n in [2, 3, 9, 10, 25, 27] {
+            run(k, n).unwrap();
+
+            let instances = FibonacciInstances::from_seeds(2, 5, FibIndex(n)).unwrap();
+            let prover = MockProver::run(k, &MyCircuit::new(n), instances.to_columns()).unwrap();
+            prover.assert_satisfied();
+        }
+        // f(0) and f(1) are the seeds, which the first row still assigns.
+        run(k, 0).unwrap();
+        run(k, 1).unwrap();
+
+        // 32 rows, less 5 blinding rows and the one above them.
+        let err = run(k, 28).unwrap_err();
+        assert_eq!(
+            err,
+            Error::NotEnoughRowsAvailable { current_k: k }.to_string()
+        );
+        let err = run(8, 94).unwrap_err();
+        assert_eq!(err, "f(94) does not fit in a u64");
+    }
+
+    #[test]
+    fn fibonacci_example1_wrong_length_output() {
+        let k = 4;
+
+        // f(9) is not the output of a circuit proving f(8).
+        let instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).unwrap();
+        assert_verify_fails_with(
+            k,
+            &MyCircuit::new(8),
+            instances.to_columns(),
+            &[VerifyFailureKind::Permutation],
+        );
+    }
+
+    /// Assigns only the first row, `f(0)` to `f(2)`, then asks for `f(3)`.
+    #[derive(Default)]
+    struct ShortCircuit;
+
+    impl Circuit<Fp> for ShortCircuit {
+        type Config = FiboConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MyCircuit::<Fp>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let instance = config.output_instance;
+            let chip = FiboChip::construct(config);
+            let (a, b, c) = chip.assign_first_row(layouter.namespace(|| names::FIRST_ROW))?;
+            chip.expose_public(
+                layouter.namespace(|| names::OUT),
+                &[a, b, c],
+                FibIndex(3),
+                instance,
+                2,
+            )
+        }
+    }
+
+    #[test]
+    fn fibonacci_example1_index_out_of_range() {
+        let k = 4;
+
+        let result = MockProver::run(k, &ShortCircuit, vec![vec![Fp::from(1), Fp::from(1)]]);
+        assert!(matches!(result, Err(Error::BoundsFailure)));
+    }
+
+    /// `MyCircuit` with the output in its own instance column.
+    #[derive(Default)]
+    struct SplitInstanceCircuit(MyCircuit<Fp>);
+
+    impl Circuit<Fp> for SplitInstanceCircuit {
+        type Config = FiboConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            let seed_instance = meta.instance_column();
+            let output_instance = meta.instance_column();
+            FiboChip::configure(meta, advice, seed_instance, output_instance)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            self.0.synthesize(config, layouter)
+        }
+    }
+
+    #[test]
+    fn fibonacci_example1_split_instances() {
+        let k = 4;
+
+        let a = Fp::from(1);
+        let b = Fp::from(1);
+        let out = reference(1, 1, OUTPUT.0);
+        let circuit = SplitInstanceCircuit::default();
+
+        let prover = MockProver::run(k, &circuit, vec![vec![a, b], vec![out]]).unwrap();
+        prover.assert_satisfied();
+
+        // The seeds are only read from the first column, and the output is only
+        // bound to the second.
+        for instances in [vec![vec![out], vec![a, b]], vec![vec![a, b, out], vec![]]] {
+            assert_verify_fails_with(k, &circuit, instances, &[VerifyFailureKind::Permutation]);
+        }
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_fibonacci1() {
+        let circuit = MyCircuit::<Fp>::default();
+        crate::plot::plot_png("fib-1-layout.png", 4, &circuit).unwrap();
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    #[ignore = "writes fib-1-layout.svg"]
+    fn plot_fibonacci1_svg() {
+        let circuit = MyCircuit::<Fp>::default();
+        crate::plot::plot_svg("fib-1-layout.svg", 4, &circuit).unwrap();
+    }
+}
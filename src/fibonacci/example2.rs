@@ -0,0 +1,649 @@
+use super::{FibIndex, FibonacciInstances};
+use crate::{common::trace_region, names, prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// The term exposed as the output by default.
+const OUTPUT: FibIndex = FibIndex(9);
+
+/// Number of terms the default circuit computes: `f(0)` to `f(9)`.
+#[cfg(test)]
+const NUM_TERMS: usize = OUTPUT.0 + 1;
+
+/// A single advice column holding one term per row.
+#[derive(Debug, Clone)]
+pub struct FiboConfig {
+    pub advice: Column<Advice>,
+    pub selector: Selector,
+    /// Where the seeds `f(0)`, `f(1)` are read from (rows 0 and 1).
+    pub seed_instance: Column<Instance>,
+    /// Where the output is exposed. May be the same column as `seed_instance`.
+    pub output_instance: Column<Instance>,
+}
+
+/// Assigns the whole sequence in one region, with the gate reading three
+/// consecutive rows.
+#[derive(Debug, Clone)]
+pub struct FiboChip<F: Field> {
+    config: FiboConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> FiboChip<F> {
+    pub fn construct(config: FiboConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        seed_instance: Column<Instance>,
+        output_instance: Column<Instance>,
+    ) -> FiboConfig {
+        let selector = meta.selector();
+
+        meta.enable_equality(advice);
+        meta.enable_equality(seed_instance);
+        meta.enable_equality(output_instance);
+
+        meta.create_gate(names::ADD, |meta| {
+            //
+            // advice | selector
+            //   a    |   s
+            //   b    |
+            //   c    |
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(advice, Rotation::cur());
+            let b = meta.query_advice(advice, Rotation::next());
+            let c = meta.query_advice(advice, Rotation(2));
+            vec![s * (a + b - c)]
+        });
+
+        FiboConfig {
+            advice,
+            selector,
+            seed_instance,
+            output_instance,
+        }
+    }
+
+    /// Assigns `f(0)` to `f(nrows - 1)`, one per row, and returns all of them.
+    ///
+    /// The values are computed up front with [`super::fib_sequence`], which runs
+    /// on the rayon thread pool with the `parallel` feature. Only that part can go
+    /// in parallel: a region takes its cells one at a time. The selectors go in
+    /// first, so a `k` too small for `nrows` fails with
+    /// [`Error::NotEnoughRowsAvailable`] before the values are computed.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        nrows: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "entire fibonacci table",
+            |mut region| {
+                // The gate on row i reaches down to row i + 2.
+                for row in 0..nrows.saturating_sub(2) {
+                    self.config.selector.enable(&mut region, row)?;
+                }
+
+                let seeds = load_instances(
+                    &mut region,
+                    self.config.seed_instance,
+                    &[(self.config.advice, 0), (self.config.advice, 1)],
+                )?;
+                let len = nrows.max(2);
+                let values = seeds[0]
+                    .value()
+                    .copied()
+                    .zip(seeds[1].value().copied())
+                    .map(|(a, b)| super::fib_sequence(a, b, len))
+                    .transpose_vec(len);
+
+                let mut terms = seeds;
+                for (row, value) in values.into_iter().enumerate().skip(2) {
+                    terms.push(region.assign_advice(
+                        || "advice",
+                        self.config.advice,
+                        row,
+                        || value,
+                    )?);
+                }
+
+                trace_region!("entire fibonacci table", nrows);
+                Ok(terms)
+            },
+        )
+    }
+
+    /// Constrains `f(index)`, taken from `terms` (all assigned terms in order), to
+    /// `row` of `instance`. Fails if `index` is past the last assigned term.
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[AssignedCell<F, F>],
+        index: FibIndex,
+        instance: Column<Instance>,
+        row: usize,
+    ) -> Result<(), Error> {
+        let cell = &terms[index.position(terms.len())?];
+        layouter.constrain_instance(cell.cell(), instance, row)
+    }
+}
+
+/// [`super::fib_term`] for `f(n)` on the seeds `a0` and `a1`.
+pub fn reference(a0: u64, a1: u64, n: usize) -> Fp {
+    super::fib_term(Fp::from(a0), Fp::from(a1), FibIndex(n))
+}
+
+/// Computes `f(0)` to `f(n)`, one per row, and exposes `f(n)`.
+pub struct MyCircuit<F> {
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> MyCircuit<F> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Rows the circuit assigns: one per term, and at least the two seeds.
+    pub fn rows(&self) -> usize {
+        self.n.max(1) + 1
+    }
+}
+
+impl<F> Default for MyCircuit<F> {
+    fn default() -> Self {
+        Self::new(OUTPUT.0)
+    }
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = FiboConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        FiboChip::configure(meta, advice, instance, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let output = config.output_instance;
+        // With a dedicated output column the output is its only value.
+        let output_row = if output == config.seed_instance {
+            super::OUTPUT_ROW
+        } else {
+            0
+        };
+        let chip = FiboChip::construct(config);
+
+        let terms = chip.assign(layouter.namespace(|| "entire table"), self.n + 1)?;
+
+        chip.expose_public(
+            layouter.namespace(|| names::OUT),
+            &terms,
+            FibIndex(self.n),
+            output,
+            output_row,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Proves `f(n)` at size `2^k` with `MockProver` on seeds 1, 1, after checking
+/// that `f(n)` fits in a `u64`. If the rows do not fit, synthesis fails with
+/// [`Error::NotEnoughRowsAvailable`].
+pub fn run(k: u32, n: usize) -> Result<(), String> {
+    let circuit = MyCircuit::<Fp>::new(n);
+    let instances = FibonacciInstances::from_seeds(1, 1, FibIndex(n))
+        .ok_or_else(|| format!("f({}) does not fit in a u64", n))?;
+    crate::registry::mock_verify(k, &circuit, instances.to_columns())
+}
+
+/// The circuit on seeds 1, 1.
+pub fn example_circuit() -> ExampleCircuit {
+    let instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).expect("f(9) fits in a u64");
+    ExampleCircuit::new(4, MyCircuit::<Fp>::new(OUTPUT.0), instances.to_columns())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        reference, run, FibIndex, FiboChip, FiboConfig, FibonacciInstances, MyCircuit, NUM_TERMS,
+        OUTPUT,
+    };
+    use crate::{names, prelude::*, prover};
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    #[test]
+    fn fibonacci_example2() {
+        let k = 4;
+
+        // F[0], F[1] and F[9]
+        let mut instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).unwrap();
+
+        let circuit = MyCircuit::default();
+
+        let prover = MockProver::run(k, &circuit, instances.to_columns()).unwrap();
+        prover.assert_satisfied();
+
+        // The last term still holds 55, so only its copy into the instance fails.
+        instances.output += Fp::ONE;
+        assert_verify_fails_with(
+            k,
+            &circuit,
+            instances.to_columns(),
+            &[VerifyFailureKind::Permutation],
+        );
+    }
+
+    #[test]
+    fn fibonacci_example2_tampered_public_inputs() {
+        let k = 4;
+
+        let circuit = MyCircuit::default();
+        for i in 0..3 {
+            let mut columns = FibonacciInstances::from_seeds(1, 1, OUTPUT)
+                .unwrap()
+                .to_columns();
+            columns[0][i] += Fp::ONE;
+            // A changed seed is copied in and changes every later term, so the
+            // sequence stays valid and only the output no longer matches.
+            assert_verify_fails_with(k, &circuit, columns, &[VerifyFailureKind::Permutation]);
+        }
+    }
+
+    /// `MyCircuit`, but the last term is one too large.
+    #[derive(Default)]
+    struct ForgedCircuit;
+
+    impl Circuit<Fp> for ForgedCircuit {
+        type Config = FiboConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MyCircuit::<Fp>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let forged = layouter.assign_region(
+                || "forged table",
+                |mut region| {
+                    for row in 0..NUM_TERMS - 2 {
+                        config.selector.enable(&mut region, row)?;
+                    }
+                    let seeds = load_instances(
+                        &mut region,
+                        config.seed_instance,
+                        &[(config.advice, 0), (config.advice, 1)],
+                    )?;
+                    let (mut a, mut b) = (seeds[0].value().copied(), seeds[1].value().copied());
+                    let mut last = None;
+                    for row in 2..NUM_TERMS {
+                        let mut c = a + b;
+                        if row == NUM_TERMS - 1 {
+                            c = c + Value::known(Fp::ONE);
+                        }
+                        last = Some(region.assign_advice(|| "advice", config.advice, row, || c)?);
+                        a = b;
+                        b = c;
+                    }
+                    Ok(last.unwrap())
+                },
+            )?;
+            layouter.constrain_instance(forged.cell(), config.output_instance, 2)
+        }
+    }
+
+    #[test]
+    fn fibonacci_example2_forged_witness() {
+        let k = 4;
+
+        // The output matches the forged last term, but the gate reaching it fails.
+        let mut instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).unwrap();
+        instances.output += Fp::ONE;
+        assert_verify_fails_with(
+            k,
+            &ForgedCircuit,
+            instances.to_columns(),
+            &[VerifyFailureKind::Constraint(names::ADD)],
+        );
+    }
+
+    #[test]
+    fn fibonacci_example2_gate_degree() {
+        // s * (a + b - c), over three rotations of one column.
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                MyCircuit::<Fp>::configure(meta);
+            }),
+            2
+        );
+    }
+
+    #[test]
+    fn fibonacci_example2_other_seeds() {
+        let k = 4;
+
+        let instances = FibonacciInstances::from_seeds(2, 5, OUTPUT).unwrap();
+        let circuit = MyCircuit::default();
+
+        let prover = MockProver::run(k, &circuit, instances.to_columns()).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn fibonacci_example2_short_public_input() {
+        let k = 4;
+
+        let public_input = vec![vec![Fp::from(1), Fp::from(1)]];
+        assert!(matches!(
+            FibonacciInstances::from_columns(&public_input),
+            Err(Error::InvalidInstances)
+        ));
+
+        // Read unchecked, the missing output is zero-padded: synthesis still
+        // succeeds and verification reports the mismatch instead of panicking.
+        let circuit = MyCircuit::default();
+        assert_verify_fails_with(k, &circuit, public_input, &[VerifyFailureKind::Permutation]);
+    }
+
+    #[test]
+    fn fibonacci_example2_fuzz() {
+        let k = 4;
+
+        let instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).unwrap();
+
+        let report = fuzz_witness_mutations(k, MyCircuit::default, instances.to_columns(), 20);
+
+        assert_eq!(report.sites.len(), 10);
+        // No cell is under-constrained: rows 0 and 1 are copies of the public seeds,
+        // and every later row is the `c` of an enabled add gate.
+        assert!(
+            report.unconstrained.is_empty(),
+            "{:#?}",
+            report.unconstrained
+        );
+    }
+
+    #[test]
+    fn fibonacci_example2_real_proofs() {
+        let k = 4;
+
+        // Only the first iteration runs key generation.
+        for (a, b) in [(1, 1), (2, 5), (0, 1)] {
+            let keys = prover::key_cache()
+                .get_or_generate(k, &MyCircuit::<Fp>::default())
+                .unwrap();
+            let columns = FibonacciInstances::from_seeds(a, b, OUTPUT)
+                .unwrap()
+                .to_columns();
+            let instance = &columns[0];
+
+            let proof = prover::prove(&keys, MyCircuit::default(), &[instance]).unwrap();
+            prover::verify(&keys, &proof, &[instance]).unwrap();
+        }
+    }
+
+    #[test]
+    fn fibonacci_example2_key_cache_tells_lengths_apart() {
+        let k = 5;
+
+        // Same type and size, but the selector runs over a different number of
+        // rows.
+        let cache = prover::KeyCache::new();
+        let short = cache.get_or_generate(k, &MyCircuit::<Fp>::new(9)).unwrap();
+        let long = cache.get_or_generate(k, &MyCircuit::<Fp>::new(20)).unwrap();
+        assert!(!std::sync::Arc::ptr_eq(&short, &long));
+        assert_eq!(cache.len(), 2);
+
+        let again = cache.get_or_generate(k, &MyCircuit::<Fp>::new(9)).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&short, &again));
+    }
+
+    #[test]
+    fn fibonacci_example2_verify_only() {
+        let k = 4;
+
+        let columns = FibonacciInstances::from_seeds(1, 1, OUTPUT)
+            .unwrap()
+            .to_columns();
+        let instance = &columns[0];
+
+        let (params, vk_bytes, proof) = {
+            let keys = prover::Keys::generate(k, &MyCircuit::<Fp>::default()).unwrap();
+            let proof = prover::prove(&keys, MyCircuit::default(), &[instance]).unwrap();
+            let vk_bytes = prover::serialize_vk(keys.vk());
+            (keys.params, vk_bytes, proof)
+        };
+
+        // The proving key and the original verifying key are gone.
+        let circuit = MyCircuit::<Fp>::default();
+        prover::verify_only(&params, &circuit, &vk_bytes, &proof, &[instance]).unwrap();
+        let mut wrong = instance.clone();
+        wrong[2] += Fp::ONE;
+        assert!(matches!(
+            prover::verify_only(&params, &circuit, &vk_bytes, &proof, &[&wrong]),
+            Err(prover::VkError::Plonk(Error::ConstraintSystemFailure))
+        ));
+    }
+
+    #[test]
+    fn fibonacci_example2_transcripts() {
+        let k = 4;
+
+        let keys = prover::key_cache()
+            .get_or_generate(k, &MyCircuit::<Fp>::default())
+            .unwrap();
+        let instance = [Fp::from(1), Fp::from(1), reference(1, 1, OUTPUT.0)];
+
+        let blake =
+            prover::prove_with::<prover::Blake2b, _>(&keys, MyCircuit::default(), &[&instance])
+                .unwrap();
+        let keccak =
+            prover::prove_with::<prover::Keccak256, _>(&keys, MyCircuit::default(), &[&instance])
+                .unwrap();
+
+        prover::verify_with::<prover::Blake2b>(&keys, &blake, &[&instance]).unwrap();
+        prover::verify_with::<prover::Keccak256>(&keys, &keccak, &[&instance]).unwrap();
+
+        // The challenges differ, so neither proof checks out under the other hash.
+        assert!(prover::verify_with::<prover::Keccak256>(&keys, &blake, &[&instance]).is_err());
+        assert!(prover::verify_with::<prover::Blake2b>(&keys, &keccak, &[&instance]).is_err());
+    }
+
+    #[cfg(feature = "trace")]
+    #[tracing_test::traced_test]
+    #[test]
+    fn fibonacci_example2_traces_regions() {
+        let k = 4;
+
+        let instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).unwrap();
+        MockProver::run(k, &MyCircuit::default(), instances.to_columns())
+            .unwrap()
+            .assert_satisfied();
+
+        assert!(logs_contain("region=\"entire fibonacci table\" rows=10"));
+    }
+
+    #[test]
+    fn fibonacci_example2_rows() {
+        let k = 4;
+
+        // 16 rows, less 5 blinding rows and the one above them.
+        assert_eq!(usable_rows::<Fp, MyCircuit<Fp>>(k), 10);
+        run(k, 9).unwrap();
+        assert!(run(k, 10).is_err());
+        let err = run(k, 30).unwrap_err();
+        assert_eq!(
+            err,
+            Error::NotEnoughRowsAvailable { current_k: k }.to_string()
+        );
+        run(7, 90).unwrap();
+
+        // f(99) does not fit in a u64, but the rows run out before it matters.
+        let instances = vec![vec![Fp::ONE; 3]];
+        assert!(matches!(
+            MockProver::run(k, &MyCircuit::new(99), instances.clone()),
+            Err(Error::NotEnoughRowsAvailable { current_k: 4 })
+        ));
+        let keys = prover::Keys::generate(k, &MyCircuit::default()).unwrap();
+        let columns: Vec<&[Fp]> = instances.iter().map(|column| &column[..]).collect();
+        assert!(matches!(
+            prover::prove(&keys, MyCircuit::new(99), &columns),
+            Err(Error::NotEnoughRowsAvailable { current_k: 4 })
+        ));
+    }
+
+    #[test]
+    fn fibonacci_example2_lengths() {
+        let k = 5;
+
+        for n in [0, 1, 2, 3, 10, 25] {
+            run(k, n).unwrap();
+
+            let instances = FibonacciInstances::from_seeds(2, 5, FibIndex(n)).unwrap();
+            let prover = MockProver::run(k, &MyCircuit::new(n), instances.to_columns()).unwrap();
+            prover.assert_satisfied();
+        }
+        assert!(run(k, 26).is_err());
+
+        // f(9) is not the output of a circuit proving f(8).
+        let instances = FibonacciInstances::from_seeds(1, 1, OUTPUT).unwrap();
+        assert_verify_fails_with(
+            k,
+            &MyCircuit::new(8),
+            instances.to_columns(),
+            &[VerifyFailureKind::Permutation],
+        );
+    }
+
+    /// `MyCircuit`, but asking for `f(NUM_TERMS)`, one past the last row.
+    #[derive(Default)]
+    struct PastTheEndCircuit;
+
+    impl Circuit<Fp> for PastTheEndCircuit {
+        type Config = FiboConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MyCircuit::<Fp>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let instance = config.output_instance;
+            let chip = FiboChip::construct(config);
+            let terms = chip.assign(layouter.namespace(|| "entire table"), NUM_TERMS)?;
+            chip.expose_public(
+                layouter.namespace(|| names::OUT),
+                &terms,
+                FibIndex(NUM_TERMS),
+                instance,
+                2,
+            )
+        }
+    }
+
+    #[test]
+    fn fibonacci_example2_index_out_of_range() {
+        let k = 4;
+
+        let result = MockProver::run(k, &PastTheEndCircuit, vec![vec![Fp::from(1), Fp::from(1)]]);
+        assert!(matches!(result, Err(Error::BoundsFailure)));
+    }
+
+    /// `MyCircuit` with the output in its own instance column.
+    #[derive(Default)]
+    struct SplitInstanceCircuit(MyCircuit<Fp>);
+
+    impl Circuit<Fp> for SplitInstanceCircuit {
+        type Config = FiboConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = meta.advice_column();
+            let seed_instance = meta.instance_column();
+            let output_instance = meta.instance_column();
+            FiboChip::configure(meta, advice, seed_instance, output_instance)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            self.0.synthesize(config, layouter)
+        }
+    }
+
+    #[test]
+    fn fibonacci_example2_split_instances() {
+        let k = 4;
+
+        let a = Fp::from(1);
+        let b = Fp::from(1);
+        let out = reference(1, 1, OUTPUT.0);
+        let circuit = SplitInstanceCircuit::default();
+
+        let prover = MockProver::run(k, &circuit, vec![vec![a, b], vec![out]]).unwrap();
+        prover.assert_satisfied();
+
+        // The seeds are only read from the first column, and the output is only
+        // bound to the second.
+        for instances in [vec![vec![out], vec![a, b]], vec![vec![a, b, out], vec![]]] {
+            assert_verify_fails_with(k, &circuit, instances, &[VerifyFailureKind::Permutation]);
+        }
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn plot_fibonacci2() {
+        let circuit = MyCircuit::<Fp>::default();
+        crate::plot::plot_png("fib-2-layout.png", 4, &circuit).unwrap();
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    #[ignore = "writes fib-2-layout.svg"]
+    fn plot_fibonacci2_svg() {
+        let circuit = MyCircuit::<Fp>::default();
+        crate::plot::plot_svg("fib-2-layout.svg", 4, &circuit).unwrap();
+    }
+}
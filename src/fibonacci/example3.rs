@@ -0,0 +1,333 @@
+use super::{FibIndex, FibonacciInstances};
+use crate::{names, prelude::*, registry::ExampleCircuit};
+
+/// Same three-column layout as example1, but the first row has its own gate.
+///
+/// `selector_first` is only ever enabled on the first row and reads both seeds
+/// straight from the instance column, so no instance cells are copied into the
+/// step regions. `selector_step` covers every later row. The two selectors are
+/// never enabled on the same row.
+#[derive(Debug, Clone)]
+struct FiboConfig {
+    pub advice: [Column<Advice>; 3],
+    pub selector_first: Selector,
+    pub selector_step: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct FiboChip<F: Field> {
+    config: FiboConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> FiboChip<F> {
+    pub fn construct(config: FiboConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+    ) -> FiboConfig {
+        let col_a = advice[0];
+        let col_b = advice[1];
+        let col_c = advice[2];
+        let selector_first = meta.selector();
+        let selector_step = meta.selector();
+        meta.enable_equality(col_a);
+        meta.enable_equality(col_b);
+        meta.enable_equality(col_c);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::INIT, |meta| {
+            //
+            // col_a | col_b | col_c | selector_first | instance
+            //   a      b        c          s              f(0)
+            //                                             f(1)
+            //
+            let s = meta.query_selector(selector_first);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            let f0 = meta.query_instance(instance, Rotation::cur());
+            let f1 = meta.query_instance(instance, Rotation::next());
+            vec![
+                s.clone() * (a.clone() - f0),
+                s.clone() * (b.clone() - f1),
+                s * (a + b - c),
+            ]
+        });
+
+        meta.create_gate(names::STEP, |meta| {
+            //
+            // col_a | col_b | col_c | selector_step
+            //   a      b        c          s
+            //
+            let s = meta.query_selector(selector_step);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a + b - c)]
+        });
+
+        FiboConfig {
+            advice: [col_a, col_b, col_c],
+            selector_first,
+            selector_step,
+            instance,
+        }
+    }
+
+    /// Assigns `f(0)`, `f(1)` and `f(2)` as private witnesses; the `init` gate ties
+    /// the seeds to instance rows 0 and 1.
+    #[allow(clippy::type_complexity)]
+    pub fn assign_first_row(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Value<F>,
+        b: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || names::FIRST_ROW,
+            |mut region| {
+                self.config.selector_first.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "f(0)", self.config.advice[0], 0, || a)?;
+                let b_cell = region.assign_advice(|| "f(1)", self.config.advice[1], 0, || b)?;
+                let c_cell =
+                    region.assign_advice(|| "a + b", self.config.advice[2], 0, || a + b)?;
+
+                Ok((b_cell, c_cell))
+            },
+        )
+    }
+
+    pub fn assign_row(
+        &self,
+        mut layouter: impl Layouter<F>,
+        prev_b: &AssignedCell<F, F>,
+        prev_c: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || names::NEXT_ROW,
+            |mut region| {
+                self.config.selector_step.enable(&mut region, 0)?;
+
+                prev_b.copy_advice(|| "a", &mut region, self.config.advice[0], 0)?;
+                prev_c.copy_advice(|| "b", &mut region, self.config.advice[1], 0)?;
+
+                region.assign_advice(
+                    || "c",
+                    self.config.advice[2],
+                    0,
+                    || prev_b.value().copied() + prev_c.value(),
+                )
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// [`super::fib_term`] for `f(n)` on the seeds `a0` and `a1`.
+#[cfg(test)]
+pub fn reference<F: PrimeField>(a0: u64, a1: u64, n: usize) -> F {
+    super::fib_term(F::from(a0), F::from(a1), FibIndex(n))
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    a: Value<F>,
+    b: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = FiboConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let instance = meta.instance_column();
+        FiboChip::configure(meta, [col_a, col_b, col_c], instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FiboChip::construct(config);
+
+        let (mut prev_b, mut prev_c) =
+            chip.assign_first_row(layouter.namespace(|| names::FIRST_ROW), self.a, self.b)?;
+
+        for _i in 3..10 {
+            let c_cell =
+                chip.assign_row(layouter.namespace(|| names::NEXT_ROW), &prev_b, &prev_c)?;
+            prev_b = prev_c;
+            prev_c = c_cell;
+        }
+
+        chip.expose_public(
+            layouter.namespace(|| names::OUT),
+            &prev_c,
+            super::OUTPUT_ROW,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on seeds 1, 1.
+pub fn example_circuit() -> ExampleCircuit {
+    let instances = FibonacciInstances::from_seeds(1, 1, FibIndex(9)).expect("f(9) fits in a u64");
+    let circuit = MyCircuit {
+        a: Value::known(instances.seed_a),
+        b: Value::known(instances.seed_b),
+    };
+    ExampleCircuit::new(4, circuit, instances.to_columns())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{metadata, FailureLocation, MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    #[test]
+    fn fibonacci_example3() {
+        let k = 4;
+
+        // F[0], F[1] and F[9]
+        let instances = FibonacciInstances::from_seeds(1, 1, FibIndex(9)).unwrap();
+
+        let circuit = MyCircuit {
+            a: Value::known(instances.seed_a),
+            b: Value::known(instances.seed_b),
+        };
+
+        let prover = MockProver::run(k, &circuit, instances.to_columns()).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn init_gate_rejects_wrong_seed() {
+        let k = 4;
+
+        // The witnessed seeds disagree with the public ones, which only the init
+        // gate can notice.
+        let circuit = MyCircuit {
+            a: Value::known(Fp::from(2)),
+            b: Value::known(Fp::from(1)),
+        };
+        let instances = FibonacciInstances {
+            seed_a: Fp::from(1),
+            seed_b: Fp::from(1),
+            output: reference(2, 1, 9),
+        };
+
+        let prover = MockProver::run(k, &circuit, instances.to_columns()).unwrap();
+        assert_constraint_fails(&prover, names::INIT);
+    }
+
+    #[test]
+    fn init_failure_is_reported_in_first_row_region() {
+        let k = 4;
+
+        let circuit = MyCircuit {
+            a: Value::known(Fp::from(2)),
+            b: Value::known(Fp::from(1)),
+        };
+        let instances = FibonacciInstances::from_seeds(1, 1, FibIndex(9)).unwrap();
+
+        let prover = MockProver::run(k, &circuit, instances.to_columns()).unwrap();
+        let failures = prover.verify().unwrap_err();
+        let region = failures.iter().find_map(|failure| match failure {
+            VerifyFailure::ConstraintNotSatisfied {
+                location: FailureLocation::InRegion { region, .. },
+                ..
+            } => Some(region.clone()),
+            _ => None,
+        });
+        // The first row is the first region the circuit assigns.
+        assert_eq!(region, Some(metadata::Region::from((0, names::FIRST_ROW))));
+    }
+
+    /// Replaces one step of the recurrence with a wrong sum.
+    struct BadStepCircuit(MyCircuit<Fp>);
+
+    impl Circuit<Fp> for BadStepCircuit {
+        type Config = FiboConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self(MyCircuit::default())
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MyCircuit::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = FiboChip::construct(config.clone());
+            let (prev_b, prev_c) =
+                chip.assign_first_row(layouter.namespace(|| names::FIRST_ROW), self.0.a, self.0.b)?;
+
+            layouter.assign_region(
+                || "bad row",
+                |mut region| {
+                    config.selector_step.enable(&mut region, 0)?;
+                    prev_b.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                    prev_c.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                    region.assign_advice(
+                        || "c",
+                        config.advice[2],
+                        0,
+                        || prev_b.value().copied() + prev_c.value() + Value::known(Fp::ONE),
+                    )
+                },
+            )?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn step_gate_rejects_wrong_sum() {
+        let k = 4;
+
+        let a = Fp::from(1);
+        let b = Fp::from(1);
+        let circuit = BadStepCircuit(MyCircuit {
+            a: Value::known(a),
+            b: Value::known(b),
+        });
+
+        let prover = MockProver::run(k, &circuit, vec![vec![a, b, Fp::ZERO]]).unwrap();
+        assert_constraint_fails(&prover, names::STEP);
+    }
+}
@@ -0,0 +1,306 @@
+use super::{FibIndex, FibonacciInstances};
+use crate::{names, prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// The term exposed as the output by default.
+const OUTPUT: FibIndex = FibIndex(9);
+
+/// example2's single column, with the gate written around the middle term:
+///
+/// ```text
+/// s * (a(prev) + a(cur) - a(next))
+/// ```
+///
+/// It constrains the same three consecutive rows as example2's
+/// `s * (a(cur) + a(next) - a(next + 1))`, only anchored one row lower. So the
+/// selector moves one row down with it: example2 enables rows `0..n - 1`, the
+/// first of each triple, and this enables rows `1..n`, the middle one. Enabling
+/// row 0 here would reach above the region.
+#[derive(Debug, Clone)]
+struct FiboConfig {
+    advice: Column<Advice>,
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct FiboChip<F: Field> {
+    config: FiboConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> FiboChip<F> {
+    fn construct(config: FiboConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> FiboConfig {
+        let selector = meta.selector();
+
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::ADD, |meta| {
+            //
+            // advice | selector
+            //   a    |
+            //   b    |   s
+            //   c    |
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(advice, Rotation::prev());
+            let b = meta.query_advice(advice, Rotation::cur());
+            let c = meta.query_advice(advice, Rotation::next());
+            vec![s * (a + b - c)]
+        });
+
+        FiboConfig {
+            advice,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns `f(0)` to `f(nrows - 1)`, one per row, and returns all of them.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        nrows: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "entire fibonacci table",
+            |mut region| {
+                // The gate on row i reaches up to row i - 1 and down to row i + 1,
+                // so neither the first nor the last row can hold it.
+                for row in 1..nrows.saturating_sub(1) {
+                    self.config.selector.enable(&mut region, row)?;
+                }
+
+                let seeds = load_instances(
+                    &mut region,
+                    self.config.instance,
+                    &[(self.config.advice, 0), (self.config.advice, 1)],
+                )?;
+                let len = nrows.max(2);
+                let values = seeds[0]
+                    .value()
+                    .copied()
+                    .zip(seeds[1].value().copied())
+                    .map(|(a, b)| super::fib_sequence(a, b, len))
+                    .transpose_vec(len);
+
+                let mut terms = seeds;
+                for (row, value) in values.into_iter().enumerate().skip(2) {
+                    terms.push(region.assign_advice(
+                        || "advice",
+                        self.config.advice,
+                        row,
+                        || value,
+                    )?);
+                }
+                Ok(terms)
+            },
+        )
+    }
+
+    /// Constrains `f(index)`, taken from `terms`, to the output instance row.
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[AssignedCell<F, F>],
+        index: FibIndex,
+    ) -> Result<(), Error> {
+        let cell = &terms[index.position(terms.len())?];
+        layouter.constrain_instance(cell.cell(), self.config.instance, super::OUTPUT_ROW)
+    }
+}
+
+/// Computes `f(0)` to `f(n)`, one per row, and exposes `f(n)`.
+struct MyCircuit<F> {
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> MyCircuit<F> {
+    fn new(n: usize) -> Self {
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F> Default for MyCircuit<F> {
+    fn default() -> Self {
+        Self::new(OUTPUT.0)
+    }
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = FiboConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        FiboChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FiboChip::construct(config);
+
+        let terms = chip.assign(layouter.namespace(|| "entire table"), self.n + 1)?;
+        chip.expose_public(layouter.namespace(|| names::OUT), &terms, FibIndex(self.n))
+    }
+}
+
+/// The circuit on seeds 1, 1.
+pub fn example_circuit() -> ExampleCircuit {
+    let instances = FibonacciInstances::new(Fp::ONE, Fp::ONE, OUTPUT).to_columns();
+    ExampleCircuit::new(4, MyCircuit::<Fp>::default(), instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+
+    fn instances(a: u64, b: u64, n: FibIndex) -> Vec<Vec<Fp>> {
+        FibonacciInstances::new(Fp::from(a), Fp::from(b), n).to_columns()
+    }
+
+    #[test]
+    fn fibonacci_example4() {
+        let k = 4;
+
+        for (a, b) in [(1, 1), (0, 1), (2, 5)] {
+            let prover =
+                MockProver::run(k, &MyCircuit::<Fp>::default(), instances(a, b, OUTPUT)).unwrap();
+            prover.assert_satisfied();
+        }
+        for n in [1, 2, 3] {
+            let prover =
+                MockProver::run(k, &MyCircuit::<Fp>::new(n), instances(1, 1, FibIndex(n))).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fibonacci_example4_wrong_output() {
+        let mut columns = instances(1, 1, OUTPUT);
+        columns[0][super::super::OUTPUT_ROW] += Fp::ONE;
+        assert_verify_fails_with(
+            4,
+            &MyCircuit::<Fp>::default(),
+            columns,
+            &[VerifyFailureKind::Permutation],
+        );
+    }
+
+    /// `MyCircuit` with example2's selector placement, `0..n - 1`, and a forged
+    /// last term.
+    #[derive(Default)]
+    struct Example2Placement;
+
+    impl Circuit<Fp> for Example2Placement {
+        type Config = FiboConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MyCircuit::<Fp>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let nrows = OUTPUT.0 + 1;
+            let last = layouter.assign_region(
+                || "shifted table",
+                |mut region| {
+                    for row in 0..nrows - 2 {
+                        config.selector.enable(&mut region, row)?;
+                    }
+                    let seeds = load_instances(
+                        &mut region,
+                        config.instance,
+                        &[(config.advice, 0), (config.advice, 1)],
+                    )?;
+                    let terms = seeds[0]
+                        .value()
+                        .copied()
+                        .zip(seeds[1].value().copied())
+                        .map(|(a, b)| super::super::fib_sequence(a, b, nrows))
+                        .transpose_vec(nrows);
+                    let mut last = None;
+                    for (row, term) in terms.into_iter().enumerate().skip(2) {
+                        // Nothing covers the last row any more, so forge it.
+                        let term = if row == nrows - 1 {
+                            term + Value::known(Fp::ONE)
+                        } else {
+                            term
+                        };
+                        last =
+                            Some(region.assign_advice(|| "advice", config.advice, row, || term)?);
+                    }
+                    Ok(last.unwrap())
+                },
+            )?;
+            layouter.constrain_instance(last.cell(), config.instance, super::super::OUTPUT_ROW)
+        }
+    }
+
+    #[test]
+    fn fibonacci_example4_needs_shifted_selectors() {
+        let mut columns = instances(1, 1, OUTPUT);
+        columns[0][super::super::OUTPUT_ROW] += Fp::ONE;
+
+        // The forged last term goes unnoticed: no gate covers it and it matches
+        // the output. What fails is the gate on row 0, whose `prev` wraps around to
+        // the last row of the circuit, which was never assigned.
+        let k = 4;
+        let prover = MockProver::run(k, &Example2Placement, columns).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(
+            failures.iter().any(|failure| matches!(
+                failure,
+                VerifyFailure::CellNotAssigned { offset, .. } if *offset == (1 << k) - 1
+            )),
+            "{:#?}",
+            failures
+        );
+        assert!(!failures
+            .iter()
+            .any(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+    }
+
+    #[test]
+    fn fibonacci_example4_gate_degree() {
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                MyCircuit::<Fp>::configure(meta);
+            }),
+            2
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use super::{fib_term, FibIndex};
+use crate::{names, prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// The term exposed as the output by default.
+const OUTPUT: FibIndex = FibIndex(9);
+
+/// Instance row of the output, the only public value.
+const OUTPUT_ROW: usize = 0;
+
+/// example2's single column, with the seeds fixed at keygen instead of read
+/// from the instance column: `f(0) = 0` and `f(1) = 1` always, and only the
+/// output is public.
+///
+/// The constants live in a fixed column enabled with `enable_constant`, and
+/// each seed is copy-constrained to its constant, in one of two ways:
+///
+/// - `f(0)` with `assign_advice_from_constant`, which assigns the cell and adds
+///   the copy in one call;
+/// - `f(1)` with a plain witness followed by `constrain_constant`, which only
+///   adds the copy, for cells that already exist (e.g. returned by another
+///   chip).
+///
+/// Either way a prover cannot start the sequence anywhere else: a different
+/// seed breaks the copy constraint, not a gate.
+#[derive(Debug, Clone)]
+struct FiboConfig {
+    advice: Column<Advice>,
+    selector: Selector,
+    instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct FiboChip<F: Field> {
+    config: FiboConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> FiboChip<F> {
+    fn construct(config: FiboConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        constant: Column<Fixed>,
+        instance: Column<Instance>,
+    ) -> FiboConfig {
+        let selector = meta.selector();
+
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate(names::ADD, |meta| {
+            //
+            // advice | selector
+            //   a    |   s
+            //   b    |
+            //   c    |
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(advice, Rotation::cur());
+            let b = meta.query_advice(advice, Rotation::next());
+            let c = meta.query_advice(advice, Rotation(2));
+            vec![s * (a + b - c)]
+        });
+
+        FiboConfig {
+            advice,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns `f(0)` to `f(nrows - 1)`, one per row, from the constant seeds.
+    /// `f1` is the witness for `f(1)`, which has to equal 1.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        f1: Value<F>,
+        nrows: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "entire fibonacci table",
+            |mut region| {
+                for row in 0..nrows.saturating_sub(2) {
+                    self.config.selector.enable(&mut region, row)?;
+                }
+
+                let a = region.assign_advice_from_constant(
+                    || "f(0)",
+                    self.config.advice,
+                    0,
+                    F::ZERO,
+                )?;
+                let b = region.assign_advice(|| "f(1)", self.config.advice, 1, || f1)?;
+                region.constrain_constant(b.cell(), F::ONE)?;
+
+                let (mut a_value, mut b_value) = (a.value().copied(), b.value().copied());
+                let mut terms = vec![a, b];
+                for row in 2..nrows {
+                    let c = a_value + b_value;
+                    terms.push(region.assign_advice(|| "advice", self.config.advice, row, || c)?);
+                    a_value = b_value;
+                    b_value = c;
+                }
+                Ok(terms)
+            },
+        )
+    }
+
+    /// Constrains `f(index)`, taken from `terms`, to the output instance row.
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[AssignedCell<F, F>],
+        index: FibIndex,
+    ) -> Result<(), Error> {
+        let cell = &terms[index.position(terms.len())?];
+        layouter.constrain_instance(cell.cell(), self.config.instance, OUTPUT_ROW)
+    }
+}
+
+/// The public inputs for `f(n)` from the seeds 0 and 1: just the output.
+fn instances(n: FibIndex) -> Vec<Vec<Fp>> {
+    vec![vec![fib_term(Fp::ZERO, Fp::ONE, n)]]
+}
+
+/// Computes `f(0) = 0` to `f(n)`, one per row, and exposes `f(n)`.
+struct MyCircuit<F> {
+    n: usize,
+    f1: Value<F>,
+}
+
+impl<F: Field> MyCircuit<F> {
+    fn new(n: usize) -> Self {
+        Self {
+            n,
+            f1: Value::known(F::ONE),
+        }
+    }
+}
+
+impl<F: Field> Default for MyCircuit<F> {
+    fn default() -> Self {
+        Self::new(OUTPUT.0)
+    }
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = FiboConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            n: self.n,
+            f1: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let constant = meta.fixed_column();
+        let instance = meta.instance_column();
+        FiboChip::configure(meta, advice, constant, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FiboChip::construct(config);
+
+        let terms = chip.assign(layouter.namespace(|| "entire table"), self.f1, self.n + 1)?;
+        chip.expose_public(layouter.namespace(|| names::OUT), &terms, FibIndex(self.n))
+    }
+}
+
+/// The circuit, exposing only `f(9) = 34`.
+pub fn example_circuit() -> ExampleCircuit {
+    ExampleCircuit::new(4, MyCircuit::<Fp>::default(), instances(OUTPUT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspect::inspect;
+    use halo2_proofs::dev::MockProver;
+
+    #[test]
+    fn example_constant() {
+        let k = 4;
+
+        assert_eq!(instances(OUTPUT), vec![vec![Fp::from(34)]]);
+        for n in [1, 2, OUTPUT.0] {
+            let prover =
+                MockProver::run(k, &MyCircuit::<Fp>::new(n), instances(FibIndex(n))).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn example_constant_wrong_output() {
+        // f(9) from the seeds 1, 1, which this circuit cannot start from.
+        let columns = vec![vec![Fp::from(55)]];
+        assert_verify_fails_with(
+            4,
+            &MyCircuit::<Fp>::default(),
+            columns,
+            &[VerifyFailureKind::Permutation],
+        );
+    }
+
+    #[test]
+    fn example_constant_rejects_other_seeds() {
+        // Every term follows from f(1) = 2 and the output matches, so the gates
+        // hold; only the copy of f(1) to its constant fails.
+        let circuit = MyCircuit {
+            n: OUTPUT.0,
+            f1: Value::known(Fp::from(2)),
+        };
+        let columns = vec![vec![fib_term(Fp::ZERO, Fp::from(2), OUTPUT)]];
+        assert_verify_fails_with(4, &circuit, columns, &[VerifyFailureKind::Permutation]);
+    }
+
+    #[test]
+    fn example_constant_structure() {
+        let structure = inspect::<Fp, MyCircuit<Fp>>();
+        assert_eq!(structure.instance_columns, 1);
+        assert_eq!(structure.constants.len(), 1);
+    }
+}
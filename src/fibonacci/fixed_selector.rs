@@ -0,0 +1,260 @@
+use super::{FibIndex, FibonacciInstances};
+use crate::{names, prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// The term exposed as the output by default.
+const OUTPUT: FibIndex = FibIndex(9);
+
+/// Gate name of the recurrence.
+const STEP: &str = "weighted step";
+
+/// example2's single column, switched on and off by a fixed column `q` that the
+/// chip assigns itself instead of a `Selector`:
+///
+/// ```text
+/// q * (q * a + b - c)
+/// ```
+///
+/// A `Selector` can only be 0 or 1, so halo2 is free to combine several of them
+/// into one fixed column. A fixed column that is queried directly is a "complex
+/// selector": it can hold any value, and the gate can use that value as a
+/// coefficient too. Here `q = 0` switches the gate off, `q = 1` gives the
+/// Fibonacci step `c = a + b`, and `q = 2` the Jacobsthal step `c = 2a + b`, all
+/// with the same gate. The price is a degree-3 gate, and a fixed column that is
+/// never shared with other selectors.
+#[derive(Debug, Clone)]
+struct FiboConfig {
+    advice: Column<Advice>,
+    q: Column<Fixed>,
+    instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct FiboChip<F: Field> {
+    config: FiboConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> FiboChip<F> {
+    fn construct(config: FiboConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> FiboConfig {
+        let q = meta.fixed_column();
+
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        meta.create_gate(STEP, |meta| {
+            //
+            // advice | q
+            //   a    | q
+            //   b    |
+            //   c    |
+            //
+            let q = meta.query_fixed(q);
+            let a = meta.query_advice(advice, Rotation::cur());
+            let b = meta.query_advice(advice, Rotation::next());
+            let c = meta.query_advice(advice, Rotation(2));
+            vec![q.clone() * (q * a + b - c)]
+        });
+
+        FiboConfig {
+            advice,
+            q,
+            instance,
+        }
+    }
+
+    /// Assigns `nrows` terms of `f(i + 2) = coeff * f(i) + f(i + 1)`, one per
+    /// row, and returns all of them.
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        coeff: F,
+        nrows: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "weighted table",
+            |mut region| {
+                // The "selector": the coefficient on every row the gate covers.
+                // Rows left unassigned read as zero, which switches the gate off.
+                for row in 0..nrows.saturating_sub(2) {
+                    region.assign_fixed(|| "q", self.config.q, row, || Value::known(coeff))?;
+                }
+
+                let seeds = load_instances(
+                    &mut region,
+                    self.config.instance,
+                    &[(self.config.advice, 0), (self.config.advice, 1)],
+                )?;
+                let (mut a, mut b) = (seeds[0].value().copied(), seeds[1].value().copied());
+                let mut terms = seeds;
+                for row in 2..nrows {
+                    let c = a * Value::known(coeff) + b;
+                    terms.push(region.assign_advice(|| "term", self.config.advice, row, || c)?);
+                    a = b;
+                    b = c;
+                }
+                Ok(terms)
+            },
+        )
+    }
+
+    /// Constrains `f(index)`, taken from `terms`, to the output instance row.
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[AssignedCell<F, F>],
+        index: FibIndex,
+    ) -> Result<(), Error> {
+        let cell = &terms[index.position(terms.len())?];
+        layouter.constrain_instance(cell.cell(), self.config.instance, super::OUTPUT_ROW)
+    }
+}
+
+/// `f(n)` of `f(i + 2) = coeff * f(i) + f(i + 1)` with seeds `a` and `b`.
+fn reference<F: Field>(coeff: F, a: F, b: F, n: usize) -> F {
+    let (mut a, mut b) = (a, b);
+    for _ in 0..n {
+        (a, b) = (b, coeff * a + b);
+    }
+    a
+}
+
+/// Computes `f(0)` to `f(n)` with the coefficient `COEFF`, and exposes `f(n)`.
+struct MyCircuit<F, const COEFF: u64> {
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F, const COEFF: u64> MyCircuit<F, COEFF> {
+    fn new(n: usize) -> Self {
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F, const COEFF: u64> Default for MyCircuit<F, COEFF> {
+    fn default() -> Self {
+        Self::new(OUTPUT.0)
+    }
+}
+
+impl<F: PrimeField, const COEFF: u64> Circuit<F> for MyCircuit<F, COEFF> {
+    type Config = FiboConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        FiboChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FiboChip::construct(config);
+
+        let terms = chip.assign(
+            layouter.namespace(|| "entire table"),
+            F::from(COEFF),
+            self.n + 1,
+        )?;
+        chip.expose_public(layouter.namespace(|| names::OUT), &terms, FibIndex(self.n))
+    }
+}
+
+/// The Fibonacci circuit on seeds 1, 1.
+pub fn example_circuit() -> ExampleCircuit {
+    let instances = FibonacciInstances::new(Fp::ONE, Fp::ONE, OUTPUT).to_columns();
+    ExampleCircuit::new(4, MyCircuit::<Fp, 1>::default(), instances)
+}
+
+/// The same gate as a Jacobsthal recurrence, `q = 2`, on seeds 0, 1.
+pub fn jacobsthal_circuit() -> ExampleCircuit {
+    let out = reference(Fp::from(2), Fp::ZERO, Fp::ONE, OUTPUT.0);
+    let instances = FibonacciInstances::new(Fp::ZERO, Fp::ONE, OUTPUT)
+        .expect_output(out)
+        .to_columns();
+    ExampleCircuit::new(4, MyCircuit::<Fp, 2>::default(), instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fibonacci::fib_term, inspect::inspect};
+    use halo2_proofs::dev::MockProver;
+
+    #[test]
+    fn fixed_selector_fibonacci() {
+        let k = 4;
+
+        for (a, b) in [(1, 1), (0, 1), (2, 5)] {
+            let (a, b) = (Fp::from(a), Fp::from(b));
+            assert_eq!(reference(Fp::ONE, a, b, OUTPUT.0), fib_term(a, b, OUTPUT));
+            let instances = FibonacciInstances::new(a, b, OUTPUT).to_columns();
+            let prover = MockProver::run(k, &MyCircuit::<Fp, 1>::default(), instances).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fixed_selector_coefficient() {
+        let k = 4;
+
+        // 0, 1, 1, 3, 5, 11, 21, 43, 85, 171
+        let out = reference(Fp::from(2), Fp::ZERO, Fp::ONE, OUTPUT.0);
+        assert_eq!(out, Fp::from(171));
+        let instances = FibonacciInstances::new(Fp::ZERO, Fp::ONE, OUTPUT).expect_output(out);
+        let prover =
+            MockProver::run(k, &MyCircuit::<Fp, 2>::default(), instances.to_columns()).unwrap();
+        prover.assert_satisfied();
+
+        // The Fibonacci term is wrong for q = 2, and the other way round.
+        let fib = FibonacciInstances::new(Fp::ZERO, Fp::ONE, OUTPUT);
+        assert_verify_fails_with(
+            k,
+            &MyCircuit::<Fp, 2>::default(),
+            fib.to_columns(),
+            &[VerifyFailureKind::Permutation],
+        );
+        assert_verify_fails_with(
+            k,
+            &MyCircuit::<Fp, 1>::default(),
+            instances.to_columns(),
+            &[VerifyFailureKind::Permutation],
+        );
+    }
+
+    #[test]
+    fn fixed_selector_has_no_simple_selectors() {
+        let structure = inspect::<Fp, MyCircuit<Fp, 1>>();
+        assert_eq!(structure.selectors, 0);
+        assert_eq!(structure.fixed_columns, 1);
+
+        // q * (q * a + b - c)
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                MyCircuit::<Fp, 1>::configure(meta);
+            }),
+            3
+        );
+    }
+}
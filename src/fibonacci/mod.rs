@@ -0,0 +1,338 @@
+//! Fibonacci circuits. [`example1`] and [`example2`] are public, so their chips
+//! and circuits can be reused outside this crate.
+
+mod continuation;
+/// Three advice columns, one step per row and region.
+pub mod example1;
+/// One advice column, the whole sequence in a single region.
+pub mod example2;
+mod example3;
+/// One advice column, with the gate reading one row back and one row ahead.
+mod example4;
+/// One advice column, seeded from constants, with only the output public.
+mod example_constant;
+/// One advice column, switched by a fixed column instead of a `Selector`.
+mod fixed_selector;
+mod modular;
+
+use ff::Field;
+use halo2_proofs::{pasta::Fp, plonk::Error};
+
+use crate::{common::check_instance_len, registry::Example};
+
+pub(crate) const EXAMPLES: &[Example] = &[
+    Example {
+        name: "fibonacci::example1",
+        circuit: example1::example_circuit,
+    },
+    Example {
+        name: "fibonacci::example2",
+        circuit: example2::example_circuit,
+    },
+    Example {
+        name: "fibonacci::example3",
+        circuit: example3::example_circuit,
+    },
+    Example {
+        name: "fibonacci::example4",
+        circuit: example4::example_circuit,
+    },
+    Example {
+        name: "fibonacci::example_constant",
+        circuit: example_constant::example_circuit,
+    },
+    Example {
+        name: "fibonacci::fixed_selector",
+        circuit: fixed_selector::example_circuit,
+    },
+    Example {
+        name: "fibonacci::fixed_selector_jacobsthal",
+        circuit: fixed_selector::jacobsthal_circuit,
+    },
+    Example {
+        name: "fibonacci::modular",
+        circuit: modular::example_circuit,
+    },
+    Example {
+        name: "fibonacci::continuation",
+        circuit: continuation::example_circuit,
+    },
+];
+
+/// Index `n` of a Fibonacci term `f(n)`, where `f(0)` and `f(1)` are the seeds.
+///
+/// The examples assign `f(0), f(1), ...` in order, so the index is also the
+/// term's position among the assigned cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FibIndex(pub usize);
+
+impl FibIndex {
+    /// Position of `f(n)` among the `num_terms` terms a circuit computes, or
+    /// `Error::BoundsFailure` if the circuit stops before `f(n)`.
+    pub fn position(self, num_terms: usize) -> Result<usize, Error> {
+        if self.0 < num_terms {
+            Ok(self.0)
+        } else {
+            Err(Error::BoundsFailure)
+        }
+    }
+}
+
+/// Instance row of the output in examples 1 to 3, right after the two seeds.
+pub const OUTPUT_ROW: usize = 2;
+
+/// Public inputs of examples 1 to 3: the seeds `f(0)`, `f(1)` and the exposed
+/// output, in that order in a single instance column.
+///
+/// ```
+/// # use halo2_proofs::pasta::Fp;
+/// # use halo2examples::fibonacci::{FibIndex, FibonacciInstances};
+/// let instances = FibonacciInstances::new(Fp::from(1), Fp::from(1), FibIndex(9));
+/// assert_eq!(instances.output, Fp::from(55));
+/// assert_eq!(
+///     instances.expect_output(Fp::from(56)).to_columns(),
+///     vec![vec![Fp::from(1), Fp::from(1), Fp::from(56)]]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FibonacciInstances {
+    pub seed_a: Fp,
+    pub seed_b: Fp,
+    pub output: Fp,
+}
+
+impl FibonacciInstances {
+    /// The instances for seeds `a` and `b` with `f(index)` as the output,
+    /// computed in the field so it wraps the way the circuits do.
+    pub fn new(a: Fp, b: Fp, index: FibIndex) -> Self {
+        Self {
+            seed_a: a,
+            seed_b: b,
+            output: fib_term(a, b, index),
+        }
+    }
+
+    /// Exposes `output` instead, whether or not the seeds lead to it.
+    pub fn expect_output(self, output: Fp) -> Self {
+        Self { output, ..self }
+    }
+
+    /// The instances for seeds `a` and `b` with `f(index)` as the output, or
+    /// `None` if that term overflows a `u64` (see [`fib_reference`]).
+    pub fn from_seeds(a: u64, b: u64, index: FibIndex) -> Option<Self> {
+        Some(Self {
+            seed_a: Fp::from(a),
+            seed_b: Fp::from(b),
+            output: Fp::from(fib_reference(a, b, index.0)?),
+        })
+    }
+
+    /// The instance columns, as `MockProver::run` and the real prover expect them.
+    pub fn to_columns(&self) -> Vec<Vec<Fp>> {
+        let mut column = vec![self.seed_a, self.seed_b];
+        column.insert(OUTPUT_ROW, self.output);
+        vec![column]
+    }
+
+    /// Reads the instances back from [`Self::to_columns`]'s layout, failing with
+    /// `Error::InvalidInstances` unless there is exactly one column of three values.
+    pub fn from_columns(columns: &[Vec<Fp>]) -> Result<Self, Error> {
+        let [column] = columns else {
+            return Err(Error::InvalidInstances);
+        };
+        check_instance_len(column, 3)?;
+        let &[seed_a, seed_b, output] = column.as_slice() else {
+            return Err(Error::InvalidInstances);
+        };
+        Ok(Self {
+            seed_a,
+            seed_b,
+            output,
+        })
+    }
+}
+
+/// `f(index)` for the seeds `f(0) = a` and `f(1) = b`, in the field, so it wraps
+/// the way the circuits do instead of overflowing. This is the plain-Rust
+/// reference for the value the Fibonacci circuits expose; their `reference`
+/// functions call it with their own seeds.
+pub fn fib_term<F: Field>(a: F, b: F, index: FibIndex) -> F {
+    fib_pair(a, b, index.0).0
+}
+
+/// `(f(n), f(n + 1))` for the seeds `a` and `b`, in `O(log n)` field operations.
+///
+/// Fast doubling gives the standard terms `F(n)` and `F(n + 1)`, seeded with 0
+/// and 1, and any other seeds follow linearly:
+///
+/// ```text
+/// f(n)     = a * F(n - 1) + b * F(n) = a * (F(n + 1) - F(n)) + b * F(n)
+/// f(n + 1) = a * F(n)     + b * F(n + 1)
+/// ```
+fn fib_pair<F: Field>(a: F, b: F, n: usize) -> (F, F) {
+    // (F(m), F(m + 1)) for the bits of n read so far.
+    let (mut x, mut y) = (F::ZERO, F::ONE);
+    for bit in (0..usize::BITS - n.leading_zeros()).rev() {
+        let double = x * (y.double() - x);
+        let double_next = x.square() + y.square();
+        (x, y) = if (n >> bit) & 1 == 1 {
+            (double_next, double + double_next)
+        } else {
+            (double, double_next)
+        };
+    }
+    (a * (y - x) + b * x, a * x + b * y)
+}
+
+/// Terms per chunk when [`fib_sequence`] runs in parallel.
+const CHUNK: usize = 1 << 12;
+
+/// `f(0)` to `f(len - 1)` for the seeds `a` and `b`, in the field.
+///
+/// With the `parallel` feature the terms are filled in chunks on the rayon
+/// thread pool. Each chunk starts from its own first two terms, found with
+/// [`fib_pair`], so no chunk waits for the one before it.
+pub fn fib_sequence<F: Field>(a: F, b: F, len: usize) -> Vec<F> {
+    let mut terms = vec![F::ZERO; len];
+    let fill = |(i, chunk): (usize, &mut [F])| {
+        let (mut x, mut y) = fib_pair(a, b, i * CHUNK);
+        for term in chunk {
+            *term = x;
+            (x, y) = (y, x + y);
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        terms.par_chunks_mut(CHUNK).enumerate().for_each(fill);
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        terms.chunks_mut(CHUNK).enumerate().for_each(fill);
+    }
+    terms
+}
+
+/// Computes the `n`-th term of the Fibonacci sequence seeded with `f(0) = a0` and
+/// `f(1) = a1` in plain Rust.
+///
+/// Returns `None` if the term does not fit in a `u64`, rather than silently wrapping
+/// the way the field arithmetic in the circuits would.
+pub fn fib_reference(a0: u64, a1: u64, n: usize) -> Option<u64> {
+    let (mut a, mut b) = (a0, a1);
+    if n == 0 {
+        return Some(a);
+    }
+    for _ in 1..n {
+        let c = a.checked_add(b)?;
+        a = b;
+        b = c;
+    }
+    Some(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fib_reference, fib_sequence, fib_term, FibIndex, FibonacciInstances, CHUNK};
+    use halo2_proofs::{pasta::Fp, plonk::Error};
+
+    #[test]
+    fn reference_small_terms() {
+        assert_eq!(fib_reference(0, 1, 0), Some(0));
+        assert_eq!(fib_reference(0, 1, 1), Some(1));
+        assert_eq!(fib_reference(0, 1, 10), Some(55));
+        assert_eq!(fib_reference(1, 1, 9), Some(55));
+    }
+
+    #[test]
+    fn index_position() {
+        assert_eq!(FibIndex(0).position(10).unwrap(), 0);
+        assert_eq!(FibIndex(9).position(10).unwrap(), 9);
+        assert!(matches!(
+            FibIndex(10).position(10),
+            Err(Error::BoundsFailure)
+        ));
+    }
+
+    #[test]
+    fn instances_round_trip() {
+        let instances = FibonacciInstances::from_seeds(1, 1, FibIndex(9)).unwrap();
+        assert_eq!(instances.output, Fp::from(55));
+
+        let columns = instances.to_columns();
+        assert_eq!(columns, vec![vec![Fp::from(1), Fp::from(1), Fp::from(55)]]);
+        assert_eq!(
+            FibonacciInstances::from_columns(&columns).unwrap(),
+            instances
+        );
+    }
+
+    #[test]
+    fn instances_reject_wrong_layout() {
+        let one = Fp::from(1);
+        for columns in [
+            vec![],
+            vec![vec![one, one]],
+            vec![vec![one, one, one, one]],
+            vec![vec![one, one], vec![one]],
+        ] {
+            assert!(matches!(
+                FibonacciInstances::from_columns(&columns),
+                Err(Error::InvalidInstances)
+            ));
+        }
+        assert_eq!(FibonacciInstances::from_seeds(0, 1, FibIndex(94)), None);
+    }
+
+    #[test]
+    fn new_places_the_output() {
+        let one = Fp::from(1);
+        let instances = FibonacciInstances::new(one, one, FibIndex(9));
+        assert_eq!(instances.to_columns(), vec![vec![one, one, Fp::from(55)]]);
+        assert_eq!(
+            Some(instances.clone()),
+            FibonacciInstances::from_seeds(1, 1, FibIndex(9))
+        );
+        assert_eq!(
+            instances.expect_output(Fp::from(7)).to_columns()[0][2],
+            Fp::from(7)
+        );
+    }
+
+    #[test]
+    fn term_wraps_in_the_field() {
+        assert_eq!(
+            fib_term(Fp::from(0), Fp::from(1), FibIndex(93)),
+            Fp::from(fib_reference(0, 1, 93).unwrap())
+        );
+        let f92 = Fp::from(fib_reference(0, 1, 92).unwrap());
+        let f93 = Fp::from(fib_reference(0, 1, 93).unwrap());
+        assert_eq!(fib_term(Fp::from(0), Fp::from(1), FibIndex(94)), f92 + f93);
+    }
+
+    #[test]
+    fn sequence_matches_the_reference_across_chunks() {
+        let len = 2 * CHUNK + 3;
+        let (a, b) = (Fp::from(3), Fp::from(7));
+        let terms = fib_sequence(a, b, len);
+
+        assert_eq!(terms.len(), len);
+        assert_eq!(&terms[..3], &[a, b, a + b]);
+        for window in terms.windows(3) {
+            assert_eq!(window[0] + window[1], window[2]);
+        }
+        for n in [0, 1, 50, CHUNK - 1, CHUNK, CHUNK + 1, len - 1] {
+            assert_eq!(terms[n], fib_term(a, b, FibIndex(n)), "f({})", n);
+        }
+        assert!(fib_sequence(a, b, 0).is_empty());
+        assert_eq!(fib_sequence(a, b, 1), vec![a]);
+    }
+
+    #[test]
+    fn reference_overflow() {
+        // F(93) is the largest Fibonacci number that fits in a u64.
+        assert_eq!(fib_reference(0, 1, 93), Some(12_200_160_415_121_876_738));
+        assert_eq!(fib_reference(0, 1, 94), None);
+    }
+}
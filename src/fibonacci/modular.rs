@@ -0,0 +1,188 @@
+use super::{
+    example1::{FiboChip, FiboConfig},
+    FibIndex, FibonacciInstances,
+};
+use crate::{names, prelude::*, range_check::table::RangeTableConfig, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// `f(n)` mod `m` on the seeds `a0` and `a1`, reduced at every step like
+/// [`super::fib_term`] in a field of size `m`.
+pub fn reference(a0: u64, a1: u64, m: u64, n: usize) -> u64 {
+    let (mut a, mut b) = (a0 % m, a1 % m);
+    for _ in 0..n {
+        (a, b) = (b, (a + b) % m);
+    }
+    a
+}
+
+/// Fibonacci modulo a fixed `M`: example1's chip with a modulus, which adds a
+/// column holding the quotient of each reduction. See
+/// [`super::example1::ModConfig`].
+#[derive(Default)]
+struct MyCircuit<F, const M: usize>(PhantomData<F>);
+
+impl<F: PrimeField, const M: usize> Circuit<F> for MyCircuit<F, M> {
+    type Config = (FiboConfig, RangeTableConfig<F, M>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let quotient = meta.advice_column();
+        let instance = meta.instance_column();
+        let table = RangeTableConfig::configure(meta);
+        let config = FiboChip::configure_mod(
+            meta,
+            [col_a, col_b, col_c],
+            quotient,
+            instance,
+            instance,
+            &table,
+        );
+        (config, table)
+    }
+
+    fn synthesize(
+        &self,
+        (config, table): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let instance = config.output_instance;
+        table.load(&mut layouter)?;
+        let chip = FiboChip::construct(config);
+
+        let terms = chip.assign(layouter.namespace(|| "fibonacci"), 10)?;
+
+        chip.expose_public(
+            layouter.namespace(|| names::OUT),
+            &terms,
+            FibIndex(9),
+            instance,
+            super::OUTPUT_ROW,
+        )
+    }
+}
+
+/// The circuit on seeds 1, 1, modulo 7.
+pub fn example_circuit() -> ExampleCircuit {
+    let instances = FibonacciInstances::new(Fp::ONE, Fp::ONE, FibIndex(9))
+        .expect_output(Fp::from(reference(1, 1, 7, 9)))
+        .to_columns();
+    ExampleCircuit::new(4, MyCircuit::<Fp, 7>(PhantomData), instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    #[test]
+    fn fibonacci_mod_7() {
+        let k = 4;
+
+        for (a, b) in [(1, 1), (0, 1), (3, 6)] {
+            let out = Fp::from(reference(a, b, 7, 9));
+            let circuit = MyCircuit::<Fp, 7>(PhantomData);
+            let prover =
+                MockProver::run(k, &circuit, vec![vec![Fp::from(a), Fp::from(b), out]]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fibonacci_mod_7_gate_degree() {
+        // s * q * (1 - q) is the highest-degree constraint.
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                MyCircuit::<Fp, 7>::configure(meta);
+            }),
+            3
+        );
+    }
+
+    #[test]
+    fn fibonacci_mod_7_rejects_unreduced_seed() {
+        let k = 4;
+
+        // 8 == 1 (mod 7), but seeds have to be reduced already.
+        let out = Fp::from(reference(8, 1, 7, 9));
+        let circuit = MyCircuit::<Fp, 7>(PhantomData);
+        let prover =
+            MockProver::run(k, &circuit, vec![vec![Fp::from(8), Fp::from(1), out]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    /// Runs the first row honestly, then reduces the next sum with the given
+    /// quotient.
+    struct BadQuotientCircuit(Fp);
+
+    impl Circuit<Fp> for BadQuotientCircuit {
+        type Config = (FiboConfig, RangeTableConfig<Fp, 7>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self(self.0)
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MyCircuit::<Fp, 7>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            (config, table): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            table.load(&mut layouter)?;
+            let chip = FiboChip::construct(config);
+
+            let (_, prev_b, prev_c) =
+                chip.assign_first_row(layouter.namespace(|| names::FIRST_ROW))?;
+            chip.assign_row_with_quotient(
+                layouter.namespace(|| "bad row"),
+                &prev_b,
+                &prev_c,
+                Some(Value::known(self.0)),
+            )?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn fibonacci_mod_7_rejects_bad_quotient() {
+        let k = 4;
+
+        // Seeds 3, 6: f(2) = 9 mod 7 = 2 (q = 1), f(3) = 6 + 2 = 8 mod 7 = 1 (q = 1).
+        // Claiming q = 0 for f(3) leaves c = 8, outside [0, 7).
+        let prover = MockProver::run(
+            k,
+            &BadQuotientCircuit(Fp::ZERO),
+            vec![vec![Fp::from(3), Fp::from(6), Fp::ZERO]],
+        )
+        .unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, halo2_proofs::dev::VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn fibonacci_mod_7_rejects_non_boolean_quotient() {
+        let k = 4;
+
+        // q = 2 for f(3) = 8 gives c = 8 - 14, and q is not a bit.
+        let prover = MockProver::run(
+            k,
+            &BadQuotientCircuit(Fp::from(2)),
+            vec![vec![Fp::from(3), Fp::from(6), Fp::ZERO]],
+        )
+        .unwrap();
+        assert_constraint_fails(&prover, names::ADD_MOD);
+    }
+}
@@ -0,0 +1,544 @@
+//! Signed fixed-point arithmetic with `SCALE` fractional bits.
+//!
+//! A real number `x` is stored as the integer `round(x * 2^SCALE)`, negative
+//! ones as `p - |v|` like the `signed_range` example does. Every value a chip
+//! outputs is range-checked to the signed 32-bit range `[-2^31, 2^31)`, so
+//! products of two values stay far below the field modulus and the gates can
+//! treat them as integers.
+
+use crate::{prelude::*, range_check::table::RangeTableConfig};
+
+/// Width of a stored value, sign included.
+pub const VALUE_BITS: usize = 32;
+
+/// A cell holding a fixed-point value in `[-2^31, 2^31)`.
+#[derive(Debug, Clone)]
+pub struct AssignedFixed<F: Field> {
+    pub cell: AssignedCell<F, F>,
+}
+
+impl<F: PrimeField> AssignedFixed<F> {
+    pub fn value(&self) -> Value<i64> {
+        self.cell.value().map(|v| from_field(*v))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FixedPointConfig<F: PrimeField> {
+    /// `[a, b, c, r, gap]` in an arithmetic row, `[v, byte_0, .., byte_3]` in a
+    /// range row.
+    pub advice: [Column<Advice>; 5],
+    pub q_add: Selector,
+    pub q_mul: Selector,
+    pub q_div: Selector,
+    pub q_rescale: Selector,
+    pub q_bytes: Selector,
+    pub q_unsigned: Selector,
+    pub q_signed: Selector,
+    pub(crate) table: RangeTableConfig<F, 256>,
+}
+
+/// Addition, multiplication and division of fixed-point values with `SCALE`
+/// fractional bits, `0 < SCALE < 32`.
+///
+/// Products and quotients are truncated to `SCALE` bits by rounding down, as an
+/// arithmetic shift would. A multiplication row proves
+///
+/// ```text
+/// a * b == c * 2^SCALE + r
+/// gap   == r * 2^(32 - SCALE)
+/// ```
+///
+/// and a division row, for a positive divisor `b`,
+///
+/// ```text
+/// a * 2^SCALE == c * b + r
+/// gap         == b - r - 1
+/// ```
+///
+/// A rescale row is a multiplication row without `b`, proving
+/// `a == c * 2^SCALE + r`.
+///
+/// In each, `c` is range-checked as a signed value and `r` and `gap` as unsigned
+/// 32-bit integers. That bounds the remainder `r` to `[0, 2^SCALE)` and
+/// `[0, b)` respectively, so `c` is the rounded-down result; a zero or
+/// negative divisor leaves no valid `r`.
+///
+/// A range row splits `v`, or `v + 2^31` for a signed check, into four bytes,
+/// each looked up in a `0..256` table.
+#[derive(Debug, Clone)]
+pub struct FixedPointChip<F: PrimeField, const SCALE: usize> {
+    config: FixedPointConfig<F>,
+}
+
+impl<F: PrimeField, const SCALE: usize> FixedPointChip<F, SCALE> {
+    pub fn construct(config: FixedPointConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+    ) -> FixedPointConfig<F> {
+        assert!(0 < SCALE && SCALE < VALUE_BITS, "bad scale {}", SCALE);
+        let q_add = meta.selector();
+        let q_mul = meta.selector();
+        let q_div = meta.selector();
+        let q_rescale = meta.selector();
+        // Lookups cannot use simple selectors.
+        let q_bytes = meta.complex_selector();
+        let q_unsigned = meta.selector();
+        let q_signed = meta.selector();
+        let table = RangeTableConfig::configure(meta);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let pow = |e: usize| Expression::Constant(F::from(1 << e));
+
+        meta.create_gate("fixed add", |meta| {
+            let s = meta.query_selector(q_add);
+            let [a, b, c] = [0, 1, 2].map(|i| meta.query_advice(advice[i], Rotation::cur()));
+            vec![s * (a + b - c)]
+        });
+
+        meta.create_gate("fixed mul", |meta| {
+            let s = meta.query_selector(q_mul);
+            let [a, b, c, r, gap] = advice.map(|column| meta.query_advice(column, Rotation::cur()));
+            vec![
+                s.clone() * (a * b - c * pow(SCALE) - r.clone()),
+                s * (gap - r * pow(VALUE_BITS - SCALE)),
+            ]
+        });
+
+        meta.create_gate("fixed div", |meta| {
+            let s = meta.query_selector(q_div);
+            let [a, b, c, r, gap] = advice.map(|column| meta.query_advice(column, Rotation::cur()));
+            vec![
+                s.clone() * (a * pow(SCALE) - c * b.clone() - r.clone()),
+                s * (b - r - Expression::Constant(F::ONE) - gap),
+            ]
+        });
+
+        meta.create_gate("fixed rescale", |meta| {
+            let s = meta.query_selector(q_rescale);
+            let [a, c, r, gap] =
+                [0, 2, 3, 4].map(|i| meta.query_advice(advice[i], Rotation::cur()));
+            vec![
+                s.clone() * (a - c * pow(SCALE) - r.clone()),
+                s * (gap - r * pow(VALUE_BITS - SCALE)),
+            ]
+        });
+
+        meta.create_gate("fixed range", |meta| {
+            let unsigned = meta.query_selector(q_unsigned);
+            let signed = meta.query_selector(q_signed);
+            let v = meta.query_advice(advice[0], Rotation::cur());
+            let bytes = (1..5).fold(Expression::Constant(F::ZERO), |acc, i| {
+                acc + meta.query_advice(advice[i], Rotation::cur()) * pow(8 * (i - 1))
+            });
+            vec![
+                unsigned * (v.clone() - bytes.clone()),
+                signed * (v + pow(VALUE_BITS - 1) - bytes),
+            ]
+        });
+
+        // Inactive rows look up 0, which is always in the table.
+        for &column in &advice[1..] {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(q_bytes);
+                let byte = meta.query_advice(column, Rotation::cur());
+                vec![(s * byte, table.value)]
+            });
+        }
+
+        FixedPointConfig {
+            advice,
+            q_add,
+            q_mul,
+            q_div,
+            q_rescale,
+            q_bytes,
+            q_unsigned,
+            q_signed,
+            table,
+        }
+    }
+
+    /// Loads the byte table of the range checks. Call once per circuit.
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.load(layouter)
+    }
+
+    /// Witnesses the raw fixed-point value `value`, i.e. `x * 2^SCALE`.
+    pub fn load(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<i64>,
+    ) -> Result<AssignedFixed<F>, Error> {
+        let cell = layouter.assign_region(
+            || "fixed",
+            |mut region| {
+                let value = value.map(to_field);
+                region.assign_advice(|| "value", self.config.advice[0], 0, || value)
+            },
+        )?;
+        self.range_check(layouter.namespace(|| "range"), &cell, true)?;
+        Ok(AssignedFixed { cell })
+    }
+
+    /// Returns `a + b`.
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedFixed<F>,
+        b: &AssignedFixed<F>,
+    ) -> Result<AssignedFixed<F>, Error> {
+        let [col_a, col_b, col_c, _, _] = self.config.advice;
+        let c = layouter.assign_region(
+            || "fixed add",
+            |mut region| {
+                self.config.q_add.enable(&mut region, 0)?;
+                let a = a.cell.copy_advice(|| "a", &mut region, col_a, 0)?;
+                let b = b.cell.copy_advice(|| "b", &mut region, col_b, 0)?;
+                let c = a.value().copied() + b.value();
+                region.assign_advice(|| "a + b", col_c, 0, || c)
+            },
+        )?;
+        self.range_check(layouter.namespace(|| "range"), &c, true)?;
+        Ok(AssignedFixed { cell: c })
+    }
+
+    /// Returns `a * b`, rounded down to `SCALE` fractional bits.
+    pub fn mul(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedFixed<F>,
+        b: &AssignedFixed<F>,
+    ) -> Result<AssignedFixed<F>, Error> {
+        let cr = a.value().zip(b.value()).map(|(a, b)| {
+            let product = a * b;
+            (product >> SCALE, product & ((1 << SCALE) - 1))
+        });
+        self.assign_op(layouter, self.config.q_mul, &a.cell, Some(&b.cell), cr)
+    }
+
+    /// Returns `a / b`, rounded down to `SCALE` fractional bits. The proof fails
+    /// unless `b` is positive.
+    pub fn div(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedFixed<F>,
+        b: &AssignedFixed<F>,
+    ) -> Result<AssignedFixed<F>, Error> {
+        let cr = a.value().zip(b.value()).map(|(a, b)| {
+            if b > 0 {
+                let n = a << SCALE;
+                (n.div_euclid(b), n.rem_euclid(b))
+            } else {
+                (0, 0)
+            }
+        });
+        self.assign_op(layouter, self.config.q_div, &a.cell, Some(&b.cell), cr)
+    }
+
+    /// Returns `a / 2^SCALE`, rounded down, for a raw value `a` at twice the
+    /// scale such as an inner product of fixed-point vectors. `a` need not be
+    /// range-checked; the proof fails if the result is out of range.
+    pub fn rescale(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+    ) -> Result<AssignedFixed<F>, Error> {
+        let cr = a.value().map(|&a| {
+            let a = from_field(a);
+            (a >> SCALE, a & ((1 << SCALE) - 1))
+        });
+        self.assign_op(layouter, self.config.q_rescale, a, None, cr)
+    }
+
+    /// Lays out a multiplication, division or rescale row with the given
+    /// result and remainder, and range-checks them.
+    fn assign_op(
+        &self,
+        mut layouter: impl Layouter<F>,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        b: Option<&AssignedCell<F, F>>,
+        cr: Value<(i64, i64)>,
+    ) -> Result<AssignedFixed<F>, Error> {
+        let [col_a, col_b, col_c, col_r, col_gap] = self.config.advice;
+        let is_div = selector == self.config.q_div;
+
+        let (c, r, gap) = layouter.assign_region(
+            || "fixed op",
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, col_a, 0)?;
+                let b = b
+                    .map(|b| b.copy_advice(|| "b", &mut region, col_b, 0))
+                    .transpose()?;
+
+                let c = cr.map(|(c, _)| to_field::<F>(c));
+                let r = cr.map(|(_, r)| to_field::<F>(r));
+                let gap = match b {
+                    Some(b) if is_div => b.value().copied() - r - Value::known(F::ONE),
+                    _ => r.map(|r| r * F::from(1 << (VALUE_BITS - SCALE))),
+                };
+                Ok((
+                    region.assign_advice(|| "c", col_c, 0, || c)?,
+                    region.assign_advice(|| "r", col_r, 0, || r)?,
+                    region.assign_advice(|| "gap", col_gap, 0, || gap)?,
+                ))
+            },
+        )?;
+
+        self.range_check(layouter.namespace(|| "c range"), &c, true)?;
+        self.range_check(layouter.namespace(|| "r range"), &r, false)?;
+        self.range_check(layouter.namespace(|| "gap range"), &gap, false)?;
+        Ok(AssignedFixed { cell: c })
+    }
+
+    /// Checks `v` against `[-2^31, 2^31)` if `signed`, and `[0, 2^32)`
+    /// otherwise.
+    fn range_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        v: &AssignedCell<F, F>,
+        signed: bool,
+    ) -> Result<(), Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "fixed range",
+            |mut region| {
+                config.q_bytes.enable(&mut region, 0)?;
+                if signed {
+                    config.q_signed.enable(&mut region, 0)?;
+                } else {
+                    config.q_unsigned.enable(&mut region, 0)?;
+                }
+                let v = v.copy_advice(|| "v", &mut region, config.advice[0], 0)?;
+
+                let offset = if signed { 1 << (VALUE_BITS - 1) } else { 0 };
+                let shifted = v.value().map(|v| to_u64(&(*v + F::from(offset))));
+                for i in 0..4 {
+                    let byte = shifted.map(|v| F::from((v >> (8 * i)) & 0xff));
+                    region.assign_advice(|| "byte", config.advice[1 + i], 0, || byte)?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// The field element for a raw fixed-point value.
+pub fn to_field<F: PrimeField>(value: i64) -> F {
+    if value < 0 {
+        -F::from(value.unsigned_abs())
+    } else {
+        F::from(value as u64)
+    }
+}
+
+/// The raw fixed-point value of a field element in `[-2^63, 2^63)`.
+pub fn from_field<F: PrimeField>(value: F) -> i64 {
+    let offset = F::from(1 << 63);
+    (to_u64(&(value + offset)) ^ (1 << 63)) as i64
+}
+
+/// `x * 2^scale`, rounded to the nearest integer.
+pub fn encode(x: f64, scale: usize) -> i64 {
+    (x * (1u64 << scale) as f64).round() as i64
+}
+
+/// The real number a raw value with `scale` fractional bits stands for.
+pub fn decode(value: i64, scale: usize) -> f64 {
+    value as f64 / (1u64 << scale) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use rand_core::{OsRng, RngCore};
+
+    const SCALE: usize = 16;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Op {
+        Add,
+        Mul,
+        Div,
+        Rescale,
+    }
+
+    /// Plain-Rust reference on raw values, or `None` if the result is out of
+    /// range or the divisor is not positive.
+    fn reference(op: Op, a: i64, b: i64) -> Option<i64> {
+        let c = match op {
+            Op::Add => a + b,
+            Op::Mul => (a * b) >> SCALE,
+            Op::Div if b > 0 => (a << SCALE).div_euclid(b),
+            Op::Div => return None,
+            Op::Rescale => a >> SCALE,
+        };
+        i32::try_from(c).ok().map(i64::from)
+    }
+
+    /// Applies each op to its pair of raw values and exposes the results.
+    /// `forged` replaces the result and remainder of the first op.
+    #[derive(Default)]
+    struct FixedCircuit {
+        ops: Vec<(Op, i64, i64)>,
+        forged: Option<(i64, i64)>,
+    }
+
+    impl Circuit<Fp> for FixedCircuit {
+        type Config = (FixedPointConfig<Fp>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                ops: self.ops.clone(),
+                forged: self.forged,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 5].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (
+                FixedPointChip::<Fp, SCALE>::configure(meta, advice),
+                instance,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = FixedPointChip::<Fp, SCALE>::construct(config.clone());
+            chip.load_table(&mut layouter)?;
+
+            for (row, &(op, a, b)) in self.ops.iter().enumerate() {
+                let a = chip.load(layouter.namespace(|| "a"), Value::known(a))?;
+                let b = chip.load(layouter.namespace(|| "b"), Value::known(b))?;
+                let forged = self.forged.filter(|_| row == 0).map(Value::known);
+                let c = match (op, forged) {
+                    (Op::Add, _) => chip.add(layouter.namespace(|| "add"), &a, &b)?,
+                    (Op::Mul, None) => chip.mul(layouter.namespace(|| "mul"), &a, &b)?,
+                    (Op::Div, None) => chip.div(layouter.namespace(|| "div"), &a, &b)?,
+                    (Op::Rescale, None) => {
+                        chip.rescale(layouter.namespace(|| "rescale"), &a.cell)?
+                    }
+                    (op, Some(cr)) => {
+                        let (selector, b) = match op {
+                            Op::Mul => (config.q_mul, Some(&b.cell)),
+                            Op::Div => (config.q_div, Some(&b.cell)),
+                            _ => (config.q_rescale, None),
+                        };
+                        let layouter = layouter.namespace(|| "forged");
+                        chip.assign_op(layouter, selector, &a.cell, b, cr)?
+                    }
+                };
+                layouter.constrain_instance(c.cell.cell(), instance, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn run(circuit: &FixedCircuit, outs: &[i64]) -> MockProver<Fp> {
+        let k = 10;
+
+        let outs = outs.iter().map(|&out| to_field(out)).collect();
+        MockProver::run(k, circuit, vec![outs]).unwrap()
+    }
+
+    /// Runs the ops and expects the results of [`reference`].
+    fn run_honest(ops: Vec<(Op, i64, i64)>) -> MockProver<Fp> {
+        let outs: Vec<_> = ops
+            .iter()
+            .map(|&(op, a, b)| reference(op, a, b).unwrap_or(0))
+            .collect();
+        run(&FixedCircuit { ops, forged: None }, &outs)
+    }
+
+    #[test]
+    fn fixed_point_matches_reference() {
+        let x = |x: f64| encode(x, SCALE);
+        let mut ops = vec![
+            (Op::Mul, x(1.5), x(2.25)),
+            (Op::Mul, x(-1.5), x(2.25)),
+            (Op::Mul, x(-0.5), x(-0.5)),
+            (Op::Div, x(1.0), x(3.0)),
+            (Op::Div, x(-1.0), x(3.0)),
+            (Op::Add, x(-7.25), x(3.5)),
+            (Op::Rescale, -123_456_789, 0),
+        ];
+        // Random values below 2^7 in magnitude and divisors of at least 1, so
+        // results stay in range.
+        let random = || (OsRng.next_u32() % (1 << 24)) as i64 - (1 << 23);
+        for op in [Op::Add, Op::Mul, Op::Div, Op::Rescale] {
+            for _ in 0..4 {
+                ops.push((op, random(), random().abs() + (1 << SCALE)));
+            }
+        }
+        run_honest(ops).assert_satisfied();
+
+        // Rounding down: -1.5 * 2.25 is -3.375, which is exact, and -1/3
+        // rounds towards negative infinity.
+        assert_eq!(
+            decode(reference(Op::Mul, x(-1.5), x(2.25)).unwrap(), SCALE),
+            -3.375
+        );
+        assert!(decode(reference(Op::Div, x(-1.0), x(3.0)).unwrap(), SCALE) < -1.0 / 3.0);
+    }
+
+    #[test]
+    fn fixed_point_rejects_out_of_range() {
+        let x = |x: f64| encode(x, SCALE);
+        for (op, a, b) in [
+            // 256 * 256 does not fit in 15 integer bits.
+            (Op::Mul, x(256.0), x(256.0)),
+            (Op::Add, x(20000.0), x(20000.0)),
+            (Op::Div, x(1.0), 0),
+            (Op::Div, x(1.0), x(-2.0)),
+        ] {
+            assert_eq!(reference(op, a, b), None);
+            assert!(run_honest(vec![(op, a, b)]).verify().is_err(), "{:?}", op);
+        }
+    }
+
+    #[test]
+    fn fixed_point_rejects_wide_remainder() {
+        let (a, b) = (encode(1.5, SCALE), encode(2.25, SCALE));
+        let c = reference(Op::Mul, a, b).unwrap();
+        let r = (a * b) & ((1 << SCALE) - 1);
+
+        // (c - 1) * 2^SCALE + (r + 2^SCALE) is still a * b.
+        let circuit = FixedCircuit {
+            ops: vec![(Op::Mul, a, b)],
+            forged: Some((c - 1, r + (1 << SCALE))),
+        };
+        assert!(run(&circuit, &[c - 1]).verify().is_err());
+
+        // For division the remainder must stay below the divisor.
+        let (a, b) = (encode(1.0, SCALE), encode(3.0, SCALE));
+        let c = reference(Op::Div, a, b).unwrap();
+        let r = (a << SCALE) % b;
+        let circuit = FixedCircuit {
+            ops: vec![(Op::Div, a, b)],
+            forged: Some((c - 1, r + b)),
+        };
+        assert!(run(&circuit, &[c - 1]).verify().is_err());
+    }
+
+    #[test]
+    fn fixed_point_field_encoding_round_trips() {
+        for value in [0, 1, -1, i64::from(i32::MIN), i64::from(i32::MAX)] {
+            assert_eq!(from_field(to_field::<Fp>(value)), value);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! The same circuit under different floor planners.
+//!
+//! A floor planner decides where each region goes: a chip lays a region out
+//! relative to row 0, and the planner picks its absolute starting row.
+//! halo2_proofs 0.3 ships two:
+//!
+//! - [`SimpleFloorPlanner`] places regions in one pass, each at the first row
+//!   where all of its columns are free after the regions placed so far.
+//! - [`V1`] first synthesizes the circuit without assigning anything to
+//!   measure every region's shape, then packs the regions into column slots,
+//!   largest first, before assigning them in a second pass.
+//!
+//! [`Planned`] swaps the planner of any circuit, and [`measure`] reports how
+//! many rows a circuit uses under its planner. Both Fibonacci layouts use the
+//! same rows under either planner, since each of their regions has to follow
+//! the previous one in the same columns anyway. [`Shapes`] is a circuit where
+//! the order matters: a tall region in one column, then a one-row region across
+//! both, then a tall region in the other column. The simple planner has to put
+//! the second tall region below the bridge, while V1 places both tall regions
+//! side by side first. A hand-written planner is not
+//! possible against halo2_proofs 0.3: a layouter has to hand out
+//! `circuit::Cell`s, which only the crate itself can construct.
+
+use halo2_proofs::{circuit::floor_planner::V1, pasta::Fp};
+
+use crate::{
+    fibonacci::{example1, example2, FibIndex, FibonacciInstances},
+    prelude::*,
+    registry::{Example, ExampleCircuit},
+};
+
+/// `C` with its floor planner replaced by `P`.
+#[derive(Debug, Clone)]
+pub struct Planned<C, P> {
+    pub circuit: C,
+    _marker: PhantomData<P>,
+}
+
+impl<C, P> Planned<C, P> {
+    pub fn new(circuit: C) -> Self {
+        Self {
+            circuit,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field, C: Circuit<F>, P: FloorPlanner> Circuit<F> for Planned<C, P> {
+    type Config = C::Config;
+    type FloorPlanner = P;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.circuit.without_witnesses())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        C::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.circuit.synthesize(config, layouter)
+    }
+}
+
+/// What a floor planner did with a circuit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// One past the last row any advice, fixed or selector cell is assigned in.
+    pub rows: usize,
+    /// Regions assigned, not counting measurement passes.
+    pub regions: usize,
+}
+
+/// Records the rows an [`Assignment`] backend is asked to fill.
+#[derive(Debug, Default)]
+struct RowCounter(Usage);
+
+impl RowCounter {
+    fn touch(&mut self, row: usize) {
+        self.0.rows = self.0.rows.max(row + 1);
+    }
+}
+
+impl<F: Field> Assignment<F> for RowCounter {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.0.regions += 1;
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<F>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+/// Synthesizes `circuit` without witnesses and reports the rows and regions
+/// its floor planner used.
+///
+/// halo2_proofs 0.3 does not expose which fixed columns a `ConstraintSystem`
+/// reserves for constants, so circuits that assign constants fail here with
+/// `Error::NotEnoughColumnsForConstants`.
+pub fn measure<F: Field, C: Circuit<F>>(circuit: &C) -> Result<Usage, Error> {
+    let mut meta = ConstraintSystem::default();
+    let config = C::configure(&mut meta);
+    let mut counter = RowCounter::default();
+    C::FloorPlanner::synthesize(&mut counter, &circuit.without_witnesses(), config, vec![])?;
+    Ok(counter.0)
+}
+
+/// Regions of three shapes on two advice columns `x` and `y`: `H` rows in `x`,
+/// one row across `x` and `y`, then `H` rows in `y`.
+#[derive(Debug, Clone, Default)]
+pub struct Shapes<const H: usize>;
+
+impl<F: Field, const H: usize> Circuit<F> for Shapes<H> {
+    type Config = [Column<Advice>; 2];
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        [meta.advice_column(), meta.advice_column()]
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let [x, y] = config;
+        let mut fill = |name: &'static str, columns: &[Column<Advice>], rows: usize| {
+            layouter.assign_region(
+                || name,
+                |mut region| {
+                    for &column in columns {
+                        for row in 0..rows {
+                            region.assign_advice(|| name, column, row, || Value::known(F::ONE))?;
+                        }
+                    }
+                    Ok(())
+                },
+            )
+        };
+        fill("tall x", &[x], H)?;
+        fill("bridge", &[x, y], 1)?;
+        fill("tall y", &[y], H)
+    }
+}
+
+/// Row usage of both Fibonacci layouts computing `f(n)`, under each planner,
+/// as `(name, usage)`.
+pub fn fibonacci_usage(n: usize) -> Result<Vec<(&'static str, Usage)>, Error> {
+    let one = || example1::MyCircuit::<Fp>::new(n);
+    let two = || example2::MyCircuit::<Fp>::new(n);
+    Ok(vec![
+        ("example1, simple", measure(&one())?),
+        ("example1, v1", measure(&Planned::<_, V1>::new(one()))?),
+        ("example2, simple", measure(&two())?),
+        ("example2, v1", measure(&Planned::<_, V1>::new(two()))?),
+    ])
+}
+
+/// The first Fibonacci example under [`V1`].
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = Planned::<_, V1>::new(example1::MyCircuit::<Fp>::new(9));
+    let instances = FibonacciInstances::from_seeds(1, 1, FibIndex(9)).unwrap();
+    ExampleCircuit::new(4, circuit, instances.to_columns())
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "floor_planner",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floor_planner_fibonacci_usage() {
+        let usage = fibonacci_usage(20).unwrap();
+        // f(0) to f(2) in one row, then a row per term.
+        assert_eq!(
+            usage[0].1,
+            Usage {
+                rows: 19,
+                regions: 19
+            }
+        );
+        // One term per row.
+        assert_eq!(
+            usage[2].1,
+            Usage {
+                rows: 21,
+                regions: 1
+            }
+        );
+        assert_eq!(usage[0].1, usage[1].1);
+        assert_eq!(usage[2].1, usage[3].1);
+    }
+
+    #[test]
+    fn floor_planner_v1_packs_shapes() {
+        let simple = measure::<Fp, _>(&Shapes::<10>).unwrap();
+        let v1 = measure::<Fp, _>(&Planned::<_, V1>::new(Shapes::<10>)).unwrap();
+        assert_eq!(
+            simple,
+            Usage {
+                rows: 21,
+                regions: 3
+            }
+        );
+        assert_eq!(
+            v1,
+            Usage {
+                rows: 11,
+                regions: 3
+            }
+        );
+    }
+}
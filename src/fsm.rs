@@ -0,0 +1,315 @@
+//! A finite state machine whose transitions live in a lookup table: proves
+//! that a string drives the machine from its start state to an accepting one.
+
+use halo2_proofs::pasta::Fp;
+
+use crate::{
+    prelude::*,
+    registry::{Example, ExampleCircuit},
+};
+
+/// A deterministic finite automaton over numbered states and input symbols.
+///
+/// State 0 is reserved: inactive rows look up `(0, 0, 0)` and `0`, which the
+/// tables always hold, so no real state may be 0.
+#[derive(Debug, Clone, Default)]
+pub struct Fsm {
+    pub start: u64,
+    /// `(state, input, next_state)`.
+    pub transitions: Vec<(u64, u64, u64)>,
+    pub accepting: Vec<u64>,
+}
+
+impl Fsm {
+    pub fn step(&self, state: u64, input: u64) -> Option<u64> {
+        self.transitions
+            .iter()
+            .find(|&&(s, i, _)| s == state && i == input)
+            .map(|&(_, _, next)| next)
+    }
+
+    /// Plain-Rust reference: whether the machine accepts `inputs`.
+    #[cfg(test)]
+    pub fn accepts(&self, inputs: &[u64]) -> bool {
+        inputs
+            .iter()
+            .try_fold(self.start, |state, &input| self.step(state, input))
+            .is_some_and(|state| self.accepting.contains(&state))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FsmConfig {
+    pub state: Column<Advice>,
+    pub input: Column<Advice>,
+    pub q_step: Selector,
+    pub q_accept: Selector,
+    /// `(state, input, next_state)` of every transition.
+    pub transitions: [TableColumn; 3],
+    pub accepting: TableColumn,
+}
+
+/// Runs an [`Fsm`] down a region, one input symbol per row:
+///
+/// ```text
+/// state   | input | q_step | q_accept
+/// start   | x_0   |   1    |   0
+/// s_1     | x_1   |   1    |   0
+/// ...     | ...   |  ...   |  ...
+/// s_n     |       |   0    |   1
+/// ```
+///
+/// Every step looks up `(state, input, next state)` in the transition table,
+/// so a state the table does not lead to, or an input symbol with no
+/// transition, fails the proof. The start state is a constant, and the last
+/// state is looked up in the table of accepting states.
+#[derive(Debug, Clone)]
+pub struct FsmChip<F: Field> {
+    config: FsmConfig,
+    fsm: Fsm,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> FsmChip<F> {
+    pub fn construct(config: FsmConfig, fsm: Fsm) -> Self {
+        Self {
+            config,
+            fsm,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        state: Column<Advice>,
+        input: Column<Advice>,
+    ) -> FsmConfig {
+        // Lookups cannot use simple selectors.
+        let q_step = meta.complex_selector();
+        let q_accept = meta.complex_selector();
+        let transitions = [(); 3].map(|_| meta.lookup_table_column());
+        let accepting = meta.lookup_table_column();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+        meta.enable_equality(state);
+        meta.enable_equality(input);
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_step);
+            let state_cur = meta.query_advice(state, Rotation::cur());
+            let input = meta.query_advice(input, Rotation::cur());
+            let state_next = meta.query_advice(state, Rotation::next());
+            [state_cur, input, state_next]
+                .into_iter()
+                .zip(transitions)
+                .map(|(value, table)| (q.clone() * value, table))
+                .collect()
+        });
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_accept);
+            let state = meta.query_advice(state, Rotation::cur());
+            vec![(q * state, accepting)]
+        });
+
+        FsmConfig {
+            state,
+            input,
+            q_step,
+            q_accept,
+            transitions,
+            accepting,
+        }
+    }
+
+    /// Loads the transition and accepting-state tables. Call once per circuit.
+    pub fn load_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let config = &self.config;
+        layouter.assign_table(
+            || "transitions",
+            |mut table| {
+                let rows = [(0, 0, 0)]
+                    .into_iter()
+                    .chain(self.fsm.transitions.iter().copied());
+                for (row, (state, input, next)) in rows.enumerate() {
+                    for (column, value) in config.transitions.into_iter().zip([state, input, next])
+                    {
+                        table.assign_cell(
+                            || "transition",
+                            column,
+                            row,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        layouter.assign_table(
+            || "accepting",
+            |mut table| {
+                for (row, &state) in [0].iter().chain(&self.fsm.accepting).enumerate() {
+                    table.assign_cell(
+                        || "accepting",
+                        config.accepting,
+                        row,
+                        || Value::known(F::from(state)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Runs the machine on `inputs` and checks that it ends in an accepting
+    /// state, which it returns.
+    pub fn accept(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+
+        layouter.assign_region(
+            || "fsm",
+            |mut region| {
+                let start = F::from(self.fsm.start);
+                let mut state =
+                    region.assign_advice_from_constant(|| "start", config.state, 0, start)?;
+                for (row, input) in inputs.iter().enumerate() {
+                    config.q_step.enable(&mut region, row)?;
+                    let input = input.copy_advice(|| "input", &mut region, config.input, row)?;
+
+                    // A missing transition gets state 0, which the lookup rejects.
+                    let next = state.value().zip(input.value()).map(|(&state, &input)| {
+                        let next = self.fsm.step(to_u64(&state), to_u64(&input));
+                        F::from(next.unwrap_or(0))
+                    });
+                    state = region.assign_advice(|| "state", config.state, row + 1, || next)?;
+                }
+                config.q_accept.enable(&mut region, inputs.len())?;
+                Ok(state)
+            },
+        )
+    }
+}
+
+/// Binary numbers, most significant bit first, that are multiples of 3. State
+/// `r + 1` means the bits so far have remainder `r`.
+pub fn multiples_of_three() -> Fsm {
+    let transitions = (0..3)
+        .flat_map(|r| (0..2).map(move |bit| (r + 1, bit, (2 * r + bit) % 3 + 1)))
+        .collect();
+    Fsm {
+        start: 1,
+        transitions,
+        accepting: vec![1],
+    }
+}
+
+/// The `N` public bits, most significant first, of a multiple of 3.
+#[derive(Debug, Clone)]
+struct MyConfig {
+    fsm: FsmConfig,
+    instance: Column<Instance>,
+}
+
+#[derive(Default)]
+struct MyCircuit<const N: usize>;
+
+impl<const N: usize> Circuit<Fp> for MyCircuit<N> {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let state = meta.advice_column();
+        let input = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        MyConfig {
+            fsm: FsmChip::configure(meta, state, input),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = FsmChip::construct(config.fsm.clone(), multiples_of_three());
+        chip.load_tables(&mut layouter)?;
+
+        let bits = layouter.assign_region(
+            || "bits",
+            |mut region| {
+                let targets: Vec<_> = (0..N).map(|row| (config.fsm.input, row)).collect();
+                load_instances(&mut region, config.instance, &targets)
+            },
+        )?;
+        chip.accept(layouter.namespace(|| "multiple of 3"), &bits)?;
+        Ok(())
+    }
+}
+
+/// The public input: the `N` bits of `value`, most significant first.
+pub fn instances<const N: usize>(value: u64) -> Vec<Vec<Fp>> {
+    vec![(0..N).rev().map(|i| Fp::from((value >> i) & 1)).collect()]
+}
+
+/// The circuit on 8-bit 201, a multiple of 3.
+pub fn example_circuit() -> ExampleCircuit {
+    ExampleCircuit::new(5, MyCircuit::<8>, instances::<8>(201))
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "fsm",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+
+    fn run(bits: Vec<Fp>) -> MockProver<Fp> {
+        let k = 5;
+
+        MockProver::run(k, &MyCircuit::<8>, vec![bits]).unwrap()
+    }
+
+    #[test]
+    fn fsm_accepts_multiples_of_three() {
+        let fsm = multiples_of_three();
+        for value in (0..256).step_by(3) {
+            let bits = instances::<8>(value).remove(0);
+            assert!(fsm.accepts(&bits.iter().map(to_u64).collect::<Vec<_>>()));
+            run(bits).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn fsm_rejects_other_numbers() {
+        let fsm = multiples_of_three();
+        for value in [1, 2, 4, 200, 202, 254] {
+            let bits = instances::<8>(value).remove(0);
+            assert!(!fsm.accepts(&bits.iter().map(to_u64).collect::<Vec<_>>()));
+            let failures = run(bits).verify().unwrap_err();
+            assert!(failures
+                .iter()
+                .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+        }
+    }
+
+    #[test]
+    fn fsm_rejects_symbol_without_transition() {
+        // 2 is not a bit; every state has transitions on 0 and 1 only.
+        let mut bits = instances::<8>(0).remove(0);
+        bits[3] = Fp::from(2);
+        assert!(run(bits).verify().is_err());
+    }
+}
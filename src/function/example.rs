@@ -0,0 +1,258 @@
+use crate::{
+    is_zero::{IsZeroChip, IsZeroConfig},
+    names,
+    prelude::*,
+    registry::ExampleCircuit,
+};
+use halo2_proofs::pasta::Fp;
+
+/// Computes `f(a, b, c) = if a == b { c } else { a - b }` and exposes the result.
+///
+/// The is-zero gadget on `a - b` picks the branch: the "function" gate enforces
+/// `out = c` where the gadget's expression is 1 and `out = a - b` where it is 0.
+#[derive(Debug, Clone)]
+struct FunctionConfig<F> {
+    pub advice: [Column<Advice>; 4],
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub a_equals_b: IsZeroConfig<F>,
+}
+
+#[derive(Debug, Clone)]
+struct FunctionChip<F: Field> {
+    config: FunctionConfig<F>,
+}
+
+impl<F: Field> FunctionChip<F> {
+    pub fn construct(config: FunctionConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        value_inv: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> FunctionConfig<F> {
+        let [col_a, col_b, col_c, col_out] = advice;
+        let selector = meta.selector();
+        meta.enable_equality(col_out);
+        meta.enable_equality(instance);
+
+        let a_equals_b = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| {
+                meta.query_advice(col_a, Rotation::cur())
+                    - meta.query_advice(col_b, Rotation::cur())
+            },
+            value_inv,
+        );
+
+        meta.create_gate("function", |meta| {
+            //
+            // col_a | col_b | col_c | col_out | value_inv  | selector
+            //   a      b       c       out     (a-b)^-1       s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            let out = meta.query_advice(col_out, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+
+            vec![
+                s.clone() * a_equals_b.expr() * (out.clone() - c),
+                s * (one - a_equals_b.expr()) * (out - (a - b)),
+            ]
+        });
+
+        FunctionConfig {
+            advice,
+            selector,
+            instance,
+            a_equals_b,
+        }
+    }
+
+    /// Assigns `a`, `b`, `c` and `f(a, b, c)`, and returns the output cell.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Value<F>,
+        b: Value<F>,
+        c: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_a, col_b, col_c, col_out] = self.config.advice;
+        let a_equals_b = IsZeroChip::construct(self.config.a_equals_b.clone());
+
+        layouter.assign_region(
+            || "function",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "a", col_a, 0, || a)?;
+                region.assign_advice(|| "b", col_b, 0, || b)?;
+                region.assign_advice(|| "c", col_c, 0, || c)?;
+                a_equals_b.assign(&mut region, 0, a - b)?;
+
+                let out = a.zip(b).zip(c).map(|((a, b), c)| reference(a, b, c));
+                region.assign_advice(|| "out", col_out, 0, || out)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference for the function the circuit computes.
+pub fn reference<F: Field>(a: F, b: F, c: F) -> F {
+    if a == b {
+        c
+    } else {
+        a - b
+    }
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    a: Value<F>,
+    b: Value<F>,
+    c: Value<F>,
+}
+
+impl<F: PrimeField> MyCircuit<F> {
+    pub fn new(a: u64, b: u64, c: u64) -> Self {
+        Self {
+            a: Value::known(F::from(a)),
+            b: Value::known(F::from(b)),
+            c: Value::known(F::from(c)),
+        }
+    }
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = FunctionConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let value_inv = meta.advice_column();
+        let instance = meta.instance_column();
+        FunctionChip::configure(meta, advice, value_inv, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FunctionChip::construct(config);
+
+        let out = chip.assign(layouter.namespace(|| "f"), self.a, self.b, self.c)?;
+
+        chip.expose_public(layouter.namespace(|| names::OUT), &out, 0)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on `f(9, 5, 1) = 4`.
+pub fn example_circuit() -> ExampleCircuit {
+    let out = reference(Fp::from(9), Fp::from(5), Fp::ONE);
+    ExampleCircuit::new(4, MyCircuit::new(9, 5, 1), vec![vec![out]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn run(circuit: &MyCircuit<Fp>, out: Fp) -> MockProver<Fp> {
+        let k = 4;
+
+        MockProver::run(k, circuit, vec![vec![out]]).unwrap()
+    }
+
+    #[test]
+    fn function_example() {
+        run(&MyCircuit::new(5, 5, 9), Fp::from(9)).assert_satisfied();
+        run(&MyCircuit::new(9, 5, 1), Fp::from(4)).assert_satisfied();
+    }
+
+    #[test]
+    fn function_wraps_negative_difference() {
+        let out = -Fp::from(4);
+        assert_eq!(reference(Fp::from(5), Fp::from(9), Fp::ONE), out);
+        run(&MyCircuit::new(5, 9, 1), out).assert_satisfied();
+    }
+
+    #[test]
+    fn function_rejects_other_branch() {
+        // a != b, so the output is a - b and not c.
+        assert!(run(&MyCircuit::new(9, 5, 1), Fp::ONE).verify().is_err());
+        // a == b, so the output is c and not a - b = 0.
+        assert!(run(&MyCircuit::new(5, 5, 9), Fp::ZERO).verify().is_err());
+    }
+
+    #[test]
+    fn function_rejects_forged_inverse() {
+        // Claiming the inverse of a - b is 0 would pick the c branch for a != b.
+        struct ForgedCircuit(MyCircuit<Fp>);
+
+        impl Circuit<Fp> for ForgedCircuit {
+            type Config = FunctionConfig<Fp>;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self(MyCircuit::default())
+            }
+
+            fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+                MyCircuit::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<Fp>,
+            ) -> Result<(), Error> {
+                let [col_a, col_b, col_c, col_out] = config.advice;
+                let out = layouter.assign_region(
+                    || "function",
+                    |mut region| {
+                        config.selector.enable(&mut region, 0)?;
+                        region.assign_advice(|| "a", col_a, 0, || self.0.a)?;
+                        region.assign_advice(|| "b", col_b, 0, || self.0.b)?;
+                        region.assign_advice(|| "c", col_c, 0, || self.0.c)?;
+                        region.assign_advice(
+                            || "value inv",
+                            config.a_equals_b.value_inv,
+                            0,
+                            || Value::known(Fp::ZERO),
+                        )?;
+                        region.assign_advice(|| "out", col_out, 0, || self.0.c)
+                    },
+                )?;
+                FunctionChip::construct(config).expose_public(layouter, &out, 0)
+            }
+        }
+
+        let k = 4;
+
+        let circuit = ForgedCircuit(MyCircuit::new(9, 5, 1));
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::ONE]]).unwrap();
+        assert_constraint_fails(&prover, "is_zero");
+    }
+}
@@ -0,0 +1,256 @@
+//! Decomposition of an assigned cell into `n` boolean cells.
+
+use crate::{decompose::chunks, names, prelude::*};
+
+/// Columns and selectors of a [`BitsChip`].
+#[derive(Debug, Clone)]
+pub struct BitsConfig {
+    pub bit: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub q_bool: Selector,
+    pub q_first: Selector,
+    pub q_step: Selector,
+}
+
+/// Decomposes a value into bits, most significant first down the region, with
+/// a Horner accumulator beside them:
+///
+/// ```text
+/// bit     | acc
+/// b_{n-1} | b_{n-1}
+/// b_{n-2} | 2 * acc + b_{n-2}
+/// ...     | ...
+/// b_0     | value            (copied in)
+/// ```
+///
+/// Every bit satisfies `b * (b - 1) == 0`, and since the last accumulator is a
+/// copy of the input, the bits recompose to it. For the decomposition to be
+/// unique `n` has to stay below the field's bit length.
+#[derive(Debug, Clone)]
+pub struct BitsChip<F: PrimeField> {
+    config: BitsConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> BitsChip<F> {
+    pub fn construct(config: BitsConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> BitsConfig {
+        let q_bool = meta.selector();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        meta.enable_equality(bit);
+        meta.enable_equality(acc);
+
+        meta.create_gate(names::BOOL, |meta| {
+            let q = meta.query_selector(q_bool);
+            let b = meta.query_advice(bit, Rotation::cur());
+            vec![q * b.clone() * (b - Expression::Constant(F::ONE))]
+        });
+
+        meta.create_gate(names::FIRST, |meta| {
+            let q = meta.query_selector(q_first);
+            let b = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - b)]
+        });
+
+        meta.create_gate(names::STEP, |meta| {
+            let q = meta.query_selector(q_step);
+            let b = meta.query_advice(bit, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - acc_prev * F::from(2) - b)]
+        });
+
+        BitsConfig {
+            bit,
+            acc,
+            q_bool,
+            q_first,
+            q_step,
+        }
+    }
+
+    /// Returns the `n` bits of `value`, least significant first.
+    ///
+    /// The proof fails if `value` does not fit in `n` bits.
+    pub fn to_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        n: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(n >= 1 && n < F::NUM_BITS as usize, "bad bit count {}", n);
+        let config = &self.config;
+
+        layouter.assign_region(
+            || "to bits",
+            |mut region| {
+                let bits = value.value().map(|v| chunks(v, 1, n));
+
+                let mut cells = Vec::with_capacity(n);
+                let mut acc = Value::known(F::ZERO);
+                for row in 0..n {
+                    config.q_bool.enable(&mut region, row)?;
+                    if row == 0 {
+                        config.q_first.enable(&mut region, row)?;
+                    } else {
+                        config.q_step.enable(&mut region, row)?;
+                    }
+
+                    let b = bits.as_ref().map(|bits| F::from(bits[n - 1 - row]));
+                    cells.push(region.assign_advice(|| "bit", config.bit, row, || b)?);
+
+                    acc = acc.map(|acc| acc.double()) + b;
+                    if row == n - 1 {
+                        value.copy_advice(|| "value", &mut region, config.acc, row)?;
+                    } else {
+                        region.assign_advice(|| "acc", config.acc, row, || acc)?;
+                    }
+                }
+
+                cells.reverse();
+                Ok(cells)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    const N: usize = 8;
+
+    /// Witnesses `value`, splits it into `N` bits and exposes them.
+    #[derive(Default)]
+    struct BitsCircuit {
+        value: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for BitsCircuit {
+        type Config = (BitsConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (BitsChip::configure(meta, bit, acc), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let value = layouter.assign_region(
+                || "value",
+                |mut region| region.assign_advice(|| "value", config.acc, 0, || self.value),
+            )?;
+
+            let chip = BitsChip::construct(config);
+            let bits = chip.to_bits(layouter.namespace(|| "bits"), &value, N)?;
+            for (row, bit) in bits.iter().enumerate() {
+                layouter.constrain_instance(bit.cell(), instance, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn run(value: u64) -> MockProver<Fp> {
+        let k = 5;
+
+        let circuit = BitsCircuit {
+            value: Value::known(Fp::from(value)),
+        };
+        let bits = (0..N).map(|i| Fp::from((value >> i) & 1)).collect();
+        MockProver::run(k, &circuit, vec![bits]).unwrap()
+    }
+
+    #[test]
+    fn to_bits_recomposes() {
+        for value in [0, 1, 0b1010_0110, 255] {
+            run(value).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn to_bits_rejects_value_too_wide() {
+        // The low 8 bits of 256 are all 0, so the last step cannot reach the
+        // copied-in 256.
+        assert_constraint_fails(&run(256), names::STEP);
+    }
+
+    /// Splits 5 into the "bits" `[1, 3]`, most significant first. They
+    /// recompose correctly as `2 * 1 + 3`, so only the boolean gate catches it.
+    struct ForgedCircuit;
+
+    impl Circuit<Fp> for ForgedCircuit {
+        type Config = BitsConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let bit = meta.advice_column();
+            let acc = meta.advice_column();
+            BitsChip::configure(meta, bit, acc)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "forged",
+                |mut region| {
+                    for (row, (b, acc)) in [(1, 1), (3, 5)].into_iter().enumerate() {
+                        config.q_bool.enable(&mut region, row)?;
+                        region.assign_advice(
+                            || "bit",
+                            config.bit,
+                            row,
+                            || Value::known(Fp::from(b)),
+                        )?;
+                        region.assign_advice(
+                            || "acc",
+                            config.acc,
+                            row,
+                            || Value::known(Fp::from(acc)),
+                        )?;
+                    }
+                    config.q_first.enable(&mut region, 0)?;
+                    config.q_step.enable(&mut region, 1)
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn to_bits_rejects_non_boolean_bit() {
+        let k = 4;
+
+        let prover = MockProver::run(k, &ForgedCircuit, vec![]).unwrap();
+        assert_constraint_fails(&prover, names::BOOL);
+    }
+}
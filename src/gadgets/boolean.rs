@@ -0,0 +1,249 @@
+//! Logic gates on boolean cells.
+
+use crate::{
+    common::{BooleanChip, BooleanConfig},
+    prelude::*,
+};
+
+/// Columns `[a, b, out]` shared by every operation, one selector per operation,
+/// and a [`BooleanChip`] on `a` for witnessing inputs.
+#[derive(Debug, Clone)]
+pub struct BoolConfig {
+    pub advice: [Column<Advice>; 3],
+    pub q_and: Selector,
+    pub q_or: Selector,
+    pub q_xor: Selector,
+    pub q_not: Selector,
+    pub bits: BooleanConfig,
+}
+
+/// `and`, `or`, `xor` and `not` as arithmetic gates on one row each:
+///
+/// ```text
+/// and: out = a * b
+/// or:  out = a + b - a * b
+/// xor: out = a + b - 2 * a * b
+/// not: out = 1 - a
+/// ```
+///
+/// The gates do not check their inputs, which must already be boolean (from
+/// [`BoolChip::witness`] or another operation); for boolean inputs every output
+/// is boolean too.
+#[derive(Debug, Clone)]
+pub struct BoolChip<F: Field> {
+    config: BoolConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> BoolChip<F> {
+    pub fn construct(config: BoolConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> BoolConfig {
+        let [col_a, col_b, col_out] = advice;
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let bits = BooleanChip::configure(meta, col_a);
+
+        let mut gate =
+            |name: &'static str, f: fn(Expression<F>, Expression<F>) -> Expression<F>| {
+                let selector = meta.selector();
+                meta.create_gate(name, |meta| {
+                    let s = meta.query_selector(selector);
+                    let a = meta.query_advice(col_a, Rotation::cur());
+                    let b = meta.query_advice(col_b, Rotation::cur());
+                    let out = meta.query_advice(col_out, Rotation::cur());
+                    vec![s * (f(a, b) - out)]
+                });
+                selector
+            };
+
+        let q_and = gate("and", |a, b| a * b);
+        let q_or = gate("or", |a, b| a.clone() + b.clone() - a * b);
+        let q_xor = gate("xor", |a, b| {
+            let ab = a.clone() * b.clone();
+            a + b - ab.clone() - ab
+        });
+        let q_not = gate("not", |a, _| Expression::Constant(F::ONE) - a);
+
+        BoolConfig {
+            advice,
+            q_and,
+            q_or,
+            q_xor,
+            q_not,
+            bits,
+        }
+    }
+
+    /// Witnesses a boolean input.
+    pub fn witness(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        BooleanChip::construct(self.config.bits.clone()).assign_bool(layouter, value)
+    }
+
+    pub fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.apply(layouter, "and", self.config.q_and, a, Some(b), |a, b| a * b)
+    }
+
+    pub fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.apply(layouter, "or", self.config.q_or, a, Some(b), |a, b| {
+            a + b - a * b
+        })
+    }
+
+    pub fn xor(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.apply(layouter, "xor", self.config.q_xor, a, Some(b), |a, b| {
+            a + b - (a * b).double()
+        })
+    }
+
+    pub fn not(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.apply(layouter, "not", self.config.q_not, a, None, |a, _| {
+            F::ONE - a
+        })
+    }
+
+    /// Copies the operands into a new row under `selector` and assigns
+    /// `f(a, b)`. A missing `b` is witnessed as 0.
+    fn apply(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &'static str,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        b: Option<&AssignedCell<F, F>>,
+        f: fn(F, F) -> F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_a, col_b, col_out] = self.config.advice;
+
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+
+                let a = a.copy_advice(|| "a", &mut region, col_a, 0)?;
+                let b = match b {
+                    Some(b) => b.copy_advice(|| "b", &mut region, col_b, 0)?,
+                    None => region.assign_advice(|| "b", col_b, 0, || Value::known(F::ZERO))?,
+                };
+                let out = a.value().zip(b.value()).map(|(&a, &b)| f(a, b));
+                region.assign_advice(|| "out", col_out, 0, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::names;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    /// Proves `f(x, y, z) = (x and y) or not (y xor z)` for private inputs and
+    /// a public output.
+    #[derive(Default)]
+    struct FormulaCircuit {
+        inputs: [Value<Fp>; 3],
+    }
+
+    fn formula(x: bool, y: bool, z: bool) -> bool {
+        (x && y) || !(y ^ z)
+    }
+
+    impl Circuit<Fp> for FormulaCircuit {
+        type Config = (BoolConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (BoolChip::configure(meta, advice), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = BoolChip::construct(config);
+
+            let [x, y, z] = self.inputs;
+            let x = chip.witness(layouter.namespace(|| "x"), x)?;
+            let y = chip.witness(layouter.namespace(|| "y"), y)?;
+            let z = chip.witness(layouter.namespace(|| "z"), z)?;
+
+            let x_and_y = chip.and(layouter.namespace(|| "x and y"), &x, &y)?;
+            let y_xor_z = chip.xor(layouter.namespace(|| "y xor z"), &y, &z)?;
+            let not_xor = chip.not(layouter.namespace(|| "not"), &y_xor_z)?;
+            let out = chip.or(layouter.namespace(|| "or"), &x_and_y, &not_xor)?;
+
+            layouter.constrain_instance(out.cell(), instance, 0)
+        }
+    }
+
+    fn run(inputs: [u64; 3], out: bool) -> MockProver<Fp> {
+        let k = 4;
+
+        let circuit = FormulaCircuit {
+            inputs: inputs.map(|v| Value::known(Fp::from(v))),
+        };
+        MockProver::run(k, &circuit, vec![vec![Fp::from(out)]]).unwrap()
+    }
+
+    #[test]
+    fn formula_truth_table() {
+        for bits in 0..8u64 {
+            let inputs = [bits & 1, bits >> 1 & 1, bits >> 2 & 1];
+            let [x, y, z] = inputs.map(|b| b == 1);
+            run(inputs, formula(x, y, z)).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn formula_rejects_wrong_output() {
+        for bits in 0..8u64 {
+            let inputs = [bits & 1, bits >> 1 & 1, bits >> 2 & 1];
+            let [x, y, z] = inputs.map(|b| b == 1);
+            assert!(run(inputs, !formula(x, y, z)).verify().is_err());
+        }
+    }
+
+    #[test]
+    fn formula_rejects_non_boolean_input() {
+        // x = 2, y = 1 gives x and y = 2, which would otherwise pass through.
+        assert_constraint_fails(&run([2, 1, 0], true), names::BOOL);
+    }
+}
@@ -0,0 +1,9 @@
+//! Reusable chips that the larger examples build on.
+//!
+//! Unlike the example modules, these have no circuit of their own outside the
+//! tests: each chip takes and returns assigned cells, so it can be dropped into
+//! another circuit's `configure` and `synthesize`.
+
+pub mod bits;
+pub mod boolean;
+pub mod mux;
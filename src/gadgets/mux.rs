@@ -0,0 +1,274 @@
+//! Two-way and `2^n`-way multiplexers.
+
+use crate::prelude::*;
+
+/// Columns `[a, b, sel, out]` of a [`MuxChip`] row.
+#[derive(Debug, Clone)]
+pub struct MuxConfig {
+    pub advice: [Column<Advice>; 4],
+    pub selector: Selector,
+}
+
+/// Selects between two cells with `out = sel * b + (1 - sel) * a`, where the
+/// gate also constrains `sel` to be boolean.
+///
+/// [`MuxChip::mux_n`] builds a `2^n`-way multiplexer from a tree of these rows,
+/// driven by the bits of an index, e.g. from [`super::bits::BitsChip`].
+#[derive(Debug, Clone)]
+pub struct MuxChip<F: Field> {
+    config: MuxConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MuxChip<F> {
+    pub fn construct(config: MuxConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 4]) -> MuxConfig {
+        let [col_a, col_b, col_sel, col_out] = advice;
+        let selector = meta.selector();
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("mux", |meta| {
+            //
+            // col_a | col_b | col_sel | col_out | selector
+            //   a      b       sel       out        s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let sel = meta.query_advice(col_sel, Rotation::cur());
+            let out = meta.query_advice(col_out, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+
+            vec![
+                s.clone() * sel.clone() * (one - sel.clone()),
+                s * (sel * (b - a.clone()) + a - out),
+            ]
+        });
+
+        MuxConfig { advice, selector }
+    }
+
+    /// Returns `b` if `sel` is 1 and `a` if it is 0.
+    pub fn mux(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sel: &AssignedCell<F, F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_a, col_b, col_sel, col_out] = self.config.advice;
+
+        layouter.assign_region(
+            || "mux",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                let a = a.copy_advice(|| "a", &mut region, col_a, 0)?;
+                let b = b.copy_advice(|| "b", &mut region, col_b, 0)?;
+                let sel = sel.copy_advice(|| "sel", &mut region, col_sel, 0)?;
+
+                let out = sel
+                    .value()
+                    .zip(a.value().zip(b.value()))
+                    .map(|(&sel, (&a, &b))| sel * (b - a) + a);
+                region.assign_advice(|| "out", col_out, 0, || out)
+            },
+        )
+    }
+
+    /// Returns `inputs[index]`, where `bits` are the bits of `index`, least
+    /// significant first, and `inputs` has `2^bits.len()` cells.
+    ///
+    /// The bits are only constrained to be boolean; that they recompose to an
+    /// index is up to whoever produced them.
+    pub fn mux_n(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[AssignedCell<F, F>],
+        inputs: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert_eq!(inputs.len(), 1 << bits.len(), "need 2^n inputs for n bits");
+
+        let mut level = inputs.to_vec();
+        for (depth, bit) in bits.iter().enumerate() {
+            level = level
+                .chunks(2)
+                .enumerate()
+                .map(|(i, pair)| {
+                    self.mux(
+                        layouter.namespace(|| format!("level {} pair {}", depth, i)),
+                        bit,
+                        &pair[0],
+                        &pair[1],
+                    )
+                })
+                .collect::<Result<_, _>>()?;
+        }
+        Ok(level.pop().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadgets::bits::{BitsChip, BitsConfig};
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    const INPUTS: [u64; 8] = [10, 11, 12, 13, 14, 15, 16, 17];
+
+    /// Selects `INPUTS[index]` through a three-level tree and exposes it.
+    #[derive(Default)]
+    struct MuxCircuit {
+        index: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for MuxCircuit {
+        type Config = (MuxConfig, BitsConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let bits = BitsChip::configure(meta, advice[0], advice[1]);
+            (MuxChip::configure(meta, advice), bits, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, bits_config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let [col_a, ..] = config.advice;
+            let (index, inputs) = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    let index = region.assign_advice(|| "index", col_a, 0, || self.index)?;
+                    let inputs = INPUTS
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &v)| {
+                            region.assign_advice(
+                                || "input",
+                                col_a,
+                                i + 1,
+                                || Value::known(Fp::from(v)),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok((index, inputs))
+                },
+            )?;
+
+            let bits = BitsChip::construct(bits_config).to_bits(
+                layouter.namespace(|| "index bits"),
+                &index,
+                3,
+            )?;
+            let chip = MuxChip::construct(config);
+            let out = chip.mux_n(layouter.namespace(|| "mux"), &bits, &inputs)?;
+
+            layouter.constrain_instance(out.cell(), instance, 0)
+        }
+    }
+
+    fn run(index: u64, out: u64) -> MockProver<Fp> {
+        let k = 6;
+
+        let circuit = MuxCircuit {
+            index: Value::known(Fp::from(index)),
+        };
+        MockProver::run(k, &circuit, vec![vec![Fp::from(out)]]).unwrap()
+    }
+
+    #[test]
+    fn mux_n_selects_each_input() {
+        for (index, &value) in INPUTS.iter().enumerate() {
+            run(index as u64, value).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn mux_n_rejects_other_input() {
+        assert!(run(3, INPUTS[4]).verify().is_err());
+        // 8 does not fit in three bits.
+        assert!(run(8, INPUTS[0]).verify().is_err());
+    }
+
+    /// A single mux row with the given, possibly dishonest, witnesses.
+    #[derive(Clone, Copy)]
+    struct RowCircuit {
+        a: u64,
+        b: u64,
+        sel: Fp,
+        out: Fp,
+    }
+
+    impl Circuit<Fp> for RowCircuit {
+        type Config = MuxConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            *self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            MuxChip::configure(meta, advice)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "mux",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    let values = [Fp::from(self.a), Fp::from(self.b), self.sel, self.out];
+                    for (column, value) in config.advice.into_iter().zip(values) {
+                        region.assign_advice(|| "", column, 0, || Value::known(value))?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn mux_rejects_non_boolean_selector() {
+        let k = 4;
+
+        // sel = 2 gives 2 * (7 - 3) + 3 = 11, which is neither input.
+        let circuit = RowCircuit {
+            a: 3,
+            b: 7,
+            sel: Fp::from(2),
+            out: Fp::from(11),
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert_constraint_fails(&prover, "mux");
+
+        let circuit = RowCircuit {
+            sel: Fp::ONE,
+            out: Fp::from(7),
+            ..circuit
+        };
+        MockProver::run(k, &circuit, vec![])
+            .unwrap()
+            .assert_satisfied();
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{names, prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Evaluates `p(x) = c_0 + c_1*x + ... + c_n*x^n` with Horner's method, one
+/// coefficient per row from the highest down:
+///
+/// ```text
+/// acc_0 = c_n
+/// acc_{i+1} = acc_i * x + c_{n-1-i}
+/// ```
+///
+/// The coefficients and `x` are private; `x` is witnessed once and copied into
+/// every row, and only `y = p(x)` is public.
+#[derive(Debug, Clone)]
+struct HornerConfig {
+    pub advice: [Column<Advice>; 3],
+    pub selector_first: Selector,
+    pub selector_step: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct HornerChip<F: Field> {
+    config: HornerConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> HornerChip<F> {
+    pub fn construct(config: HornerConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+    ) -> HornerConfig {
+        let [col_acc, col_coeff, col_x] = advice;
+        let selector_first = meta.selector();
+        let selector_step = meta.selector();
+        meta.enable_equality(col_acc);
+        meta.enable_equality(col_x);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::FIRST, |meta| {
+            let s = meta.query_selector(selector_first);
+            let acc = meta.query_advice(col_acc, Rotation::cur());
+            let coeff = meta.query_advice(col_coeff, Rotation::cur());
+            vec![s * (acc - coeff)]
+        });
+
+        meta.create_gate(names::STEP, |meta| {
+            //
+            // col_acc  | col_coeff  | col_x | selector
+            //   acc         -           x        s
+            //   acc_next  coeff_next
+            //
+            let s = meta.query_selector(selector_step);
+            let acc = meta.query_advice(col_acc, Rotation::cur());
+            let x = meta.query_advice(col_x, Rotation::cur());
+            let acc_next = meta.query_advice(col_acc, Rotation::next());
+            let coeff_next = meta.query_advice(col_coeff, Rotation::next());
+            vec![s * (acc * x + coeff_next - acc_next)]
+        });
+
+        HornerConfig {
+            advice,
+            selector_first,
+            selector_step,
+            instance,
+        }
+    }
+
+    /// Assigns the whole evaluation table and returns the cell holding `p(x)`.
+    /// `coeffs[i]` is the coefficient of `x^i`.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        coeffs: &[Value<F>],
+        x: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_acc, col_coeff, col_x] = self.config.advice;
+
+        layouter.assign_region(
+            || "horner",
+            |mut region| {
+                self.config.selector_first.enable(&mut region, 0)?;
+
+                let x_cell = region.assign_advice(|| "x", col_x, 0, || x)?;
+                let mut acc = Value::known(F::ZERO);
+                let mut acc_cell = None;
+
+                for (row, coeff) in coeffs.iter().rev().enumerate() {
+                    if row + 1 < coeffs.len() {
+                        self.config.selector_step.enable(&mut region, row)?;
+                    }
+                    if row > 0 {
+                        x_cell.copy_advice(|| "x", &mut region, col_x, row)?;
+                    }
+
+                    region.assign_advice(|| "coeff", col_coeff, row, || *coeff)?;
+                    acc = acc * x + coeff;
+                    acc_cell = Some(region.assign_advice(|| "acc", col_acc, row, || acc)?);
+                }
+
+                Ok(acc_cell.expect("at least one coefficient"))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference for `p(x)`, with `coeffs[i]` the coefficient of `x^i`.
+pub fn reference(coeffs: &[u64], x: u64) -> u64 {
+    coeffs.iter().rev().fold(0, |acc, coeff| acc * x + coeff)
+}
+
+struct MyCircuit<F> {
+    coeffs: Vec<Value<F>>,
+    x: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = HornerConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            coeffs: vec![Value::unknown(); self.coeffs.len()],
+            x: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let instance = meta.instance_column();
+        HornerChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = HornerChip::construct(config);
+
+        let y_cell = chip.assign(layouter.namespace(|| "horner"), &self.coeffs, self.x)?;
+
+        chip.expose_public(layouter.namespace(|| "y"), &y_cell, 0)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on `5 + 2x + 3x^3` at `x = 4`.
+pub fn example_circuit() -> ExampleCircuit {
+    let coeffs = [5, 2, 0, 3];
+    let circuit = MyCircuit {
+        coeffs: coeffs.iter().map(|&c| Value::known(Fp::from(c))).collect(),
+        x: Value::known(Fp::from(4)),
+    };
+    let y = Fp::from(reference(&coeffs, 4));
+    ExampleCircuit::new(4, circuit, vec![vec![y]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    // p(x) = 5 + 2x + 3x^3
+    const COEFFS: [u64; 4] = [5, 2, 0, 3];
+
+    fn circuit(coeffs: &[u64], x: u64) -> MyCircuit<Fp> {
+        MyCircuit {
+            coeffs: coeffs.iter().map(|&c| Value::known(Fp::from(c))).collect(),
+            x: Value::known(Fp::from(x)),
+        }
+    }
+
+    #[test]
+    fn horner_example() {
+        let k = 4;
+
+        // 5 + 8 + 192
+        let y = reference(&COEFFS, 4);
+        assert_eq!(y, 205);
+
+        let prover = MockProver::run(k, &circuit(&COEFFS, 4), vec![vec![Fp::from(y)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn horner_constant_polynomial() {
+        let k = 4;
+
+        let prover = MockProver::run(k, &circuit(&[7], 4), vec![vec![Fp::from(7)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn horner_rejects_wrong_coefficient() {
+        let k = 4;
+
+        let y = reference(&COEFFS, 4);
+        let prover =
+            MockProver::run(k, &circuit(&[5, 2, 1, 3], 4), vec![vec![Fp::from(y)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn horner_gate_degree() {
+        // s * (acc * x + coeff_next - acc_next)
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                MyCircuit::<Fp>::configure(meta);
+            }),
+            3
+        );
+    }
+}
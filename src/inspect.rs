@@ -0,0 +1,402 @@
+//! Dumps the structure of a circuit's constraint system, as `configure` leaves
+//! it, to JSON and Graphviz, so it can be reviewed and diffed without reading
+//! the code.
+//!
+//! Cells are named the way `halo2_proofs::dev::CircuitGates` prints them:
+//! `A1@-1` is advice column 1 at rotation -1, `F0@0` and `I0@0` are fixed and
+//! instance cells, and `S2` is selector 2. Columns drop the rotation: `A1`.
+//!
+//! halo2_proofs 0.3 keeps the constraint system's fields crate-private. The
+//! gates are read from `CircuitGates`, and the rest from the `Debug` output of
+//! the pinned constraint system.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    fs, io,
+    path::Path,
+};
+
+use halo2_proofs::{
+    dev::CircuitGates,
+    plonk::{Circuit, ConstraintSystem},
+};
+use serde::Serialize;
+
+use crate::prelude::PrimeField;
+
+/// Everything `configure` declared.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CircuitStructure {
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+    pub gates: Vec<GateInfo>,
+    /// Columns in the permutation argument, that is with equality enabled.
+    pub equality: Vec<String>,
+    /// Fixed columns reserved for constants.
+    pub constants: Vec<String>,
+    pub lookups: Vec<LookupInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GateInfo {
+    pub name: String,
+    pub constraints: Vec<ConstraintInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConstraintInfo {
+    /// Empty if the constraint was not given a name.
+    pub name: String,
+    pub expression: String,
+    /// The cells the expression queries, sorted.
+    pub queries: Vec<String>,
+}
+
+/// One lookup argument: the cells its input expressions query, and the table
+/// columns they are looked up in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LookupInfo {
+    pub inputs: Vec<String>,
+    pub tables: Vec<String>,
+}
+
+/// Runs `C::configure` and collects what it declared.
+pub fn inspect<F: PrimeField, C: Circuit<F>>() -> CircuitStructure {
+    let mut meta = ConstraintSystem::<F>::default();
+    C::configure(&mut meta);
+    let pinned = format!("{:?}", meta.pinned());
+
+    let section = |start: &str, end: &str| {
+        let from = pinned.find(start).unwrap() + start.len();
+        let to = from + pinned[from..].find(end).unwrap();
+        pinned[from..to].to_string()
+    };
+    let lookups = section("lookups: [", ", constants: ");
+    let lookups = lookups
+        .split("Argument { input_expressions: ")
+        .skip(1)
+        .map(|argument| {
+            let (inputs, tables) = argument.split_once("table_expressions: ").unwrap();
+            LookupInfo {
+                inputs: queries(inputs),
+                tables: queries(tables)
+                    .iter()
+                    .map(|query| column_of(query).to_string())
+                    .collect(),
+            }
+        })
+        .collect();
+
+    CircuitStructure {
+        advice_columns: count(&pinned, "num_advice_columns"),
+        fixed_columns: count(&pinned, "num_fixed_columns"),
+        instance_columns: count(&pinned, "num_instance_columns"),
+        selectors: count(&pinned, "num_selectors"),
+        gates: gates(&CircuitGates::collect::<F, C>().to_string()),
+        equality: columns(&section("permutation: Argument { columns: [", "] }")),
+        constants: columns(&section("constants: [", "]")),
+        lookups,
+    }
+}
+
+/// The number after `name: ` in `debug`.
+fn count(debug: &str, name: &str) -> usize {
+    let start = debug.find(&format!("{}: ", name)).unwrap() + name.len() + 2;
+    let digits: String = debug[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().unwrap()
+}
+
+/// The columns in a `Debug`-formatted list of `Column`s.
+fn columns(debug: &str) -> Vec<String> {
+    debug
+        .split("Column { ")
+        .skip(1)
+        .map(|column| {
+            let index = count(column, "index");
+            let kind = column.split("column_type: ").nth(1).unwrap();
+            format!("{}{}", &kind[..1], index)
+        })
+        .collect()
+}
+
+/// The cells a `Debug`-formatted expression queries, sorted and deduplicated.
+fn queries(debug: &str) -> Vec<String> {
+    let mut found = BTreeSet::new();
+    for (prefix, kind) in [("Advice { ", 'A'), ("Fixed { ", 'F'), ("Instance { ", 'I')] {
+        for query in debug.split(prefix).skip(1) {
+            let column = count(query, "column_index");
+            let rotation = query.split("Rotation(").nth(1).unwrap();
+            let rotation: String = rotation.chars().take_while(|&c| c != ')').collect();
+            found.insert(format!("{}{}@{}", kind, column, rotation));
+        }
+    }
+    for selector in debug.split("Selector(Selector(").skip(1) {
+        let index: String = selector
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        found.insert(format!("S{}", index));
+    }
+    found.into_iter().collect()
+}
+
+/// The column of a cell name: `A1@-1` to `A1`.
+fn column_of(query: &str) -> &str {
+    query.split('@').next().unwrap()
+}
+
+/// Parses the `Display` output of `CircuitGates`.
+fn gates(display: &str) -> Vec<GateInfo> {
+    let mut gates: Vec<GateInfo> = vec![];
+    let mut pending_name = None;
+    for line in display.lines() {
+        if line.starts_with("Total gates: ") {
+            break;
+        }
+        let constraint = |name: String, expression: &str| ConstraintInfo {
+            name,
+            expression: expression.to_string(),
+            queries: expression_queries(expression),
+        };
+        if let Some(expression) = line.strip_prefix("  ") {
+            let name = pending_name.take().unwrap_or_default();
+            gates
+                .last_mut()
+                .unwrap()
+                .constraints
+                .push(constraint(name, expression));
+        } else if let Some(rest) = line.strip_prefix("- ") {
+            match rest.strip_suffix(':') {
+                Some(name) => pending_name = Some(name.to_string()),
+                None => gates
+                    .last_mut()
+                    .unwrap()
+                    .constraints
+                    .push(constraint(String::new(), rest)),
+            }
+        } else {
+            let name = line.strip_suffix(':').unwrap_or(line);
+            gates.push(GateInfo {
+                name: name.to_string(),
+                constraints: vec![],
+            });
+        }
+    }
+    gates
+}
+
+/// The cells named in a `CircuitGates` expression, sorted and deduplicated.
+fn expression_queries(expression: &str) -> Vec<String> {
+    let mut found = BTreeSet::new();
+    let bytes = expression.as_bytes();
+    for (i, &byte) in bytes.iter().enumerate() {
+        let starts_token = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        if starts_token && b"AFIS".contains(&byte) {
+            let token: String = expression[i..]
+                .chars()
+                .take_while(|&c| c.is_ascii_alphanumeric() || c == '@' || c == '-')
+                .collect();
+            let digits = token[1..].split('@').next().unwrap();
+            if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+                found.insert(token);
+            }
+        }
+    }
+    found.into_iter().collect()
+}
+
+impl CircuitStructure {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("the structure is plain data")
+    }
+
+    /// A Graphviz digraph with a node per column, gate and lookup, and an edge
+    /// from each gate or lookup to every column it queries, labelled with the
+    /// rotations. Columns with equality enabled have a double border.
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n    rankdir=LR;\n", name);
+        let mut edges = |node: &str, queries: &[String], style: &str| {
+            let mut rotations: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+            for query in queries {
+                let mut parts = query.split('@');
+                let column = parts.next().unwrap();
+                rotations.entry(column).or_default().extend(parts);
+            }
+            for (column, rotations) in rotations {
+                writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\" [label=\"{}\"{}];",
+                    node,
+                    column,
+                    rotations.join(","),
+                    style
+                )
+                .unwrap();
+            }
+        };
+        for gate in &self.gates {
+            let queries: Vec<String> = gate
+                .constraints
+                .iter()
+                .flat_map(|constraint| constraint.queries.iter().cloned())
+                .collect();
+            edges(&format!("gate: {}", gate.name), &queries, "");
+        }
+        for (i, lookup) in self.lookups.iter().enumerate() {
+            let node = format!("lookup {}", i);
+            edges(&node, &lookup.inputs, "");
+            edges(&node, &lookup.tables, ", style=dashed");
+        }
+
+        for (kind, count) in [
+            ('A', self.advice_columns),
+            ('F', self.fixed_columns),
+            ('I', self.instance_columns),
+        ] {
+            for index in 0..count {
+                let column = format!("{}{}", kind, index);
+                let peripheries = if self.equality.contains(&column) {
+                    2
+                } else {
+                    1
+                };
+                writeln!(
+                    dot,
+                    "    \"{}\" [shape=box, peripheries={}];",
+                    column, peripheries
+                )
+                .unwrap();
+            }
+        }
+        for index in 0..self.selectors {
+            writeln!(dot, "    \"S{}\" [shape=box, style=dotted];", index).unwrap();
+        }
+        for gate in &self.gates {
+            writeln!(dot, "    \"gate: {}\" [shape=ellipse];", gate.name).unwrap();
+        }
+        for i in 0..self.lookups.len() {
+            writeln!(dot, "    \"lookup {}\" [shape=diamond];", i).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Writes the structure of `C` as JSON to `json`, and as a Graphviz digraph to
+/// `dot` if given.
+pub fn write<F: PrimeField, C: Circuit<F>>(json: &Path, dot: Option<&Path>) -> io::Result<()> {
+    let structure = inspect::<F, C>();
+    fs::write(json, structure.to_json())?;
+    if let Some(dot) = dot {
+        fs::write(dot, structure.to_dot(std::any::type_name::<C>()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fibonacci::example2,
+        fsm::{FsmChip, FsmConfig},
+    };
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        pasta::Fp,
+        plonk::Error,
+    };
+
+    #[test]
+    fn inspect_fibonacci_example2() {
+        let structure = inspect::<Fp, example2::MyCircuit<Fp>>();
+
+        assert_eq!(
+            (
+                structure.advice_columns,
+                structure.fixed_columns,
+                structure.instance_columns,
+                structure.selectors
+            ),
+            (1, 0, 1, 1)
+        );
+        assert_eq!(
+            structure.gates,
+            vec![GateInfo {
+                name: "add".to_string(),
+                constraints: vec![ConstraintInfo {
+                    name: String::new(),
+                    expression: "S0 * (A0@0 + A0@1 - A0@2)".to_string(),
+                    queries: ["A0@0", "A0@1", "A0@2", "S0"].map(String::from).to_vec(),
+                }],
+            }]
+        );
+        assert_eq!(structure.equality, ["A0", "I0"]);
+        assert!(structure.constants.is_empty() && structure.lookups.is_empty());
+    }
+
+    /// Only the configuration of an `FsmChip`, which has lookups and a
+    /// constant column.
+    #[derive(Default)]
+    struct FsmCircuit;
+
+    impl Circuit<Fp> for FsmCircuit {
+        type Config = FsmConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let state = meta.advice_column();
+            let input = meta.advice_column();
+            FsmChip::configure(meta, state, input)
+        }
+
+        fn synthesize(&self, _: Self::Config, _: impl Layouter<Fp>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn inspect_lookups_and_constants() {
+        let structure = inspect::<Fp, FsmCircuit>();
+
+        // (state, input, next state) into the transitions, and the last state
+        // into the accepting states.
+        assert_eq!(
+            structure.lookups,
+            vec![
+                LookupInfo {
+                    inputs: ["A0@0", "A0@1", "A1@0", "S0"].map(String::from).to_vec(),
+                    tables: ["F0", "F1", "F2"].map(String::from).to_vec(),
+                },
+                LookupInfo {
+                    inputs: ["A0@0", "S1"].map(String::from).to_vec(),
+                    tables: vec!["F3".to_string()],
+                },
+            ]
+        );
+        assert_eq!(structure.constants, ["F4"]);
+    }
+
+    #[test]
+    fn inspect_json_and_dot() {
+        let structure = inspect::<Fp, example2::MyCircuit<Fp>>();
+
+        let json: serde_json::Value = serde_json::from_str(&structure.to_json()).unwrap();
+        assert_eq!(json["gates"][0]["name"], "add");
+        assert_eq!(json["equality"][1], "I0");
+
+        let dot = structure.to_dot("example2");
+        assert!(dot.starts_with("digraph \"example2\" {"));
+        assert!(dot.contains("\"gate: add\" -> \"A0\" [label=\"0,1,2\"];"));
+        assert!(dot.contains("\"A0\" [shape=box, peripheries=2];"));
+    }
+}
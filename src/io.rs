@@ -0,0 +1,414 @@
+//! Circuit inputs from a file, for running the examples on values that are not
+//! baked into the code.
+//!
+//! An inputs file is a flat JSON object or TOML table of named values:
+//!
+//! ```json
+//! { "a": 0, "b": 1, "n": 10, "out": 55 }
+//! ```
+//!
+//! ```toml
+//! a = 0
+//! b = "0x01"
+//! n = 10
+//! ```
+//!
+//! Field elements are decimal or `0x`-prefixed big-endian hex, and must be
+//! smaller than the field modulus. JSON numbers past `u64` lose precision before
+//! they get here, so larger values have to be written as strings. Only the flat
+//! `key = value` part of TOML is read: no tables, arrays or escaped strings.
+
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+use ff::PrimeField;
+use halo2_proofs::pasta::Fp;
+use serde_json::Value;
+
+use crate::fibonacci::{fib_term, FibIndex, FibonacciInstances};
+
+/// Why an inputs file was rejected.
+#[derive(Debug)]
+pub enum InputError {
+    Io(io::Error),
+    /// The file is not a flat JSON object or TOML table.
+    Syntax(String),
+    Missing(&'static str),
+    Unknown(String),
+    /// `key` holds a value of the wrong kind, or one out of range.
+    Invalid {
+        key: String,
+        value: String,
+        reason: String,
+    },
+    /// The output the file claims is not the term its seeds lead to.
+    WrongOutput {
+        claimed: Fp,
+        computed: Fp,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io(e) => write!(f, "{}", e),
+            InputError::Syntax(message) => write!(f, "{}", message),
+            InputError::Missing(key) => write!(f, "missing input {:?}", key),
+            InputError::Unknown(key) => write!(f, "unknown input {:?}", key),
+            InputError::Invalid { key, value, reason } => {
+                write!(f, "input {:?} = {:?} {}", key, value, reason)
+            }
+            InputError::WrongOutput { claimed, computed } => write!(
+                f,
+                "the claimed output {:?} is not the computed term {:?}",
+                claimed, computed
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl From<io::Error> for InputError {
+    fn from(e: io::Error) -> Self {
+        InputError::Io(e)
+    }
+}
+
+/// Parses a field element written in decimal or as `0x`-prefixed hex.
+///
+/// Values of at least the field modulus are rejected rather than reduced, so a
+/// typo cannot silently turn into a different element.
+pub fn parse_field(text: &str) -> Result<Fp, String> {
+    let text = text.trim();
+    let bytes = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(digits) => {
+            if digits.is_empty() {
+                return Err("has no digits".to_string());
+            }
+            let digits = if digits.len() % 2 == 1 {
+                format!("0{}", digits)
+            } else {
+                digits.to_string()
+            };
+            hex::decode(digits).map_err(|e| format!("is not hex: {}", e))?
+        }
+        None => decimal_bytes(text)?,
+    };
+
+    let start = bytes
+        .iter()
+        .position(|&byte| byte != 0)
+        .unwrap_or(bytes.len());
+    let bytes = &bytes[start..];
+    if bytes.len() > 32 {
+        return Err("does not fit in 256 bits".to_string());
+    }
+    let mut repr = [0u8; 32];
+    repr[32 - bytes.len()..].copy_from_slice(bytes);
+    repr.reverse();
+    Option::from(Fp::from_repr(repr)).ok_or_else(|| "is not below the field modulus".to_string())
+}
+
+/// The big-endian bytes of a decimal number.
+fn decimal_bytes(text: &str) -> Result<Vec<u8>, String> {
+    if text.is_empty() || !text.bytes().all(|c| c.is_ascii_digit()) {
+        return Err("is not a decimal or 0x-prefixed hex number".to_string());
+    }
+    let mut bytes: Vec<u8> = Vec::new();
+    for digit in text.bytes() {
+        let mut carry = u32::from(digit - b'0');
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry > 0 {
+            bytes.insert(0, carry as u8);
+        }
+        if bytes.len() > 33 {
+            return Err("does not fit in 256 bits".to_string());
+        }
+    }
+    Ok(bytes)
+}
+
+/// The raw values of an inputs file, by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inputs(BTreeMap<String, String>);
+
+impl Inputs {
+    pub fn from_json(json: &str) -> Result<Self, InputError> {
+        let object: serde_json::Map<String, Value> =
+            serde_json::from_str(json).map_err(|e| InputError::Syntax(e.to_string()))?;
+        let mut values = BTreeMap::new();
+        for (key, value) in object {
+            let text = match value {
+                Value::String(text) => text,
+                Value::Number(number) if number.is_u64() => number.to_string(),
+                other => {
+                    return Err(InputError::Invalid {
+                        key,
+                        value: other.to_string(),
+                        reason: "is not a non-negative integer or a string".to_string(),
+                    })
+                }
+            };
+            values.insert(key, text);
+        }
+        Ok(Self(values))
+    }
+
+    /// Reads `key = value` lines, where a value is a bare integer or a string
+    /// in double quotes. Blank lines and `#` comments are skipped.
+    pub fn from_toml(toml: &str) -> Result<Self, InputError> {
+        let mut values = BTreeMap::new();
+        for (number, line) in toml.lines().enumerate() {
+            let syntax =
+                |message: &str| InputError::Syntax(format!("line {}: {}", number + 1, message));
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                return Err(syntax("tables are not supported"));
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| syntax("expected `key = value`"))?;
+            let key = key.trim().trim_matches('"');
+            if key.is_empty() {
+                return Err(syntax("empty key"));
+            }
+
+            let value = value.trim();
+            let text = match value.strip_prefix('"') {
+                Some(quoted) => {
+                    let (text, rest) = quoted
+                        .split_once('"')
+                        .ok_or_else(|| syntax("unterminated string"))?;
+                    let rest = rest.trim();
+                    if !rest.is_empty() && !rest.starts_with('#') {
+                        return Err(syntax("unexpected text after the string"));
+                    }
+                    text.to_string()
+                }
+                None => {
+                    let bare = value.split('#').next().unwrap_or_default().trim();
+                    if bare.is_empty() {
+                        return Err(syntax("missing value"));
+                    }
+                    bare.replace('_', "")
+                }
+            };
+            if values.insert(key.to_string(), text).is_some() {
+                return Err(syntax(&format!("{:?} is set twice", key)));
+            }
+        }
+        Ok(Self(values))
+    }
+
+    /// Reads `path` as TOML if it ends in `.toml`, and as JSON otherwise.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            _ => Self::from_json(&text),
+        }
+    }
+
+    /// Fails on the first key not in `known`, so a misspelled input is not
+    /// silently ignored.
+    pub fn check_keys(&self, known: &[&str]) -> Result<(), InputError> {
+        match self.0.keys().find(|key| !known.contains(&key.as_str())) {
+            Some(key) => Err(InputError::Unknown(key.clone())),
+            None => Ok(()),
+        }
+    }
+
+    pub fn field(&self, key: &'static str) -> Result<Option<Fp>, InputError> {
+        self.parse(key, parse_field)
+    }
+
+    pub fn usize(&self, key: &'static str) -> Result<Option<usize>, InputError> {
+        self.parse(key, |text| {
+            text.parse()
+                .map_err(|_| "is not a non-negative integer".to_string())
+        })
+    }
+
+    fn parse<T>(
+        &self,
+        key: &'static str,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> Result<Option<T>, InputError> {
+        self.0
+            .get(key)
+            .map(|value| {
+                parse(value).map_err(|reason| InputError::Invalid {
+                    key: key.to_string(),
+                    value: value.clone(),
+                    reason,
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Inputs of the Fibonacci examples: the seeds `a = f(0)` and `b = f(1)`, the
+/// index `n` of the term to prove and, optionally, the expected term `out`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FibonacciInputs {
+    pub a: Fp,
+    pub b: Fp,
+    pub n: FibIndex,
+    pub out: Option<Fp>,
+}
+
+impl FibonacciInputs {
+    const KEYS: &'static [&'static str] = &["a", "b", "n", "out"];
+
+    pub fn from_inputs(inputs: &Inputs) -> Result<Self, InputError> {
+        inputs.check_keys(Self::KEYS)?;
+        Ok(Self {
+            a: inputs.field("a")?.ok_or(InputError::Missing("a"))?,
+            b: inputs.field("b")?.ok_or(InputError::Missing("b"))?,
+            n: FibIndex(inputs.usize("n")?.ok_or(InputError::Missing("n"))?),
+            out: inputs.field("out")?,
+        })
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        Self::from_inputs(&Inputs::read(path)?)
+    }
+
+    /// The public inputs, after checking `out` against the computed term.
+    pub fn instances(&self) -> Result<FibonacciInstances, InputError> {
+        let computed = fib_term(self.a, self.b, self.n);
+        match self.out {
+            Some(claimed) if claimed != computed => {
+                Err(InputError::WrongOutput { claimed, computed })
+            }
+            _ => Ok(FibonacciInstances {
+                seed_a: self.a,
+                seed_b: self.b,
+                output: computed,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn parses_decimal_and_hex() {
+        assert_eq!(parse_field("55"), Ok(Fp::from(55)));
+        assert_eq!(parse_field("0x37"), Ok(Fp::from(55)));
+        assert_eq!(parse_field("0x0037"), Ok(Fp::from(55)));
+        assert_eq!(parse_field("0x7"), Ok(Fp::from(7)));
+        assert_eq!(parse_field("0"), Ok(Fp::ZERO));
+
+        // 2^64 needs more than a u64.
+        assert_eq!(
+            parse_field("18446744073709551616"),
+            Ok(Fp::from(u64::MAX) + Fp::ONE)
+        );
+
+        // The modulus itself is one past the largest field element.
+        let max = -Fp::ONE;
+        let mut max_be = max.to_repr();
+        max_be.as_mut().reverse();
+        let modulus = Fp::MODULUS.trim_start_matches("0x");
+        assert_eq!(
+            parse_field(&format!("0x{}", modulus)).map(|_| ()),
+            Err("is not below the field modulus".to_string())
+        );
+        assert_eq!(parse_field(&format!("0x{}", hex::encode(max_be))), Ok(max));
+
+        for bad in ["", "-1", "0x", "0xzz", "1.5", "12a"] {
+            assert!(parse_field(bad).is_err(), "{:?}", bad);
+        }
+        assert!(parse_field(&"9".repeat(100)).is_err());
+    }
+
+    #[test]
+    fn json_and_toml_agree() {
+        let json = Inputs::from_json(r#"{ "a": 0, "b": "0x01", "n": 10, "out": 55 }"#).unwrap();
+        let toml = Inputs::from_toml(
+            "# f(10) from 0, 1\na = 0\nb = \"0x01\"  # hex in a string\nn = 1_0\nout = 55\n",
+        )
+        .unwrap();
+        assert_eq!(json, toml);
+
+        let inputs = FibonacciInputs::from_inputs(&json).unwrap();
+        assert_eq!(inputs.n, FibIndex(10));
+        assert_eq!(
+            inputs.instances().unwrap(),
+            FibonacciInstances::from_seeds(0, 1, FibIndex(10)).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        let fib = |json: &str| FibonacciInputs::from_inputs(&Inputs::from_json(json)?)?.instances();
+
+        assert!(matches!(
+            fib(r#"{ "a": 0, "b": 1 }"#),
+            Err(InputError::Missing("n"))
+        ));
+        assert!(matches!(
+            fib(r#"{ "a": 0, "b": 1, "n": 9, "c": 2 }"#),
+            Err(InputError::Unknown(key)) if key == "c"
+        ));
+        assert!(matches!(
+            fib(r#"{ "a": -1, "b": 1, "n": 9 }"#),
+            Err(InputError::Invalid { key, .. }) if key == "a"
+        ));
+        assert!(matches!(
+            fib(r#"{ "a": 0, "b": 1, "n": "0x9" }"#),
+            Err(InputError::Invalid { key, .. }) if key == "n"
+        ));
+        assert!(matches!(
+            fib(r#"{ "a": 0, "b": 1, "n": 9, "out": 55 }"#),
+            Err(InputError::WrongOutput { .. })
+        ));
+        assert!(matches!(fib("[0, 1, 9]"), Err(InputError::Syntax(_))));
+
+        for bad in ["[fib]\na = 1", "a 1", "a = \"1", "a =", "a = 1\na = 2"] {
+            assert!(
+                matches!(Inputs::from_toml(bad), Err(InputError::Syntax(_))),
+                "{:?}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn reads_files_by_extension() {
+        let dir = std::env::temp_dir();
+        let json = dir.join(format!("fib-inputs-{}.json", std::process::id()));
+        let toml = dir.join(format!("fib-inputs-{}.toml", std::process::id()));
+        fs::write(&json, r#"{ "a": 1, "b": 1, "n": 9 }"#).unwrap();
+        fs::write(&toml, "a = 1\nb = 1\nn = 9\n").unwrap();
+
+        let expected = FibonacciInstances::from_seeds(1, 1, FibIndex(9));
+        assert_eq!(
+            FibonacciInputs::read(&json).unwrap().instances().ok(),
+            expected
+        );
+        assert_eq!(
+            FibonacciInputs::read(&toml).unwrap().instances().ok(),
+            expected
+        );
+        fs::remove_file(json).unwrap();
+        fs::remove_file(toml).unwrap();
+
+        assert!(matches!(
+            FibonacciInputs::read(dir.join("missing.json")),
+            Err(InputError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,241 @@
+//! An is-zero gadget: an expression that is 1 when a value is zero and 0
+//! otherwise, for use inside other gates.
+
+use crate::prelude::*;
+
+/// Witnesses `value_inv`, the inverse of a value (or 0 if the value is zero),
+/// and constrains `value * (1 - value * value_inv) == 0`.
+///
+/// With that gate in place `1 - value * value_inv` is exactly the is-zero bit:
+/// a non-zero value forces `value_inv` to be its inverse, and a zero value makes
+/// the expression 1 whatever `value_inv` is.
+#[derive(Debug, Clone)]
+pub struct IsZeroConfig<F> {
+    pub value_inv: Column<Advice>,
+    is_zero_expr: Expression<F>,
+}
+
+impl<F: Field> IsZeroConfig<F> {
+    /// 1 if the value is zero, 0 otherwise.
+    pub fn expr(&self) -> Expression<F> {
+        self.is_zero_expr.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IsZeroChip<F: Field> {
+    config: IsZeroConfig<F>,
+}
+
+impl<F: Field> IsZeroChip<F> {
+    pub fn construct(config: IsZeroConfig<F>) -> Self {
+        Self { config }
+    }
+
+    /// `q_enable` and `value` are queried in the gate's context; the gadget is
+    /// active on the rows where `q_enable` is non-zero.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value_inv: Column<Advice>,
+    ) -> IsZeroConfig<F> {
+        let mut is_zero_expr = Expression::Constant(F::ZERO);
+
+        meta.create_gate("is_zero", |meta| {
+            let q_enable = q_enable(meta);
+            let value = value(meta);
+            let value_inv = meta.query_advice(value_inv, Rotation::cur());
+
+            is_zero_expr = Expression::Constant(F::ONE) - value.clone() * value_inv;
+            vec![q_enable * value * is_zero_expr.clone()]
+        });
+
+        IsZeroConfig {
+            value_inv,
+            is_zero_expr,
+        }
+    }
+
+    /// Witnesses the inverse of `value` at `offset` of `region`.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: Value<F>,
+    ) -> Result<(), Error> {
+        let value_inv = value.map(|value| value.invert().unwrap_or(F::ZERO));
+        region.assign_advice(|| "value inv", self.config.value_inv, offset, || value_inv)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    /// `out = if a == b { a } else { a - b }`, with the branch chosen by
+    /// `is_zero(a - b)` inside a single gate.
+    #[derive(Debug, Clone)]
+    struct ConditionalConfig {
+        a: Column<Advice>,
+        b: Column<Advice>,
+        out: Column<Advice>,
+        selector: Selector,
+        a_equals_b: IsZeroConfig<Fp>,
+    }
+
+    #[derive(Default)]
+    struct ConditionalCircuit {
+        a: Value<Fp>,
+        b: Value<Fp>,
+        out: Value<Fp>,
+        /// Replaces the honest inverse of `a - b`, to play a cheating prover.
+        value_inv: Option<Fp>,
+    }
+
+    impl ConditionalCircuit {
+        fn new(a: u64, b: u64) -> Self {
+            let (a, b) = (Fp::from(a), Fp::from(b));
+            let out = if a == b { a } else { a - b };
+            Self {
+                a: Value::known(a),
+                b: Value::known(b),
+                out: Value::known(out),
+                value_inv: None,
+            }
+        }
+    }
+
+    impl Circuit<Fp> for ConditionalCircuit {
+        type Config = ConditionalConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let [a, b, out, value_inv] = [(); 4].map(|_| meta.advice_column());
+            let selector = meta.selector();
+
+            let a_equals_b = IsZeroChip::configure(
+                meta,
+                |meta| meta.query_selector(selector),
+                |meta| {
+                    meta.query_advice(a, Rotation::cur()) - meta.query_advice(b, Rotation::cur())
+                },
+                value_inv,
+            );
+
+            meta.create_gate("conditional", |meta| {
+                let s = meta.query_selector(selector);
+                let a = meta.query_advice(a, Rotation::cur());
+                let b = meta.query_advice(b, Rotation::cur());
+                let out = meta.query_advice(out, Rotation::cur());
+                let eq = a_equals_b.expr();
+                let one = Expression::Constant(Fp::ONE);
+
+                vec![
+                    s.clone() * eq.clone() * (out.clone() - a.clone()),
+                    s * (one - eq) * (out - (a - b)),
+                ]
+            });
+
+            ConditionalConfig {
+                a,
+                b,
+                out,
+                selector,
+                a_equals_b,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = IsZeroChip::construct(config.a_equals_b.clone());
+
+            layouter.assign_region(
+                || "conditional",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    region.assign_advice(|| "a", config.a, 0, || self.a)?;
+                    region.assign_advice(|| "b", config.b, 0, || self.b)?;
+                    region.assign_advice(|| "out", config.out, 0, || self.out)?;
+                    match self.value_inv {
+                        Some(inv) => {
+                            region.assign_advice(
+                                || "value inv",
+                                config.a_equals_b.value_inv,
+                                0,
+                                || Value::known(inv),
+                            )?;
+                        }
+                        None => chip.assign(&mut region, 0, self.a - self.b)?,
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn run(circuit: &ConditionalCircuit) -> MockProver<Fp> {
+        let k = 4;
+
+        MockProver::run(k, circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn equal_values_take_first_branch() {
+        run(&ConditionalCircuit::new(7, 7)).assert_satisfied();
+        run(&ConditionalCircuit::new(0, 0)).assert_satisfied();
+    }
+
+    #[test]
+    fn different_values_take_second_branch() {
+        run(&ConditionalCircuit::new(9, 2)).assert_satisfied();
+        run(&ConditionalCircuit::new(2, 9)).assert_satisfied();
+    }
+
+    #[test]
+    fn rejects_output_from_wrong_branch() {
+        // a != b, but out claims the a == b branch.
+        let circuit = ConditionalCircuit {
+            out: Value::known(Fp::from(9)),
+            ..ConditionalCircuit::new(9, 2)
+        };
+        assert_constraint_fails(&run(&circuit), "conditional");
+
+        // a == b, but out claims the a != b branch.
+        let circuit = ConditionalCircuit {
+            out: Value::known(Fp::ZERO),
+            ..ConditionalCircuit::new(7, 7)
+        };
+        assert_constraint_fails(&run(&circuit), "conditional");
+    }
+
+    #[test]
+    fn rejects_forged_inverse() {
+        // A zero inverse would make the expression 1 for a non-zero difference,
+        // switching to the a == b branch.
+        let circuit = ConditionalCircuit {
+            out: Value::known(Fp::from(9)),
+            value_inv: Some(Fp::ZERO),
+            ..ConditionalCircuit::new(9, 2)
+        };
+        assert_constraint_fails(&run(&circuit), "is_zero");
+    }
+
+    #[test]
+    fn any_inverse_is_accepted_for_zero() {
+        let circuit = ConditionalCircuit {
+            value_inv: Some(Fp::from(123)),
+            ..ConditionalCircuit::new(7, 7)
+        };
+        run(&circuit).assert_satisfied();
+    }
+}
@@ -0,0 +1,76 @@
+pub mod bigint;
+mod collatz;
+#[cfg(feature = "ecc")]
+mod commitment;
+pub mod common;
+mod compare;
+mod composition;
+pub mod cost;
+#[cfg(feature = "ecc")]
+mod curve;
+pub mod decompose;
+mod divmod;
+mod dynamic_lookup;
+pub mod ecc;
+#[cfg(feature = "ecc")]
+mod ecdsa;
+mod factor;
+mod factorial;
+pub mod fibonacci;
+pub mod fixed_point;
+pub mod floor_planner;
+mod fsm;
+mod function;
+pub mod gadgets;
+mod horner;
+pub mod inspect;
+pub mod io;
+pub mod is_zero;
+pub mod linalg;
+mod linsys;
+#[cfg(feature = "poseidon")]
+mod mastermind;
+mod max;
+mod memory;
+pub mod merkle;
+mod mimc;
+mod multiple_of;
+pub mod names;
+#[cfg(feature = "poseidon")]
+mod note;
+mod permutation;
+mod popcount;
+#[cfg(feature = "poseidon")]
+pub mod poseidon;
+mod pow;
+pub mod prelude;
+pub mod prover;
+mod range_check;
+mod recurrence;
+pub mod registry;
+pub mod rlc;
+mod rotation;
+#[cfg(all(feature = "ecc", feature = "poseidon"))]
+mod schnorr;
+pub mod serialize;
+mod set_membership;
+mod sha256;
+mod shuffle;
+mod signed_range;
+mod sudoku;
+mod transfer;
+pub mod word32;
+mod xor;
+mod zkml;
+mod zkvm;
+
+pub use registry::run_all_examples;
+
+#[cfg(feature = "dev-graph")]
+pub mod plot;
+
+#[cfg(test)]
+mod testing;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,189 @@
+//! Inner products accumulated down a running-sum column.
+
+use crate::prelude::*;
+
+/// Columns of a [`DotChip`]: `[a, b, acc]`.
+#[derive(Debug, Clone)]
+pub struct DotConfig {
+    pub advice: [Column<Advice>; 3],
+    pub selector: Selector,
+    pub constant: Column<Fixed>,
+}
+
+/// Computes `sum_i a_i * b_i` with one row per term under a single gate:
+///
+/// ```text
+/// a   | b   | acc
+///     |     | 0                    (fixed)
+/// a_0 | b_0 | acc_prev + a_0 * b_0
+/// a_1 | b_1 | acc_prev + a_1 * b_1
+/// ...
+/// ```
+///
+/// The accumulator starts from a constant zero, so its last cell is the inner
+/// product and can be constrained against an instance value.
+#[derive(Debug, Clone)]
+pub struct DotChip<F: Field> {
+    config: DotConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> DotChip<F> {
+    pub fn construct(config: DotConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> DotConfig {
+        let [col_a, col_b, col_acc] = advice;
+        let selector = meta.selector();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("dot", |meta| {
+            //
+            // col_a | col_b | col_acc  | selector
+            //                  acc_prev
+            //   a      b       acc         s
+            //
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let acc_prev = meta.query_advice(col_acc, Rotation::prev());
+            let acc = meta.query_advice(col_acc, Rotation::cur());
+            vec![s * (acc_prev + a * b - acc)]
+        });
+
+        DotConfig {
+            advice,
+            selector,
+            constant,
+        }
+    }
+
+    /// Returns the inner product of `a` and `b`, which must have the same
+    /// length.
+    pub fn dot(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[AssignedCell<F, F>],
+        b: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert_eq!(a.len(), b.len(), "vectors of different lengths");
+        let [col_a, col_b, col_acc] = self.config.advice;
+
+        layouter.assign_region(
+            || "dot",
+            |mut region| {
+                let mut acc = region.assign_advice_from_constant(|| "acc", col_acc, 0, F::ZERO)?;
+                for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                    let row = i + 1;
+                    self.config.selector.enable(&mut region, row)?;
+
+                    let a = a.copy_advice(|| "a", &mut region, col_a, row)?;
+                    let b = b.copy_advice(|| "b", &mut region, col_b, row)?;
+                    let value = acc.value().copied() + a.value().copied() * b.value();
+                    acc = region.assign_advice(|| "acc", col_acc, row, || value)?;
+                }
+                Ok(acc)
+            },
+        )
+    }
+}
+
+/// Plain-Rust reference: the inner product of `a` and `b`.
+pub fn reference<F: Field>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).map(|(&a, &b)| a * b).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    /// Witnesses both vectors, takes their inner product and exposes it.
+    #[derive(Default)]
+    struct DotCircuit {
+        a: Vec<Fp>,
+        b: Vec<Fp>,
+    }
+
+    impl Circuit<Fp> for DotCircuit {
+        type Config = (DotConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: self.a.clone(),
+                b: self.b.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (DotChip::configure(meta, advice), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let [col_a, col_b, _] = config.advice;
+            let (a, b) = layouter.assign_region(
+                || "vectors",
+                |mut region| {
+                    let mut load = |column, values: &[Fp]| {
+                        values
+                            .iter()
+                            .enumerate()
+                            .map(|(row, &v)| {
+                                region.assign_advice(|| "entry", column, row, || Value::known(v))
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    };
+                    Ok((load(col_a, &self.a)?, load(col_b, &self.b)?))
+                },
+            )?;
+
+            let chip = DotChip::construct(config);
+            let out = chip.dot(layouter.namespace(|| "a . b"), &a, &b)?;
+            layouter.constrain_instance(out.cell(), instance, 0)
+        }
+    }
+
+    fn run(a: &[u64], b: &[u64], out: Fp) -> MockProver<Fp> {
+        let k = 5;
+
+        let circuit = DotCircuit {
+            a: a.iter().map(|&v| Fp::from(v)).collect(),
+            b: b.iter().map(|&v| Fp::from(v)).collect(),
+        };
+        MockProver::run(k, &circuit, vec![vec![out]]).unwrap()
+    }
+
+    #[test]
+    fn dot_matches_reference() {
+        let (a, b) = ([1, 2, 3, 4, 5, 6, 7, 8], [8, 7, 6, 5, 4, 3, 2, 1]);
+        let out = reference(&a.map(Fp::from), &b.map(Fp::from));
+        assert_eq!(out, Fp::from(120));
+        run(&a, &b, out).assert_satisfied();
+
+        // An empty inner product is the starting zero.
+        run(&[], &[], Fp::ZERO).assert_satisfied();
+    }
+
+    #[test]
+    fn dot_rejects_wrong_output() {
+        // 3 * 5 + 4 * 6 is 39.
+        assert!(run(&[3, 4], &[5, 6], Fp::from(38)).verify().is_err());
+        assert!(run(&[3, 4], &[5, 6], Fp::ZERO).verify().is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Products of small matrices whose entries are advice cells.
+
+use crate::prelude::*;
+
+/// An `R x C` matrix of assigned cells, row by row.
+pub type Matrix<F, const R: usize, const C: usize> = [[AssignedCell<F, F>; C]; R];
+
+/// Columns of a [`MatMulChip`]: one row of `A` in `a`, one column of `B` in
+/// `b`, and their inner product in `c`.
+#[derive(Debug, Clone)]
+pub struct MatMulConfig<const N: usize> {
+    pub a: [Column<Advice>; N],
+    pub b: [Column<Advice>; N],
+    pub c: Column<Advice>,
+    pub selector: Selector,
+}
+
+/// Constrains `C = A * B` for an `M x N` matrix `A` and an `N x P` matrix `B`.
+///
+/// Every entry of `C` takes one row, with row `i` of `A` and column `j` of `B`
+/// copied beside it:
+///
+/// ```text
+/// a_0     .. a_{N-1}   | b_0     .. b_{N-1}   | c
+/// A[i][0] .. A[i][N-1] | B[0][j] .. B[N-1][j] | C[i][j]
+/// ```
+///
+/// and a single gate checks `c == sum_k a_k * b_k`. Entries are plain field
+/// elements, so the product wraps around the field modulus unless the caller
+/// keeps them small.
+#[derive(Debug, Clone)]
+pub struct MatMulChip<F: Field, const M: usize, const N: usize, const P: usize> {
+    config: MatMulConfig<N>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, const M: usize, const N: usize, const P: usize> MatMulChip<F, M, N, P> {
+    pub fn construct(config: MatMulConfig<N>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a: [Column<Advice>; N],
+        b: [Column<Advice>; N],
+        c: Column<Advice>,
+    ) -> MatMulConfig<N> {
+        let selector = meta.selector();
+        for column in a.into_iter().chain(b).chain([c]) {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("matmul", |meta| {
+            let s = meta.query_selector(selector);
+            let c = meta.query_advice(c, Rotation::cur());
+            let sum = a
+                .iter()
+                .zip(&b)
+                .fold(Expression::Constant(F::ZERO), |sum, (&a, &b)| {
+                    sum + meta.query_advice(a, Rotation::cur())
+                        * meta.query_advice(b, Rotation::cur())
+                });
+            vec![s * (sum - c)]
+        });
+
+        MatMulConfig { a, b, c, selector }
+    }
+
+    /// Witnesses an `R x C` matrix down the `c` column.
+    pub fn load<const R: usize, const C: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: [[Value<F>; C]; R],
+    ) -> Result<Matrix<F, R, C>, Error> {
+        layouter.assign_region(
+            || "matrix",
+            |mut region| {
+                let cells = values
+                    .iter()
+                    .flatten()
+                    .enumerate()
+                    .map(|(row, &value)| {
+                        region.assign_advice(|| "entry", self.config.c, row, || value)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(to_matrix(cells))
+            },
+        )
+    }
+
+    /// Returns `a * b`.
+    pub fn mul(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Matrix<F, M, N>,
+        b: &Matrix<F, N, P>,
+    ) -> Result<Matrix<F, M, P>, Error> {
+        let config = &self.config;
+
+        layouter.assign_region(
+            || "matmul",
+            |mut region| {
+                let mut cells = Vec::with_capacity(M * P);
+                for i in 0..M {
+                    for j in 0..P {
+                        let row = i * P + j;
+                        config.selector.enable(&mut region, row)?;
+
+                        let mut c = Value::known(F::ZERO);
+                        for k in 0..N {
+                            let a = a[i][k].copy_advice(|| "a", &mut region, config.a[k], row)?;
+                            let b = b[k][j].copy_advice(|| "b", &mut region, config.b[k], row)?;
+                            c = c + a.value().copied() * b.value();
+                        }
+                        cells.push(region.assign_advice(|| "c", config.c, row, || c)?);
+                    }
+                }
+                Ok(to_matrix(cells))
+            },
+        )
+    }
+}
+
+/// Plain-Rust reference: the product of `a` and `b`.
+pub fn reference<F: Field, const M: usize, const N: usize, const P: usize>(
+    a: &[[F; N]; M],
+    b: &[[F; P]; N],
+) -> [[F; P]; M] {
+    std::array::from_fn(|i| std::array::from_fn(|j| (0..N).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+/// Arranges `R * C` cells, row by row, into a matrix.
+fn to_matrix<F: Field, const R: usize, const C: usize>(
+    cells: Vec<AssignedCell<F, F>>,
+) -> Matrix<F, R, C> {
+    let mut cells = cells.into_iter();
+    std::array::from_fn(|_| std::array::from_fn(|_| cells.next().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use rand_core::{OsRng, RngCore};
+
+    const M: usize = 2;
+    const N: usize = 3;
+    const P: usize = 4;
+
+    /// Multiplies `a` by `b` and exposes the product row by row.
+    #[derive(Default)]
+    struct MatMulCircuit {
+        a: [[Value<Fp>; N]; M],
+        b: [[Value<Fp>; P]; N],
+    }
+
+    impl Circuit<Fp> for MatMulCircuit {
+        type Config = (MatMulConfig<N>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let a = [(); N].map(|_| meta.advice_column());
+            let b = [(); N].map(|_| meta.advice_column());
+            let c = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (
+                MatMulChip::<Fp, M, N, P>::configure(meta, a, b, c),
+                instance,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = MatMulChip::<Fp, M, N, P>::construct(config);
+            let a = chip.load(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load(layouter.namespace(|| "b"), self.b)?;
+            let c = chip.mul(layouter.namespace(|| "a * b"), &a, &b)?;
+
+            for (row, cell) in c.iter().flatten().enumerate() {
+                layouter.constrain_instance(cell.cell(), instance, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn random<const R: usize, const C: usize>() -> [[Fp; C]; R] {
+        [(); R].map(|_| [(); C].map(|_| Fp::from(u64::from(OsRng.next_u32() % 1000))))
+    }
+
+    fn run(a: [[Fp; N]; M], b: [[Fp; P]; N], c: [[Fp; P]; M]) -> MockProver<Fp> {
+        let k = 5;
+
+        let circuit = MatMulCircuit {
+            a: a.map(|row| row.map(Value::known)),
+            b: b.map(|row| row.map(Value::known)),
+        };
+        MockProver::run(k, &circuit, vec![c.concat()]).unwrap()
+    }
+
+    #[test]
+    fn matmul_matches_reference() {
+        let a = [[1, 2, 3], [4, 5, 6]].map(|row| row.map(Fp::from));
+        let b = [[1, 0, 0, 1], [0, 1, 0, 1], [0, 0, 1, 1]].map(|row| row.map(Fp::from));
+        let c = reference(&a, &b);
+        assert_eq!(
+            c,
+            [[1, 2, 3, 6], [4, 5, 6, 15]].map(|row| row.map(Fp::from))
+        );
+        run(a, b, c).assert_satisfied();
+
+        for _ in 0..4 {
+            let (a, b) = (random(), random());
+            run(a, b, reference(&a, &b)).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn matmul_rejects_wrong_product() {
+        let (a, b) = (random(), random());
+        let mut c = reference(&a, &b);
+        c[1][2] += Fp::ONE;
+
+        assert!(run(a, b, c).verify().is_err());
+    }
+}
@@ -0,0 +1,5 @@
+//! Linear algebra over advice cells, the building blocks of circuits that
+//! evaluate small neural networks.
+
+pub mod dot;
+pub mod matmul;
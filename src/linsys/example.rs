@@ -0,0 +1,279 @@
+use crate::{prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Checks that `(x, y)` solves
+///
+/// ```text
+/// a*x + b*y = c1
+/// d*x + e*y = c2
+/// ```
+///
+/// for a coefficient matrix fixed at keygen time. Each equation is one row of the
+/// same multiply-add gate, with the coefficients in fixed columns and `x`, `y`
+/// shared between the rows through copy constraints.
+#[derive(Debug, Clone)]
+struct LinSysConfig {
+    pub advice: [Column<Advice>; 3],
+    pub coeff: [Column<Fixed>; 2],
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct LinSysChip<F: Field> {
+    config: LinSysConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> LinSysChip<F> {
+    pub fn construct(config: LinSysConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        coeff: [Column<Fixed>; 2],
+        instance: Column<Instance>,
+    ) -> LinSysConfig {
+        let [col_x, col_y, col_out] = advice;
+        let selector = meta.selector();
+        meta.enable_equality(col_x);
+        meta.enable_equality(col_y);
+        meta.enable_equality(col_out);
+        meta.enable_equality(instance);
+
+        meta.create_gate("mul_add", |meta| {
+            //
+            // col_x | col_y | col_out | coeff_x | coeff_y | selector
+            //   x      y       out        a         b          s
+            //
+            let s = meta.query_selector(selector);
+            let x = meta.query_advice(col_x, Rotation::cur());
+            let y = meta.query_advice(col_y, Rotation::cur());
+            let out = meta.query_advice(col_out, Rotation::cur());
+            let a = meta.query_fixed(coeff[0]);
+            let b = meta.query_fixed(coeff[1]);
+            vec![s * (a * x + b * y - out)]
+        });
+
+        LinSysConfig {
+            advice,
+            coeff,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns one equation `coeffs[0]*x + coeffs[1]*y = out` in its own row and
+    /// returns `(x, y, out)`.
+    ///
+    /// When `shared` is given, `x` and `y` are copied from an earlier row instead
+    /// of being witnessed again.
+    #[allow(clippy::type_complexity)]
+    pub fn assign_equation(
+        &self,
+        mut layouter: impl Layouter<F>,
+        coeffs: [F; 2],
+        x: Value<F>,
+        y: Value<F>,
+        shared: Option<(&AssignedCell<F, F>, &AssignedCell<F, F>)>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "equation",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                region.assign_fixed(
+                    || "coeff x",
+                    self.config.coeff[0],
+                    0,
+                    || Value::known(coeffs[0]),
+                )?;
+                region.assign_fixed(
+                    || "coeff y",
+                    self.config.coeff[1],
+                    0,
+                    || Value::known(coeffs[1]),
+                )?;
+
+                let (x_cell, y_cell) = match shared {
+                    Some((x_cell, y_cell)) => (
+                        x_cell.copy_advice(|| "x", &mut region, self.config.advice[0], 0)?,
+                        y_cell.copy_advice(|| "y", &mut region, self.config.advice[1], 0)?,
+                    ),
+                    None => (
+                        region.assign_advice(|| "x", self.config.advice[0], 0, || x)?,
+                        region.assign_advice(|| "y", self.config.advice[1], 0, || y)?,
+                    ),
+                };
+
+                let out =
+                    x_cell.value().map(|x| coeffs[0] * x) + y_cell.value().map(|y| coeffs[1] * y);
+                let out_cell = region.assign_advice(|| "out", self.config.advice[2], 0, || out)?;
+
+                Ok((x_cell, y_cell, out_cell))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference for the right-hand sides `(c1, c2)` of the system.
+pub fn reference(coeffs: [[i64; 2]; 2], x: i64, y: i64) -> (i64, i64) {
+    (
+        coeffs[0][0] * x + coeffs[0][1] * y,
+        coeffs[1][0] * x + coeffs[1][1] * y,
+    )
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    coeffs: [[F; 2]; 2],
+    x: Value<F>,
+    y: Value<F>,
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = LinSysConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            coeffs: self.coeffs,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let coeff = [meta.fixed_column(), meta.fixed_column()];
+        let instance = meta.instance_column();
+        LinSysChip::configure(meta, advice, coeff, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = LinSysChip::construct(config);
+
+        let (x_cell, y_cell, c1) = chip.assign_equation(
+            layouter.namespace(|| "first equation"),
+            self.coeffs[0],
+            self.x,
+            self.y,
+            None,
+        )?;
+        let (_, _, c2) = chip.assign_equation(
+            layouter.namespace(|| "second equation"),
+            self.coeffs[1],
+            self.x,
+            self.y,
+            Some((&x_cell, &y_cell)),
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "c1"), &c1, C1_ROW)?;
+        chip.expose_public(layouter.namespace(|| "c2"), &c2, C2_ROW)?;
+
+        Ok(())
+    }
+}
+
+const C1_ROW: usize = 0;
+const C2_ROW: usize = 1;
+
+/// Places the public inputs by name, like [`crate::fibonacci::FibonacciInstances`].
+pub struct LinSysInstances<F> {
+    pub c1: F,
+    pub c2: F,
+}
+
+impl<F: Field> LinSysInstances<F> {
+    pub fn new(c1: F, c2: F) -> Self {
+        Self { c1, c2 }
+    }
+
+    pub fn build(self) -> Vec<Vec<F>> {
+        let mut column = vec![F::ZERO; 2];
+        column[C1_ROW] = self.c1;
+        column[C2_ROW] = self.c2;
+        vec![column]
+    }
+}
+
+/// The circuit on `2x + 3y`, `4x + 5y` at `(3, 4)`.
+pub fn example_circuit() -> ExampleCircuit {
+    let coeffs = [[2, 3], [4, 5]];
+    let (c1, c2) = reference(coeffs, 3, 4);
+    let circuit = MyCircuit {
+        coeffs: coeffs.map(|row| row.map(|c| Fp::from(c as u64))),
+        x: Value::known(Fp::from(3)),
+        y: Value::known(Fp::from(4)),
+    };
+    let instances = LinSysInstances::new(Fp::from(c1 as u64), Fp::from(c2 as u64)).build();
+    ExampleCircuit::new(4, circuit, instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    const COEFFS: [[i64; 2]; 2] = [[2, 3], [4, 5]];
+
+    fn fp(v: i64) -> Fp {
+        if v < 0 {
+            -Fp::from(v.unsigned_abs())
+        } else {
+            Fp::from(v as u64)
+        }
+    }
+
+    fn circuit(x: i64, y: i64) -> MyCircuit<Fp> {
+        MyCircuit {
+            coeffs: COEFFS.map(|row| row.map(fp)),
+            x: Value::known(fp(x)),
+            y: Value::known(fp(y)),
+        }
+    }
+
+    #[test]
+    fn linsys_example() {
+        let k = 4;
+
+        // 2*3 + 3*4 = 18, 4*3 + 5*4 = 32
+        let (c1, c2) = reference(COEFFS, 3, 4);
+        let prover = MockProver::run(k, &circuit(3, 4), vec![vec![fp(c1), fp(c2)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn linsys_rejects_partial_solution() {
+        let k = 4;
+
+        // (6, 2) satisfies the first equation (12 + 6 = 18) but not the second
+        // (24 + 10 = 34 != 32).
+        let (c1, c2) = reference(COEFFS, 3, 4);
+        assert_eq!(reference(COEFFS, 6, 2).0, c1);
+
+        let prover = MockProver::run(k, &circuit(6, 2), vec![vec![fp(c1), fp(c2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,430 @@
+//! Mastermind: the codemaker proves that the feedback they give for a public
+//! guess is honest, against a code they committed to before the game.
+//!
+//! This is the variant with distinct colours: neither the code nor a guess may
+//! repeat a colour. Then black pegs count positions where code and guess agree,
+//! and white pegs count pairs of distinct positions that agree, so both are
+//! sums of equality bits.
+
+use halo2_proofs::pasta::Fp;
+
+use crate::{
+    is_zero::{IsZeroChip, IsZeroConfig},
+    names,
+    poseidon::{self, PoseidonChip, PoseidonConfig},
+    prelude::*,
+    range_check::table::RangeTableConfig,
+    registry::{Example, ExampleCircuit},
+};
+
+/// Pegs in a code or a guess.
+const PEGS: usize = 4;
+/// Colours are `0..COLOURS`.
+const COLOURS: usize = 6;
+
+/// Plain-Rust reference: the `(black, white)` feedback for `guess`, by the
+/// usual rules, which also cover repeated colours.
+pub fn feedback(code: &[u64; PEGS], guess: &[u64; PEGS]) -> (u64, u64) {
+    let black = code.iter().zip(guess).filter(|(c, g)| c == g).count() as u64;
+    let common: u64 = (0..COLOURS as u64)
+        .map(|colour| {
+            let in_code = code.iter().filter(|&&c| c == colour).count();
+            let in_guess = guess.iter().filter(|&&g| g == colour).count();
+            in_code.min(in_guess) as u64
+        })
+        .sum();
+    (black, common - black)
+}
+
+/// The commitment to `code`, hiding it behind `salt`.
+pub fn commit(code: &[u64; PEGS], salt: Fp) -> Fp {
+    let [a, b, c, d] = code.map(Fp::from);
+    poseidon::hash([a, b, c, d, salt])
+}
+
+/// The public inputs for `guess` with `feedback`, against `commitment`.
+pub fn instances(commitment: Fp, guess: &[u64; PEGS], (black, white): (u64, u64)) -> Vec<Vec<Fp>> {
+    let mut column = vec![commitment];
+    column.extend(guess.map(Fp::from));
+    column.extend([Fp::from(black), Fp::from(white)]);
+    vec![column]
+}
+
+#[derive(Debug, Clone)]
+struct MastermindConfig {
+    code: Column<Advice>,
+    guess: Column<Advice>,
+    eq: Column<Advice>,
+    acc: Column<Advice>,
+    instance: Column<Instance>,
+    q_range: Selector,
+    q_eq: Selector,
+    q_first: Selector,
+    q_step: Selector,
+    q_distinct: Selector,
+    code_eq_guess: IsZeroConfig<Fp>,
+    x_eq_y: IsZeroConfig<Fp>,
+    table: RangeTableConfig<Fp, COLOURS>,
+    poseidon: PoseidonConfig,
+}
+
+/// Each of the 16 `(code_i, guess_j)` pairs takes a row, diagonal first:
+///
+/// ```text
+/// code   | guess   | inv | eq           | acc
+/// code_0 | guess_0 | ... | [c_0 == g_0] | eq          <- q_first
+/// ...
+/// code_3 | guess_3 | ... | [c_3 == g_3] | black
+/// code_0 | guess_1 | ... | [c_0 == g_1] | eq          <- q_first
+/// ...
+/// code_3 | guess_2 | ... | [c_3 == g_2] | white
+/// ```
+///
+/// `acc` sums `eq` down each half, restarting at `q_first`. The code pegs on
+/// the diagonal are looked up in a `0..COLOURS` table, and a separate region
+/// checks that the code pegs, then the guess pegs, are pairwise distinct.
+#[derive(Debug, Clone)]
+struct MastermindChip {
+    config: MastermindConfig,
+}
+
+impl MastermindChip {
+    pub fn construct(config: MastermindConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<Fp>,
+        advice: [Column<Advice>; 5],
+        instance: Column<Instance>,
+    ) -> MastermindConfig {
+        let [code, guess, inv, eq, acc] = advice;
+        // Lookups cannot use simple selectors.
+        let q_range = meta.complex_selector();
+        let q_eq = meta.selector();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        let q_distinct = meta.selector();
+        let table = RangeTableConfig::configure(meta);
+        for column in [code, guess, acc] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let s = meta.query_selector(q_range);
+            let code = meta.query_advice(code, Rotation::cur());
+            vec![(s * code, table.value)]
+        });
+
+        let diff = |meta: &mut VirtualCells<'_, Fp>| {
+            meta.query_advice(code, Rotation::cur()) - meta.query_advice(guess, Rotation::cur())
+        };
+        let code_eq_guess =
+            IsZeroChip::configure(meta, |meta| meta.query_selector(q_eq), diff, inv);
+        let x_eq_y = IsZeroChip::configure(meta, |meta| meta.query_selector(q_distinct), diff, inv);
+
+        meta.create_gate("peg", |meta| {
+            let s = meta.query_selector(q_eq);
+            let eq = meta.query_advice(eq, Rotation::cur());
+            vec![s * (eq - code_eq_guess.expr())]
+        });
+
+        meta.create_gate(names::FIRST, |meta| {
+            let s = meta.query_selector(q_first);
+            let eq = meta.query_advice(eq, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![s * (acc - eq)]
+        });
+
+        meta.create_gate(names::STEP, |meta| {
+            let s = meta.query_selector(q_step);
+            let eq = meta.query_advice(eq, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![s * (acc - acc_prev - eq)]
+        });
+
+        meta.create_gate("distinct", |meta| {
+            let s = meta.query_selector(q_distinct);
+            vec![s * x_eq_y.expr()]
+        });
+
+        let poseidon = PoseidonChip::<{ PEGS + 1 }>::configure(meta, [code, guess, inv, eq]);
+
+        MastermindConfig {
+            code,
+            guess,
+            eq,
+            acc,
+            instance,
+            q_range,
+            q_eq,
+            q_first,
+            q_step,
+            q_distinct,
+            code_eq_guess,
+            x_eq_y,
+            table,
+            poseidon,
+        }
+    }
+
+    /// Loads the code and salt, and returns the code cells and the commitment.
+    #[allow(clippy::type_complexity)]
+    pub fn commit(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        code: [Value<Fp>; PEGS],
+        salt: Value<Fp>,
+    ) -> Result<([AssignedCell<Fp, Fp>; PEGS], AssignedCell<Fp, Fp>), Error> {
+        let chip = PoseidonChip::<{ PEGS + 1 }>::construct(self.config.poseidon.clone());
+        let [a, b, c, d] = code;
+        let message = chip.load(layouter.namespace(|| "code"), [a, b, c, d, salt])?;
+        let code = [0, 1, 2, 3].map(|i| message[i].clone());
+        let commitment = chip.hash(layouter.namespace(|| "commitment"), message)?;
+        Ok((code, commitment))
+    }
+
+    /// Copies the guess from instance rows `1..=PEGS`.
+    pub fn load_guess(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<Vec<AssignedCell<Fp, Fp>>, Error> {
+        layouter.assign_region(
+            || "guess",
+            |mut region| {
+                (0..PEGS)
+                    .map(|i| {
+                        region.assign_advice_from_instance(
+                            || "guess",
+                            self.config.instance,
+                            1 + i,
+                            self.config.guess,
+                            i,
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Returns the black and white counts of `guess` against `code`.
+    #[allow(clippy::type_complexity)]
+    pub fn feedback(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        code: &[AssignedCell<Fp, Fp>],
+        guess: &[AssignedCell<Fp, Fp>],
+    ) -> Result<(AssignedCell<Fp, Fp>, AssignedCell<Fp, Fp>), Error> {
+        let config = &self.config;
+        let is_zero = IsZeroChip::construct(config.code_eq_guess.clone());
+        let diagonal = (0..PEGS).map(|i| (i, i));
+        let off_diagonal =
+            (0..PEGS).flat_map(|i| (0..PEGS).filter(move |&j| j != i).map(move |j| (i, j)));
+
+        layouter.assign_region(
+            || "feedback",
+            |mut region| {
+                let mut acc = Value::known(Fp::ZERO);
+                let mut sums = vec![];
+                for (row, (i, j)) in diagonal.clone().chain(off_diagonal.clone()).enumerate() {
+                    config.q_eq.enable(&mut region, row)?;
+                    if row < PEGS {
+                        config.q_range.enable(&mut region, row)?;
+                    }
+                    if row == 0 || row == PEGS {
+                        config.q_first.enable(&mut region, row)?;
+                        acc = Value::known(Fp::ZERO);
+                    } else {
+                        config.q_step.enable(&mut region, row)?;
+                    }
+
+                    let c = code[i].copy_advice(|| "code", &mut region, config.code, row)?;
+                    let g = guess[j].copy_advice(|| "guess", &mut region, config.guess, row)?;
+                    let diff = c.value().copied() - g.value();
+                    is_zero.assign(&mut region, row, diff)?;
+                    let eq = diff.map(|diff| Fp::from(u64::from(diff.is_zero_vartime())));
+                    region.assign_advice(|| "eq", config.eq, row, || eq)?;
+                    acc = acc + eq;
+                    let cell = region.assign_advice(|| "acc", config.acc, row, || acc)?;
+                    if row == PEGS - 1 || row == PEGS * PEGS - 1 {
+                        sums.push(cell);
+                    }
+                }
+                Ok((sums[0].clone(), sums[1].clone()))
+            },
+        )
+    }
+
+    /// Constrains every pair of `cells` to differ.
+    pub fn distinct(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        cells: &[AssignedCell<Fp, Fp>],
+    ) -> Result<(), Error> {
+        let x_eq_y = IsZeroChip::construct(self.config.x_eq_y.clone());
+        layouter.assign_region(
+            || "distinct",
+            |mut region| {
+                let pairs =
+                    (0..cells.len()).flat_map(|i| (i + 1..cells.len()).map(move |j| (i, j)));
+                for (row, (i, j)) in pairs.enumerate() {
+                    self.config.q_distinct.enable(&mut region, row)?;
+                    let x = cells[i].copy_advice(|| "x", &mut region, self.config.code, row)?;
+                    let y = cells[j].copy_advice(|| "y", &mut region, self.config.guess, row)?;
+                    x_eq_y.assign(&mut region, row, x.value().copied() - y.value())?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Proves that the public feedback for the public guess is right for the code
+/// behind the public commitment. The instance column holds
+/// `[commitment, guess_0, .., guess_3, black, white]`.
+#[derive(Default)]
+struct MyCircuit {
+    code: [Value<Fp>; PEGS],
+    salt: Value<Fp>,
+}
+
+impl MyCircuit {
+    pub fn new(code: &[u64; PEGS], salt: Fp) -> Self {
+        Self {
+            code: code.map(|peg| Value::known(Fp::from(peg))),
+            salt: Value::known(salt),
+        }
+    }
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = MastermindConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 5].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        MastermindChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let chip = MastermindChip::construct(config.clone());
+
+        let (code, commitment) =
+            chip.commit(layouter.namespace(|| "commit"), self.code, self.salt)?;
+        let guess = chip.load_guess(layouter.namespace(|| "guess"))?;
+        chip.distinct(layouter.namespace(|| "code colours"), &code)?;
+        chip.distinct(layouter.namespace(|| "guess colours"), &guess)?;
+        let (black, white) = chip.feedback(layouter.namespace(|| "feedback"), &code, &guess)?;
+
+        let mut layouter = layouter.namespace(|| names::OUT);
+        layouter.constrain_instance(commitment.cell(), config.instance, 0)?;
+        layouter.constrain_instance(black.cell(), config.instance, 1 + PEGS)?;
+        layouter.constrain_instance(white.cell(), config.instance, 2 + PEGS)
+    }
+}
+
+/// The circuit on the code `0 1 2 3` and the guess `0 2 4 5`: one black peg
+/// and one white.
+pub fn example_circuit() -> ExampleCircuit {
+    let (code, guess, salt) = ([0, 1, 2, 3], [0, 2, 4, 5], Fp::from(1234));
+    let instances = instances(commit(&code, salt), &guess, feedback(&code, &guess));
+    ExampleCircuit::new(8, MyCircuit::new(&code, salt), instances)
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "mastermind",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+    use rand_core::OsRng;
+
+    fn run(code: &[u64; PEGS], salt: Fp, public: Vec<Vec<Fp>>) -> MockProver<Fp> {
+        let k = 8;
+
+        MockProver::run(k, &MyCircuit::new(code, salt), public).unwrap()
+    }
+
+    #[test]
+    fn mastermind_feedback() {
+        let code = [5, 1, 3, 0];
+        let salt = Fp::random(OsRng);
+        for (guess, expected) in [
+            ([5, 1, 3, 0], (4, 0)),
+            ([0, 5, 1, 3], (0, 4)),
+            ([5, 3, 2, 4], (1, 1)),
+            ([2, 4, 1, 0], (1, 1)),
+            ([2, 4, 0, 1], (0, 2)),
+        ] {
+            assert_eq!(feedback(&code, &guess), expected);
+            run(
+                &code,
+                salt,
+                instances(commit(&code, salt), &guess, expected),
+            )
+            .assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn mastermind_rejects_wrong_feedback() {
+        let (code, guess, salt) = ([5, 1, 3, 0], [5, 3, 2, 4], Fp::random(OsRng));
+        let commitment = commit(&code, salt);
+        for wrong in [(2, 0), (1, 2), (0, 2)] {
+            assert!(run(&code, salt, instances(commitment, &guess, wrong))
+                .verify()
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn mastermind_rejects_other_code() {
+        // Feedback that is right for another code than the committed one.
+        let (code, guess, salt) = ([5, 1, 3, 0], [5, 3, 2, 4], Fp::random(OsRng));
+        let other = [5, 3, 2, 1];
+        let public = instances(commit(&code, salt), &guess, feedback(&other, &guess));
+        assert!(run(&other, salt, public).verify().is_err());
+    }
+
+    #[test]
+    fn mastermind_rejects_repeated_colours() {
+        let (guess, salt) = ([0, 1, 2, 3], Fp::random(OsRng));
+        let code = [4, 4, 2, 3];
+        let public = instances(commit(&code, salt), &guess, (2, 0));
+        assert_constraint_fails(&run(&code, salt, public), "distinct");
+
+        let code = [0, 1, 4, 5];
+        let guess = [0, 0, 1, 2];
+        let public = instances(commit(&code, salt), &guess, (1, 1));
+        assert_constraint_fails(&run(&code, salt, public), "distinct");
+    }
+
+    #[test]
+    fn mastermind_rejects_unknown_colour() {
+        let (code, guess, salt) = ([0, 1, 2, 6], [0, 1, 2, 3], Fp::random(OsRng));
+        let public = instances(commit(&code, salt), &guess, (3, 0));
+        let failures = run(&code, salt, public).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{
+    common::{BooleanChip, BooleanConfig},
+    names,
+    prelude::*,
+    range_check::table::RangeTableConfig,
+    registry::ExampleCircuit,
+};
+use halo2_proofs::pasta::Fp;
+
+/// Proves that a public `max` is the largest of `N` private values.
+///
+/// Row `i` holds `x_i`, a copy of `max`, a selection bit `b_i` and a running sum
+/// `acc_i` of the bits:
+///
+/// ```text
+/// b_i * (max - x_i) == 0             (a selected x_i equals max)
+/// acc_0 == 0, acc_{i+1} == acc_i + b_i
+/// acc_N == 1                         (exactly one x_i is selected)
+/// ```
+///
+/// `x_i` and `max - x_i` are both looked up in a `0..RANGE` table, so `max` is at
+/// least every `x_i` as an integer, not just in the field. The bits come from a
+/// [`BooleanChip`] and are copied in.
+#[derive(Debug, Clone)]
+struct MaxConfig<F: PrimeField, const RANGE: usize> {
+    pub advice: [Column<Advice>; 4],
+    pub boolean: BooleanConfig,
+    pub selector: Selector,
+    pub selector_first: Selector,
+    pub selector_last: Selector,
+    pub instance: Column<Instance>,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+#[derive(Debug, Clone)]
+struct MaxChip<F: PrimeField, const N: usize, const RANGE: usize> {
+    config: MaxConfig<F, RANGE>,
+}
+
+impl<F: PrimeField, const N: usize, const RANGE: usize> MaxChip<F, N, RANGE> {
+    pub fn construct(config: MaxConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        bool_column: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> MaxConfig<F, RANGE> {
+        let [col_x, col_max, col_bit, col_acc] = advice;
+        let boolean = BooleanChip::configure(meta, bool_column);
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        let selector_first = meta.selector();
+        let selector_last = meta.selector();
+        let table = RangeTableConfig::configure(meta);
+        meta.enable_equality(col_max);
+        meta.enable_equality(col_bit);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::FIRST, |meta| {
+            let s = meta.query_selector(selector_first);
+            let acc = meta.query_advice(col_acc, Rotation::cur());
+            vec![s * acc]
+        });
+
+        meta.create_gate(names::STEP, |meta| {
+            //
+            // col_x | col_max | col_bit | col_acc  | selector
+            //   x      max        b       acc          s
+            //                             acc_next
+            //
+            let s = meta.query_selector(selector);
+            let x = meta.query_advice(col_x, Rotation::cur());
+            let max = meta.query_advice(col_max, Rotation::cur());
+            let b = meta.query_advice(col_bit, Rotation::cur());
+            let acc = meta.query_advice(col_acc, Rotation::cur());
+            let acc_next = meta.query_advice(col_acc, Rotation::next());
+            vec![s.clone() * b.clone() * (max - x), s * (acc + b - acc_next)]
+        });
+
+        meta.create_gate(names::LAST, |meta| {
+            let s = meta.query_selector(selector_last);
+            let acc = meta.query_advice(col_acc, Rotation::cur());
+            vec![s * (acc - Expression::Constant(F::ONE))]
+        });
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let x = meta.query_advice(col_x, Rotation::cur());
+            vec![(s * x, table.value)]
+        });
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let x = meta.query_advice(col_x, Rotation::cur());
+            let max = meta.query_advice(col_max, Rotation::cur());
+            vec![(s * (max - x), table.value)]
+        });
+
+        MaxConfig {
+            advice,
+            boolean,
+            selector,
+            selector_first,
+            selector_last,
+            instance,
+            table,
+        }
+    }
+
+    /// Assigns `values` next to copies of `max`, selecting the first value equal
+    /// to it, and returns the first `max` cell.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<F>; N],
+        max: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_x, col_max, col_bit, col_acc] = self.config.advice;
+
+        let boolean = BooleanChip::construct(self.config.boolean.clone());
+        let mut found = Value::known(false);
+        let bits = values
+            .iter()
+            .map(|x| {
+                let bit = found
+                    .zip(x.zip(max))
+                    .map(|(found, (x, max))| !found && x == max);
+                found = found.zip(bit).map(|(found, bit)| found || bit);
+                let bit = bit.map(|bit| if bit { F::ONE } else { F::ZERO });
+                boolean.assign_bool(layouter.namespace(|| "selection"), bit)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        layouter.assign_region(
+            || "max",
+            |mut region| {
+                self.config.selector_first.enable(&mut region, 0)?;
+
+                let mut acc = Value::known(F::ZERO);
+                region.assign_advice(|| "acc", col_acc, 0, || acc)?;
+                let max_cell = region.assign_advice(|| "max", col_max, 0, || max)?;
+
+                for (row, (x, bit)) in values.iter().zip(&bits).enumerate() {
+                    self.config.selector.enable(&mut region, row)?;
+                    if row > 0 {
+                        max_cell.copy_advice(|| "max", &mut region, col_max, row)?;
+                    }
+                    region.assign_advice(|| "x", col_x, row, || *x)?;
+                    let bit = bit.copy_advice(|| "bit", &mut region, col_bit, row)?;
+
+                    acc = acc + bit.value();
+                    region.assign_advice(|| "acc", col_acc, row + 1, || acc)?;
+                }
+
+                self.config.selector_last.enable(&mut region, N)?;
+                Ok(max_cell)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference for the value this circuit exposes.
+#[cfg(test)]
+pub fn reference(values: &[u64]) -> Option<u64> {
+    values.iter().copied().max()
+}
+
+struct MyCircuit<F, const N: usize, const RANGE: usize> {
+    values: [Value<F>; N],
+    max: Value<F>,
+}
+
+impl<F: PrimeField, const N: usize, const RANGE: usize> MyCircuit<F, N, RANGE> {
+    /// The circuit claiming `max` is the largest of `values`.
+    pub fn new(values: [u64; N], max: u64) -> Self {
+        Self {
+            values: values.map(|x| Value::known(F::from(x))),
+            max: Value::known(F::from(max)),
+        }
+    }
+}
+
+impl<F: PrimeField, const N: usize, const RANGE: usize> Circuit<F> for MyCircuit<F, N, RANGE> {
+    type Config = MaxConfig<F, RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: [Value::unknown(); N],
+            max: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let bool_column = meta.advice_column();
+        let instance = meta.instance_column();
+        MaxChip::<F, N, RANGE>::configure(meta, advice, bool_column, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let chip = MaxChip::<F, N, RANGE>::construct(config);
+
+        let max = chip.assign(layouter.namespace(|| "max"), &self.values, self.max)?;
+        chip.expose_public(layouter.namespace(|| names::OUT), &max, 0)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on `max([3, 7, 2]) == 7`.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp, 3, 256>::new([3, 7, 2], 7);
+    ExampleCircuit::new(9, circuit, vec![vec![Fp::from(7)]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    const RANGE: usize = 256;
+
+    fn run(values: [u64; 3], max: u64) -> MockProver<Fp> {
+        let k = 9;
+
+        let circuit = MyCircuit::<Fp, 3, RANGE>::new(values, max);
+        MockProver::run(k, &circuit, vec![vec![Fp::from(max)]]).unwrap()
+    }
+
+    #[test]
+    fn max_example() {
+        assert_eq!(reference(&[3, 7, 2]), Some(7));
+        run([3, 7, 2], 7).assert_satisfied();
+        // Ties select only the first occurrence.
+        run([7, 7, 0], 7).assert_satisfied();
+    }
+
+    #[test]
+    fn max_rejects_missing_value() {
+        // 8 bounds every value, but no bit can select it.
+        assert_constraint_fails(&run([3, 7, 2], 8), names::LAST);
+    }
+
+    #[test]
+    fn max_rejects_smaller_value() {
+        // 3 is one of the values, but 3 - 7 is not in the table.
+        let failures = run([3, 7, 2], 3).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+}
@@ -0,0 +1,490 @@
+//! Read/write memory consistency: proves that every read in an access log
+//! returns the value of the latest write to the same address, or 0 if there
+//! was none.
+//!
+//! The log lists `(addr, value, time, is_write)` in execution order, with
+//! `time` the position in the log. The same accesses sorted by address, and by
+//! time within an address, put every read right after the access it has to
+//! agree with, so local gates between neighbouring rows can check it.
+//!
+//! Copy constraints are fixed at keygen, so the sorted log cannot simply be
+//! copied from the witness order. Instead it is the output of a
+//! [`SortingNetworkChip`] over the log, and the gates on the output check that
+//! it is actually sorted.
+
+use halo2_proofs::pasta::Fp;
+
+use crate::{
+    common::{sorting_switches, SortingNetworkChip, SortingNetworkConfig},
+    names,
+    prelude::*,
+    range_check::table::RangeTableConfig,
+    registry::{Example, ExampleCircuit},
+};
+
+/// One access, in execution order. `is_write` must be boolean, which
+/// [`MemoryChip::check`] constrains.
+#[derive(Debug, Clone)]
+pub struct Access<F: Field> {
+    pub addr: AssignedCell<F, F>,
+    pub value: AssignedCell<F, F>,
+    pub is_write: AssignedCell<F, F>,
+}
+
+/// `[addr, value, time, is_write]` of a log row in the first four columns, the
+/// same after a network round in the next four, and the switch bit in the
+/// last. In the sorted log the last column holds `same` and the fifth `diff`.
+#[derive(Debug, Clone)]
+pub struct MemoryConfig<F: PrimeField, const RANGE: usize> {
+    pub advice: [Column<Advice>; 9],
+    pub network: SortingNetworkConfig<4>,
+    pub q_order: Selector,
+    pub q_first: Selector,
+    pub q_bool: Selector,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+/// Checks an access log for consistency.
+///
+/// The log goes through the network as rows of four fields. On the sorted log, a boolean `same` at each row but the last says whether
+/// the next row has the same address, and
+///
+/// ```text
+/// diff = same * (time' - time - 1) + (1 - same) * (addr' - addr - 1)
+/// ```
+///
+/// is looked up in `0..RANGE`, next to
+///
+/// ```text
+/// same * (addr' - addr) == 0
+/// ```
+///
+/// With `same = 1` the two rows then share an address and the time goes up;
+/// with `same = 0` the address goes up, which two rows with the same address
+/// cannot do. So `same` is honest, addresses increase, and times increase
+/// within an address; addresses and times must therefore differ by less than
+/// `RANGE`. The lookup alone is not enough: without the second constraint a
+/// prover could set `same = 1` between two addresses and have a read return
+/// the other address's value. A read must then equal the previous value at
+/// the same address, or 0 on a new address:
+///
+/// ```text
+/// (1 - is_write') * (value' - same * value) == 0
+/// ```
+///
+/// and the first row, if a read, must read 0.
+#[derive(Debug, Clone)]
+pub struct MemoryChip<F: PrimeField, const RANGE: usize> {
+    config: MemoryConfig<F, RANGE>,
+}
+
+impl<F: PrimeField, const RANGE: usize> MemoryChip<F, RANGE> {
+    pub fn construct(config: MemoryConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 9],
+        table: &RangeTableConfig<F, RANGE>,
+    ) -> MemoryConfig<F, RANGE> {
+        let network = SortingNetworkChip::configure(
+            meta,
+            [0, 1, 2, 3].map(|j| advice[j]),
+            [4, 5, 6, 7].map(|j| advice[j]),
+            advice[8],
+        );
+        // Lookups cannot use simple selectors.
+        let q_order = meta.complex_selector();
+        let q_first = meta.selector();
+        let q_bool = meta.selector();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let one = || Expression::Constant(F::ONE);
+
+        let [addr, value, time, is_write, diff, _, _, _, same] = advice;
+
+        meta.create_gate("memory order", |meta| {
+            let q = meta.query_selector(q_order);
+            let [addr_next, value_next, time_next, is_write_next] = [addr, value, time, is_write]
+                .map(|column| meta.query_advice(column, Rotation::next()));
+            let [addr, value, time] =
+                [addr, value, time].map(|column| meta.query_advice(column, Rotation::cur()));
+            let same = meta.query_advice(same, Rotation::cur());
+            let diff = meta.query_advice(diff, Rotation::cur());
+
+            let expected = same.clone() * (time_next - time - one())
+                + (one() - same.clone()) * (addr_next.clone() - addr.clone() - one());
+            vec![
+                q.clone() * same.clone() * (one() - same.clone()),
+                q.clone() * same.clone() * (addr_next - addr),
+                q.clone() * (expected - diff),
+                q * (one() - is_write_next) * (value_next - same * value),
+            ]
+        });
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_order);
+            let diff = meta.query_advice(diff, Rotation::cur());
+            vec![(q * diff, table.value)]
+        });
+
+        meta.create_gate("memory first", |meta| {
+            let q = meta.query_selector(q_first);
+            let value = meta.query_advice(value, Rotation::cur());
+            let is_write = meta.query_advice(is_write, Rotation::cur());
+            vec![q * (one() - is_write) * value]
+        });
+
+        meta.create_gate(names::BOOL, |meta| {
+            let q = meta.query_selector(q_bool);
+            let is_write = meta.query_advice(is_write, Rotation::cur());
+            vec![q * is_write.clone() * (one() - is_write)]
+        });
+
+        MemoryConfig {
+            advice,
+            network,
+            q_order,
+            q_first,
+            q_bool,
+            table: table.clone(),
+        }
+    }
+
+    /// Checks that `accesses`, in execution order, are consistent.
+    pub fn check(&self, layouter: impl Layouter<F>, accesses: &[Access<F>]) -> Result<(), Error> {
+        let keys: Value<Vec<(u64, u64)>> =
+            accesses
+                .iter()
+                .enumerate()
+                .fold(Value::known(vec![]), |keys, (time, access)| {
+                    keys.zip(access.addr.value()).map(|(mut keys, addr)| {
+                        keys.push((to_u64(addr), time as u64));
+                        keys
+                    })
+                });
+        let switches = keys.map(|keys| sorting_switches(&keys));
+        self.assign(layouter, accesses, switches, None)
+    }
+
+    /// Lays out the network with the given switch bits, round by round, and
+    /// checks its output. `forged`, if given, replaces the honest `same` bits
+    /// of the output, to play a cheating prover; [`Self::check`] passes `None`.
+    pub(crate) fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        accesses: &[Access<F>],
+        switches: Value<Vec<Vec<bool>>>,
+        forged: Option<&[bool]>,
+    ) -> Result<(), Error> {
+        let n = accesses.len();
+        let config = &self.config;
+        if n == 0 {
+            return Ok(());
+        }
+
+        let rows = layouter.assign_region(
+            || "memory log",
+            |mut region| {
+                accesses
+                    .iter()
+                    .enumerate()
+                    .map(|(time, access)| {
+                        let [addr, value, time_col, is_write] =
+                            [0, 1, 2, 3].map(|j| config.advice[j]);
+                        Ok([
+                            access
+                                .addr
+                                .copy_advice(|| "addr", &mut region, addr, time)?,
+                            access
+                                .value
+                                .copy_advice(|| "value", &mut region, value, time)?,
+                            region.assign_advice_from_constant(
+                                || "time",
+                                time_col,
+                                time,
+                                F::from(time as u64),
+                            )?,
+                            access.is_write.copy_advice(
+                                || "is_write",
+                                &mut region,
+                                is_write,
+                                time,
+                            )?,
+                        ])
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+
+        let network = SortingNetworkChip::construct(config.network.clone());
+        let rows = network.permute(layouter.namespace(|| "memory network"), &rows, &switches)?;
+
+        layouter.assign_region(
+            || "memory sorted",
+            |mut region| {
+                let [addr, value, time, is_write, diff, _, _, _, same] = config.advice;
+                config.q_first.enable(&mut region, 0)?;
+                for (i, row) in rows.iter().enumerate() {
+                    config.q_bool.enable(&mut region, i)?;
+                    for (cell, column) in row.iter().zip([addr, value, time, is_write]) {
+                        cell.copy_advice(|| "sorted", &mut region, column, i)?;
+                    }
+                }
+                for i in 0..n - 1 {
+                    config.q_order.enable(&mut region, i)?;
+                    let [cur, next] = [&rows[i], &rows[i + 1]].map(|row| {
+                        row[0]
+                            .value()
+                            .zip(row[2].value())
+                            .map(|(&addr, &time)| (addr, time))
+                    });
+                    let same_diff = cur.zip(next).map(|((addr, time), (addr_next, time_next))| {
+                        if forged.map_or(addr == addr_next, |forged| forged[i]) {
+                            (F::ONE, time_next - time - F::ONE)
+                        } else {
+                            (F::ZERO, addr_next - addr - F::ONE)
+                        }
+                    });
+                    region.assign_advice(|| "same", same, i, || same_diff.map(|(s, _)| s))?;
+                    region.assign_advice(|| "diff", diff, i, || same_diff.map(|(_, d)| d))?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Plain-Rust reference: whether every read in `log`, a list of
+/// `(is_write, addr, value)`, returns the latest value written to its address,
+/// or 0.
+#[cfg(test)]
+fn consistent(log: &[(bool, u64, u64)]) -> bool {
+    let mut memory = std::collections::HashMap::new();
+    log.iter().all(|&(is_write, addr, value)| {
+        if is_write {
+            memory.insert(addr, value);
+            true
+        } else {
+            memory.get(&addr).copied().unwrap_or(0) == value
+        }
+    })
+}
+
+/// Addresses and times differ by less than this.
+const RANGE: usize = 256;
+
+/// Witnesses a log of `(is_write, addr, value)` accesses and checks it.
+#[derive(Default)]
+struct MyCircuit {
+    log: Vec<(bool, u64, u64)>,
+    /// Replaces the switch bits that sort the log.
+    switches: Option<Vec<Vec<bool>>>,
+    /// Replaces the `same` bits of the sorted log.
+    same: Option<Vec<bool>>,
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = MemoryConfig<Fp, RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            log: self.log.clone(),
+            switches: self.switches.clone(),
+            same: self.same.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 9].map(|_| meta.advice_column());
+        let table = RangeTableConfig::configure(meta);
+        MemoryChip::configure(meta, advice, &table)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let accesses = layouter.assign_region(
+            || "accesses",
+            |mut region| {
+                self.log
+                    .iter()
+                    .enumerate()
+                    .map(|(row, &(is_write, addr, value))| {
+                        let mut assign = |column: usize, v: u64| {
+                            let v = Value::known(Fp::from(v));
+                            region.assign_advice(|| "access", config.advice[column], row, || v)
+                        };
+                        Ok(Access {
+                            addr: assign(0, addr)?,
+                            value: assign(1, value)?,
+                            is_write: assign(3, u64::from(is_write))?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+
+        let chip = MemoryChip::construct(config);
+        let layouter = layouter.namespace(|| "memory");
+        match (&self.switches, &self.same) {
+            (None, None) => chip.check(layouter, &accesses),
+            (switches, same) => {
+                let switches = switches.clone().unwrap_or_else(|| {
+                    let keys: Vec<_> = self
+                        .log
+                        .iter()
+                        .enumerate()
+                        .map(|(time, &(_, addr, _))| (addr, time as u64))
+                        .collect();
+                    sorting_switches(&keys)
+                });
+                chip.assign(layouter, &accesses, Value::known(switches), same.as_deref())
+            }
+        }
+    }
+}
+
+/// The circuit on a short log over three addresses.
+pub fn example_circuit() -> ExampleCircuit {
+    let log = vec![
+        (true, 2, 7),
+        (false, 1, 0),
+        (true, 1, 5),
+        (false, 2, 7),
+        (true, 2, 9),
+        (false, 1, 5),
+        (false, 2, 9),
+        (false, 3, 0),
+    ];
+    ExampleCircuit::new(
+        9,
+        MyCircuit {
+            log,
+            ..Default::default()
+        },
+        vec![],
+    )
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "memory",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+    use rand_core::{OsRng, RngCore};
+
+    fn run(circuit: &MyCircuit) -> MockProver<Fp> {
+        let k = 9;
+
+        MockProver::run(k, circuit, vec![]).unwrap()
+    }
+
+    fn circuit(log: Vec<(bool, u64, u64)>) -> MyCircuit {
+        MyCircuit {
+            log,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn memory_accepts_consistent_logs() {
+        run(&circuit(vec![(false, 4, 0)])).assert_satisfied();
+        run(&circuit(vec![])).assert_satisfied();
+
+        // Random logs over a few addresses, with every read honest.
+        for _ in 0..4 {
+            let mut memory = std::collections::HashMap::new();
+            let log: Vec<_> = (0..12)
+                .map(|_| {
+                    let addr = u64::from(OsRng.next_u32() % 4);
+                    if OsRng.next_u32().is_multiple_of(2) {
+                        let value = u64::from(OsRng.next_u32());
+                        memory.insert(addr, value);
+                        (true, addr, value)
+                    } else {
+                        (false, addr, memory.get(&addr).copied().unwrap_or(0))
+                    }
+                })
+                .collect();
+            assert!(consistent(&log));
+            run(&circuit(log)).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn memory_rejects_stale_read() {
+        let log = vec![(true, 1, 5), (true, 1, 6), (false, 1, 5)];
+        assert!(!consistent(&log));
+        assert_constraint_fails(&run(&circuit(log)), "memory order");
+    }
+
+    #[test]
+    fn memory_rejects_uninitialized_read() {
+        // The read is the first access to address 2, in the middle of the
+        // sorted log and at its start.
+        for log in [
+            vec![(true, 1, 5), (false, 2, 5)],
+            vec![(true, 3, 5), (false, 2, 5)],
+        ] {
+            assert!(!consistent(&log));
+            assert!(run(&circuit(log)).verify().is_err());
+        }
+    }
+
+    #[test]
+    fn memory_rejects_unsorted_network_output() {
+        // Without any swaps the addresses go 2, 1, 1, and the step down from 2
+        // to 1 falls outside the diff table.
+        let log = vec![(true, 2, 7), (false, 1, 0), (true, 1, 5)];
+        let switches = sorting_switches(&[(2, 0), (1, 1), (1, 2)]);
+        run(&MyCircuit {
+            log: log.clone(),
+            switches: Some(switches.clone()),
+            same: None,
+        })
+        .assert_satisfied();
+
+        let unsorted = switches
+            .iter()
+            .map(|round| vec![false; round.len()])
+            .collect();
+        let circuit = MyCircuit {
+            log,
+            switches: Some(unsorted),
+            same: None,
+        };
+        let failures = run(&circuit).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn memory_rejects_same_across_addresses() {
+        // The read is the first access to address 2 and has to return 0. With
+        // `same` forged to 1 it would only have to match address 1's 5, and
+        // the times still go up, so only the address check catches it.
+        let log = vec![(true, 1, 5), (false, 2, 5)];
+        assert!(!consistent(&log));
+        let circuit = MyCircuit {
+            log,
+            switches: None,
+            same: Some(vec![true]),
+        };
+        assert_constraint_fails(&run(&circuit), "memory order");
+    }
+}
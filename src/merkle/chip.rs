@@ -0,0 +1,80 @@
+use super::PathHasher;
+use crate::{
+    gadgets::mux::{MuxChip, MuxConfig},
+    prelude::*,
+};
+
+#[derive(Debug, Clone)]
+pub struct MerklePathConfig<F: Field, H: PathHasher<F>> {
+    pub sibling: Column<Advice>,
+    pub mux: MuxConfig,
+    pub hasher: H::Config,
+}
+
+/// Hashes a leaf up to the root along an authentication path.
+///
+/// At each level the direction bit picks the order of the children with two
+/// [`MuxChip`] rows, `left = bit ? sibling : node` and
+/// `right = bit ? node : sibling`, and the hasher `H` combines them. The mux
+/// gate also keeps every bit boolean.
+#[derive(Debug, Clone)]
+pub struct MerklePathChip<F: Field, H: PathHasher<F>> {
+    config: MerklePathConfig<F, H>,
+}
+
+impl<F: Field, H: PathHasher<F>> MerklePathChip<F, H> {
+    pub fn construct(config: MerklePathConfig<F, H>) -> Self {
+        Self { config }
+    }
+
+    /// The siblings are witnessed in `advice[0]`, and the mux rows use all four
+    /// columns. `hasher` is configured by the caller, on any columns.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        hasher: H::Config,
+    ) -> MerklePathConfig<F, H> {
+        MerklePathConfig {
+            sibling: advice[0],
+            mux: MuxChip::configure(meta, advice),
+            hasher,
+        }
+    }
+
+    /// Returns the root reached from `leaf` through `siblings`, both listed
+    /// bottom up, with `bits` the direction bits (for instance the bits of the
+    /// leaf index, least significant first).
+    pub fn root(
+        &self,
+        mut layouter: impl Layouter<F>,
+        leaf: &AssignedCell<F, F>,
+        bits: &[AssignedCell<F, F>],
+        siblings: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert_eq!(bits.len(), siblings.len(), "one bit per sibling");
+        let mux = MuxChip::construct(self.config.mux.clone());
+        let hasher = H::construct(self.config.hasher.clone());
+
+        let siblings = layouter.assign_region(
+            || "siblings",
+            |mut region| {
+                siblings
+                    .iter()
+                    .enumerate()
+                    .map(|(row, &sibling)| {
+                        region.assign_advice(|| "sibling", self.config.sibling, row, || sibling)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+
+        let mut node = leaf.clone();
+        for (level, (bit, sibling)) in bits.iter().zip(&siblings).enumerate() {
+            let mut layouter = layouter.namespace(|| format!("level {}", level));
+            let left = mux.mux(layouter.namespace(|| "left"), bit, &node, sibling)?;
+            let right = mux.mux(layouter.namespace(|| "right"), bit, sibling, &node)?;
+            node = hasher.hash(layouter.namespace(|| "hash"), &left, &right)?;
+        }
+        Ok(node)
+    }
+}
@@ -0,0 +1,191 @@
+use halo2_proofs::pasta::Fp;
+
+use super::{MerklePath, MerklePathChip, MerklePathConfig, MerkleTree, ToyHashChip};
+use crate::{
+    gadgets::bits::{BitsChip, BitsConfig},
+    prelude::*,
+    registry::ExampleCircuit,
+};
+
+/// Proves that a public leaf sits in the tree with a public root, at a private
+/// index, with [`ToyHashChip`] as the node hash.
+///
+/// The index is split into `DEPTH` bits, which also bounds it to the tree.
+#[derive(Debug, Clone)]
+struct InclusionConfig {
+    pub advice: [Column<Advice>; 4],
+    pub instance: Column<Instance>,
+    pub bits: BitsConfig,
+    pub path: MerklePathConfig<Fp, ToyHashChip<Fp>>,
+}
+
+#[derive(Default)]
+struct MyCircuit<const DEPTH: usize> {
+    leaf: Value<Fp>,
+    index: Value<Fp>,
+    siblings: Vec<Value<Fp>>,
+}
+
+impl<const DEPTH: usize> MyCircuit<DEPTH> {
+    pub fn new(leaf: Fp, path: &MerklePath<Fp>) -> Self {
+        assert_eq!(path.siblings.len(), DEPTH);
+        Self {
+            leaf: Value::known(leaf),
+            index: Value::known(Fp::from(path.index)),
+            siblings: path.siblings.iter().copied().map(Value::known).collect(),
+        }
+    }
+}
+
+impl<const DEPTH: usize> Circuit<Fp> for MyCircuit<DEPTH> {
+    type Config = InclusionConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            leaf: Value::unknown(),
+            index: Value::unknown(),
+            siblings: vec![Value::unknown(); DEPTH],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let bits = BitsChip::configure(meta, advice[0], advice[1]);
+        let hasher = ToyHashChip::configure(meta, [advice[0], advice[1], advice[2]]);
+        let path = MerklePathChip::configure(meta, advice, hasher);
+
+        InclusionConfig {
+            advice,
+            instance,
+            bits,
+            path,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let (leaf, index) = layouter.assign_region(
+            || "leaf and index",
+            |mut region| {
+                let leaf = region.assign_advice(|| "leaf", config.advice[0], 0, || self.leaf)?;
+                let index = region.assign_advice(|| "index", config.advice[1], 0, || self.index)?;
+                Ok((leaf, index))
+            },
+        )?;
+
+        let bits = BitsChip::construct(config.bits).to_bits(
+            layouter.namespace(|| "index bits"),
+            &index,
+            DEPTH,
+        )?;
+        let chip = MerklePathChip::construct(config.path);
+        let root = chip.root(layouter.namespace(|| "path"), &leaf, &bits, &self.siblings)?;
+
+        layouter.constrain_instance(leaf.cell(), config.instance, LEAF_ROW)?;
+        layouter.constrain_instance(root.cell(), config.instance, ROOT_ROW)
+    }
+}
+
+const LEAF_ROW: usize = 0;
+const ROOT_ROW: usize = 1;
+
+/// Places the public inputs by name, like [`crate::fibonacci::FibonacciInstances`].
+pub struct InclusionInstances {
+    pub leaf: Fp,
+    pub root: Fp,
+}
+
+impl InclusionInstances {
+    pub fn new(leaf: Fp, root: Fp) -> Self {
+        Self { leaf, root }
+    }
+
+    pub fn build(self) -> Vec<Vec<Fp>> {
+        let mut column = vec![Fp::ZERO; 2];
+        column[LEAF_ROW] = self.leaf;
+        column[ROOT_ROW] = self.root;
+        vec![column]
+    }
+}
+
+/// The circuit on leaf 42 at index 5 of a depth-8 tree.
+pub fn example_circuit() -> ExampleCircuit {
+    let mut tree = MerkleTree::<Fp, ToyHashChip<Fp>>::new(8);
+    let leaf = Fp::from(42);
+    tree.insert(5, leaf);
+    tree.insert(6, Fp::from(7));
+
+    let circuit = MyCircuit::<8>::new(leaf, &tree.path(5));
+    let instances = InclusionInstances::new(leaf, tree.root()).build();
+    ExampleCircuit::new(7, circuit, instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    type Tree = MerkleTree<Fp, ToyHashChip<Fp>>;
+
+    fn tree(depth: usize) -> Tree {
+        let mut tree = Tree::new(depth);
+        for (index, leaf) in [(0, 10), (3, 11), (200, 12), (255, 13)] {
+            tree.insert(index, Fp::from(leaf));
+        }
+        tree
+    }
+
+    fn run<const DEPTH: usize>(tree: &Tree, index: u64, instances: [Fp; 2]) -> MockProver<Fp> {
+        let k = 9;
+
+        let circuit = MyCircuit::<DEPTH>::new(tree.leaf(index), &tree.path(index));
+        MockProver::run(k, &circuit, vec![instances.to_vec()]).unwrap()
+    }
+
+    #[test]
+    fn merkle_depth_8() {
+        let tree = tree(8);
+        for index in [0, 3, 200, 255, 17] {
+            run::<8>(&tree, index, [tree.leaf(index), tree.root()]).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn merkle_depth_32() {
+        let mut tree = tree(32);
+        let index = u32::MAX as u64 - 6;
+        tree.insert(index, Fp::from(99));
+        run::<32>(&tree, index, [Fp::from(99), tree.root()]).assert_satisfied();
+        run::<32>(&tree, 200, [Fp::from(12), tree.root()]).assert_satisfied();
+    }
+
+    #[test]
+    fn merkle_rejects_wrong_leaf_or_root() {
+        let tree = tree(8);
+        assert!(run::<8>(&tree, 3, [Fp::from(10), tree.root()])
+            .verify()
+            .is_err());
+        assert!(run::<8>(&tree, 3, [Fp::from(11), tree.root() + Fp::ONE])
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn merkle_rejects_wrong_index() {
+        // The path of index 3 with the siblings of index 2 (its own sibling
+        // swapped in) does not reach the root.
+        let tree = tree(8);
+        let mut path = tree.path(3);
+        path.index = 2;
+        let circuit = MyCircuit::<8>::new(tree.leaf(3), &path);
+        let prover = MockProver::run(9, &circuit, vec![vec![tree.leaf(3), tree.root()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
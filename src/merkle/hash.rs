@@ -0,0 +1,97 @@
+use std::fmt::Debug;
+
+use crate::prelude::*;
+
+/// A two-to-one hash for Merkle nodes, in and out of the circuit.
+///
+/// The in-circuit hash must agree with [`PathHasher::native`], which the
+/// native [`super::MerkleTree`] uses to build witnesses.
+pub trait PathHasher<F: Field>: Clone + Debug {
+    type Config: Clone + Debug;
+
+    fn construct(config: Self::Config) -> Self;
+
+    /// Hashes the children of a node into their parent.
+    fn hash(
+        &self,
+        layouter: impl Layouter<F>,
+        left: &AssignedCell<F, F>,
+        right: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
+    /// Plain-Rust reference for [`PathHasher::hash`].
+    fn native(left: F, right: F) -> F;
+}
+
+/// Columns `[left, right, parent]` of a [`ToyHashChip`] row.
+#[derive(Debug, Clone)]
+pub struct ToyHashConfig {
+    pub advice: [Column<Advice>; 3],
+    pub selector: Selector,
+}
+
+/// `H(l, r) = l^2 + 3r + 5`: cheap and order-sensitive, but trivially
+/// invertible, so only fit for exercising the path logic.
+#[derive(Debug, Clone)]
+pub struct ToyHashChip<F: Field> {
+    config: ToyHashConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> ToyHashChip<F> {
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> ToyHashConfig {
+        let [col_l, col_r, col_out] = advice;
+        let selector = meta.selector();
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("toy hash", |meta| {
+            let s = meta.query_selector(selector);
+            let l = meta.query_advice(col_l, Rotation::cur());
+            let r = meta.query_advice(col_r, Rotation::cur());
+            let out = meta.query_advice(col_out, Rotation::cur());
+            let three = Expression::Constant(F::from(3));
+            let five = Expression::Constant(F::from(5));
+            vec![s * (l.clone() * l + three * r + five - out)]
+        });
+
+        ToyHashConfig { advice, selector }
+    }
+}
+
+impl<F: PrimeField> PathHasher<F> for ToyHashChip<F> {
+    type Config = ToyHashConfig;
+
+    fn construct(config: ToyHashConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn hash(
+        &self,
+        mut layouter: impl Layouter<F>,
+        left: &AssignedCell<F, F>,
+        right: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_l, col_r, col_out] = self.config.advice;
+
+        layouter.assign_region(
+            || "toy hash",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                let l = left.copy_advice(|| "left", &mut region, col_l, 0)?;
+                let r = right.copy_advice(|| "right", &mut region, col_r, 0)?;
+                let out = l.value().zip(r.value()).map(|(&l, &r)| Self::native(l, r));
+                region.assign_advice(|| "parent", col_out, 0, || out)
+            },
+        )
+    }
+
+    fn native(left: F, right: F) -> F {
+        left.square() + F::from(3) * right + F::from(5)
+    }
+}
@@ -0,0 +1,32 @@
+//! Merkle inclusion proofs: a path chip generic over the node hash, and a
+//! native sparse tree to produce its witnesses.
+//!
+//! With the `poseidon` feature, [`PoseidonMerkleCircuit`] uses Poseidon as the
+//! node hash instead of the toy hash of the basic example.
+
+mod chip;
+mod example;
+mod hash;
+#[cfg(feature = "poseidon")]
+mod poseidon;
+mod tree;
+
+pub use chip::{MerklePathChip, MerklePathConfig};
+pub use hash::{PathHasher, ToyHashChip, ToyHashConfig};
+#[cfg(feature = "poseidon")]
+pub use poseidon::{PoseidonMerkleCircuit, PoseidonMerkleConfig, PoseidonMerkleTree};
+pub use tree::{MerklePath, MerkleTree};
+
+use crate::registry::Example;
+
+pub(crate) const EXAMPLES: &[Example] = &[
+    Example {
+        name: "merkle",
+        circuit: example::example_circuit,
+    },
+    #[cfg(feature = "poseidon")]
+    Example {
+        name: "merkle::poseidon",
+        circuit: poseidon::example_circuit,
+    },
+];
@@ -0,0 +1,186 @@
+//! Merkle membership with Poseidon as the node hash.
+
+use halo2_proofs::pasta::Fp;
+
+use super::{MerklePath, MerklePathChip, MerklePathConfig, MerkleTree, PathHasher};
+use crate::{
+    gadgets::bits::{BitsChip, BitsConfig},
+    poseidon::{self, PoseidonChip, PoseidonConfig},
+    prelude::*,
+    registry::ExampleCircuit,
+};
+
+impl PathHasher<Fp> for PoseidonChip<2> {
+    type Config = PoseidonConfig;
+
+    fn construct(config: PoseidonConfig) -> Self {
+        PoseidonChip::construct(config)
+    }
+
+    fn hash(
+        &self,
+        layouter: impl Layouter<Fp>,
+        left: &AssignedCell<Fp, Fp>,
+        right: &AssignedCell<Fp, Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        PoseidonChip::hash(self, layouter, [left.clone(), right.clone()])
+    }
+
+    fn native(left: Fp, right: Fp) -> Fp {
+        poseidon::hash([left, right])
+    }
+}
+
+/// A sparse Merkle tree hashed with Poseidon.
+pub type PoseidonMerkleTree = MerkleTree<Fp, PoseidonChip<2>>;
+
+#[derive(Debug, Clone)]
+pub struct PoseidonMerkleConfig {
+    advice: [Column<Advice>; 4],
+    instance: Column<Instance>,
+    bits: BitsConfig,
+    path: MerklePathConfig<Fp, PoseidonChip<2>>,
+}
+
+/// Proves that a private leaf sits at a public index of a tree with a public
+/// root. The instance column holds `[index, root]`.
+///
+/// The index is split into `DEPTH` bits, which both drive the path and bound
+/// the index to the tree.
+#[derive(Debug, Clone)]
+pub struct PoseidonMerkleCircuit<const DEPTH: usize> {
+    leaf: Value<Fp>,
+    index: Value<Fp>,
+    siblings: Vec<Value<Fp>>,
+}
+
+impl<const DEPTH: usize> PoseidonMerkleCircuit<DEPTH> {
+    pub fn new(leaf: Fp, path: &MerklePath<Fp>) -> Self {
+        assert_eq!(path.siblings.len(), DEPTH, "path has the wrong depth");
+        Self {
+            leaf: Value::known(leaf),
+            index: Value::known(Fp::from(path.index)),
+            siblings: path.siblings.iter().copied().map(Value::known).collect(),
+        }
+    }
+
+    /// The public inputs for the leaf at `index` of a tree with `root`.
+    pub fn instances(index: u64, root: Fp) -> Vec<Vec<Fp>> {
+        vec![vec![Fp::from(index), root]]
+    }
+}
+
+impl<const DEPTH: usize> Circuit<Fp> for PoseidonMerkleCircuit<DEPTH> {
+    type Config = PoseidonMerkleConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            leaf: Value::unknown(),
+            index: Value::unknown(),
+            siblings: vec![Value::unknown(); DEPTH],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let bits = BitsChip::configure(meta, advice[0], advice[1]);
+        let hasher = PoseidonChip::<2>::configure(meta, advice);
+        let path = MerklePathChip::configure(meta, advice, hasher);
+
+        PoseidonMerkleConfig {
+            advice,
+            instance,
+            bits,
+            path,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let (leaf, index) = layouter.assign_region(
+            || "leaf and index",
+            |mut region| {
+                let leaf = region.assign_advice(|| "leaf", config.advice[0], 0, || self.leaf)?;
+                let index = region.assign_advice(|| "index", config.advice[1], 0, || self.index)?;
+                Ok((leaf, index))
+            },
+        )?;
+
+        let bits = BitsChip::construct(config.bits).to_bits(
+            layouter.namespace(|| "index bits"),
+            &index,
+            DEPTH,
+        )?;
+        let chip = MerklePathChip::construct(config.path);
+        let root = chip.root(layouter.namespace(|| "path"), &leaf, &bits, &self.siblings)?;
+
+        layouter.constrain_instance(index.cell(), config.instance, 0)?;
+        layouter.constrain_instance(root.cell(), config.instance, 1)
+    }
+}
+
+/// The circuit on a leaf at index 5 of a depth-8 tree.
+pub(super) fn example_circuit() -> ExampleCircuit {
+    let mut tree = PoseidonMerkleTree::new(8);
+    tree.insert(5, Fp::from(42));
+    tree.insert(6, Fp::from(7));
+
+    let circuit = PoseidonMerkleCircuit::<8>::new(Fp::from(42), &tree.path(5));
+    let instances = PoseidonMerkleCircuit::<8>::instances(5, tree.root());
+    ExampleCircuit::new(10, circuit, instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    const DEPTH: usize = 20;
+
+    fn tree() -> PoseidonMerkleTree {
+        let mut tree = PoseidonMerkleTree::new(DEPTH);
+        for (index, leaf) in [(0, 10), (3, 11), (700_000, 12), (1 << 19, 13)] {
+            tree.insert(index, Fp::from(leaf));
+        }
+        tree
+    }
+
+    fn run(leaf: Fp, path: &MerklePath<Fp>, index: u64, root: Fp) -> MockProver<Fp> {
+        let k = 11;
+
+        let circuit = PoseidonMerkleCircuit::<DEPTH>::new(leaf, path);
+        let instances = PoseidonMerkleCircuit::<DEPTH>::instances(index, root);
+        MockProver::run(k, &circuit, instances).unwrap()
+    }
+
+    #[test]
+    fn poseidon_merkle_depth_20() {
+        let tree = tree();
+        for index in [3, 700_000] {
+            let path = tree.path(index);
+            run(tree.leaf(index), &path, index, tree.root()).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn poseidon_merkle_rejects_wrong_leaf() {
+        let tree = tree();
+        let path = tree.path(3);
+        assert!(run(Fp::from(12), &path, 3, tree.root()).verify().is_err());
+    }
+
+    #[test]
+    fn poseidon_merkle_rejects_other_index() {
+        // A valid path, claimed for another public index.
+        let tree = tree();
+        let path = tree.path(3);
+        assert!(run(tree.leaf(3), &path, 2, tree.root()).verify().is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use super::PathHasher;
+use crate::prelude::*;
+
+/// A sparse Merkle tree of `2^depth` leaves, all zero until set.
+///
+/// Only the nodes above set leaves are stored; every other node is the root of
+/// an empty subtree, precomputed per level, so trees of depth 32 are cheap.
+#[derive(Debug, Clone)]
+pub struct MerkleTree<F: Field, H: PathHasher<F>> {
+    depth: usize,
+    /// `empty[level]` is the root of an empty subtree of height `level`.
+    empty: Vec<F>,
+    /// Non-empty nodes by `(level, index)`, with leaves at level 0.
+    nodes: HashMap<(usize, u64), F>,
+    _marker: PhantomData<H>,
+}
+
+/// The siblings of a leaf from the bottom up, and the leaf's index, whose bits
+/// say at each level whether the path goes through a right child.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerklePath<F> {
+    pub index: u64,
+    pub siblings: Vec<F>,
+}
+
+impl<F: Field> MerklePath<F> {
+    /// The direction bits of the path, bottom up: 1 where the node on the path
+    /// is a right child.
+    pub fn bits(&self) -> Vec<bool> {
+        (0..self.siblings.len())
+            .map(|level| self.index >> level & 1 == 1)
+            .collect()
+    }
+
+    /// Plain-Rust reference: the root that `leaf` hashes up to along this path.
+    pub fn root<H: PathHasher<F>>(&self, leaf: F) -> F {
+        self.siblings
+            .iter()
+            .zip(self.bits())
+            .fold(leaf, |node, (&sibling, is_right)| {
+                if is_right {
+                    H::native(sibling, node)
+                } else {
+                    H::native(node, sibling)
+                }
+            })
+    }
+}
+
+impl<F: Field, H: PathHasher<F>> MerkleTree<F, H> {
+    pub fn new(depth: usize) -> Self {
+        assert!(depth <= 63, "depth {} is too large", depth);
+        let mut empty = vec![F::ZERO];
+        for level in 0..depth {
+            empty.push(H::native(empty[level], empty[level]));
+        }
+
+        Self {
+            depth,
+            empty,
+            nodes: HashMap::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn node(&self, level: usize, index: u64) -> F {
+        self.nodes
+            .get(&(level, index))
+            .copied()
+            .unwrap_or(self.empty[level])
+    }
+
+    /// Sets the leaf at `index` and rehashes the nodes above it.
+    pub fn insert(&mut self, index: u64, leaf: F) {
+        assert!(index >> self.depth == 0, "index {} is out of range", index);
+
+        let mut node = leaf;
+        let mut index = index;
+        for level in 0..self.depth {
+            self.nodes.insert((level, index), node);
+            let sibling = self.node(level, index ^ 1);
+            node = if index & 1 == 1 {
+                H::native(sibling, node)
+            } else {
+                H::native(node, sibling)
+            };
+            index >>= 1;
+        }
+        self.nodes.insert((self.depth, 0), node);
+    }
+
+    pub fn root(&self) -> F {
+        self.node(self.depth, 0)
+    }
+
+    pub fn leaf(&self, index: u64) -> F {
+        self.node(0, index)
+    }
+
+    /// The authentication path of the leaf at `index`.
+    pub fn path(&self, index: u64) -> MerklePath<F> {
+        assert!(index >> self.depth == 0, "index {} is out of range", index);
+
+        let siblings = (0..self.depth)
+            .map(|level| self.node(level, (index >> level) ^ 1))
+            .collect();
+        MerklePath { index, siblings }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::ToyHashChip;
+    use halo2_proofs::pasta::Fp;
+
+    type Tree = MerkleTree<Fp, ToyHashChip<Fp>>;
+
+    #[test]
+    fn paths_hash_to_root() {
+        let mut tree = Tree::new(16);
+        for (index, leaf) in [(0, 11), (1, 12), (4_000, 13), (65_535, 14)] {
+            tree.insert(index, Fp::from(leaf));
+        }
+
+        for index in [0, 1, 2, 4_000, 65_535] {
+            let path = tree.path(index);
+            assert_eq!(path.root::<ToyHashChip<Fp>>(tree.leaf(index)), tree.root());
+        }
+    }
+
+    #[test]
+    fn empty_tree_matches_dense_hashing() {
+        let depth = 3;
+        let tree = Tree::new(depth);
+
+        let mut level = vec![Fp::ZERO; 1 << depth];
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| ToyHashChip::<Fp>::native(pair[0], pair[1]))
+                .collect();
+        }
+        assert_eq!(tree.root(), level[0]);
+    }
+
+    #[test]
+    fn insert_changes_root() {
+        let mut tree = Tree::new(8);
+        let before = tree.root();
+        tree.insert(5, Fp::from(1));
+        assert_ne!(tree.root(), before);
+    }
+}
@@ -0,0 +1,237 @@
+//! MiMC built from scratch: `ROUNDS` rounds of `x -> (x + c_i)^3`, with the
+//! round constants in a fixed column.
+//!
+//! This is the keyless MiMC-91 shape (91 rounds suit a 254-bit field with the
+//! cube map). Over the Pasta fields `3` divides `p - 1`, so cubing is not a
+//! permutation there and this instance has easy collisions: it shows how an
+//! algebraic hash is laid out, and is not a hash to rely on. Poseidon on Pasta
+//! uses `x^5` for exactly this reason.
+
+use ff::FromUniformBytes;
+use halo2_proofs::pasta::Fp;
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    names,
+    prelude::*,
+    registry::{Example, ExampleCircuit},
+};
+
+/// Number of rounds.
+pub const ROUNDS: usize = 91;
+
+/// Domain separator for deriving the round constants.
+const SEED: &[u8] = b"halo2examples:mimc";
+
+/// The round constants: `c_0 = 0`, as in the MiMC paper, and the rest hashed
+/// from [`SEED`] and the round index.
+pub fn round_constants<F: FromUniformBytes<64>>() -> Vec<F> {
+    let mut constants = vec![F::ZERO];
+    constants.extend((1..ROUNDS as u32).map(|i| {
+        let mut wide = [0; 64];
+        for (half, tag) in wide.chunks_exact_mut(32).zip([0u8, 1]) {
+            let digest = Keccak256::new()
+                .chain_update(SEED)
+                .chain_update(i.to_le_bytes())
+                .chain_update([tag])
+                .finalize();
+            half.copy_from_slice(&digest);
+        }
+        F::from_uniform_bytes(&wide)
+    }));
+    constants
+}
+
+/// Plain-Rust reference for the hash the circuit computes.
+pub fn reference<F: FromUniformBytes<64>>(x: F) -> F {
+    round_constants::<F>()
+        .into_iter()
+        .fold(x, |x, c| (x + c).cube())
+}
+
+#[derive(Debug, Clone)]
+struct MimcConfig {
+    pub advice: Column<Advice>,
+    pub constant: Column<Fixed>,
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+}
+
+/// One row per round:
+///
+/// ```text
+/// advice  | constant | selector
+/// x_0     | c_0      | 1
+/// x_1     | c_1      | 1
+/// ...     | ...      | ...
+/// x_91    |          | 0
+/// ```
+///
+/// with the gate `x_{i+1} = (x_i + c_i)^3`.
+#[derive(Debug, Clone)]
+struct MimcChip<F: FromUniformBytes<64>> {
+    config: MimcConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FromUniformBytes<64>> MimcChip<F> {
+    pub fn construct(config: MimcConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> MimcConfig {
+        let constant = meta.fixed_column();
+        let selector = meta.selector();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        meta.create_gate("mimc round", |meta| {
+            let s = meta.query_selector(selector);
+            let x = meta.query_advice(advice, Rotation::cur());
+            let c = meta.query_fixed(constant);
+            let x_next = meta.query_advice(advice, Rotation::next());
+            let t = x + c;
+            vec![s * (t.clone() * t.clone() * t - x_next)]
+        });
+
+        MimcConfig {
+            advice,
+            constant,
+            selector,
+            instance,
+        }
+    }
+
+    /// Runs every round on `x` and returns the hash.
+    pub fn hash(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        let constants = round_constants::<F>();
+
+        layouter.assign_region(
+            || "mimc",
+            |mut region| {
+                let mut x = region.assign_advice(|| "x_0", config.advice, 0, || x)?;
+                for (round, &c) in constants.iter().enumerate() {
+                    config.selector.enable(&mut region, round)?;
+                    region.assign_fixed(|| "c", config.constant, round, || Value::known(c))?;
+
+                    let next = x.value().map(|&x| (x + c).cube());
+                    x = region.assign_advice(|| "x", config.advice, round + 1, || next)?;
+                }
+                Ok(x)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Proves knowledge of a private `x` with `MiMC(x)` equal to the public hash.
+#[derive(Default)]
+struct MyCircuit<F> {
+    x: Value<F>,
+}
+
+impl<F: FromUniformBytes<64>> Circuit<F> for MyCircuit<F> {
+    type Config = MimcConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        MimcChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = MimcChip::construct(config);
+
+        let hash = chip.hash(layouter.namespace(|| "mimc"), self.x)?;
+
+        chip.expose_public(layouter.namespace(|| names::OUT), &hash, 0)
+    }
+}
+
+/// The circuit on `x = 42`.
+pub fn example_circuit() -> ExampleCircuit {
+    let x = Fp::from(42);
+    let circuit = MyCircuit { x: Value::known(x) };
+    ExampleCircuit::new(7, circuit, vec![vec![reference(x)]])
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "mimc",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::WithSmallOrderMulGroup;
+    use halo2_proofs::dev::MockProver;
+    use rand_core::OsRng;
+
+    fn run(x: Fp, hash: Fp) -> MockProver<Fp> {
+        let k = 7;
+
+        MockProver::run(k, &MyCircuit { x: Value::known(x) }, vec![vec![hash]]).unwrap()
+    }
+
+    #[test]
+    fn round_constants_are_fixed() {
+        let constants = round_constants::<Fp>();
+        assert_eq!(constants.len(), ROUNDS);
+        assert_eq!(constants[0], Fp::ZERO);
+        assert_eq!(constants, round_constants::<Fp>());
+        assert_ne!(constants[1], constants[2]);
+    }
+
+    #[test]
+    fn mimc_example() {
+        let x = Fp::random(OsRng);
+        run(x, reference(x)).assert_satisfied();
+    }
+
+    #[test]
+    fn mimc_rejects_wrong_preimage() {
+        let hash = reference(Fp::from(42));
+        assert!(run(Fp::from(43), hash).verify().is_err());
+    }
+
+    #[test]
+    fn mimc_cube_collides_on_pasta() {
+        // For a cube root of unity w, (w * x)^3 = x^3, and c_0 = 0, so x and
+        // w * x have the same hash.
+        let w = Fp::ZETA;
+        assert_ne!(w, Fp::ONE);
+        assert_eq!(w.cube(), Fp::ONE);
+
+        let x = Fp::from(42);
+        run(w * x, reference(x)).assert_satisfied();
+    }
+}
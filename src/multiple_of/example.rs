@@ -0,0 +1,225 @@
+use crate::{names, prelude::*, range_check::table::RangeTableConfig, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Proves that a public `v` is a multiple of the constant `K`, by witnessing a
+/// quotient `q` with
+///
+/// ```text
+/// v == K * q
+/// ```
+///
+/// and looking `q` up in a `0..RANGE` table. Without the range check every `v`
+/// would pass, with `q = v / K` computed in the field. With it, `v` must be one
+/// of `0, K, ..., K * (RANGE - 1)`, which is an honest multiple as long as
+/// `K * RANGE` is far below the field modulus.
+#[derive(Debug, Clone)]
+struct MultipleOfConfig<F: PrimeField, const RANGE: usize> {
+    pub advice: [Column<Advice>; 2],
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+#[derive(Debug, Clone)]
+struct MultipleOfChip<F: PrimeField, const K: u64, const RANGE: usize> {
+    config: MultipleOfConfig<F, RANGE>,
+}
+
+impl<F: PrimeField, const K: u64, const RANGE: usize> MultipleOfChip<F, K, RANGE> {
+    pub fn construct(config: MultipleOfConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> MultipleOfConfig<F, RANGE> {
+        let [col_v, col_q] = advice;
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        let table = RangeTableConfig::configure(meta);
+        meta.enable_equality(col_v);
+        meta.enable_equality(instance);
+
+        meta.create_gate("multiple", |meta| {
+            //
+            // col_v | col_q | selector
+            //   v       q        s
+            //
+            let s = meta.query_selector(selector);
+            let v = meta.query_advice(col_v, Rotation::cur());
+            let q = meta.query_advice(col_q, Rotation::cur());
+            vec![s * (v - Expression::Constant(F::from(K)) * q)]
+        });
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let q = meta.query_advice(col_q, Rotation::cur());
+            vec![(s * q, table.value)]
+        });
+
+        MultipleOfConfig {
+            advice,
+            selector,
+            instance,
+            table,
+        }
+    }
+
+    /// Assigns `v` and its quotient `q`, and returns the cell holding `v`.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        v: Value<F>,
+        q: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_v, col_q] = self.config.advice;
+        layouter.assign_region(
+            || "multiple",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "q", col_q, 0, || q)?;
+                region.assign_advice(|| "v", col_v, 0, || v)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference: the quotient `v / k`, if `k` divides `v`.
+#[cfg(test)]
+pub fn reference(v: u64, k: u64) -> Option<u64> {
+    v.is_multiple_of(k).then(|| v / k)
+}
+
+#[derive(Default)]
+struct MyCircuit<F, const K: u64, const RANGE: usize> {
+    v: Value<F>,
+    q: Value<F>,
+}
+
+impl<F: PrimeField, const K: u64, const RANGE: usize> MyCircuit<F, K, RANGE> {
+    /// The circuit for `v` with the integer quotient `v / K`, which is only
+    /// exact if `K` divides `v`.
+    pub fn new(v: u64) -> Self {
+        Self {
+            v: Value::known(F::from(v)),
+            q: Value::known(F::from(v / K)),
+        }
+    }
+}
+
+impl<F: PrimeField, const K: u64, const RANGE: usize> Circuit<F> for MyCircuit<F, K, RANGE> {
+    type Config = MultipleOfConfig<F, RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        MultipleOfChip::<F, K, RANGE>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let chip = MultipleOfChip::<F, K, RANGE>::construct(config);
+
+        let v = chip.assign(layouter.namespace(|| "multiple"), self.v, self.q)?;
+        chip.expose_public(layouter.namespace(|| names::OUT), &v, 0)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on `21 = 7 * 3`.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp, 7, 256>::new(21);
+    ExampleCircuit::new(9, circuit, vec![vec![Fp::from(21)]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    const RANGE: usize = 256;
+
+    fn run(circuit: &MyCircuit<Fp, 7, RANGE>, v: u64) -> MockProver<Fp> {
+        let k = 9;
+
+        MockProver::run(k, circuit, vec![vec![Fp::from(v)]]).unwrap()
+    }
+
+    fn circuit(v: u64, q: Fp) -> MyCircuit<Fp, 7, RANGE> {
+        MyCircuit {
+            v: Value::known(Fp::from(v)),
+            q: Value::known(q),
+        }
+    }
+
+    #[test]
+    fn multiple_of_example() {
+        assert_eq!(reference(21, 7), Some(3));
+        run(&MyCircuit::new(21), 21).assert_satisfied();
+        run(&MyCircuit::new(0), 0).assert_satisfied();
+    }
+
+    #[test]
+    fn multiple_of_accepts_whole_range() {
+        let k = 9;
+
+        let sets: Vec<_> = (0..RANGE as u64)
+            .map(|q| vec![vec![Fp::from(7 * q)]])
+            .collect();
+        assert_all_satisfied(
+            k,
+            |instances| MyCircuit::<Fp, 7, RANGE>::new(to_u64(&instances[0][0])),
+            &sets,
+        );
+    }
+
+    #[test]
+    fn multiple_of_rejects_non_multiple() {
+        assert_eq!(reference(22, 7), None);
+        for q in 0..RANGE as u64 {
+            assert!(run(&circuit(22, Fp::from(q)), 22).verify().is_err());
+        }
+    }
+
+    #[test]
+    fn multiple_of_rejects_field_quotient() {
+        // 22 / 7 in the field satisfies the gate, but is no small integer.
+        let q = Fp::from(22) * Fp::from(7).invert().unwrap();
+        let failures = run(&circuit(22, q), 22).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn multiple_of_rejects_quotient_past_range() {
+        // 7 * 256 is a multiple of 7, but its quotient is outside the table.
+        let v = 7 * RANGE as u64;
+        assert!(run(&MyCircuit::new(v), v).verify().is_err());
+    }
+}
@@ -0,0 +1,35 @@
+//! Gate, region and namespace names shared by several chips.
+//!
+//! Negative tests match `VerifyFailure`s by these names, so chips and tests both
+//! refer to the constants here rather than repeating the strings.
+
+/// The boolean gate of [`crate::common::BooleanChip`], and the region it assigns.
+pub const BOOL: &str = "bool";
+
+/// The `a + sign*b = c` gate of [`crate::common::AddSubChip`], and the region it
+/// assigns.
+pub const ADD_SUB: &str = "add_sub";
+/// The gate keeping an [`crate::common::AddSubChip`] sign to `+1` or `-1`.
+pub const SIGN: &str = "sign";
+
+/// Gate constraining the first row of a table.
+pub const FIRST: &str = "first";
+/// Gate constraining each step from one row to the next.
+pub const STEP: &str = "step";
+/// Gate constraining the last row of a table.
+pub const LAST: &str = "last";
+
+/// The Fibonacci `a + b = c` gate.
+pub const ADD: &str = "add";
+/// The Fibonacci `a + b = c + M*q` gate, with a boolean quotient `q`.
+pub const ADD_MOD: &str = "add mod";
+/// The Fibonacci gate tying the seeds to the instance column.
+pub const INIT: &str = "init";
+
+/// Region holding the first row of a Fibonacci table.
+pub const FIRST_ROW: &str = "first row";
+/// Region holding one later row of a Fibonacci table.
+pub const NEXT_ROW: &str = "next row";
+
+/// Namespace for exposing a circuit's output.
+pub const OUT: &str = "out";
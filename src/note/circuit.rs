@@ -0,0 +1,218 @@
+use halo2_proofs::pasta::Fp;
+
+use super::Note;
+use crate::{
+    gadgets::bits::{BitsChip, BitsConfig},
+    merkle::{MerklePath, MerklePathChip, MerklePathConfig, PoseidonMerkleTree},
+    poseidon::{PoseidonChip, PoseidonConfig},
+    prelude::*,
+    registry::ExampleCircuit,
+};
+
+#[derive(Debug, Clone)]
+pub struct NoteConfig {
+    advice: [Column<Advice>; 4],
+    instance: Column<Instance>,
+    bits: BitsConfig,
+    poseidon: PoseidonConfig,
+    path: MerklePathConfig<Fp, PoseidonChip<2>>,
+}
+
+/// Proves that a private note's commitment is a leaf of a tree of depth
+/// `DEPTH`, and that its nullifier is the one revealed. The instance column
+/// holds `[root, nullifier]`; the note, its position and its path stay private.
+///
+/// The commitment and the nullifier are hashed from the same `secret` and
+/// `rho` cells, so the nullifier cannot belong to another note.
+#[derive(Debug, Clone)]
+pub struct NoteCircuit<const DEPTH: usize> {
+    value: Value<Fp>,
+    secret: Value<Fp>,
+    rho: Value<Fp>,
+    index: Value<Fp>,
+    siblings: Vec<Value<Fp>>,
+}
+
+impl<const DEPTH: usize> NoteCircuit<DEPTH> {
+    pub fn new(note: &Note, path: &MerklePath<Fp>) -> Self {
+        assert_eq!(path.siblings.len(), DEPTH, "path has the wrong depth");
+        Self {
+            value: Value::known(Fp::from(note.value)),
+            secret: Value::known(note.secret),
+            rho: Value::known(note.rho),
+            index: Value::known(Fp::from(path.index)),
+            siblings: path.siblings.iter().copied().map(Value::known).collect(),
+        }
+    }
+
+    /// The public inputs for spending a note with `nullifier` from a tree with
+    /// `root`.
+    pub fn instances(root: Fp, nullifier: Fp) -> Vec<Vec<Fp>> {
+        vec![vec![root, nullifier]]
+    }
+}
+
+impl<const DEPTH: usize> Circuit<Fp> for NoteCircuit<DEPTH> {
+    type Config = NoteConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+            secret: Value::unknown(),
+            rho: Value::unknown(),
+            index: Value::unknown(),
+            siblings: vec![Value::unknown(); DEPTH],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let bits = BitsChip::configure(meta, advice[0], advice[1]);
+        // One Poseidon configuration serves every message length.
+        let poseidon = PoseidonChip::<2>::configure(meta, advice);
+        let path = MerklePathChip::configure(meta, advice, poseidon.clone());
+
+        NoteConfig {
+            advice,
+            instance,
+            bits,
+            poseidon,
+            path,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let commit = PoseidonChip::<3>::construct(config.poseidon.clone());
+        let [value, secret, rho] = commit.load(
+            layouter.namespace(|| "note"),
+            [self.value, self.secret, self.rho],
+        )?;
+        let commitment = commit.hash(
+            layouter.namespace(|| "commitment"),
+            [value, secret.clone(), rho.clone()],
+        )?;
+        let nullifier = PoseidonChip::<2>::construct(config.poseidon)
+            .hash(layouter.namespace(|| "nullifier"), [secret, rho])?;
+
+        let index = layouter.assign_region(
+            || "index",
+            |mut region| region.assign_advice(|| "index", config.advice[0], 0, || self.index),
+        )?;
+        let bits = BitsChip::construct(config.bits).to_bits(
+            layouter.namespace(|| "index bits"),
+            &index,
+            DEPTH,
+        )?;
+        let root = MerklePathChip::construct(config.path).root(
+            layouter.namespace(|| "path"),
+            &commitment,
+            &bits,
+            &self.siblings,
+        )?;
+
+        layouter.constrain_instance(root.cell(), config.instance, 0)?;
+        layouter.constrain_instance(nullifier.cell(), config.instance, 1)
+    }
+}
+
+/// The circuit spending the second of two notes in a depth-8 tree.
+pub(super) fn example_circuit() -> ExampleCircuit {
+    let notes = [(10, 1, 2), (25, 3, 4)].map(|(value, secret, rho)| Note {
+        value,
+        secret: Fp::from(secret),
+        rho: Fp::from(rho),
+    });
+    let mut tree = PoseidonMerkleTree::new(8);
+    for (index, note) in notes.iter().enumerate() {
+        tree.insert(index as u64, note.commitment());
+    }
+
+    let circuit = NoteCircuit::<8>::new(&notes[1], &tree.path(1));
+    let instances = NoteCircuit::<8>::instances(tree.root(), notes[1].nullifier());
+    ExampleCircuit::new(10, circuit, instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+    use rand_core::OsRng;
+
+    const DEPTH: usize = 16;
+
+    fn random_note(value: u64) -> Note {
+        Note {
+            value,
+            secret: Fp::random(OsRng),
+            rho: Fp::random(OsRng),
+        }
+    }
+
+    /// A tree holding `notes` at indices 0, 1, 2, ...
+    fn tree(notes: &[Note]) -> PoseidonMerkleTree {
+        let mut tree = PoseidonMerkleTree::new(DEPTH);
+        for (index, note) in notes.iter().enumerate() {
+            tree.insert(index as u64, note.commitment());
+        }
+        tree
+    }
+
+    fn run(note: &Note, path: &MerklePath<Fp>, root: Fp, nullifier: Fp) -> MockProver<Fp> {
+        let k = 11;
+
+        let circuit = NoteCircuit::<DEPTH>::new(note, path);
+        let instances = NoteCircuit::<DEPTH>::instances(root, nullifier);
+        MockProver::run(k, &circuit, instances).unwrap()
+    }
+
+    #[test]
+    fn note_spend() {
+        let notes = [random_note(5), random_note(7), random_note(9)];
+        let tree = tree(&notes);
+        for (index, note) in notes.iter().enumerate() {
+            let path = tree.path(index as u64);
+            run(note, &path, tree.root(), note.nullifier()).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn note_rejects_note_not_in_tree() {
+        let notes = [random_note(5), random_note(7)];
+        let tree = tree(&notes);
+        let forged = Note {
+            value: 1_000,
+            ..notes[0]
+        };
+        let path = tree.path(0);
+        assert!(run(&forged, &path, tree.root(), forged.nullifier())
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn note_rejects_other_nullifier() {
+        // A valid spend of the first note cannot reveal the second's nullifier.
+        let notes = [random_note(5), random_note(7)];
+        let tree = tree(&notes);
+        let path = tree.path(0);
+        assert!(run(&notes[0], &path, tree.root(), notes[1].nullifier())
+            .verify()
+            .is_err());
+    }
+
+    #[test]
+    fn nullifier_does_not_depend_on_value() {
+        let note = random_note(5);
+        let other = Note { value: 6, ..note };
+        assert_eq!(note.nullifier(), other.nullifier());
+        assert_ne!(note.commitment(), other.commitment());
+    }
+}
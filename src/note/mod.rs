@@ -0,0 +1,20 @@
+//! A shielded note, in the style of Zcash: spending it proves that its
+//! commitment is in the tree of notes and reveals its nullifier, without saying
+//! which note it is.
+//!
+//! Notes are committed as `Com(value, secret, rho) = Poseidon(value, secret,
+//! rho)` and nullified as `Poseidon(secret, rho)`. The nullifier is the same
+//! however the note is found, so a second spend reveals it again and can be
+//! rejected, while nothing links it to the commitment without the secret.
+
+mod circuit;
+mod native;
+
+pub(crate) use native::Note;
+
+use crate::registry::Example;
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "note",
+    circuit: circuit::example_circuit,
+}];
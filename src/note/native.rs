@@ -0,0 +1,24 @@
+use halo2_proofs::pasta::Fp;
+
+use crate::poseidon;
+
+/// A note of `value`, spendable by whoever knows `secret`. `rho` is unique per
+/// note, so two notes of the same owner and value have distinct nullifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Note {
+    pub value: u64,
+    pub secret: Fp,
+    pub rho: Fp,
+}
+
+impl Note {
+    /// The leaf this note is stored as in the tree of notes.
+    pub fn commitment(&self) -> Fp {
+        poseidon::hash([Fp::from(self.value), self.secret, self.rho])
+    }
+
+    /// Revealed when the note is spent.
+    pub fn nullifier(&self) -> Fp {
+        poseidon::hash([self.secret, self.rho])
+    }
+}
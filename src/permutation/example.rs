@@ -0,0 +1,219 @@
+use crate::{names, prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Proves that two private vectors `a` and `b` are permutations of each other
+/// with a grand product: for a challenge `x`,
+///
+/// ```text
+/// prod (x - a_i) == prod (x - b_i)
+/// ```
+///
+/// holds for all `x` exactly when the two multisets are equal, and for a random
+/// `x` it fails with overwhelming probability otherwise.
+///
+/// The running product `z_{i+1} = z_i * (x - a_i) / (x - b_i)` starts and must
+/// end at 1. `x` is read from the instance column and copied into every row.
+/// That is only sound if the prover cannot choose `a` and `b` after seeing `x`;
+/// a real argument draws `x` from the transcript after the vectors are committed,
+/// which halo2_proofs 0.3 has no API for.
+#[derive(Debug, Clone)]
+struct PermutationConfig {
+    pub advice: [Column<Advice>; 4],
+    pub selector_first: Selector,
+    pub selector_step: Selector,
+    pub selector_last: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct PermutationChip<F: Field> {
+    config: PermutationConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> PermutationChip<F> {
+    pub fn construct(config: PermutationConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        instance: Column<Instance>,
+    ) -> PermutationConfig {
+        let [col_a, col_b, col_x, col_z] = advice;
+        let selector_first = meta.selector();
+        let selector_step = meta.selector();
+        let selector_last = meta.selector();
+        meta.enable_equality(col_x);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::FIRST, |meta| {
+            let s = meta.query_selector(selector_first);
+            let z = meta.query_advice(col_z, Rotation::cur());
+            vec![s * (z - Expression::Constant(F::ONE))]
+        });
+
+        meta.create_gate("grand product", |meta| {
+            //
+            // col_a | col_b | col_x | col_z  | selector
+            //   a       b       x       z          s
+            //                           z_next
+            //
+            let s = meta.query_selector(selector_step);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let x = meta.query_advice(col_x, Rotation::cur());
+            let z = meta.query_advice(col_z, Rotation::cur());
+            let z_next = meta.query_advice(col_z, Rotation::next());
+            vec![s * (z_next * (x.clone() - b) - z * (x - a))]
+        });
+
+        meta.create_gate(names::LAST, |meta| {
+            let s = meta.query_selector(selector_last);
+            let z = meta.query_advice(col_z, Rotation::cur());
+            vec![s * (z - Expression::Constant(F::ONE))]
+        });
+
+        PermutationConfig {
+            advice,
+            selector_first,
+            selector_step,
+            selector_last,
+            instance,
+        }
+    }
+
+    /// Assigns both vectors and the running product over them, with the
+    /// challenge taken from row 0 of the instance column.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[Value<F>],
+        b: &[Value<F>],
+    ) -> Result<(), Error> {
+        let [col_a, col_b, col_x, col_z] = self.config.advice;
+        assert_eq!(a.len(), b.len(), "vectors must have the same length");
+
+        layouter.assign_region(
+            || "grand product",
+            |mut region| {
+                self.config.selector_first.enable(&mut region, 0)?;
+                self.config.selector_last.enable(&mut region, a.len())?;
+
+                let mut z = Value::known(F::ONE);
+                region.assign_advice(|| "z", col_z, 0, || z)?;
+
+                for (row, (a, b)) in a.iter().zip(b).enumerate() {
+                    self.config.selector_step.enable(&mut region, row)?;
+
+                    let x = region.assign_advice_from_instance(
+                        || "x",
+                        self.config.instance,
+                        0,
+                        col_x,
+                        row,
+                    )?;
+                    let x = x.value().copied();
+                    region.assign_advice(|| "a", col_a, row, || *a)?;
+                    region.assign_advice(|| "b", col_b, row, || *b)?;
+
+                    let numerator = x - a;
+                    let denominator = (x - b).map(|d| d.invert().unwrap_or(F::ZERO));
+                    z = z * numerator * denominator;
+                    region.assign_advice(|| "z", col_z, row + 1, || z)?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+struct MyCircuit<F> {
+    a: Vec<Value<F>>,
+    b: Vec<Value<F>>,
+}
+
+impl<F: Field> Circuit<F> for MyCircuit<F> {
+    type Config = PermutationConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            a: vec![Value::unknown(); self.a.len()],
+            b: vec![Value::unknown(); self.b.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let instance = meta.instance_column();
+        PermutationChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PermutationChip::construct(config);
+        chip.assign(layouter.namespace(|| "permutation"), &self.a, &self.b)
+    }
+}
+
+/// The circuit on `[1, 2, 3]` and `[3, 1, 2]`.
+pub fn example_circuit() -> ExampleCircuit {
+    let values = |v: [u64; 3]| v.map(|v| Value::known(Fp::from(v))).to_vec();
+    let circuit = MyCircuit {
+        a: values([1, 2, 3]),
+        b: values([3, 1, 2]),
+    };
+    let x = Fp::from(0x5eed_5eed);
+    ExampleCircuit::new(4, circuit, vec![vec![x]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use rand_core::OsRng;
+
+    fn circuit(a: &[u64], b: &[u64]) -> MyCircuit<Fp> {
+        let values = |v: &[u64]| v.iter().map(|&v| Value::known(Fp::from(v))).collect();
+        MyCircuit {
+            a: values(a),
+            b: values(b),
+        }
+    }
+
+    #[test]
+    fn permutation_example() {
+        let k = 4;
+
+        let x = Fp::random(OsRng);
+        let prover = MockProver::run(k, &circuit(&[1, 2, 3], &[3, 1, 2]), vec![vec![x]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn permutation_rejects_different_multiset() {
+        let k = 4;
+
+        let x = Fp::random(OsRng);
+        let prover = MockProver::run(k, &circuit(&[1, 2, 3], &[1, 2, 4]), vec![vec![x]]).unwrap();
+        assert_constraint_fails(&prover, names::LAST);
+
+        // Same elements, different multiplicities.
+        let prover = MockProver::run(k, &circuit(&[1, 1, 2], &[1, 2, 2]), vec![vec![x]]).unwrap();
+        assert_constraint_fails(&prover, names::LAST);
+    }
+}
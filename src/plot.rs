@@ -0,0 +1,157 @@
+//! Circuit layout diagrams, rendered with `halo2_proofs::dev::CircuitLayout`.
+//!
+//! The PNG and SVG helpers share the same canvas size and title font, and take
+//! the title from the output file name so both formats of a diagram match.
+//!
+//! [`render_examples`] draws the circuit every registered example builds, at
+//! the `k` it runs at.
+
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use halo2_proofs::{dev::CircuitLayout, pasta::Fp, plonk::Circuit};
+use plotters::{coord::Shift, prelude::*};
+
+/// Canvas size of every layout diagram, in pixels.
+pub const LAYOUT_SIZE: (u32, u32) = (1024, 3096);
+
+/// Font used for the diagram title.
+pub const TITLE_FONT: (&str, u32) = ("sans-serif", 60);
+
+/// Title for the diagram written to `path`: the file name without extension.
+fn layout_title(path: &str) -> &str {
+    Path::new(path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(path)
+}
+
+/// How to draw a layout diagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutOptions {
+    /// Canvas size, in pixels.
+    pub size: (u32, u32),
+    /// Whether to print region names on the diagram.
+    pub labels: bool,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            size: LAYOUT_SIZE,
+            labels: false,
+        }
+    }
+}
+
+fn render<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    k: u32,
+    circuit: &impl Circuit<Fp>,
+    options: &LayoutOptions,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let root = root.titled(title, TITLE_FONT)?;
+    CircuitLayout::default()
+        .show_labels(options.labels)
+        .render(k, circuit, &root)?;
+    root.present()?;
+    Ok(())
+}
+
+/// Writes the layout of `circuit` at size `2^k` to a PNG file.
+pub fn plot_png(path: &str, k: u32, circuit: &impl Circuit<Fp>) -> Result<(), Box<dyn Error>> {
+    plot_png_with(path, k, circuit, &LayoutOptions::default())
+}
+
+/// Writes the layout of `circuit` at size `2^k` to an SVG file.
+pub fn plot_svg(path: &str, k: u32, circuit: &impl Circuit<Fp>) -> Result<(), Box<dyn Error>> {
+    plot_svg_with(path, k, circuit, &LayoutOptions::default())
+}
+
+/// [`plot_png`] with a custom canvas size and labels.
+pub fn plot_png_with(
+    path: &str,
+    k: u32,
+    circuit: &impl Circuit<Fp>,
+    options: &LayoutOptions,
+) -> Result<(), Box<dyn Error>> {
+    let root = BitMapBackend::new(path, options.size).into_drawing_area();
+    render(root, layout_title(path), k, circuit, options)
+}
+
+/// [`plot_svg`] with a custom canvas size and labels.
+pub fn plot_svg_with(
+    path: &str,
+    k: u32,
+    circuit: &impl Circuit<Fp>,
+    options: &LayoutOptions,
+) -> Result<(), Box<dyn Error>> {
+    let root = SVGBackend::new(path, options.size).into_drawing_area();
+    render(root, layout_title(path), k, circuit, options)
+}
+
+/// Draws the circuit of every registered example whose name `filter`
+/// accepts, at the `k` it runs at, to `<dir>/<name>.png` and `.svg`. `::` in
+/// names becomes `-`. Returns each example's name and the files written.
+pub fn render_examples(
+    dir: &Path,
+    options: &LayoutOptions,
+    filter: impl Fn(&str) -> bool,
+) -> Vec<(String, Result<Vec<PathBuf>, String>)> {
+    crate::registry::examples()
+        .filter(|example| filter(example.name))
+        .map(|example| {
+            let stem = dir.join(example.name.replace("::", "-"));
+            let built = (example.circuit)();
+            let written = ["png", "svg"]
+                .map(|extension| stem.with_extension(extension))
+                .into_iter()
+                .map(|path| {
+                    let name = path.to_str().ok_or("output path is not UTF-8")?;
+                    match path.extension().and_then(|e| e.to_str()) {
+                        Some("png") => built.circuit.plot_png(name, built.k, options),
+                        _ => built.circuit.plot_svg(name, built.k, options),
+                    }
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                    Ok(path)
+                })
+                .collect();
+            (example.name.to_string(), written)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_examples_writes_both_formats() {
+        let dir = std::env::temp_dir().join(format!("layouts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let options = LayoutOptions {
+            size: (400, 800),
+            labels: true,
+        };
+
+        let wanted = ["fibonacci::example1", "fibonacci::example2"];
+        let rendered = render_examples(&dir, &options, |name| wanted.contains(&name));
+        let names: Vec<_> = rendered.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, wanted);
+        for (_, written) in &rendered {
+            for path in written.as_ref().unwrap() {
+                assert!(std::fs::metadata(path).unwrap().len() > 0);
+            }
+        }
+        assert!(dir.join("fibonacci-example2.svg").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{
+    common::{BooleanChip, BooleanConfig},
+    names,
+    prelude::*,
+    registry::ExampleCircuit,
+};
+use halo2_proofs::pasta::Fp;
+
+/// Proves the Hamming weight of a private `BITS`-bit value.
+///
+/// The value is decomposed into bits, most significant first, one bit per row,
+/// next to two running sums:
+///
+/// ```text
+/// z_0 = 0, z_{i+1} = 2*z_i + b_i      (recomposes the value)
+/// w_0 = 0, w_{i+1} = w_i + b_i        (counts the set bits)
+/// ```
+///
+/// Row `BITS` holds the value `z` and its weight `w`; only `w` is public. The
+/// bits are witnessed by a [`BooleanChip`] and copied in.
+#[derive(Debug, Clone)]
+struct PopcountConfig {
+    pub advice: [Column<Advice>; 3],
+    pub boolean: BooleanConfig,
+    pub selector_first: Selector,
+    pub selector_bit: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct PopcountChip<F: PrimeField, const BITS: usize> {
+    config: PopcountConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField, const BITS: usize> PopcountChip<F, BITS> {
+    pub fn construct(config: PopcountConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        bool_column: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> PopcountConfig {
+        let [col_bit, col_z, col_w] = advice;
+        let boolean = BooleanChip::configure(meta, bool_column);
+        let selector_first = meta.selector();
+        let selector_bit = meta.selector();
+        meta.enable_equality(col_bit);
+        meta.enable_equality(col_z);
+        meta.enable_equality(col_w);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::FIRST, |meta| {
+            let s = meta.query_selector(selector_first);
+            let z = meta.query_advice(col_z, Rotation::cur());
+            let w = meta.query_advice(col_w, Rotation::cur());
+            vec![s.clone() * z, s * w]
+        });
+
+        meta.create_gate("running sums", |meta| {
+            //
+            // col_bit | col_z  | col_w  | selector
+            //    b       z        w         s
+            //            z_next   w_next
+            //
+            let s = meta.query_selector(selector_bit);
+            let b = meta.query_advice(col_bit, Rotation::cur());
+            let z = meta.query_advice(col_z, Rotation::cur());
+            let w = meta.query_advice(col_w, Rotation::cur());
+            let z_next = meta.query_advice(col_z, Rotation::next());
+            let w_next = meta.query_advice(col_w, Rotation::next());
+            vec![
+                s.clone() * (z.clone() + z + b.clone() - z_next),
+                s * (w + b - w_next),
+            ]
+        });
+
+        PopcountConfig {
+            advice,
+            boolean,
+            selector_first,
+            selector_bit,
+            instance,
+        }
+    }
+
+    /// Assigns `bits`, most significant first, and the running sums over them.
+    /// Returns the cells holding the recomposed value and its weight.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[Value<F>; BITS],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let [col_bit, col_z, col_w] = self.config.advice;
+
+        let boolean = BooleanChip::construct(self.config.boolean.clone());
+        let bits = bits
+            .iter()
+            .map(|bit| boolean.assign_bool(layouter.namespace(|| "bit"), *bit))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        layouter.assign_region(
+            || "popcount",
+            |mut region| {
+                self.config.selector_first.enable(&mut region, 0)?;
+
+                let mut z = Value::known(F::ZERO);
+                let mut w = Value::known(F::ZERO);
+                let mut z_cell = region.assign_advice(|| "z", col_z, 0, || z)?;
+                let mut w_cell = region.assign_advice(|| "w", col_w, 0, || w)?;
+
+                for (row, bit) in bits.iter().enumerate() {
+                    self.config.selector_bit.enable(&mut region, row)?;
+                    let bit = bit.copy_advice(|| "bit", &mut region, col_bit, row)?;
+                    let bit = bit.value().copied();
+
+                    z = z.map(|z| z.double()) + bit;
+                    w = w + bit;
+                    z_cell = region.assign_advice(|| "z", col_z, row + 1, || z)?;
+                    w_cell = region.assign_advice(|| "w", col_w, row + 1, || w)?;
+                }
+
+                Ok((z_cell, w_cell))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// The low `BITS` bits of `value`, most significant first.
+pub fn to_bits<F: PrimeField, const BITS: usize>(value: Value<F>) -> [Value<F>; BITS] {
+    let value = value.map(|value| to_u64(&value));
+    std::array::from_fn(|i| value.map(|value| F::from((value >> (BITS - 1 - i)) & 1)))
+}
+
+/// Plain-Rust reference for the weight this circuit exposes.
+pub fn reference(value: u64) -> u32 {
+    value.count_ones()
+}
+
+struct MyCircuit<F, const BITS: usize> {
+    bits: [Value<F>; BITS],
+}
+
+impl<F: PrimeField, const BITS: usize> MyCircuit<F, BITS> {
+    pub fn new(value: Value<F>) -> Self {
+        Self {
+            bits: to_bits(value),
+        }
+    }
+}
+
+impl<F: PrimeField, const BITS: usize> Circuit<F> for MyCircuit<F, BITS> {
+    type Config = PopcountConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            bits: [Value::unknown(); BITS],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let bool_column = meta.advice_column();
+        let instance = meta.instance_column();
+        PopcountChip::<F, BITS>::configure(meta, advice, bool_column, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PopcountChip::<F, BITS>::construct(config);
+
+        let (_, weight) = chip.assign(layouter.namespace(|| "popcount"), &self.bits)?;
+
+        chip.expose_public(layouter.namespace(|| "weight"), &weight, 0)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on the 8-bit value `0b1011`.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp, 8>::new(Value::known(Fp::from(0b1011)));
+    let weight = Fp::from(reference(0b1011) as u64);
+    ExampleCircuit::new(4, circuit, vec![vec![weight]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn circuit<const BITS: usize>(value: u64) -> MyCircuit<Fp, BITS> {
+        MyCircuit::new(Value::known(Fp::from(value)))
+    }
+
+    #[test]
+    fn popcount_example() {
+        let k = 4;
+
+        assert_eq!(reference(0b1011), 3);
+        let prover = MockProver::run(k, &circuit::<4>(0b1011), vec![vec![Fp::from(3)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit::<8>(0b1011), vec![vec![Fp::from(3)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn popcount_zero() {
+        let k = 4;
+
+        let prover = MockProver::run(k, &circuit::<8>(0), vec![vec![Fp::ZERO]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn popcount_rejects_wrong_weight() {
+        let k = 4;
+
+        let prover = MockProver::run(k, &circuit::<4>(0b1011), vec![vec![Fp::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn popcount_rejects_non_boolean_bits() {
+        let k = 4;
+
+        // 0b100 recomposed from [0, 2, 0] still gives 4, but with weight 2.
+        let bits = [0, 2, 0].map(|b| Value::known(Fp::from(b)));
+        let prover =
+            MockProver::run(k, &MyCircuit::<Fp, 3> { bits }, vec![vec![Fp::from(2)]]).unwrap();
+        assert_constraint_fails(&prover, names::BOOL);
+    }
+}
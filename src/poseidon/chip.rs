@@ -0,0 +1,90 @@
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, P128Pow5T3},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::pasta::Fp;
+
+use crate::prelude::*;
+
+/// Width of the Poseidon state.
+const WIDTH: usize = 3;
+/// Field elements absorbed per permutation.
+const RATE: usize = 2;
+
+/// Plain-Rust reference: the Poseidon hash of `message`.
+pub fn hash<const L: usize>(message: [Fp; L]) -> Fp {
+    poseidon::Hash::<_, P128Pow5T3, ConstantLength<L>, WIDTH, RATE>::init().hash(message)
+}
+
+#[derive(Debug, Clone)]
+pub struct PoseidonConfig {
+    /// The state columns, which are equality-enabled and free to load
+    /// messages into.
+    pub state: [Column<Advice>; WIDTH],
+    pub pow5: Pow5Config<Fp, WIDTH, RATE>,
+}
+
+/// Hashes `L` assigned cells with `P128Pow5T3`, the Orchard Poseidon instance.
+#[derive(Debug, Clone)]
+pub struct PoseidonChip<const L: usize> {
+    config: PoseidonConfig,
+}
+
+impl<const L: usize> PoseidonChip<L> {
+    pub fn construct(config: PoseidonConfig) -> Self {
+        Self { config }
+    }
+
+    /// Takes four advice columns and allocates the six fixed columns holding
+    /// the round constants, one of which also holds the padding constants.
+    pub fn configure(
+        meta: &mut ConstraintSystem<Fp>,
+        advice: [Column<Advice>; 4],
+    ) -> PoseidonConfig {
+        let [s0, s1, s2, partial_sbox] = advice;
+        let state = [s0, s1, s2];
+        let rc_a = [(); WIDTH].map(|_| meta.fixed_column());
+        let rc_b = [(); WIDTH].map(|_| meta.fixed_column());
+        meta.enable_constant(rc_b[0]);
+
+        let pow5 = Pow5Chip::configure::<P128Pow5T3>(meta, state, partial_sbox, rc_a, rc_b);
+
+        PoseidonConfig { state, pow5 }
+    }
+
+    /// Loads `message` into a fresh row of the state columns.
+    pub fn load(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        message: [Value<Fp>; L],
+    ) -> Result<[AssignedCell<Fp, Fp>; L], Error> {
+        layouter.assign_region(
+            || "load message",
+            |mut region| {
+                let cells = message
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &value)| {
+                        let column = self.config.state[i % WIDTH];
+                        region.assign_advice(|| "message", column, i / WIDTH, || value)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(cells.try_into().unwrap())
+            },
+        )
+    }
+
+    /// Hashes `message` and returns the digest.
+    pub fn hash(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        message: [AssignedCell<Fp, Fp>; L],
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        let pow5 = Pow5Chip::construct(self.config.pow5.clone());
+        let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<L>, WIDTH, RATE>::init(
+            pow5,
+            layouter.namespace(|| "init"),
+        )?;
+        hasher.hash(layouter.namespace(|| "hash"), message)
+    }
+}
@@ -0,0 +1,95 @@
+use halo2_proofs::pasta::Fp;
+
+use super::{hash, PoseidonChip, PoseidonConfig};
+use crate::{names, prelude::*, registry::ExampleCircuit};
+
+/// Number of field elements in the preimage.
+const L: usize = 2;
+
+/// Proves knowledge of a private preimage `[x, y]` whose Poseidon hash is the
+/// public digest.
+#[derive(Default)]
+struct MyCircuit {
+    preimage: [Value<Fp>; L],
+}
+
+impl MyCircuit {
+    pub fn new(preimage: [Fp; L]) -> Self {
+        Self {
+            preimage: preimage.map(Value::known),
+        }
+    }
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = (PoseidonConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (PoseidonChip::<L>::configure(meta, advice), instance)
+    }
+
+    fn synthesize(
+        &self,
+        (config, instance): Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = PoseidonChip::<L>::construct(config);
+
+        let preimage = chip.load(layouter.namespace(|| "preimage"), self.preimage)?;
+        let digest = chip.hash(layouter.namespace(|| "poseidon"), preimage)?;
+
+        layouter
+            .namespace(|| names::OUT)
+            .constrain_instance(digest.cell(), instance, 0)
+    }
+}
+
+/// The circuit on the preimage `[1, 2]`.
+pub fn example_circuit() -> ExampleCircuit {
+    let preimage = [Fp::from(1), Fp::from(2)];
+    ExampleCircuit::new(6, MyCircuit::new(preimage), vec![vec![hash(preimage)]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use rand_core::OsRng;
+
+    fn run(preimage: [Fp; L], digest: Fp) -> MockProver<Fp> {
+        let k = 6;
+
+        MockProver::run(k, &MyCircuit::new(preimage), vec![vec![digest]]).unwrap()
+    }
+
+    #[test]
+    fn poseidon_example() {
+        let preimage = [Fp::random(OsRng), Fp::random(OsRng)];
+        run(preimage, hash(preimage)).assert_satisfied();
+    }
+
+    #[test]
+    fn poseidon_rejects_wrong_preimage() {
+        let preimage = [Fp::from(1), Fp::from(2)];
+        let digest = hash(preimage);
+        assert!(run([Fp::from(2), Fp::from(1)], digest).verify().is_err());
+        assert!(run(preimage, digest + Fp::ONE).verify().is_err());
+    }
+
+    #[test]
+    fn hash_depends_on_length() {
+        // ConstantLength domains separate a message from its zero-padded form.
+        assert_ne!(
+            hash([Fp::from(1), Fp::from(2)]),
+            hash([Fp::from(1), Fp::from(2), Fp::ZERO])
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! Poseidon hashing with the `halo2_gadgets` `Pow5Chip`.
+//!
+//! [`PoseidonChip`] wraps the gadget's sponge behind a `configure`/`hash` pair,
+//! fixed to the `P128Pow5T3` specification over Pasta `Fp`, and [`hash`] is the
+//! matching plain-Rust hash. Other examples reuse both.
+
+mod chip;
+mod example;
+
+pub use chip::{hash, PoseidonChip, PoseidonConfig};
+
+use crate::registry::Example;
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "poseidon",
+    circuit: example::example_circuit,
+}];
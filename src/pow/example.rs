@@ -0,0 +1,353 @@
+use crate::{
+    common::{BooleanChip, BooleanConfig},
+    names,
+    prelude::*,
+    registry::ExampleCircuit,
+};
+use halo2_proofs::pasta::Fp;
+
+/// Proves `y = base^exp` in the field for a `BITS`-bit exponent, by
+/// square-and-multiply over the exponent's bits, most significant first.
+///
+/// Row `i` holds the accumulator `acc_i` and the exponent prefix `e_i` so far,
+/// next to bit `b_i`:
+///
+/// ```text
+/// factor_i  = b_i * base + (1 - b_i)      (select base or 1)
+/// sq_i      = acc_i * acc_i
+/// acc_{i+1} = sq_i * factor_i
+/// e_{i+1}   = 2 * e_i + b_i
+/// ```
+///
+/// with `acc_0 = 1` and `e_0 = 0`. Row `BITS` holds `y` and `exp`. `base`, `exp`
+/// and `y` are public, in that order; `base` is copied into every row. The bits
+/// are witnessed by a [`BooleanChip`] and copied in.
+#[derive(Debug, Clone)]
+struct PowConfig {
+    pub advice: [Column<Advice>; 6],
+    pub boolean: BooleanConfig,
+    pub selector_first: Selector,
+    pub selector_step: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct PowChip<F: PrimeField, const BITS: usize> {
+    config: PowConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField, const BITS: usize> PowChip<F, BITS> {
+    pub fn construct(config: PowConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 6],
+        bool_column: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> PowConfig {
+        let [col_acc, col_e, col_bit, col_base, col_factor, col_sq] = advice;
+        let boolean = BooleanChip::configure(meta, bool_column);
+        let selector_first = meta.selector();
+        let selector_step = meta.selector();
+        meta.enable_equality(col_acc);
+        meta.enable_equality(col_e);
+        meta.enable_equality(col_bit);
+        meta.enable_equality(col_base);
+        meta.enable_equality(instance);
+
+        meta.create_gate(names::FIRST, |meta| {
+            let s = meta.query_selector(selector_first);
+            let acc = meta.query_advice(col_acc, Rotation::cur());
+            let e = meta.query_advice(col_e, Rotation::cur());
+            vec![s.clone() * (acc - Expression::Constant(F::ONE)), s * e]
+        });
+
+        meta.create_gate("select", |meta| {
+            //
+            // col_bit | col_base | col_factor | selector
+            //    b        base       factor        s
+            //
+            let s = meta.query_selector(selector_step);
+            let b = meta.query_advice(col_bit, Rotation::cur());
+            let base = meta.query_advice(col_base, Rotation::cur());
+            let factor = meta.query_advice(col_factor, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+            vec![s * (factor - (b.clone() * base + (one - b)))]
+        });
+
+        meta.create_gate("square and multiply", |meta| {
+            //
+            // col_acc  | col_e  | col_bit | col_factor | col_sq | selector
+            //   acc        e        b         factor       sq        s
+            //   acc_next   e_next
+            //
+            let s = meta.query_selector(selector_step);
+            let acc = meta.query_advice(col_acc, Rotation::cur());
+            let e = meta.query_advice(col_e, Rotation::cur());
+            let b = meta.query_advice(col_bit, Rotation::cur());
+            let factor = meta.query_advice(col_factor, Rotation::cur());
+            let sq = meta.query_advice(col_sq, Rotation::cur());
+            let acc_next = meta.query_advice(col_acc, Rotation::next());
+            let e_next = meta.query_advice(col_e, Rotation::next());
+            vec![
+                s.clone() * (sq.clone() - acc.clone() * acc),
+                s.clone() * (acc_next - sq * factor),
+                s * (e.clone() + e + b - e_next),
+            ]
+        });
+
+        PowConfig {
+            advice,
+            boolean,
+            selector_first,
+            selector_step,
+            instance,
+        }
+    }
+
+    /// Assigns the square-and-multiply table for `bits`, most significant first,
+    /// with `base` read from instance row 0. Returns the cells holding `exp` and
+    /// `y`.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[Value<F>; BITS],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let [col_acc, col_e, col_bit, col_base, col_factor, col_sq] = self.config.advice;
+
+        let boolean = BooleanChip::construct(self.config.boolean.clone());
+        let bits = bits
+            .iter()
+            .map(|bit| boolean.assign_bool(layouter.namespace(|| "bit"), *bit))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        layouter.assign_region(
+            || "square and multiply",
+            |mut region| {
+                self.config.selector_first.enable(&mut region, 0)?;
+
+                let mut acc = Value::known(F::ONE);
+                let mut e = Value::known(F::ZERO);
+                let mut acc_cell = region.assign_advice(|| "acc", col_acc, 0, || acc)?;
+                let mut e_cell = region.assign_advice(|| "e", col_e, 0, || e)?;
+
+                for (row, bit) in bits.iter().enumerate() {
+                    self.config.selector_step.enable(&mut region, row)?;
+                    let bit = bit.copy_advice(|| "bit", &mut region, col_bit, row)?;
+                    let bit = bit.value().copied();
+                    let base = region.assign_advice_from_instance(
+                        || "base",
+                        self.config.instance,
+                        BASE_ROW,
+                        col_base,
+                        row,
+                    )?;
+                    let base = base.value().copied();
+
+                    let factor = bit.zip(base).map(|(b, base)| b * base + F::ONE - b);
+                    region.assign_advice(|| "factor", col_factor, row, || factor)?;
+                    let sq = acc.map(|acc| acc.square());
+                    region.assign_advice(|| "sq", col_sq, row, || sq)?;
+
+                    acc = sq * factor;
+                    e = e.map(|e| e.double()) + bit;
+                    acc_cell = region.assign_advice(|| "acc", col_acc, row + 1, || acc)?;
+                    e_cell = region.assign_advice(|| "e", col_e, row + 1, || e)?;
+                }
+
+                Ok((e_cell, acc_cell))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference: `base^exp` in the field.
+pub fn reference<F: Field>(base: F, exp: u64) -> F {
+    base.pow_vartime([exp])
+}
+
+struct MyCircuit<F, const BITS: usize> {
+    bits: [Value<F>; BITS],
+}
+
+impl<F: PrimeField, const BITS: usize> MyCircuit<F, BITS> {
+    /// The circuit for the low `BITS` bits of `exp`.
+    pub fn new(exp: Value<u64>) -> Self {
+        Self {
+            bits: std::array::from_fn(|i| exp.map(|exp| F::from((exp >> (BITS - 1 - i)) & 1))),
+        }
+    }
+}
+
+impl<F: PrimeField, const BITS: usize> Circuit<F> for MyCircuit<F, BITS> {
+    type Config = PowConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            bits: [Value::unknown(); BITS],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let bool_column = meta.advice_column();
+        let instance = meta.instance_column();
+        PowChip::<F, BITS>::configure(meta, advice, bool_column, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowChip::<F, BITS>::construct(config);
+
+        let (exp, y) = chip.assign(layouter.namespace(|| "pow"), &self.bits)?;
+
+        chip.expose_public(layouter.namespace(|| "exp"), &exp, EXP_ROW)?;
+        chip.expose_public(layouter.namespace(|| "y"), &y, Y_ROW)?;
+
+        Ok(())
+    }
+}
+
+const BASE_ROW: usize = 0;
+const EXP_ROW: usize = 1;
+const Y_ROW: usize = 2;
+
+/// Places the public inputs by name, like [`crate::fibonacci::FibonacciInstances`].
+/// The output defaults to `base^exp`.
+pub struct PowInstances<F> {
+    pub base: F,
+    pub exp: u64,
+    pub y: F,
+}
+
+impl<F: PrimeField> PowInstances<F> {
+    pub fn new(base: F, exp: u64) -> Self {
+        Self {
+            base,
+            exp,
+            y: reference(base, exp),
+        }
+    }
+
+    /// Exposes `y` as the output, whether or not it is `base^exp`.
+    #[cfg(test)]
+    pub fn expect_output(self, y: F) -> Self {
+        Self { y, ..self }
+    }
+
+    pub fn build(self) -> Vec<Vec<F>> {
+        let mut column = vec![F::ZERO; 3];
+        column[BASE_ROW] = self.base;
+        column[EXP_ROW] = F::from(self.exp);
+        column[Y_ROW] = self.y;
+        vec![column]
+    }
+}
+
+/// The circuit on `3^5` with an 8-bit exponent.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp, 8>::new(Value::known(5));
+    let instances = PowInstances::new(Fp::from(3), 5).build();
+    ExampleCircuit::new(5, circuit, instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn run<const BITS: usize>(base: u64, exp: u64, y: Fp) -> MockProver<Fp> {
+        let k = 5;
+
+        let circuit = MyCircuit::<Fp, BITS>::new(Value::known(exp));
+        let instances = PowInstances::new(Fp::from(base), exp)
+            .expect_output(y)
+            .build();
+        MockProver::run(k, &circuit, instances).unwrap()
+    }
+
+    #[test]
+    fn pow_example() {
+        let y = reference(Fp::from(3), 5);
+        assert_eq!(y, Fp::from(243));
+        run::<8>(3, 5, y).assert_satisfied();
+    }
+
+    #[test]
+    fn pow_matches_reference() {
+        for (base, exp) in [(2, 10), (7, 1), (5, 255), (0, 3), (0, 0)] {
+            let y = reference(Fp::from(base), exp);
+            run::<8>(base, exp, y).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn pow_zero_exponent() {
+        assert_eq!(reference(Fp::from(3), 0), Fp::ONE);
+        run::<8>(3, 0, Fp::ONE).assert_satisfied();
+        assert!(run::<8>(3, 0, Fp::ZERO).verify().is_err());
+    }
+
+    #[test]
+    fn pow_rejects_wrong_result() {
+        assert!(run::<8>(3, 5, Fp::from(244)).verify().is_err());
+        // 3^4, as if the low bit were dropped.
+        assert!(run::<8>(3, 5, Fp::from(81)).verify().is_err());
+    }
+
+    #[test]
+    fn pow_rejects_wrong_exponent() {
+        // The bits compute 3^5, but the public exponent claims 6.
+        let circuit = MyCircuit::<Fp, 8>::new(Value::known(5));
+        let instance = vec![Fp::from(3), Fp::from(6), Fp::from(243)];
+        let prover = MockProver::run(5, &circuit, vec![instance]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn pow_rejects_non_boolean_bits() {
+        // A "bit" of 2 selects 2*base - 1 = 5 instead of base, so 3^1 becomes 5.
+        let bits = [0, 0, 0, 2].map(|b| Value::known(Fp::from(b)));
+        let circuit = MyCircuit::<Fp, 4> { bits };
+        let instance = vec![Fp::from(3), Fp::from(2), Fp::from(5)];
+        let prover = MockProver::run(5, &circuit, vec![instance]).unwrap();
+        assert_constraint_fails(&prover, names::BOOL);
+    }
+
+    #[test]
+    fn gate_degree() {
+        assert_eq!(
+            max_gate_degree(|meta| {
+                MyCircuit::<Fp, 8>::configure(meta);
+            }),
+            3
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Everything an example usually needs, in one import.
+//!
+//! ```
+//! use halo2examples::prelude::*;
+//!
+//! #[derive(Debug, Clone)]
+//! struct DoubleConfig {
+//!     advice: Column<Advice>,
+//!     selector: Selector,
+//! }
+//!
+//! fn configure<F: Field>(meta: &mut ConstraintSystem<F>) -> DoubleConfig {
+//!     let advice = meta.advice_column();
+//!     let selector = meta.selector();
+//!     meta.create_gate("double", |meta| {
+//!         let s = meta.query_selector(selector);
+//!         let x = meta.query_advice(advice, Rotation::cur());
+//!         let y = meta.query_advice(advice, Rotation::next());
+//!         vec![s * (x.clone() + x - y)]
+//!     });
+//!     DoubleConfig { advice, selector }
+//! }
+//!
+//! let mut meta = ConstraintSystem::<halo2_proofs::pasta::Fp>::default();
+//! let config = configure(&mut meta);
+//! assert_eq!(meta.degree(), 3);
+//! # let _ = (config.advice, config.selector);
+//! ```
+
+pub use std::marker::PhantomData;
+
+pub use ff::{Field, PrimeField};
+pub use halo2_proofs::{circuit::*, plonk::*, poly::Rotation};
+
+pub use crate::common::{
+    check_instance_len, check_rows, load_instances, min_k_for_circuit, to_u64, usable_rows,
+};
+
+#[cfg(test)]
+pub use crate::testing::{
+    assert_all_satisfied, assert_constraint_fails, assert_verify_fails_with,
+    fuzz_witness_mutations, max_gate_degree, VerifyFailureKind,
+};
@@ -0,0 +1,398 @@
+//! Real proofs, as opposed to `MockProver` runs: IPA commitments over the Pasta
+//! curves, with a Blake2b transcript unless another [`TranscriptScheme`] is
+//! chosen.
+//!
+//! Key generation dominates the cost of a single proof, so keys are memoized in
+//! a [`KeyCache`] and shared by every proof of the same circuit shape at the same
+//! `k`.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use halo2_proofs::{
+    pasta::{EqAffine, Fp},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, Error, ProvingKey,
+        SingleVerifier, VerifyingKey,
+    },
+    poly::commitment::Params,
+};
+use rand_core::OsRng;
+
+mod store;
+mod transcript;
+mod vk;
+
+pub use store::ParamsStore;
+pub use transcript::{Blake2b, Keccak256, KeccakRead, KeccakWrite, TranscriptScheme};
+pub use vk::{deserialize_vk, serialize_vk, verify_only, VkError};
+
+/// Parameters and keys for one circuit at one size.
+#[derive(Debug)]
+pub struct Keys {
+    pub params: Params<EqAffine>,
+    pub pk: ProvingKey<EqAffine>,
+}
+
+impl Keys {
+    /// Runs key generation for `circuit` at size `2^k`. The circuit's witness
+    /// values are not used.
+    pub fn generate<C: Circuit<Fp>>(k: u32, circuit: &C) -> Result<Self, Error> {
+        let params = Params::new(k);
+        let vk = keygen_vk(&params, circuit)?;
+        let pk = keygen_pk(&params, vk, circuit)?;
+        Ok(Self { params, pk })
+    }
+
+    pub fn vk(&self) -> &VerifyingKey<EqAffine> {
+        self.pk.get_vk()
+    }
+}
+
+/// Memoizes [`Keys`] by `k` and circuit shape.
+///
+/// The shape is the verifying key's fingerprint (see [`serialize_vk`]): the
+/// constraint system, the fixed-column assignment and the selector placement.
+/// Two circuits of one type whose layout depends on their fields, such as the
+/// number of rows they assign, therefore get separate keys. Finding the shape
+/// runs `keygen_vk` on every lookup; a hit saves parameter generation and
+/// `keygen_pk`.
+///
+/// Lookups and key generation run under one lock, so concurrent callers never
+/// generate the same keys twice.
+#[derive(Debug, Default)]
+pub struct KeyCache {
+    inner: Mutex<CacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    params: HashMap<u32, Params<EqAffine>>,
+    keys: HashMap<(u32, [u8; 32]), Arc<Keys>>,
+}
+
+impl KeyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The keys for circuits shaped like `circuit` at size `2^k`, generated on
+    /// first use.
+    pub fn get_or_generate<C: Circuit<Fp>>(&self, k: u32, circuit: &C) -> Result<Arc<Keys>, Error> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let CacheInner { params, keys } = &mut *inner;
+        let params = params.entry(k).or_insert_with(|| Params::new(k));
+        let vk = keygen_vk(params, circuit)?;
+        let shape = (k, vk::fingerprint(&vk));
+        if let Some(cached) = keys.get(&shape) {
+            return Ok(cached.clone());
+        }
+        let pk = keygen_pk(params, vk, circuit)?;
+        let generated = Arc::new(Keys {
+            params: params.clone(),
+            pk,
+        });
+        keys.insert(shape, generated.clone());
+        Ok(generated)
+    }
+
+    /// Number of cached key sets.
+    pub fn len(&self) -> usize {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A cache shared by the whole process, e.g. by every test in a test binary.
+pub fn key_cache() -> &'static KeyCache {
+    static CACHE: OnceLock<KeyCache> = OnceLock::new();
+    CACHE.get_or_init(KeyCache::new)
+}
+
+/// Proves `circuit` with one value vector per instance column.
+pub fn prove<C: Circuit<Fp>>(
+    keys: &Keys,
+    circuit: C,
+    instances: &[&[Fp]],
+) -> Result<Vec<u8>, Error> {
+    prove_with::<Blake2b, C>(keys, circuit, instances)
+}
+
+/// Verifies a proof made by [`prove`] against the same instance values.
+pub fn verify(keys: &Keys, proof: &[u8], instances: &[&[Fp]]) -> Result<(), Error> {
+    verify_with::<Blake2b>(keys, proof, instances)
+}
+
+/// Proves each `(circuit, instances)` pair with [`prove`], sharing `keys`.
+///
+/// With the `parallel` feature the proofs run on the rayon thread pool;
+/// otherwise they run one after another. Either way the results come back in
+/// input order, one per pair.
+pub fn prove_many<C: Circuit<Fp> + Send>(
+    keys: &Keys,
+    circuits_and_instances: Vec<(C, Vec<Vec<Fp>>)>,
+) -> Vec<Result<Vec<u8>, Error>> {
+    let prove_one = |(circuit, instances): (C, Vec<Vec<Fp>>)| {
+        let instances: Vec<&[Fp]> = instances.iter().map(Vec::as_slice).collect();
+        prove(keys, circuit, &instances)
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        circuits_and_instances
+            .into_par_iter()
+            .map(prove_one)
+            .collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        circuits_and_instances.into_iter().map(prove_one).collect()
+    }
+}
+
+/// [`prove`] with the transcript `T`.
+pub fn prove_with<T: TranscriptScheme, C: Circuit<Fp>>(
+    keys: &Keys,
+    circuit: C,
+    instances: &[&[Fp]],
+) -> Result<Vec<u8>, Error> {
+    let mut transcript = T::writer();
+    create_proof(
+        &keys.params,
+        &keys.pk,
+        &[circuit],
+        &[instances],
+        OsRng,
+        &mut transcript,
+    )?;
+    Ok(T::finalize(transcript))
+}
+
+/// [`verify`] for a proof made by [`prove_with`] with the same transcript `T`.
+pub fn verify_with<T: TranscriptScheme>(
+    keys: &Keys,
+    proof: &[u8],
+    instances: &[&[Fp]],
+) -> Result<(), Error> {
+    verify_with_vk::<T>(&keys.params, keys.vk(), proof, instances)
+}
+
+/// [`verify_with`] from the parameters and verifying key alone.
+fn verify_with_vk<T: TranscriptScheme>(
+    params: &Params<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &[u8],
+    instances: &[&[Fp]],
+) -> Result<(), Error> {
+    let strategy = SingleVerifier::new(params);
+    let mut transcript = T::reader(proof);
+    verify_proof(params, vk, strategy, &[instances], &mut transcript)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::{fs, thread, time::Instant};
+
+    /// Proves knowledge of the square root of a public input.
+    #[derive(Default)]
+    struct SquareCircuit(Value<Fp>);
+
+    impl Circuit<Fp> for SquareCircuit {
+        type Config = (Column<Advice>, Selector, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = meta.advice_column();
+            let selector = meta.selector();
+            let instance = meta.instance_column();
+            meta.enable_equality(advice);
+            meta.enable_equality(instance);
+            meta.create_gate("square", |meta| {
+                let s = meta.query_selector(selector);
+                let x = meta.query_advice(advice, Rotation::cur());
+                let y = meta.query_advice(advice, Rotation::next());
+                vec![s * (x.clone() * x - y)]
+            });
+            (advice, selector, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (advice, selector, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let y = layouter.assign_region(
+                || "square",
+                |mut region| {
+                    selector.enable(&mut region, 0)?;
+                    region.assign_advice(|| "x", advice, 0, || self.0)?;
+                    region.assign_advice(|| "y", advice, 1, || self.0.map(|x| x.square()))
+                },
+            )?;
+            layouter.constrain_instance(y.cell(), instance, 0)
+        }
+    }
+
+    fn square(x: u64) -> SquareCircuit {
+        SquareCircuit(Value::known(Fp::from(x)))
+    }
+
+    #[test]
+    fn prove_and_verify() {
+        let keys = Keys::generate(4, &SquareCircuit::default()).unwrap();
+
+        let proof = prove(&keys, square(3), &[&[Fp::from(9)]]).unwrap();
+        verify(&keys, &proof, &[&[Fp::from(9)]]).unwrap();
+        assert!(verify(&keys, &proof, &[&[Fp::from(10)]]).is_err());
+    }
+
+    #[test]
+    fn key_cache_reuses_keys() {
+        let cache = KeyCache::new();
+
+        let start = Instant::now();
+        let first = cache.get_or_generate(4, &SquareCircuit::default()).unwrap();
+        let keygen = start.elapsed();
+
+        let start = Instant::now();
+        let second = cache.get_or_generate(4, &SquareCircuit::default()).unwrap();
+        let cached = start.elapsed();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(cached < keygen, "cached {:?}, keygen {:?}", cached, keygen);
+
+        // A different size is a different key set.
+        cache.get_or_generate(5, &SquareCircuit::default()).unwrap();
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn key_cache_is_shared_across_threads() {
+        let cache = KeyCache::new();
+
+        thread::scope(|scope| {
+            for x in 1..=4u64 {
+                let cache = &cache;
+                scope.spawn(move || {
+                    let keys = cache.get_or_generate(4, &SquareCircuit::default()).unwrap();
+                    let instance = [Fp::from(x * x)];
+                    let proof = prove(&keys, square(x), &[&instance]).unwrap();
+                    verify(&keys, &proof, &[&instance]).unwrap();
+                });
+            }
+        });
+
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn vk_bytes_reject_bad_input() {
+        let keys = key_cache()
+            .get_or_generate(4, &SquareCircuit::default())
+            .unwrap();
+        let bytes = serialize_vk(keys.vk());
+        deserialize_vk(&keys.params, &SquareCircuit::default(), &bytes).unwrap();
+
+        let truncated = deserialize_vk(&keys.params, &SquareCircuit::default(), &bytes[..10]);
+        assert!(matches!(truncated, Err(VkError::Truncated)));
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 1;
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        let mut other_key = bytes.clone();
+        *other_key.last_mut().unwrap() ^= 1;
+        for (bytes, expected) in [
+            (bad_magic, "not a verifying key fingerprint"),
+            (trailing, "trailing bytes after verifying key fingerprint"),
+            (other_key, "verifying key does not match the circuit"),
+        ] {
+            let result = deserialize_vk(&keys.params, &SquareCircuit::default(), &bytes);
+            assert_eq!(result.unwrap_err().to_string(), expected);
+        }
+
+        // Same circuit, but keys for another size.
+        let params = Params::new(5);
+        assert!(matches!(
+            deserialize_vk(&params, &SquareCircuit::default(), &bytes),
+            Err(VkError::Mismatch)
+        ));
+    }
+
+    #[test]
+    fn prove_many_proves_each_pair() {
+        let keys = key_cache()
+            .get_or_generate(4, &SquareCircuit::default())
+            .unwrap();
+
+        let jobs = (1..=8u64)
+            .map(|x| (square(x), vec![vec![Fp::from(x * x)]]))
+            .collect();
+        let proofs = prove_many(&keys, jobs);
+
+        assert_eq!(proofs.len(), 8);
+        for (x, proof) in (1..=8u64).zip(proofs) {
+            let instance = [Fp::from(x * x)];
+            verify(&keys, &proof.unwrap(), &[&instance]).unwrap();
+        }
+    }
+
+    #[test]
+    fn params_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("params-store-{}", std::process::id()));
+        let store = ParamsStore::new(&dir).unwrap();
+
+        assert!(store
+            .regenerate_keys("square", 4, &SquareCircuit::default())
+            .is_err());
+        let keys = store
+            .load_or_generate("square", 4, &SquareCircuit::default())
+            .unwrap();
+        let proof = prove(&keys, square(3), &[&[Fp::from(9)]]).unwrap();
+
+        // A later run rebuilds the keys on the stored parameters and accepts the
+        // old proof.
+        let keys = store
+            .regenerate_keys("square", 4, &SquareCircuit::default())
+            .unwrap();
+        verify(&keys, &proof, &[&[Fp::from(9)]]).unwrap();
+
+        // Under the same name, a changed circuit makes the entry stale.
+        type Fib = crate::fibonacci::example2::MyCircuit<Fp>;
+        store
+            .write_entry("fib", &Keys::generate(4, &Fib::new(9)).unwrap())
+            .unwrap();
+        store.regenerate_keys("fib", 4, &Fib::new(9)).unwrap();
+        assert!(matches!(
+            store.regenerate_keys("fib", 4, &Fib::new(8)),
+            Err(VkError::Mismatch)
+        ));
+        store.load_or_generate("fib", 4, &Fib::new(8)).unwrap();
+        store.regenerate_keys("fib", 4, &Fib::new(8)).unwrap();
+        assert!(store.regenerate_keys("fib", 4, &Fib::new(9)).is_err());
+
+        // Names that include the length keep both entries.
+        store.load_or_generate("fib-n9", 4, &Fib::new(9)).unwrap();
+        store.load_or_generate("fib-n8", 4, &Fib::new(8)).unwrap();
+        store.regenerate_keys("fib-n9", 4, &Fib::new(9)).unwrap();
+        store.regenerate_keys("fib-n8", 4, &Fib::new(8)).unwrap();
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
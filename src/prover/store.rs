@@ -0,0 +1,141 @@
+//! Parameters on disk, in a cache directory shared between runs.
+//!
+//! halo2_proofs 0.3 can write `Params`, but not proving or verifying keys (see
+//! [`super::vk`]), so the keys themselves cannot be cached. A [`ParamsStore`]
+//! keeps, per entry name and `k`:
+//!
+//! - `params-k{k}.bin`: the IPA parameters, shared by every circuit at that size;
+//! - `{name}-k{k}.vk`: the verifying key's fingerprint from [`serialize_vk`].
+//!
+//! Loading an entry reads the parameters and runs `keygen_vk` and `keygen_pk`
+//! again, so it only saves parameter generation. On the way it checks the
+//! regenerated verifying key against the stored fingerprint: a circuit that
+//! changed since the entry was written no longer matches, so the entry is stale
+//! and [`ParamsStore::load_or_generate`] replaces it. The fingerprint file starts
+//! with a format version, so a new format invalidates old entries in the same
+//! way.
+//!
+//! The entry name has to tell apart circuits of different shapes. One whose
+//! layout depends on its inputs, such as the number of Fibonacci terms, needs
+//! those inputs in the name, or its entries keep replacing each other.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use halo2_proofs::{
+    pasta::{EqAffine, Fp},
+    plonk::{keygen_pk, Circuit, Error, VerifyingKey},
+    poly::commitment::Params,
+};
+
+use super::{deserialize_vk, serialize_vk, Keys, VkError};
+
+/// A directory of cached parameters and verifying key fingerprints.
+#[derive(Debug, Clone)]
+pub struct ParamsStore {
+    dir: PathBuf,
+}
+
+impl ParamsStore {
+    /// Opens the store in `dir`, creating the directory if needed.
+    pub fn new(dir: impl AsRef<Path>) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    fn params_path(&self, k: u32) -> PathBuf {
+        self.dir.join(format!("params-k{}.bin", k))
+    }
+
+    fn vk_path(&self, name: &str, k: u32) -> PathBuf {
+        // Registry names such as `fibonacci::example1` are not file names.
+        self.dir
+            .join(format!("{}-k{}.vk", name.replace("::", "-"), k))
+    }
+
+    pub fn write_params(&self, k: u32, params: &Params<EqAffine>) -> io::Result<()> {
+        let mut bytes = vec![];
+        params.write(&mut bytes)?;
+        fs::write(self.params_path(k), bytes)
+    }
+
+    pub fn read_params(&self, k: u32) -> io::Result<Params<EqAffine>> {
+        let bytes = fs::read(self.params_path(k))?;
+        let params = Params::read(&mut bytes.as_slice())?;
+        if params.k() != k {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} holds parameters for k = {}",
+                    self.params_path(k).display(),
+                    params.k()
+                ),
+            ));
+        }
+        Ok(params)
+    }
+
+    pub fn write_fingerprint(
+        &self,
+        name: &str,
+        k: u32,
+        vk: &VerifyingKey<EqAffine>,
+    ) -> io::Result<()> {
+        fs::write(self.vk_path(name, k), serialize_vk(vk))
+    }
+
+    /// Regenerates the verifying key of `circuit` and checks it against the
+    /// stored fingerprint, failing with `VkError::Io` if the entry is missing
+    /// and `VkError::Mismatch` if it is stale.
+    pub fn regenerate_vk<C: Circuit<Fp>>(
+        &self,
+        name: &str,
+        params: &Params<EqAffine>,
+        circuit: &C,
+    ) -> Result<VerifyingKey<EqAffine>, VkError> {
+        let bytes = fs::read(self.vk_path(name, params.k()))?;
+        deserialize_vk(params, circuit, &bytes)
+    }
+
+    /// Writes what is needed to rebuild `keys`: the parameters and the verifying
+    /// key fingerprint.
+    pub fn write_entry(&self, name: &str, keys: &Keys) -> io::Result<()> {
+        let k = keys.params.k();
+        self.write_params(k, &keys.params)?;
+        self.write_fingerprint(name, k, keys.vk())
+    }
+
+    /// Runs key generation for `circuit` on the stored parameters, checking the
+    /// verifying key against the fingerprint from [`Self::write_entry`].
+    pub fn regenerate_keys<C: Circuit<Fp>>(
+        &self,
+        name: &str,
+        k: u32,
+        circuit: &C,
+    ) -> Result<Keys, VkError> {
+        let params = self.read_params(k)?;
+        let vk = self.regenerate_vk(name, &params, circuit)?;
+        let pk = keygen_pk(&params, vk, circuit)?;
+        Ok(Keys { params, pk })
+    }
+
+    /// [`Self::regenerate_keys`], falling back to [`Keys::generate`] and writing
+    /// a new entry if the entry is missing or stale.
+    pub fn load_or_generate<C: Circuit<Fp>>(
+        &self,
+        name: &str,
+        k: u32,
+        circuit: &C,
+    ) -> Result<Keys, Error> {
+        if let Ok(keys) = self.regenerate_keys(name, k, circuit) {
+            return Ok(keys);
+        }
+        let keys = Keys::generate(k, circuit)?;
+        self.write_entry(name, &keys).map_err(Error::Transcript)?;
+        Ok(keys)
+    }
+}
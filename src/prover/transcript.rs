@@ -0,0 +1,225 @@
+//! Fiat-Shamir transcripts the prover can be run with.
+//!
+//! halo2_proofs only ships Blake2b. [`Keccak256`] hashes the same messages with
+//! Keccak-256 instead, which is what EVM verifiers can recompute cheaply.
+
+use std::io::{self, Read, Write};
+
+use ff::{FromUniformBytes, PrimeField};
+use halo2_proofs::{
+    arithmetic::{Coordinates, CurveAffine},
+    pasta::EqAffine,
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, EncodedChallenge, Transcript, TranscriptRead,
+        TranscriptWrite,
+    },
+};
+use sha3::{Digest, Keccak256 as KeccakHasher};
+
+/// A choice of transcript for [`super::prove_with`] and [`super::verify_with`].
+pub trait TranscriptScheme {
+    type Writer: TranscriptWrite<EqAffine, Challenge255<EqAffine>>;
+    type Reader<'a>: TranscriptRead<EqAffine, Challenge255<EqAffine>>;
+
+    fn writer() -> Self::Writer;
+
+    /// The proof bytes written so far.
+    fn finalize(writer: Self::Writer) -> Vec<u8>;
+
+    fn reader(proof: &[u8]) -> Self::Reader<'_>;
+}
+
+/// The halo2_proofs Blake2b transcript. This is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake2b;
+
+impl TranscriptScheme for Blake2b {
+    type Writer = Blake2bWrite<Vec<u8>, EqAffine, Challenge255<EqAffine>>;
+    type Reader<'a> = Blake2bRead<&'a [u8], EqAffine, Challenge255<EqAffine>>;
+
+    fn writer() -> Self::Writer {
+        Blake2bWrite::init(vec![])
+    }
+
+    fn finalize(writer: Self::Writer) -> Vec<u8> {
+        writer.finalize()
+    }
+
+    fn reader(proof: &[u8]) -> Self::Reader<'_> {
+        Blake2bRead::init(proof)
+    }
+}
+
+/// A Keccak-256 transcript, for verifiers on the EVM.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Keccak256;
+
+impl TranscriptScheme for Keccak256 {
+    type Writer = KeccakWrite<Vec<u8>>;
+    type Reader<'a> = KeccakRead<&'a [u8]>;
+
+    fn writer() -> Self::Writer {
+        KeccakWrite::init(vec![])
+    }
+
+    fn finalize(writer: Self::Writer) -> Vec<u8> {
+        writer.writer
+    }
+
+    fn reader(proof: &[u8]) -> Self::Reader<'_> {
+        KeccakRead::init(proof)
+    }
+}
+
+/// Prefix to a prover's message soliciting a challenge.
+const KECCAK_PREFIX_CHALLENGE: u8 = 0;
+
+/// Prefix to a prover's message containing a curve point.
+const KECCAK_PREFIX_POINT: u8 = 1;
+
+/// Prefix to a prover's message containing a scalar.
+const KECCAK_PREFIX_SCALAR: u8 = 2;
+
+/// The hash state shared by the reading and writing sides. Messages are
+/// absorbed exactly as the Blake2b transcript absorbs them.
+#[derive(Debug, Clone)]
+struct KeccakState(KeccakHasher);
+
+impl KeccakState {
+    fn new() -> Self {
+        let mut state = KeccakHasher::new();
+        state.update(b"Halo2-Transcript");
+        Self(state)
+    }
+
+    /// Squeezes a 256-bit digest, reduced into the scalar field.
+    fn squeeze_challenge<C: CurveAffine>(&mut self) -> Challenge255<C>
+    where
+        C::Scalar: FromUniformBytes<64>,
+    {
+        self.0.update([KECCAK_PREFIX_CHALLENGE]);
+        let mut wide = [0u8; 64];
+        wide[..32].copy_from_slice(&self.0.clone().finalize());
+        Challenge255::new(&wide)
+    }
+
+    fn common_point<C: CurveAffine>(&mut self, point: C) -> io::Result<()> {
+        self.0.update([KECCAK_PREFIX_POINT]);
+        let coords: Coordinates<C> = Option::from(point.coordinates())
+            .ok_or_else(|| io::Error::other("cannot write points at infinity to the transcript"))?;
+        self.0.update(coords.x().to_repr().as_ref());
+        self.0.update(coords.y().to_repr().as_ref());
+        Ok(())
+    }
+
+    fn common_scalar<F: PrimeField>(&mut self, scalar: F) {
+        self.0.update([KECCAK_PREFIX_SCALAR]);
+        self.0.update(scalar.to_repr().as_ref());
+    }
+}
+
+/// Reads a proof made with [`KeccakWrite`].
+#[derive(Debug, Clone)]
+pub struct KeccakRead<R: Read> {
+    state: KeccakState,
+    reader: R,
+}
+
+impl<R: Read> KeccakRead<R> {
+    pub fn init(reader: R) -> Self {
+        Self {
+            state: KeccakState::new(),
+            reader,
+        }
+    }
+}
+
+impl<R: Read, C: CurveAffine> Transcript<C, Challenge255<C>> for KeccakRead<R>
+where
+    C::Scalar: FromUniformBytes<64>,
+{
+    fn squeeze_challenge(&mut self) -> Challenge255<C> {
+        self.state.squeeze_challenge()
+    }
+
+    fn common_point(&mut self, point: C) -> io::Result<()> {
+        self.state.common_point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.state.common_scalar(scalar);
+        Ok(())
+    }
+}
+
+impl<R: Read, C: CurveAffine> TranscriptRead<C, Challenge255<C>> for KeccakRead<R>
+where
+    C::Scalar: FromUniformBytes<64>,
+{
+    fn read_point(&mut self) -> io::Result<C> {
+        let mut compressed = C::Repr::default();
+        self.reader.read_exact(compressed.as_mut())?;
+        let point: C = Option::from(C::from_bytes(&compressed))
+            .ok_or_else(|| io::Error::other("invalid point encoding in proof"))?;
+        self.state.common_point(point)?;
+        Ok(point)
+    }
+
+    fn read_scalar(&mut self) -> io::Result<C::Scalar> {
+        let mut data = <C::Scalar as PrimeField>::Repr::default();
+        self.reader.read_exact(data.as_mut())?;
+        let scalar: C::Scalar = Option::from(C::Scalar::from_repr(data))
+            .ok_or_else(|| io::Error::other("invalid field element encoding in proof"))?;
+        self.state.common_scalar(scalar);
+        Ok(scalar)
+    }
+}
+
+/// Writes a proof with a Keccak-256 transcript.
+#[derive(Debug, Clone)]
+pub struct KeccakWrite<W: Write> {
+    state: KeccakState,
+    writer: W,
+}
+
+impl<W: Write> KeccakWrite<W> {
+    pub fn init(writer: W) -> Self {
+        Self {
+            state: KeccakState::new(),
+            writer,
+        }
+    }
+}
+
+impl<W: Write, C: CurveAffine> Transcript<C, Challenge255<C>> for KeccakWrite<W>
+where
+    C::Scalar: FromUniformBytes<64>,
+{
+    fn squeeze_challenge(&mut self) -> Challenge255<C> {
+        self.state.squeeze_challenge()
+    }
+
+    fn common_point(&mut self, point: C) -> io::Result<()> {
+        self.state.common_point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.state.common_scalar(scalar);
+        Ok(())
+    }
+}
+
+impl<W: Write, C: CurveAffine> TranscriptWrite<C, Challenge255<C>> for KeccakWrite<W>
+where
+    C::Scalar: FromUniformBytes<64>,
+{
+    fn write_point(&mut self, point: C) -> io::Result<()> {
+        self.state.common_point(point)?;
+        self.writer.write_all(point.to_bytes().as_ref())
+    }
+
+    fn write_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.state.common_scalar(scalar);
+        self.writer.write_all(scalar.to_repr().as_ref())
+    }
+}
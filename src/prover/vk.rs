@@ -0,0 +1,122 @@
+//! Verifying keys as bytes, for a verifier that only holds the parameters.
+//!
+//! halo2_proofs 0.3 cannot read a `VerifyingKey` back from bytes: the key embeds
+//! the circuit's constraint system, which only the circuit's `configure` can
+//! rebuild. The bytes here are therefore not the key but a fingerprint of it: a
+//! Keccak-256 digest of the `Debug` output of its pinned form.
+//! [`deserialize_vk`] regenerates the key from the circuit with `keygen_vk`
+//! (no witnesses, no proving key) and accepts it only if it matches the
+//! fingerprint, so a verifier cannot be handed a key for another circuit,
+//! another `k`, or another fixed-column assignment.
+//!
+//! `Debug` output is not a stable format. A fingerprint is only comparable with
+//! one computed by the same halo2_proofs version.
+
+use std::{fmt, io};
+
+use halo2_proofs::{
+    pasta::{EqAffine, Fp},
+    plonk::{keygen_vk, Circuit, Error, VerifyingKey},
+    poly::commitment::Params,
+};
+use sha3::{Digest, Keccak256};
+
+use super::{verify_with_vk, Blake2b};
+
+/// Leading bytes of a serialized verifying key, including a format version.
+const MAGIC: &[u8; 4] = b"VK01";
+
+pub(super) fn fingerprint(vk: &VerifyingKey<EqAffine>) -> [u8; 32] {
+    Keccak256::digest(format!("{:?}", vk.pinned()).as_bytes()).into()
+}
+
+/// Why a verifying key fingerprint was rejected.
+#[derive(Debug)]
+pub enum VkError {
+    Io(io::Error),
+    /// Shorter than the header and digest.
+    Truncated,
+    /// Does not start with the `VK01` header.
+    BadMagic,
+    TrailingBytes,
+    /// The fingerprint is of another key: another circuit, `k` or fixed-column
+    /// assignment.
+    Mismatch,
+    /// Regenerating the key, or verifying with it, failed.
+    Plonk(Error),
+}
+
+impl fmt::Display for VkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VkError::Io(e) => write!(f, "{}", e),
+            VkError::Truncated => write!(f, "truncated verifying key fingerprint"),
+            VkError::BadMagic => write!(f, "not a verifying key fingerprint"),
+            VkError::TrailingBytes => write!(f, "trailing bytes after verifying key fingerprint"),
+            VkError::Mismatch => write!(f, "verifying key does not match the circuit"),
+            VkError::Plonk(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for VkError {}
+
+impl From<io::Error> for VkError {
+    fn from(e: io::Error) -> Self {
+        VkError::Io(e)
+    }
+}
+
+impl From<Error> for VkError {
+    fn from(e: Error) -> Self {
+        VkError::Plonk(e)
+    }
+}
+
+/// The fingerprint of `vk`: the magic bytes `VK01` followed by a 32-byte digest
+/// of its pinned form. This is not a serialization the key can be read back
+/// from.
+pub fn serialize_vk(vk: &VerifyingKey<EqAffine>) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&fingerprint(vk));
+    bytes
+}
+
+/// Regenerates the verifying key of `circuit` under `params` and checks it
+/// against the fingerprint `bytes` from [`serialize_vk`].
+pub fn deserialize_vk<C: Circuit<Fp>>(
+    params: &Params<EqAffine>,
+    circuit: &C,
+    bytes: &[u8],
+) -> Result<VerifyingKey<EqAffine>, VkError> {
+    if bytes.len() < MAGIC.len() + 32 {
+        return Err(VkError::Truncated);
+    }
+    let (magic, digest) = bytes.split_at(MAGIC.len());
+    if magic != MAGIC {
+        return Err(VkError::BadMagic);
+    }
+    if digest.len() != 32 {
+        return Err(VkError::TrailingBytes);
+    }
+
+    let vk = keygen_vk(params, circuit)?;
+    if fingerprint(&vk) != digest {
+        return Err(VkError::Mismatch);
+    }
+    Ok(vk)
+}
+
+/// Verifies a Blake2b proof against a verifying key fingerprint, without a
+/// proving key. `circuit` is only used for its shape: the key is regenerated
+/// from `circuit.without_witnesses()` with `keygen_vk`.
+pub fn verify_only<C: Circuit<Fp>>(
+    params: &Params<EqAffine>,
+    circuit: &C,
+    vk_bytes: &[u8],
+    proof: &[u8],
+    instances: &[&[Fp]],
+) -> Result<(), VkError> {
+    let vk = deserialize_vk(params, &circuit.without_witnesses(), vk_bytes)?;
+    Ok(verify_with_vk::<Blake2b>(params, &vk, proof, instances)?)
+}
@@ -0,0 +1,154 @@
+use crate::{prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Range-checks a private value against `[0, RANGE)` with a single gate:
+///
+/// ```text
+/// v * (1 - v) * (2 - v) * ... * (RANGE - 1 - v) == 0
+/// ```
+///
+/// The product vanishes exactly on `0..RANGE`. Its degree grows with `RANGE`
+/// (`RANGE + 1` with the selector), so this only suits small ranges; the lookup
+/// in example2 handles larger ones at a fixed degree.
+#[derive(Debug, Clone)]
+struct RangeCheckConfig<const RANGE: usize> {
+    pub advice: Column<Advice>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+struct RangeCheckChip<F: PrimeField, const RANGE: usize> {
+    config: RangeCheckConfig<RANGE>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField, const RANGE: usize> RangeCheckChip<F, RANGE> {
+    pub fn construct(config: RangeCheckConfig<RANGE>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+    ) -> RangeCheckConfig<RANGE> {
+        let selector = meta.selector();
+
+        meta.create_gate("range check", |meta| {
+            //
+            // advice | selector
+            //   v    |    s
+            //
+            let s = meta.query_selector(selector);
+            let value = meta.query_advice(advice, Rotation::cur());
+            let product = (1..RANGE).fold(value.clone(), |product, i| {
+                product * (Expression::Constant(F::from(i as u64)) - value.clone())
+            });
+            vec![s * product]
+        });
+
+        RangeCheckConfig { advice, selector }
+    }
+
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "range check",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", self.config.advice, 0, || value)
+            },
+        )
+    }
+}
+
+/// Plain-Rust reference: whether `value` is in `[0, range)`.
+#[cfg(test)]
+pub fn reference(value: u64, range: usize) -> bool {
+    value < range as u64
+}
+
+#[derive(Default)]
+struct MyCircuit<F, const RANGE: usize> {
+    value: Value<F>,
+}
+
+impl<F: PrimeField, const RANGE: usize> Circuit<F> for MyCircuit<F, RANGE> {
+    type Config = RangeCheckConfig<RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        RangeCheckChip::<F, RANGE>::configure(meta, advice)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = RangeCheckChip::<F, RANGE>::construct(config);
+        chip.assign(layouter.namespace(|| "range check"), self.value)?;
+        Ok(())
+    }
+}
+
+/// The circuit on 5 in `[0, 8)`.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp, 8> {
+        value: Value::known(Fp::from(5)),
+    };
+    ExampleCircuit::new(4, circuit, vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    const RANGE: usize = 8;
+
+    fn run(value: u64) -> MockProver<Fp> {
+        let k = 4;
+
+        let circuit = MyCircuit::<Fp, RANGE> {
+            value: Value::known(Fp::from(value)),
+        };
+        MockProver::run(k, &circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn range_check_example1() {
+        for value in 0..RANGE as u64 {
+            assert!(reference(value, RANGE));
+            run(value).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn range_check_example1_rejects_out_of_range() {
+        assert!(!reference(RANGE as u64, RANGE));
+        assert!(run(RANGE as u64).verify().is_err());
+        assert_constraint_fails(&run(1000), "range check");
+    }
+
+    #[test]
+    fn range_check_example1_gate_degree() {
+        // s * v * (1 - v) * ... * (RANGE - 1 - v)
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                MyCircuit::<Fp, RANGE>::configure(meta);
+            }),
+            RANGE + 1
+        );
+    }
+}
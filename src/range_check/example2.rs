@@ -0,0 +1,181 @@
+use super::table::RangeTableConfig;
+use crate::{prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Width of the values example2 checks.
+const NUM_BITS: usize = 8;
+
+/// Size of the lookup table, `2^NUM_BITS`.
+const RANGE: usize = 1 << NUM_BITS;
+
+/// Range-checks private values against `[0, RANGE)` by looking each one up in a
+/// table column loaded with `0..RANGE`.
+///
+/// Unlike the product gate of example1, the lookup keeps degree 3 whatever the
+/// range; the cost moves to the table, which needs `RANGE` rows.
+#[derive(Debug, Clone)]
+struct LookupRangeCheckConfig<F: PrimeField, const RANGE: usize> {
+    pub advice: Column<Advice>,
+    pub selector: Selector,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+#[derive(Debug, Clone)]
+struct LookupRangeCheckChip<F: PrimeField, const RANGE: usize> {
+    config: LookupRangeCheckConfig<F, RANGE>,
+}
+
+impl<F: PrimeField, const RANGE: usize> LookupRangeCheckChip<F, RANGE> {
+    pub fn construct(config: LookupRangeCheckConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+    ) -> LookupRangeCheckConfig<F, RANGE> {
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        let table = RangeTableConfig::configure(meta);
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let value = meta.query_advice(advice, Rotation::cur());
+            vec![(s * value, table.value)]
+        });
+
+        LookupRangeCheckConfig {
+            advice,
+            selector,
+            table,
+        }
+    }
+
+    /// Assigns `values` one per row, each looked up in the table.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "lookup range check",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(row, &value)| {
+                        self.config.selector.enable(&mut region, row)?;
+                        region.assign_advice(|| "value", self.config.advice, row, || value)
+                    })
+                    .collect()
+            },
+        )
+    }
+}
+
+/// Plain-Rust reference: whether `value` fits in `NUM_BITS` bits.
+#[cfg(test)]
+pub fn reference(value: u64) -> bool {
+    value < RANGE as u64
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    values: Vec<Value<F>>,
+}
+
+impl<F: PrimeField> MyCircuit<F> {
+    pub fn new(values: &[u64]) -> Self {
+        Self {
+            values: values.iter().map(|&v| Value::known(F::from(v))).collect(),
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
+    type Config = LookupRangeCheckConfig<F, RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        LookupRangeCheckChip::configure(meta, advice)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let chip = LookupRangeCheckChip::construct(config);
+        chip.assign(layouter.namespace(|| "values"), &self.values)?;
+        Ok(())
+    }
+}
+
+/// The circuit on a few 8-bit values.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp>::new(&[0, 7, 200, 255]);
+    ExampleCircuit::new(9, circuit, vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    fn run(circuit: &MyCircuit<Fp>) -> MockProver<Fp> {
+        let k = 9;
+
+        MockProver::run(k, circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn range_check_example2() {
+        let values: Vec<u64> = (0..RANGE as u64)
+            .step_by(17)
+            .chain([RANGE as u64 - 1])
+            .collect();
+        assert!(values.iter().all(|&v| reference(v)));
+        run(&MyCircuit::new(&values)).assert_satisfied();
+    }
+
+    #[test]
+    fn range_check_example2_rejects_out_of_range() {
+        assert!(!reference(RANGE as u64));
+
+        // Only the second value is out of range, so only its row fails.
+        let failures = run(&MyCircuit::new(&[3, RANGE as u64, 4]))
+            .verify()
+            .unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(failures[0], VerifyFailure::Lookup { .. }));
+
+        // -1 is the largest field element.
+        let circuit = MyCircuit {
+            values: vec![Value::known(-Fp::ONE)],
+        };
+        assert!(run(&circuit).verify().is_err());
+    }
+
+    #[test]
+    fn range_check_example2_gate_degree() {
+        // No custom gates: the lookup does all the work.
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                MyCircuit::<Fp>::configure(meta);
+            }),
+            0
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Range checks: constraining a value to `[0, RANGE)`.
+
+mod example1;
+mod example2;
+pub(crate) mod table;
+
+use crate::registry::Example;
+
+pub(crate) const EXAMPLES: &[Example] = &[
+    Example {
+        name: "range_check::example1",
+        circuit: example1::example_circuit,
+    },
+    Example {
+        name: "range_check::example2",
+        circuit: example2::example_circuit,
+    },
+];
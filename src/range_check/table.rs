@@ -0,0 +1,36 @@
+use crate::prelude::*;
+
+/// A lookup table of values from `0..RANGE`.
+#[derive(Debug, Clone)]
+pub(crate) struct RangeTableConfig<F: PrimeField, const RANGE: usize> {
+    pub(crate) value: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField, const RANGE: usize> RangeTableConfig<F, RANGE> {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        let value = meta.lookup_table_column();
+
+        Self {
+            value,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load range-check table",
+            |mut table| {
+                for value in 0..RANGE {
+                    table.assign_cell(
+                        || "value",
+                        self.value,
+                        value,
+                        || Value::known(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{names, prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Coefficients and seeds of the Lucas numbers: `L(n) = L(n-2) + L(n-1)`.
+pub const LUCAS: ([u64; 2], [u64; 2]) = ([1, 1], [2, 1]);
+/// Coefficients and seeds of the Pell numbers: `P(n) = P(n-2) + 2*P(n-1)`.
+pub const PELL: ([u64; 2], [u64; 2]) = ([1, 2], [0, 1]);
+/// Coefficients and seeds of the Tribonacci numbers:
+/// `T(n) = T(n-3) + T(n-2) + T(n-1)`.
+pub const TRIBONACCI: ([u64; 3], [u64; 3]) = ([1, 1, 1], [0, 0, 1]);
+
+/// Proves a term of a linear recurrence of order `ORDER`,
+///
+/// ```text
+/// f(n) = alpha_0 * f(n - ORDER) + ... + alpha_{ORDER-1} * f(n - 1)
+/// ```
+///
+/// with one term per row of a single advice column, like Fibonacci example2.
+/// The coefficients `alpha_i` sit in fixed columns, so they are part of the
+/// circuit rather than the witness: Fibonacci and Lucas share a circuit, Pell
+/// does not. The first `ORDER` terms are the seeds, read from instance rows
+/// `0..ORDER`, and the output goes to instance row `ORDER`.
+#[derive(Debug, Clone)]
+struct RecurrenceConfig<const ORDER: usize> {
+    pub advice: Column<Advice>,
+    pub coeffs: [Column<Fixed>; ORDER],
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct RecurrenceChip<F: PrimeField, const ORDER: usize> {
+    config: RecurrenceConfig<ORDER>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField, const ORDER: usize> RecurrenceChip<F, ORDER> {
+    pub fn construct(config: RecurrenceConfig<ORDER>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> RecurrenceConfig<ORDER> {
+        let coeffs = [(); ORDER].map(|_| meta.fixed_column());
+        let selector = meta.selector();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        meta.create_gate("recurrence", |meta| {
+            //
+            // advice | alpha_0 ... alpha_{ORDER-1} | selector
+            //   f_0  |    a_0  ...    a_{ORDER-1}  |    s
+            //   ...  |
+            //   f_ORDER
+            //
+            let s = meta.query_selector(selector);
+            let next = meta.query_advice(advice, Rotation(ORDER as i32));
+            let sum = coeffs
+                .iter()
+                .enumerate()
+                .map(|(i, &coeff)| {
+                    meta.query_fixed(coeff) * meta.query_advice(advice, Rotation(i as i32))
+                })
+                .fold(Expression::Constant(F::ZERO), |sum, term| sum + term);
+            vec![s * (sum - next)]
+        });
+
+        RecurrenceConfig {
+            advice,
+            coeffs,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns `f(0)` to `f(nrows - 1)`, one per row, with the seeds copied
+    /// from the instance column, and returns all of them.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        coeffs: &[u64; ORDER],
+        nrows: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let coeffs = coeffs.map(F::from);
+        layouter.assign_region(
+            || "recurrence",
+            |mut region| {
+                let seeds: Vec<_> = (0..ORDER).map(|row| (self.config.advice, row)).collect();
+                let mut terms = load_instances(&mut region, self.config.instance, &seeds)?;
+
+                for row in ORDER..nrows {
+                    // The gate on row i reaches down to row i + ORDER.
+                    let first = row - ORDER;
+                    self.config.selector.enable(&mut region, first)?;
+                    for (&column, &coeff) in self.config.coeffs.iter().zip(&coeffs) {
+                        region.assign_fixed(|| "alpha", column, first, || Value::known(coeff))?;
+                    }
+
+                    let next = terms[first..]
+                        .iter()
+                        .zip(&coeffs)
+                        .fold(Value::known(F::ZERO), |sum, (term, &coeff)| {
+                            sum + term.value().map(|&term| term * coeff)
+                        });
+                    terms.push(region.assign_advice(|| "f", self.config.advice, row, || next)?);
+                }
+
+                Ok(terms)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference: `f(n)` of the recurrence with `coeffs` and `seeds`, or
+/// `None` if a term overflows a `u64`.
+pub fn reference<const ORDER: usize>(
+    coeffs: [u64; ORDER],
+    seeds: [u64; ORDER],
+    n: usize,
+) -> Option<u64> {
+    let mut terms = seeds.to_vec();
+    while terms.len() <= n {
+        let window = &terms[terms.len() - ORDER..];
+        let next = window
+            .iter()
+            .zip(coeffs)
+            .try_fold(0u64, |sum, (&term, coeff)| {
+                sum.checked_add(term.checked_mul(coeff)?)
+            })?;
+        terms.push(next);
+    }
+    Some(terms[n])
+}
+
+/// The instance column: the seeds followed by `f(n)`.
+pub fn instances<const ORDER: usize>(
+    coeffs: [u64; ORDER],
+    seeds: [u64; ORDER],
+    n: usize,
+) -> Option<Vec<Vec<Fp>>> {
+    let output = reference(coeffs, seeds, n)?;
+    let column = seeds
+        .iter()
+        .chain([&output])
+        .map(|&x| Fp::from(x))
+        .collect();
+    Some(vec![column])
+}
+
+/// Computes `f(0)` to `f(n)` with the coefficients `coeffs` and exposes `f(n)`.
+struct MyCircuit<F, const ORDER: usize> {
+    coeffs: [u64; ORDER],
+    n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F, const ORDER: usize> MyCircuit<F, ORDER> {
+    pub fn new(coeffs: [u64; ORDER], n: usize) -> Self {
+        Self {
+            coeffs,
+            n,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField, const ORDER: usize> Circuit<F> for MyCircuit<F, ORDER> {
+    type Config = RecurrenceConfig<ORDER>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.coeffs, self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        RecurrenceChip::<F, ORDER>::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = RecurrenceChip::<F, ORDER>::construct(config);
+
+        let terms = chip.assign(
+            layouter.namespace(|| "recurrence"),
+            &self.coeffs,
+            self.n + 1,
+        )?;
+        chip.expose_public(layouter.namespace(|| names::OUT), &terms[self.n], ORDER)?;
+
+        Ok(())
+    }
+}
+
+fn example_circuit<const ORDER: usize>(sequence: ([u64; ORDER], [u64; ORDER])) -> ExampleCircuit {
+    let (coeffs, seeds) = sequence;
+    let instances = instances(coeffs, seeds, 9).expect("f(9) fits in a u64");
+    ExampleCircuit::new(5, MyCircuit::<Fp, ORDER>::new(coeffs, 9), instances)
+}
+
+/// The circuit on the 9th Lucas number.
+pub fn lucas_circuit() -> ExampleCircuit {
+    example_circuit(LUCAS)
+}
+
+/// The circuit on the 9th Pell number.
+pub fn pell_circuit() -> ExampleCircuit {
+    example_circuit(PELL)
+}
+
+/// The circuit on the 9th Tribonacci number.
+pub fn tribonacci_circuit() -> ExampleCircuit {
+    example_circuit(TRIBONACCI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn prove<const ORDER: usize>(
+        coeffs: [u64; ORDER],
+        instances: Vec<Vec<Fp>>,
+        n: usize,
+    ) -> MockProver<Fp> {
+        let k = 5;
+
+        MockProver::run(k, &MyCircuit::new(coeffs, n), instances).unwrap()
+    }
+
+    #[test]
+    fn reference_sequences() {
+        let terms = |(coeffs, seeds): ([u64; 2], [u64; 2])| {
+            (0..8)
+                .map(|n| reference(coeffs, seeds, n).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(terms(LUCAS), [2, 1, 3, 4, 7, 11, 18, 29]);
+        assert_eq!(terms(PELL), [0, 1, 2, 5, 12, 29, 70, 169]);
+        let (coeffs, seeds) = TRIBONACCI;
+        let tribonacci: Vec<_> = (0..8)
+            .map(|n| reference(coeffs, seeds, n).unwrap())
+            .collect();
+        assert_eq!(tribonacci, [0, 0, 1, 1, 2, 4, 7, 13]);
+
+        // Fibonacci is the order-2 recurrence with both coefficients 1.
+        for n in 0..20 {
+            assert_eq!(
+                reference([1, 1], [0, 1], n),
+                crate::fibonacci::fib_reference(0, 1, n)
+            );
+        }
+    }
+
+    #[test]
+    fn recurrence_sequences() {
+        for n in [2, 3, 9] {
+            let (coeffs, seeds) = LUCAS;
+            prove(coeffs, instances(coeffs, seeds, n).unwrap(), n).assert_satisfied();
+            let (coeffs, seeds) = PELL;
+            prove(coeffs, instances(coeffs, seeds, n).unwrap(), n).assert_satisfied();
+        }
+        for n in [3, 4, 9] {
+            let (coeffs, seeds) = TRIBONACCI;
+            prove(coeffs, instances(coeffs, seeds, n).unwrap(), n).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn recurrence_rejects_wrong_output() {
+        let (coeffs, seeds) = PELL;
+        let mut instances = instances(coeffs, seeds, 9).unwrap();
+        instances[0][2] += Fp::ONE;
+        assert!(prove(coeffs, instances, 9).verify().is_err());
+    }
+
+    #[test]
+    fn recurrence_coefficients_are_fixed() {
+        // Lucas terms do not satisfy the Pell circuit, even from the same seeds.
+        let lucas = instances(LUCAS.0, LUCAS.1, 9).unwrap();
+        assert!(prove(PELL.0, lucas.clone(), 9).verify().is_err());
+        prove(LUCAS.0, lucas, 9).assert_satisfied();
+    }
+}
@@ -0,0 +1,18 @@
+mod example;
+
+use crate::registry::Example;
+
+pub(crate) const EXAMPLES: &[Example] = &[
+    Example {
+        name: "recurrence::lucas",
+        circuit: example::lucas_circuit,
+    },
+    Example {
+        name: "recurrence::pell",
+        circuit: example::pell_circuit,
+    },
+    Example {
+        name: "recurrence::tribonacci",
+        circuit: example::tribonacci_circuit,
+    },
+];
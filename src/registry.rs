@@ -0,0 +1,203 @@
+//! Every example circuit, runnable in one go with `MockProver`.
+//!
+//! Each example module lists its circuits in an `EXAMPLES` table next to the
+//! circuits themselves; [`run_all_examples`] walks those tables, so adding an
+//! example only touches its own module. An entry builds its circuit rather
+//! than running it, so the same circuit can also be measured or drawn.
+
+use halo2_proofs::{dev::MockProver, pasta::Fp, plonk::Circuit};
+
+#[cfg(feature = "dev-graph")]
+use std::error::Error;
+
+use crate::cost::Cost;
+#[cfg(feature = "dev-graph")]
+use crate::plot::{self, LayoutOptions};
+
+use crate::{
+    collatz, compare, composition, divmod, dynamic_lookup, ecc, factor, factorial, fibonacci,
+    floor_planner, fsm, function, horner, linsys, max, memory, merkle, mimc, multiple_of,
+    permutation, popcount, pow, range_check, recurrence, rlc, rotation, set_membership, sha256,
+    shuffle, signed_range, sudoku, transfer, xor, zkml, zkvm,
+};
+
+#[cfg(all(feature = "ecc", feature = "poseidon"))]
+use crate::schnorr;
+#[cfg(feature = "ecc")]
+use crate::{commitment, ecdsa};
+#[cfg(feature = "poseidon")]
+use crate::{mastermind, note, poseidon};
+
+/// An example circuit together with default inputs it should accept.
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub name: &'static str,
+    /// Builds the circuit, with its `k` and public inputs.
+    pub circuit: fn() -> ExampleCircuit,
+}
+
+impl Example {
+    /// Builds the circuit and checks it with `MockProver`.
+    pub fn run(&self) -> Result<(), String> {
+        let example = (self.circuit)();
+        example
+            .circuit
+            .mock_verify(example.k, example.instances.clone())
+    }
+}
+
+/// What the registry needs from a circuit, whatever its type.
+pub trait AnyCircuit {
+    /// [`mock_verify`] on this circuit.
+    fn mock_verify(&self, k: u32, instances: Vec<Vec<Fp>>) -> Result<(), String>;
+    /// [`Cost::measure`] on this circuit.
+    fn cost(&self, k: u32) -> Option<Cost>;
+    /// [`plot::plot_png_with`] on this circuit.
+    #[cfg(feature = "dev-graph")]
+    fn plot_png(&self, path: &str, k: u32, options: &LayoutOptions) -> Result<(), Box<dyn Error>>;
+    /// [`plot::plot_svg_with`] on this circuit.
+    #[cfg(feature = "dev-graph")]
+    fn plot_svg(&self, path: &str, k: u32, options: &LayoutOptions) -> Result<(), Box<dyn Error>>;
+}
+
+impl<C: Circuit<Fp>> AnyCircuit for C {
+    fn mock_verify(&self, k: u32, instances: Vec<Vec<Fp>>) -> Result<(), String> {
+        mock_verify(k, self, instances)
+    }
+
+    fn cost(&self, k: u32) -> Option<Cost> {
+        Cost::measure(k, self)
+    }
+
+    #[cfg(feature = "dev-graph")]
+    fn plot_png(&self, path: &str, k: u32, options: &LayoutOptions) -> Result<(), Box<dyn Error>> {
+        plot::plot_png_with(path, k, self, options)
+    }
+
+    #[cfg(feature = "dev-graph")]
+    fn plot_svg(&self, path: &str, k: u32, options: &LayoutOptions) -> Result<(), Box<dyn Error>> {
+        plot::plot_svg_with(path, k, self, options)
+    }
+}
+
+/// An example's circuit at the size it runs at, with public inputs it should
+/// accept.
+pub struct ExampleCircuit {
+    pub k: u32,
+    pub circuit: Box<dyn AnyCircuit>,
+    pub instances: Vec<Vec<Fp>>,
+}
+
+impl ExampleCircuit {
+    pub fn new<C: Circuit<Fp> + 'static>(k: u32, circuit: C, instances: Vec<Vec<Fp>>) -> Self {
+        Self {
+            k,
+            circuit: Box::new(circuit),
+            instances,
+        }
+    }
+}
+
+/// The per-module `EXAMPLES` tables.
+const MODULES: &[&[Example]] = &[
+    collatz::EXAMPLES,
+    #[cfg(feature = "ecc")]
+    commitment::EXAMPLES,
+    compare::EXAMPLES,
+    composition::EXAMPLES,
+    divmod::EXAMPLES,
+    dynamic_lookup::EXAMPLES,
+    ecc::EXAMPLES,
+    #[cfg(feature = "ecc")]
+    ecdsa::EXAMPLES,
+    factor::EXAMPLES,
+    factorial::EXAMPLES,
+    fibonacci::EXAMPLES,
+    floor_planner::EXAMPLES,
+    fsm::EXAMPLES,
+    function::EXAMPLES,
+    horner::EXAMPLES,
+    linsys::EXAMPLES,
+    #[cfg(feature = "poseidon")]
+    mastermind::EXAMPLES,
+    max::EXAMPLES,
+    memory::EXAMPLES,
+    merkle::EXAMPLES,
+    mimc::EXAMPLES,
+    multiple_of::EXAMPLES,
+    #[cfg(feature = "poseidon")]
+    note::EXAMPLES,
+    permutation::EXAMPLES,
+    popcount::EXAMPLES,
+    #[cfg(feature = "poseidon")]
+    poseidon::EXAMPLES,
+    pow::EXAMPLES,
+    range_check::EXAMPLES,
+    recurrence::EXAMPLES,
+    rlc::EXAMPLES,
+    rotation::EXAMPLES,
+    #[cfg(all(feature = "ecc", feature = "poseidon"))]
+    schnorr::EXAMPLES,
+    set_membership::EXAMPLES,
+    sha256::EXAMPLES,
+    shuffle::EXAMPLES,
+    signed_range::EXAMPLES,
+    sudoku::EXAMPLES,
+    transfer::EXAMPLES,
+    xor::EXAMPLES,
+    zkml::EXAMPLES,
+    zkvm::EXAMPLES,
+];
+
+/// Every registered example, in module order.
+pub fn examples() -> impl Iterator<Item = &'static Example> {
+    MODULES.iter().flat_map(|examples| examples.iter())
+}
+
+/// Runs every example and returns each one's name and outcome.
+pub fn run_all_examples() -> Vec<(String, Result<(), String>)> {
+    examples()
+        .map(|example| (example.name.to_string(), example.run()))
+        .collect()
+}
+
+/// Runs `circuit` with `MockProver` and flattens any synthesis error or
+/// verification failure into a message.
+pub fn mock_verify<C: Circuit<Fp>>(
+    k: u32,
+    circuit: &C,
+    instances: Vec<Vec<Fp>>,
+) -> Result<(), String> {
+    let prover = MockProver::run(k, circuit, instances).map_err(|e| e.to_string())?;
+    prover.verify().map_err(|failures| {
+        failures
+            .iter()
+            .map(|failure| failure.to_string())
+            .collect::<Vec<_>>()
+            .join("; ")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_examples_pass() {
+        let results = run_all_examples();
+
+        assert!(results.len() >= MODULES.len());
+        for (name, result) in results {
+            assert_eq!(result, Ok(()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn example_names_are_unique() {
+        let mut names: Vec<_> = examples().map(|example| example.name).collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+}
@@ -0,0 +1,284 @@
+//! Random linear combinations of byte strings: the trick zkEVM-style circuits
+//! use to handle variable-length data. A string `b_0 .. b_{n-1}` is folded
+//! into one field element
+//!
+//! ```text
+//! rlc = b_0 * r^(n-1) + b_1 * r^(n-2) + ... + b_{n-1}
+//! ```
+//!
+//! and two strings of the same length with equal RLCs are equal, except with
+//! probability about `n / |F|` over the choice of `r`. Folding on from an
+//! existing RLC appends to it, so a string split across regions can be
+//! compared with one laid out in one piece.
+//!
+//! This is only sound if `r` is chosen after the bytes are fixed. Newer halo2
+//! versions provide that with second-phase advice columns and
+//! `meta.challenge_usable_after`; halo2_proofs 0.3 has no challenge API, so,
+//! as in the `permutation` example, the example reads `r` from the instance,
+//! which a real protocol would have to derive from commitments to the bytes.
+
+use halo2_proofs::pasta::Fp;
+
+use crate::{
+    prelude::*,
+    range_check::table::RangeTableConfig,
+    registry::{Example, ExampleCircuit},
+};
+
+/// `[acc, byte, r]` columns and the byte range table.
+#[derive(Debug, Clone)]
+pub struct RlcConfig<F: PrimeField> {
+    pub advice: [Column<Advice>; 3],
+    pub selector: Selector,
+    pub constant: Column<Fixed>,
+    pub(crate) table: RangeTableConfig<F, 256>,
+}
+
+/// Folds bytes into an accumulator, one per row:
+///
+/// ```text
+/// acc       | byte    | r | selector
+/// init      | b_0     | r |    1
+/// acc_1     | b_1     | r |    1
+/// ...       | ...     |   |   ...
+/// acc_n     |         |   |    0
+/// ```
+///
+/// with `acc_{i+1} = acc_i * r + b_i`, and every byte looked up in `0..256`.
+#[derive(Debug, Clone)]
+pub struct RlcChip<F: PrimeField> {
+    config: RlcConfig<F>,
+}
+
+impl<F: PrimeField> RlcChip<F> {
+    pub fn construct(config: RlcConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> RlcConfig<F> {
+        let [acc, byte, r] = advice;
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let table = RangeTableConfig::configure(meta);
+
+        meta.create_gate("rlc", |meta| {
+            let s = meta.query_selector(selector);
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let r = meta.query_advice(r, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+            vec![s * (acc_cur * r + byte - acc_next)]
+        });
+
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(s * byte, table.value)]
+        });
+
+        RlcConfig {
+            advice,
+            selector,
+            constant,
+            table,
+        }
+    }
+
+    /// Loads the byte table. Call once per circuit.
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.load(layouter)
+    }
+
+    /// Folds `bytes` into `init`, or into 0 to start a new string, with
+    /// challenge `r`, and returns the new accumulator.
+    pub fn accumulate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        r: &AssignedCell<F, F>,
+        init: Option<&AssignedCell<F, F>>,
+        bytes: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [acc_col, byte_col, r_col] = self.config.advice;
+
+        layouter.assign_region(
+            || "rlc",
+            |mut region| {
+                let mut acc = match init {
+                    Some(init) => init.copy_advice(|| "init", &mut region, acc_col, 0)?,
+                    None => region.assign_advice_from_constant(|| "init", acc_col, 0, F::ZERO)?,
+                };
+                for (row, byte) in bytes.iter().enumerate() {
+                    self.config.selector.enable(&mut region, row)?;
+                    let byte = byte.copy_advice(|| "byte", &mut region, byte_col, row)?;
+                    let r = r.copy_advice(|| "r", &mut region, r_col, row)?;
+                    let next = acc.value().copied() * r.value() + byte.value();
+                    acc = region.assign_advice(|| "acc", acc_col, row + 1, || next)?;
+                }
+                Ok(acc)
+            },
+        )
+    }
+}
+
+/// Plain-Rust reference: the RLC of `bytes` with challenge `r`.
+pub fn rlc<F: PrimeField>(bytes: &[u64], r: F) -> F {
+    bytes
+        .iter()
+        .fold(F::ZERO, |acc, &byte| acc * r + F::from(byte))
+}
+
+/// The challenge the example and tests use.
+fn challenge() -> Fp {
+    Fp::from(0x5eed_5eed)
+}
+
+/// Proves that a string laid out in one piece equals another laid out in two.
+/// RLCs alone cannot tell leading zero bytes apart, so the lengths, which are
+/// part of the circuit's shape, have to match as well.
+#[derive(Debug, Clone)]
+struct MyConfig {
+    rlc: RlcConfig<Fp>,
+    instance: Column<Instance>,
+}
+
+#[derive(Default)]
+struct MyCircuit {
+    whole: Vec<u64>,
+    parts: [Vec<u64>; 2],
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            whole: self.whole.clone(),
+            parts: self.parts.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        MyConfig {
+            rlc: RlcChip::configure(meta, advice),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        if self.whole.len() != self.parts[0].len() + self.parts[1].len() {
+            return Err(Error::Synthesis);
+        }
+        let [_, byte_col, r_col] = config.rlc.advice;
+        let chip = RlcChip::construct(config.rlc.clone());
+        chip.load_table(&mut layouter)?;
+
+        let r = layouter.assign_region(
+            || "challenge",
+            |mut region| region.assign_advice_from_instance(|| "r", config.instance, 0, r_col, 0),
+        )?;
+        let mut load = |bytes: &[u64]| {
+            layouter.assign_region(
+                || "bytes",
+                |mut region| {
+                    bytes
+                        .iter()
+                        .enumerate()
+                        .map(|(row, &byte)| {
+                            let byte = Value::known(Fp::from(byte));
+                            region.assign_advice(|| "byte", byte_col, row, || byte)
+                        })
+                        .collect::<Result<Vec<_>, Error>>()
+                },
+            )
+        };
+        let whole = load(&self.whole)?;
+        let [first, second] = [load(&self.parts[0])?, load(&self.parts[1])?];
+
+        let whole = chip.accumulate(layouter.namespace(|| "whole"), &r, None, &whole)?;
+        let first = chip.accumulate(layouter.namespace(|| "first"), &r, None, &first)?;
+        let joined = chip.accumulate(layouter.namespace(|| "second"), &r, Some(&first), &second)?;
+        layouter.assign_region(
+            || "equal",
+            |mut region| region.constrain_equal(whole.cell(), joined.cell()),
+        )
+    }
+}
+
+/// The circuit on `"hello, world"` split after the comma.
+pub fn example_circuit() -> ExampleCircuit {
+    let bytes = |s: &str| s.bytes().map(u64::from).collect::<Vec<_>>();
+    let circuit = MyCircuit {
+        whole: bytes("hello, world"),
+        parts: [bytes("hello,"), bytes(" world")],
+    };
+    ExampleCircuit::new(9, circuit, vec![vec![challenge()]])
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "rlc",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+
+    fn run(whole: &[u64], first: &[u64], second: &[u64]) -> MockProver<Fp> {
+        let k = 9;
+        let circuit = MyCircuit {
+            whole: whole.to_vec(),
+            parts: [first.to_vec(), second.to_vec()],
+        };
+
+        MockProver::run(k, &circuit, vec![vec![challenge()]]).unwrap()
+    }
+
+    #[test]
+    fn rlc_accepts_equal_encodings() {
+        let bytes = [0, 255, 7, 7, 0, 128, 1];
+        for split in 0..=bytes.len() {
+            let (first, second) = bytes.split_at(split);
+            run(&bytes, first, second).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn rlc_rejects_different_bytes() {
+        let failures = run(&[1, 2, 3, 4], &[1, 2], &[3, 5]).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+    }
+
+    #[test]
+    fn rlc_rejects_non_bytes() {
+        // Without the byte range check, any string would have many encodings.
+        let prover = run(&[2, 256], &[2], &[256]);
+        assert!(prover
+            .verify()
+            .unwrap_err()
+            .iter()
+            .any(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn rlc_matches_reference() {
+        let r = challenge();
+        assert_eq!(rlc(&[1, 2, 3], r), (r + Fp::from(2)) * r + Fp::from(3));
+        assert_eq!(rlc(&[0, 0, 5], r), rlc(&[5], r));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::{prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Proves that a private sequence is an arithmetic progression between public
+/// first and last terms, with a gate that looks one row back and one row ahead:
+///
+/// ```text
+/// prev - 2*cur + next == 0
+/// ```
+///
+/// A zero second difference means every step adds the same amount. The terms go
+/// down one column in a single region, and the selector is only enabled on the
+/// interior rows, so the gate never reaches before the first term or past the
+/// last. A sequence of one or two terms has no interior rows and is always an
+/// arithmetic progression.
+#[derive(Debug, Clone)]
+struct RotationConfig {
+    pub advice: Column<Advice>,
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+struct RotationChip<F: Field> {
+    config: RotationConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> RotationChip<F> {
+    pub fn construct(config: RotationConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> RotationConfig {
+        let selector = meta.selector();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        meta.create_gate("second difference", |meta| {
+            //
+            // advice | selector
+            //  prev
+            //  cur        s
+            //  next
+            //
+            let s = meta.query_selector(selector);
+            let prev = meta.query_advice(advice, Rotation::prev());
+            let cur = meta.query_advice(advice, Rotation::cur());
+            let next = meta.query_advice(advice, Rotation::next());
+            vec![s * (prev - cur.clone() - cur + next)]
+        });
+
+        RotationConfig {
+            advice,
+            selector,
+            instance,
+        }
+    }
+
+    /// Assigns `terms` down the advice column and enables the gate on every row
+    /// that has a neighbour on both sides. Returns the assigned terms.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[Value<F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "progression",
+            |mut region| {
+                for row in 1..terms.len().saturating_sub(1) {
+                    self.config.selector.enable(&mut region, row)?;
+                }
+                terms
+                    .iter()
+                    .enumerate()
+                    .map(|(row, term)| {
+                        region.assign_advice(|| "term", self.config.advice, row, || *term)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference: whether `terms` have a constant step.
+#[cfg(test)]
+pub fn reference(terms: &[i64]) -> bool {
+    terms.windows(3).all(|w| w[0] - 2 * w[1] + w[2] == 0)
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    terms: Vec<Value<F>>,
+}
+
+impl<F: PrimeField> MyCircuit<F> {
+    pub fn new(terms: &[u64]) -> Self {
+        Self {
+            terms: terms.iter().map(|t| Value::known(F::from(*t))).collect(),
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
+    type Config = RotationConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    // The number of terms fixes the layout, so it survives without witnesses.
+    fn without_witnesses(&self) -> Self {
+        Self {
+            terms: vec![Value::unknown(); self.terms.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        RotationChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = RotationChip::construct(config);
+
+        let terms = chip.assign(layouter.namespace(|| "progression"), &self.terms)?;
+        if let (Some(first), Some(last)) = (terms.first(), terms.last()) {
+            chip.expose_public(layouter.namespace(|| "first"), first, FIRST_ROW)?;
+            chip.expose_public(layouter.namespace(|| "last"), last, LAST_ROW)?;
+        }
+
+        Ok(())
+    }
+}
+
+const FIRST_ROW: usize = 0;
+const LAST_ROW: usize = 1;
+
+/// Places the public inputs by name, like [`crate::fibonacci::FibonacciInstances`].
+pub struct RotationInstances<F> {
+    pub first: F,
+    pub last: F,
+}
+
+impl<F: Field> RotationInstances<F> {
+    pub fn new(first: F, last: F) -> Self {
+        Self { first, last }
+    }
+
+    pub fn build(self) -> Vec<Vec<F>> {
+        let mut column = vec![F::ZERO; 2];
+        column[FIRST_ROW] = self.first;
+        column[LAST_ROW] = self.last;
+        vec![column]
+    }
+}
+
+/// The circuit on `2, 4, 6, 8`.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp>::new(&[2, 4, 6, 8]);
+    let instances = RotationInstances::new(Fp::from(2), Fp::from(8)).build();
+    ExampleCircuit::new(4, circuit, instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn run(terms: &[u64]) -> MockProver<Fp> {
+        let k = 4;
+
+        let instances =
+            RotationInstances::new(Fp::from(terms[0]), Fp::from(terms[terms.len() - 1])).build();
+        MockProver::run(k, &MyCircuit::new(terms), instances).unwrap()
+    }
+
+    #[test]
+    fn rotation_accepts_progression() {
+        assert!(reference(&[2, 4, 6, 8]));
+        run(&[2, 4, 6, 8]).assert_satisfied();
+        // A constant step of zero, and a decreasing one.
+        run(&[5, 5, 5]).assert_satisfied();
+        run(&[9, 6, 3, 0]).assert_satisfied();
+    }
+
+    #[test]
+    fn rotation_rejects_uneven_step() {
+        assert!(!reference(&[2, 4, 7, 8]));
+        assert_constraint_fails(&run(&[2, 4, 7, 8]), "second difference");
+    }
+
+    #[test]
+    fn rotation_boundary_rows() {
+        // No row has both neighbours, so nothing is constrained beyond the
+        // public ends, and the gate never reads outside the region.
+        run(&[5]).assert_satisfied();
+        run(&[5, 9]).assert_satisfied();
+
+        // Only the last step is wrong; the gate on the row before the last term
+        // still catches it.
+        assert_constraint_fails(&run(&[1, 2, 3, 5]), "second difference");
+    }
+
+    #[test]
+    fn rotation_gate_degree() {
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                MyCircuit::<Fp>::configure(meta);
+            }),
+            2
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Schnorr signatures over the Pallas curve, with a Poseidon challenge.
+//!
+//! A signature on a message `m` under `P = sk*G` is `(R, s)` with `R = k*G`
+//! and `s = k + c*sk`, where `c = Poseidon(x(R), x(P), m)`. It is valid when
+//! `s*G == R + c*P`. The circuit recomputes `c` from its cells, so the
+//! challenge cannot be chosen apart from `R`.
+
+use ff::Field;
+use halo2_gadgets::ecc::NonIdentityPoint;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    pasta::{pallas, Fp},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use rand_core::RngCore;
+
+use crate::{
+    curve::{
+        self, base_to_scalar, coordinates, mul_generator, scalar_to_base, CurveChip, CurveConfig,
+    },
+    names,
+    poseidon::{self, PoseidonChip, PoseidonConfig},
+    registry::{Example, ExampleCircuit},
+};
+
+/// A Schnorr signature. [`sign`] only returns an `s` below the base field's
+/// modulus, so that the circuit can load it as a base field cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub r: pallas::Affine,
+    pub s: Fp,
+}
+
+/// The challenge `c = Poseidon(x(R), x(P), m)`.
+pub fn challenge(r: pallas::Affine, pk: pallas::Affine, m: Fp) -> Fp {
+    poseidon::hash([coordinates(r)[0], coordinates(pk)[0], m])
+}
+
+/// Signs the message `m` with the secret key `sk`.
+pub fn sign(sk: pallas::Scalar, m: Fp, mut rng: impl RngCore) -> Signature {
+    let pk = mul_generator(sk);
+    loop {
+        let k = pallas::Scalar::random(&mut rng);
+        let r = mul_generator(k);
+        let s = k + base_to_scalar(challenge(r, pk, m)) * sk;
+        // An `s` at or above the base modulus has probability about 2^-126.
+        if let (false, Some(s)) = (k.is_zero_vartime(), scalar_to_base(s)) {
+            return Signature { r, s };
+        }
+    }
+}
+
+/// Plain-Rust reference: whether `signature` is valid for `m` under `pk`.
+#[cfg(test)]
+pub fn verify(pk: pallas::Affine, m: Fp, signature: &Signature) -> bool {
+    let c = base_to_scalar(challenge(signature.r, pk, m));
+    mul_generator(base_to_scalar(signature.s)) == (signature.r + pk * c).into()
+}
+
+#[derive(Debug, Clone)]
+struct SchnorrConfig {
+    curve: CurveConfig,
+    poseidon: PoseidonConfig,
+    instance: Column<Instance>,
+}
+
+/// Proves knowledge of a valid signature on the public message `m` under the
+/// public key `P`. The instance column holds `[x(P), y(P), m]`.
+///
+/// The challenge is hashed in the circuit from the cells of `x(R)`, `x(P)` and
+/// `m`, and then used as a scalar, like `s`. `G` is pinned to the Pallas
+/// generator. The Poseidon chip shares the first four of the ECC chip's
+/// advice columns.
+#[derive(Default)]
+struct MyCircuit {
+    r: Value<pallas::Affine>,
+    s: Value<Fp>,
+    pk: Value<pallas::Affine>,
+    m: Value<Fp>,
+}
+
+impl MyCircuit {
+    pub fn new(pk: pallas::Affine, m: Fp, signature: &Signature) -> Self {
+        Self {
+            r: Value::known(signature.r),
+            s: Value::known(signature.s),
+            pk: Value::known(pk),
+            m: Value::known(m),
+        }
+    }
+}
+
+/// The public inputs for a signature on `m` under `pk`.
+pub fn instances(pk: pallas::Affine, m: Fp) -> Vec<Vec<Fp>> {
+    let [x, y] = coordinates(pk);
+    vec![vec![x, y, m]]
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = SchnorrConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 10].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let curve = CurveChip::configure(meta, advice);
+        let poseidon =
+            PoseidonChip::<3>::configure(meta, [advice[0], advice[1], advice[2], advice[3]]);
+
+        SchnorrConfig {
+            curve,
+            poseidon,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = CurveChip::construct(config.curve);
+        chip.load(&mut layouter)?;
+        let ecc = chip.ecc_chip();
+
+        let g = chip.load_generator(layouter.namespace(|| "G"))?;
+        let pk = NonIdentityPoint::new(ecc.clone(), layouter.namespace(|| "P"), self.pk)?;
+        let r = NonIdentityPoint::new(ecc, layouter.namespace(|| "R"), self.r)?;
+        let s = chip.load_private(layouter.namespace(|| "s"), self.s)?;
+        let m = chip.load_private(layouter.namespace(|| "m"), self.m)?;
+
+        let c = PoseidonChip::<3>::construct(config.poseidon).hash(
+            layouter.namespace(|| "challenge"),
+            [r.inner().x(), pk.inner().x(), m.clone()],
+        )?;
+
+        let s = chip.scalar(layouter.namespace(|| "s scalar"), &s)?;
+        let c = chip.scalar(layouter.namespace(|| "c scalar"), &c)?;
+        let (lhs, _) = g.mul(layouter.namespace(|| "s*G"), s)?;
+        let (cp, _) = pk.mul(layouter.namespace(|| "c*P"), c)?;
+        let rhs = cp.add(layouter.namespace(|| "R + c*P"), &r)?;
+        lhs.constrain_equal(layouter.namespace(|| "verify"), &rhs)?;
+
+        let mut layouter = layouter.namespace(|| names::OUT);
+        let pk = pk.inner();
+        layouter.constrain_instance(pk.x().cell(), config.instance, 0)?;
+        layouter.constrain_instance(pk.y().cell(), config.instance, 1)?;
+        layouter.constrain_instance(m.cell(), config.instance, 2)
+    }
+}
+
+/// The circuit on a signature of `m = 7` under the key `sk = 42`.
+pub fn example_circuit() -> ExampleCircuit {
+    let (sk, m) = (pallas::Scalar::from(42), Fp::from(7));
+    let signature = sign(sk, m, rand_core::OsRng);
+    let pk = curve::mul_generator(sk);
+    ExampleCircuit::new(11, MyCircuit::new(pk, m, &signature), instances(pk, m))
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "schnorr",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+    use rand_core::OsRng;
+
+    fn keypair() -> (pallas::Scalar, pallas::Affine) {
+        let sk = pallas::Scalar::random(OsRng);
+        (sk, mul_generator(sk))
+    }
+
+    fn run(pk: pallas::Affine, m: Fp, signature: &Signature) -> MockProver<Fp> {
+        let k = 11;
+
+        MockProver::run(k, &MyCircuit::new(pk, m, signature), instances(pk, m)).unwrap()
+    }
+
+    #[test]
+    fn schnorr_verifies() {
+        let (sk, pk) = keypair();
+        let m = Fp::random(OsRng);
+        let signature = sign(sk, m, OsRng);
+        assert!(verify(pk, m, &signature));
+        run(pk, m, &signature).assert_satisfied();
+    }
+
+    #[test]
+    fn schnorr_rejects_bad_signatures() {
+        let (sk, pk) = keypair();
+        let m = Fp::random(OsRng);
+        let signature = sign(sk, m, OsRng);
+
+        let (_, other_pk) = keypair();
+        let s = signature.s + Fp::ONE;
+        let r = mul_generator(pallas::Scalar::random(OsRng));
+        for (pk, m, signature) in [
+            (pk, m + Fp::ONE, signature),
+            (other_pk, m, signature),
+            (pk, m, Signature { s, ..signature }),
+            (pk, m, Signature { r, ..signature }),
+        ] {
+            assert!(!verify(pk, m, &signature));
+            assert!(run(pk, m, &signature).verify().is_err());
+        }
+    }
+
+    #[test]
+    fn schnorr_rejects_forged_challenge() {
+        // Without the challenge hash, `R = s*G - c*P` would pass for any `s`
+        // and `c`. Here `c` is not the hash of `R`.
+        let (_, pk) = keypair();
+        let m = Fp::random(OsRng);
+        let (s, c) = (pallas::Scalar::from(5), pallas::Scalar::from(3));
+        let r = (mul_generator(s) - pk * c).into();
+        let signature = Signature { r, s: Fp::from(5) };
+        assert!(!verify(pk, m, &signature));
+        assert!(run(pk, m, &signature).verify().is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Proofs as files, for handing them from a prover process to a verifier.
+//!
+//! A proof file is a small JSON envelope:
+//!
+//! ```json
+//! {
+//!   "circuit": "fibonacci::example1",
+//!   "k": 4,
+//!   "instances": [["0x00...01", "0x00...01", "0x00...37"]],
+//!   "proof": "9f3a..."
+//! }
+//! ```
+//!
+//! Instance values are 32-byte big-endian hex words, one array per instance
+//! column, and the proof is the raw transcript in hex. The verifier still needs
+//! the circuit itself to rebuild the verifying key; `circuit` and `k` tell it
+//! which one.
+
+use std::{fs, io, path::Path};
+
+use ff::PrimeField;
+use halo2_proofs::pasta::Fp;
+use serde::{Deserialize, Serialize};
+
+/// A proof together with what a verifier needs to check it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofEnvelope {
+    /// Name of the circuit, as listed in the registry.
+    pub circuit: String,
+    pub k: u32,
+    /// One value vector per instance column.
+    pub instances: Vec<Vec<Fp>>,
+    pub proof: Vec<u8>,
+}
+
+/// The envelope as it appears in JSON.
+#[derive(Serialize, Deserialize)]
+struct RawEnvelope {
+    circuit: String,
+    k: u32,
+    instances: Vec<Vec<String>>,
+    proof: String,
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+pub(crate) fn encode_fp(value: &Fp) -> String {
+    let mut word = value.to_repr();
+    word.as_mut().reverse();
+    format!("0x{}", hex::encode(word))
+}
+
+fn decode_fp(word: &str) -> io::Result<Fp> {
+    let digits = word.strip_prefix("0x").unwrap_or(word);
+    let mut repr = [0u8; 32];
+    hex::decode_to_slice(digits, &mut repr)
+        .map_err(|e| invalid(format!("instance {:?}: {}", word, e)))?;
+    repr.reverse();
+    Option::from(Fp::from_repr(repr))
+        .ok_or_else(|| invalid(format!("instance {:?} is not a field element", word)))
+}
+
+impl ProofEnvelope {
+    pub fn to_json(&self) -> String {
+        let raw = RawEnvelope {
+            circuit: self.circuit.clone(),
+            k: self.k,
+            instances: self
+                .instances
+                .iter()
+                .map(|column| column.iter().map(encode_fp).collect())
+                .collect(),
+            proof: hex::encode(&self.proof),
+        };
+        serde_json::to_string_pretty(&raw).expect("the envelope is plain data")
+    }
+
+    /// Parses [`Self::to_json`]'s output. Malformed JSON, hex, or instance
+    /// values of at least the field modulus fail with `InvalidData`.
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let raw: RawEnvelope = serde_json::from_str(json).map_err(io::Error::from)?;
+        let instances = raw
+            .instances
+            .iter()
+            .map(|column| column.iter().map(|word| decode_fp(word)).collect())
+            .collect::<io::Result<_>>()?;
+        let proof = hex::decode(&raw.proof).map_err(|e| invalid(format!("proof: {}", e)))?;
+        Ok(Self {
+            circuit: raw.circuit,
+            k: raw.k,
+            instances,
+            proof,
+        })
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// The instances in the form [`crate::prover::verify`] takes them.
+    pub fn instance_slices(&self) -> Vec<&[Fp]> {
+        self.instances.iter().map(Vec::as_slice).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn envelope() -> ProofEnvelope {
+        ProofEnvelope {
+            circuit: "fibonacci::example1".to_string(),
+            k: 4,
+            instances: vec![vec![Fp::from(1), Fp::from(55)], vec![-Fp::ONE]],
+            proof: vec![0x9f, 0x3a, 0x00],
+        }
+    }
+
+    #[test]
+    fn envelope_round_trip() {
+        let json = envelope().to_json();
+        assert!(json.contains("\"proof\": \"9f3a00\""), "{}", json);
+        assert!(
+            json.contains(&format!("\"0x{}37\"", "00".repeat(31))),
+            "{}",
+            json
+        );
+        assert_eq!(ProofEnvelope::from_json(&json).unwrap(), envelope());
+    }
+
+    #[test]
+    fn envelope_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("envelope-{}.json", std::process::id()));
+        envelope().write(&path).unwrap();
+        assert_eq!(ProofEnvelope::read(&path).unwrap(), envelope());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn envelope_rejects_bad_input() {
+        let json = envelope().to_json();
+
+        let err = ProofEnvelope::from_json(&json.replace("9f3a00", "9f3a0")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // The modulus itself is one past the largest field element.
+        let modulus = format!("0x{}", Fp::MODULUS.trim_start_matches("0x"));
+        let max = encode_fp(&-Fp::ONE);
+        let err = ProofEnvelope::from_json(&json.replace(&max, &modulus)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        assert!(ProofEnvelope::from_json("{\"k\": 4}").is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Constrains a witnessed value to be one of a fixed allowlist.
+///
+/// The allowlist is loaded into a two-column lookup table of `(tag, value)`
+/// pairs: every allowed value is stored with tag 1, plus a single `(0, 0)` row.
+/// Each lookup input is `(s, s * value)`, so rows where the selector is off look
+/// up `(0, 0)` and always pass, while enabled rows must match a tag-1 entry.
+/// Without the tag, 0 would silently become a member whenever the table does
+/// not list it, because disabled rows look it up.
+#[derive(Debug, Clone)]
+struct SetMembershipConfig {
+    pub advice: Column<Advice>,
+    pub selector: Selector,
+    pub table_tag: TableColumn,
+    pub table_value: TableColumn,
+}
+
+#[derive(Debug, Clone)]
+struct SetMembershipChip<F: PrimeField> {
+    config: SetMembershipConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> SetMembershipChip<F> {
+    pub fn construct(config: SetMembershipConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+    ) -> SetMembershipConfig {
+        let selector = meta.complex_selector();
+        let table_tag = meta.lookup_table_column();
+        let table_value = meta.lookup_table_column();
+
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let value = meta.query_advice(advice, Rotation::cur());
+            vec![(s.clone(), table_tag), (s * value, table_value)]
+        });
+
+        SetMembershipConfig {
+            advice,
+            selector,
+            table_tag,
+            table_value,
+        }
+    }
+
+    /// Loads `allowlist` into the lookup table. An empty allowlist is valid and
+    /// rejects every value.
+    pub fn load_allowlist(
+        &self,
+        mut layouter: impl Layouter<F>,
+        allowlist: &[F],
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "allowlist",
+            |mut table| {
+                let rows = std::iter::once((F::ZERO, F::ZERO))
+                    .chain(allowlist.iter().map(|value| (F::ONE, *value)));
+                for (offset, (tag, value)) in rows.enumerate() {
+                    table.assign_cell(
+                        || "tag",
+                        self.config.table_tag,
+                        offset,
+                        || Value::known(tag),
+                    )?;
+                    table.assign_cell(
+                        || "value",
+                        self.config.table_value,
+                        offset,
+                        || Value::known(value),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "member",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", self.config.advice, 0, || value)
+            },
+        )
+    }
+}
+
+/// Plain-Rust reference: whether `value` is in `allowlist`.
+#[cfg(test)]
+pub fn reference<T: PartialEq>(allowlist: &[T], value: &T) -> bool {
+    allowlist.contains(value)
+}
+
+#[derive(Default)]
+struct MyCircuit<F> {
+    allowlist: Vec<F>,
+    value: Value<F>,
+}
+
+impl<F: PrimeField> MyCircuit<F> {
+    pub fn new(allowlist: Vec<F>, value: Value<F>) -> Self {
+        Self { allowlist, value }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
+    type Config = SetMembershipConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.allowlist.clone(), Value::unknown())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        SetMembershipChip::configure(meta, advice)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SetMembershipChip::construct(config);
+
+        chip.load_allowlist(layouter.namespace(|| "allowlist"), &self.allowlist)?;
+        chip.assign(layouter.namespace(|| "member"), self.value)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on 42 against `[3, 7, 42, 1000]`.
+pub fn example_circuit() -> ExampleCircuit {
+    let allowlist = [3, 7, 42, 1000].map(Fp::from).to_vec();
+    let circuit = MyCircuit::new(allowlist, Value::known(Fp::from(42)));
+    ExampleCircuit::new(4, circuit, vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    fn run(allowlist: &[u64], value: u64) -> MockProver<Fp> {
+        let k = 4;
+
+        let circuit = MyCircuit::new(
+            allowlist.iter().map(|v| Fp::from(*v)).collect(),
+            Value::known(Fp::from(value)),
+        );
+        MockProver::run(k, &circuit, vec![]).unwrap()
+    }
+
+    fn assert_rejected(prover: &MockProver<Fp>) {
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn set_membership_accepts_member() {
+        let allowlist = [3, 7, 42, 1000];
+        assert!(reference(&allowlist, &42));
+        run(&allowlist, 42).assert_satisfied();
+    }
+
+    #[test]
+    fn set_membership_rejects_non_member() {
+        let allowlist = [3, 7, 42, 1000];
+        assert!(!reference(&allowlist, &8));
+        assert_rejected(&run(&allowlist, 8));
+    }
+
+    #[test]
+    fn set_membership_zero_is_not_implicit() {
+        // The (0, 0) padding row must not make 0 a member.
+        assert_rejected(&run(&[3, 7], 0));
+        run(&[0, 7], 0).assert_satisfied();
+    }
+
+    #[test]
+    fn set_membership_single_element() {
+        run(&[5], 5).assert_satisfied();
+        assert_rejected(&run(&[5], 6));
+    }
+
+    #[test]
+    fn set_membership_empty_allowlist() {
+        assert!(!reference(&[], &5));
+        assert_rejected(&run(&[], 5));
+        assert_rejected(&run(&[], 0));
+    }
+}
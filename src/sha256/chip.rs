@@ -0,0 +1,618 @@
+use crate::{prelude::*, word32::AssignedWord};
+
+/// The first 32 bits of the fractional parts of the cube roots of the first 64
+/// primes.
+pub const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The initial hash value: the first 32 bits of the fractional parts of the
+/// square roots of the first 8 primes.
+pub const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Bits in a word.
+const BITS: usize = 32;
+
+/// Rows of the round region before the first round: `d, c, b, a` of the input
+/// state in the `a` half, `h, g, f, e` in the `e` half.
+const STATE_ROWS: usize = 4;
+
+/// Columns and selectors of a [`Sha256Chip`].
+#[derive(Debug, Clone)]
+pub struct Sha256Config {
+    /// Bits of `a`, or of a schedule or output word, least significant first.
+    pub a: [Column<Advice>; BITS],
+    /// Bits of `e`, least significant first.
+    pub e: [Column<Advice>; BITS],
+    /// The bits of each half, packed into a word.
+    pub word: [Column<Advice>; 2],
+    /// The schedule word of a round, or the state word a feed-forward row adds.
+    pub w: Column<Advice>,
+    /// Carry bits out of `a'`, then out of `e'`. The schedule and the
+    /// feed-forward use the first ones.
+    pub carry: [Column<Advice>; 6],
+    /// The round constant of each round row.
+    pub round_constant: Column<Fixed>,
+    /// `2^(32 - 8 * bytes)` and the padding bits of a last message word with
+    /// `bytes` message bytes.
+    pub shift: Column<Fixed>,
+    pub padding: Column<Fixed>,
+    pub q_a: Selector,
+    pub q_e: Selector,
+    pub q_round: Selector,
+    pub q_schedule: Selector,
+    pub q_feed: Selector,
+    pub q_pad: Selector,
+}
+
+/// The SHA-256 compression function, on words decomposed into bits.
+///
+/// Every word the chip touches is spread over 32 boolean cells of one row and
+/// packed back into a word cell by the "sha256 a bits" or "sha256 e bits"
+/// gate. Rotations and shifts are then only a matter of which bit column a gate
+/// reads, and the bitwise functions are polynomials in the bits:
+///
+/// ```text
+/// x ^ y ^ z  = x + y + z - 2 * (xy + yz + zx) + 4 * xyz
+/// Ch(e,f,g)  = e * (f - g) + g
+/// Maj(a,b,c) = ab + bc + ca - 2 * abc
+/// ```
+///
+/// The rounds take one row each, with `a` and `e` of the current state in the
+/// two halves. `b, c, d` are `a` of the three rows above, and `f, g, h` are
+/// `e` of those rows, so the round gate reads them with negative rotations and
+/// checks the next row:
+///
+/// ```text
+/// T1 = h + Σ1(e) + Ch(e, f, g) + K + W
+/// T2 = Σ0(a) + Maj(a, b, c)
+/// e' + 2^32 * carry_e == d + T1
+/// a' + 2^32 * carry_a == T1 + T2
+/// ```
+///
+/// with each carry below 8 as three bits. Since the packed words are below
+/// `2^32`, none of these sums wraps around the field. The message schedule is
+/// laid out the same way, one word per row, with
+///
+/// ```text
+/// W_t + 2^32 * carry == σ1(W_{t-2}) + W_{t-7} + σ0(W_{t-15}) + W_{t-16}
+/// ```
+///
+/// and the feed-forward adds the input state to the output of the last round,
+/// one word per row.
+///
+/// A message that does not end on a word boundary shares its last word with
+/// the padding. [`Sha256Chip::load_last_word`] puts that word in the `a` half
+/// and the message bytes in it in the `e` half, and checks
+///
+/// ```text
+/// word == bytes * shift + padding
+/// ```
+///
+/// As both halves are below `2^32`, `bytes` fits in the high bits and the low
+/// bits are exactly the padding.
+///
+/// This needs no lookup table: wide rows buy a small `k`.
+#[derive(Debug, Clone)]
+pub struct Sha256Chip<F: PrimeField> {
+    config: Sha256Config,
+    _marker: PhantomData<F>,
+}
+
+/// `x`, with its bits as expressions, least significant first.
+fn pack<F: PrimeField>(bits: &[Expression<F>]) -> Expression<F> {
+    bits.iter()
+        .rev()
+        .fold(Expression::Constant(F::ZERO), |acc, bit| {
+            acc * Expression::Constant(F::from(2)) + bit.clone()
+        })
+}
+
+fn xor<F: PrimeField>(x: Expression<F>, y: Expression<F>) -> Expression<F> {
+    x.clone() + y.clone() - Expression::Constant(F::from(2)) * x * y
+}
+
+fn xor3<F: PrimeField>(x: Expression<F>, y: Expression<F>, z: Expression<F>) -> Expression<F> {
+    let two = Expression::Constant(F::from(2));
+    let four = Expression::Constant(F::from(4));
+    x.clone() + y.clone() + z.clone()
+        - two * (x.clone() * y.clone() + y.clone() * z.clone() + z.clone() * x.clone())
+        + four * x * y * z
+}
+
+/// `Σ(x)`: `x` rotated right by each of `r`, XORed together.
+fn big_sigma<F: PrimeField>(x: &[Expression<F>], r: [usize; 3]) -> Expression<F> {
+    let bits: Vec<_> = (0..BITS)
+        .map(|i| r.map(|r| x[(i + r) % BITS].clone()))
+        .map(|[x, y, z]| xor3(x, y, z))
+        .collect();
+    pack(&bits)
+}
+
+/// `σ(x)`: `x` rotated right by `r[0]` and `r[1]` and shifted right by
+/// `shift`, XORed together.
+fn small_sigma<F: PrimeField>(x: &[Expression<F>], r: [usize; 2], shift: usize) -> Expression<F> {
+    let bits: Vec<_> = (0..BITS)
+        .map(|i| {
+            let [y, z] = r.map(|r| x[(i + r) % BITS].clone());
+            match x.get(i + shift) {
+                Some(shifted) => xor3(y, z, shifted.clone()),
+                None => xor(y, z),
+            }
+        })
+        .collect();
+    pack(&bits)
+}
+
+fn bool_constraint<F: PrimeField>(q: &Expression<F>, bit: Expression<F>) -> Expression<F> {
+    q.clone() * bit.clone() * (Expression::Constant(F::ONE) - bit)
+}
+
+impl<F: PrimeField> Sha256Chip<F> {
+    pub fn construct(config: Sha256Config) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Allocates the chip's 73 advice columns, four fixed columns and its gates.
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Sha256Config {
+        let a = [(); BITS].map(|_| meta.advice_column());
+        let e = [(); BITS].map(|_| meta.advice_column());
+        let word = [(); 2].map(|_| meta.advice_column());
+        let w = meta.advice_column();
+        let carry = [(); 6].map(|_| meta.advice_column());
+        let round_constant = meta.fixed_column();
+        let shift = meta.fixed_column();
+        let padding = meta.fixed_column();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+        for column in word.into_iter().chain([w]) {
+            meta.enable_equality(column);
+        }
+
+        let q_a = meta.selector();
+        let q_e = meta.selector();
+        let q_round = meta.selector();
+        let q_schedule = meta.selector();
+        let q_feed = meta.selector();
+        let q_pad = meta.selector();
+        let two_pow_32 = || Expression::Constant(F::from(1 << 32));
+
+        for (name, q, bits, word) in [
+            ("sha256 a bits", q_a, a, word[0]),
+            ("sha256 e bits", q_e, e, word[1]),
+        ] {
+            meta.create_gate(name, |meta| {
+                let q = meta.query_selector(q);
+                let bits = bits.map(|column| meta.query_advice(column, Rotation::cur()));
+                let word = meta.query_advice(word, Rotation::cur());
+                let mut constraints: Vec<_> = bits
+                    .iter()
+                    .map(|bit| bool_constraint(&q, bit.clone()))
+                    .collect();
+                constraints.push(q * (pack(&bits) - word));
+                constraints
+            });
+        }
+
+        meta.create_gate("sha256 round", |meta| {
+            let q = meta.query_selector(q_round);
+            let [a_bits, b_bits, c_bits] =
+                [0, -1, -2].map(|at| a.map(|column| meta.query_advice(column, Rotation(at))));
+            let [e_bits, f_bits, g_bits] =
+                [0, -1, -2].map(|at| e.map(|column| meta.query_advice(column, Rotation(at))));
+            let [d, h] = word.map(|column| meta.query_advice(column, Rotation(-3)));
+            let [a_next, e_next] = word.map(|column| meta.query_advice(column, Rotation::next()));
+            let carry = carry.map(|column| meta.query_advice(column, Rotation::cur()));
+            let k = meta.query_fixed(round_constant);
+            let w = meta.query_advice(w, Rotation::cur());
+
+            let ch: Vec<_> = (0..BITS)
+                .map(|i| {
+                    e_bits[i].clone() * (f_bits[i].clone() - g_bits[i].clone()) + g_bits[i].clone()
+                })
+                .collect();
+            let maj: Vec<_> = (0..BITS)
+                .map(|i| {
+                    let (x, y, z) = (a_bits[i].clone(), b_bits[i].clone(), c_bits[i].clone());
+                    x.clone() * y.clone() + y.clone() * z.clone() + z.clone() * x.clone()
+                        - Expression::Constant(F::from(2)) * x * y * z
+                })
+                .collect();
+            let t1 = h + big_sigma(&e_bits, [6, 11, 25]) + pack(&ch) + k + w;
+            let t2 = big_sigma(&a_bits, [2, 13, 22]) + pack(&maj);
+            let [carry_a, carry_e] = [&carry[..3], &carry[3..]].map(pack);
+
+            let mut constraints: Vec<_> = carry
+                .iter()
+                .map(|bit| bool_constraint(&q, bit.clone()))
+                .collect();
+            constraints.push(q.clone() * (e_next + two_pow_32() * carry_e - d - t1.clone()));
+            constraints.push(q * (a_next + two_pow_32() * carry_a - t1 - t2));
+            constraints
+        });
+
+        meta.create_gate("sha256 schedule", |meta| {
+            let q = meta.query_selector(q_schedule);
+            let [w_2, w_15] =
+                [-2, -15].map(|at| a.map(|column| meta.query_advice(column, Rotation(at))));
+            let [w, w_7, w_16] = [0, -7, -16].map(|at| meta.query_advice(word[0], Rotation(at)));
+            let carry = carry[..2]
+                .iter()
+                .map(|&column| meta.query_advice(column, Rotation::cur()))
+                .collect::<Vec<_>>();
+
+            let sum = small_sigma(&w_2, [17, 19], 10) + w_7 + small_sigma(&w_15, [7, 18], 3) + w_16;
+            let mut constraints: Vec<_> = carry
+                .iter()
+                .map(|bit| bool_constraint(&q, bit.clone()))
+                .collect();
+            constraints.push(q * (w + two_pow_32() * pack(&carry) - sum));
+            constraints
+        });
+
+        meta.create_gate("sha256 feed forward", |meta| {
+            let q = meta.query_selector(q_feed);
+            let [out, input] = word.map(|column| meta.query_advice(column, Rotation::cur()));
+            let last = meta.query_advice(w, Rotation::cur());
+            let carry = meta.query_advice(carry[0], Rotation::cur());
+            vec![
+                bool_constraint(&q, carry.clone()),
+                q * (out + two_pow_32() * carry - input - last),
+            ]
+        });
+
+        meta.create_gate("sha256 pad", |meta| {
+            let q = meta.query_selector(q_pad);
+            let [word, bytes] = word.map(|column| meta.query_advice(column, Rotation::cur()));
+            let shift = meta.query_fixed(shift);
+            let padding = meta.query_fixed(padding);
+            vec![q * (word - bytes * shift - padding)]
+        });
+
+        Sha256Config {
+            a,
+            e,
+            word,
+            w,
+            carry,
+            round_constant,
+            shift,
+            padding,
+            q_a,
+            q_e,
+            q_round,
+            q_schedule,
+            q_feed,
+            q_pad,
+        }
+    }
+
+    /// Witnesses words, one per row, each decomposed into bits.
+    pub fn load(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<u32>],
+    ) -> Result<Vec<AssignedWord<F>>, Error> {
+        layouter.assign_region(
+            || "sha256 words",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(row, &value)| {
+                        let cell = self.assign_word(&mut region, 0, row, value)?;
+                        Ok(AssignedWord { cell })
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Words fixed at keygen, such as [`INITIAL_STATE`] or a padding block.
+    pub fn load_constants(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[u32],
+    ) -> Result<Vec<AssignedWord<F>>, Error> {
+        layouter.assign_region(
+            || "sha256 constants",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(row, &value)| {
+                        let cell = self.assign_word(&mut region, 0, row, Value::known(value))?;
+                        region.constrain_constant(cell.cell(), F::from(u64::from(value)))?;
+                        Ok(AssignedWord { cell })
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Witnesses the last word of a message that ends `bytes` bytes into it,
+    /// `1 <= bytes <= 3`, from those bytes as a big-endian integer. The rest of
+    /// the word is the start of the padding: a 1 bit and zeros.
+    pub fn load_last_word(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tail: Value<u32>,
+        bytes: usize,
+    ) -> Result<AssignedWord<F>, Error> {
+        assert!(
+            (1..4).contains(&bytes),
+            "a last word holds 1 to 3 message bytes"
+        );
+        let config = &self.config;
+        let shift = 1u64 << (32 - 8 * bytes);
+        let padding = 0x80u64 << (24 - 8 * bytes);
+        layouter.assign_region(
+            || "sha256 last message word",
+            |mut region| {
+                config.q_pad.enable(&mut region, 0)?;
+                region.assign_fixed(
+                    || "shift",
+                    config.shift,
+                    0,
+                    || Value::known(F::from(shift)),
+                )?;
+                region.assign_fixed(
+                    || "padding",
+                    config.padding,
+                    0,
+                    || Value::known(F::from(padding)),
+                )?;
+                let word = tail.map(|tail| (u64::from(tail) * shift + padding) as u32);
+                let cell = self.assign_word(&mut region, 0, 0, word)?;
+                self.assign_word(&mut region, 1, 0, tail)?;
+                Ok(AssignedWord { cell })
+            },
+        )
+    }
+
+    /// Compresses `block` into `state` and returns the new state.
+    pub fn compress(
+        &self,
+        mut layouter: impl Layouter<F>,
+        state: &[AssignedWord<F>; 8],
+        block: &[AssignedWord<F>; 16],
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        let schedule = self.schedule(layouter.namespace(|| "schedule"), block)?;
+        let last = self.rounds(layouter.namespace(|| "rounds"), state, &schedule)?;
+        self.feed_forward(layouter.namespace(|| "feed forward"), state, &last)
+    }
+
+    /// Lays out `W_0` to `W_63`, the first 16 copied from `block`.
+    fn schedule(
+        &self,
+        mut layouter: impl Layouter<F>,
+        block: &[AssignedWord<F>; 16],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let words: Value<Vec<u32>> = block.iter().map(|word| word.value()).collect();
+        let schedule = words.map(|words| message_schedule(&words.try_into().unwrap()));
+
+        layouter.assign_region(
+            || "message schedule",
+            |mut region| {
+                (0..64)
+                    .map(|t| {
+                        let value = schedule.map(|(words, _)| words[t]);
+                        let cell = self.assign_word(&mut region, 0, t, value)?;
+                        if t < 16 {
+                            region.constrain_equal(cell.cell(), block[t].cell.cell())?;
+                        } else {
+                            self.config.q_schedule.enable(&mut region, t)?;
+                            let carry = schedule.map(|(_, carries)| carries[t]);
+                            self.assign_carry(&mut region, &self.config.carry[..2], t, carry)?;
+                        }
+                        Ok(cell)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Lays out the 64 rounds on `state` and returns the state after the last.
+    fn rounds(
+        &self,
+        mut layouter: impl Layouter<F>,
+        state: &[AssignedWord<F>; 8],
+        schedule: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedCell<F, F>; 8], Error> {
+        let config = &self.config;
+        let initial: Value<Vec<u32>> = state.iter().map(|word| word.value()).collect();
+        let words: Value<Vec<u32>> = schedule
+            .iter()
+            .map(|cell| cell.value().map(|v| to_u64(v) as u32))
+            .collect();
+        let rounds = initial.zip(words).map(|(initial, words)| {
+            let mut state: [u32; 8] = initial.try_into().unwrap();
+            (0..64)
+                .map(|t| {
+                    let sums = round_sums(&state, ROUND_CONSTANTS[t], words[t]);
+                    state = next_state(&state, sums);
+                    sums
+                })
+                .collect::<Vec<_>>()
+        });
+
+        layouter.assign_region(
+            || "compression rounds",
+            |mut region| {
+                // A row's `a` and `e` words, by row.
+                let mut rows = vec![];
+                for j in 0..STATE_ROWS {
+                    let row = STATE_ROWS - 1 - j;
+                    let [a, e] = [j, 4 + j].map(|i| &state[i]);
+                    let a = self.assign_word(&mut region, 0, row, a.value())?;
+                    let e = self.assign_word(&mut region, 1, row, e.value())?;
+                    region.constrain_equal(a.cell(), state[j].cell.cell())?;
+                    region.constrain_equal(e.cell(), state[4 + j].cell.cell())?;
+                    rows.push((row, [a, e]));
+                }
+                rows.sort_by_key(|(row, _)| *row);
+                let mut rows: Vec<_> = rows.into_iter().map(|(_, words)| words).collect();
+
+                for t in 0..64 {
+                    let row = STATE_ROWS - 1 + t;
+                    config.q_round.enable(&mut region, row)?;
+                    region.assign_fixed(
+                        || "K",
+                        config.round_constant,
+                        row,
+                        || Value::known(F::from(u64::from(ROUND_CONSTANTS[t]))),
+                    )?;
+                    schedule[t].copy_advice(|| "W", &mut region, config.w, row)?;
+
+                    let sums = rounds.as_ref().map(|rounds| rounds[t]);
+                    let carry_a = sums.map(|(a, _)| a >> 32);
+                    let carry_e = sums.map(|(_, e)| e >> 32);
+                    self.assign_carry(&mut region, &config.carry[..3], row, carry_a)?;
+                    self.assign_carry(&mut region, &config.carry[3..], row, carry_e)?;
+
+                    let a =
+                        self.assign_word(&mut region, 0, row + 1, sums.map(|(a, _)| a as u32))?;
+                    let e =
+                        self.assign_word(&mut region, 1, row + 1, sums.map(|(_, e)| e as u32))?;
+                    rows.push([a, e]);
+                }
+
+                // a, b, c, d are `a` of the last four rows, newest first, and
+                // e, f, g, h are `e` of the same rows.
+                let last: Vec<_> = rows.iter().rev().take(4).collect();
+                Ok(std::array::from_fn(|i| last[i % 4][i / 4].clone()))
+            },
+        )
+    }
+
+    /// Adds `last` to `state` word by word, mod `2^32`.
+    fn feed_forward(
+        &self,
+        mut layouter: impl Layouter<F>,
+        state: &[AssignedWord<F>; 8],
+        last: &[AssignedCell<F, F>; 8],
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "feed forward",
+            |mut region| {
+                let words = (0..8)
+                    .map(|i| {
+                        config.q_feed.enable(&mut region, i)?;
+                        state[i]
+                            .cell
+                            .copy_advice(|| "input", &mut region, config.word[1], i)?;
+                        last[i].copy_advice(|| "last", &mut region, config.w, i)?;
+
+                        let sum = state[i]
+                            .value()
+                            .zip(last[i].value())
+                            .map(|(a, b)| u64::from(a) + to_u64(b));
+                        self.assign_carry(
+                            &mut region,
+                            &config.carry[..1],
+                            i,
+                            sum.map(|s| s >> 32),
+                        )?;
+                        let cell = self.assign_word(&mut region, 0, i, sum.map(|s| s as u32))?;
+                        Ok(AssignedWord { cell })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(words.try_into().unwrap())
+            },
+        )
+    }
+
+    /// Assigns `value` on `row` of the `a` half, `half = 0`, or the `e` half,
+    /// `half = 1`: its bits and the packed word, which it returns.
+    fn assign_word(
+        &self,
+        region: &mut Region<'_, F>,
+        half: usize,
+        row: usize,
+        value: Value<u32>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        let (q, bits) = [(config.q_a, config.a), (config.q_e, config.e)][half];
+        q.enable(region, row)?;
+        for (i, column) in bits.into_iter().enumerate() {
+            let bit = value.map(|v| F::from(u64::from(v >> i & 1)));
+            region.assign_advice(|| "bit", column, row, || bit)?;
+        }
+        let word = value.map(|v| F::from(u64::from(v)));
+        region.assign_advice(|| "word", config.word[half], row, || word)
+    }
+
+    /// Assigns the bits of `carry` to `columns` on `row`.
+    fn assign_carry(
+        &self,
+        region: &mut Region<'_, F>,
+        columns: &[Column<Advice>],
+        row: usize,
+        carry: Value<u64>,
+    ) -> Result<(), Error> {
+        for (i, &column) in columns.iter().enumerate() {
+            let bit = carry.map(|c| F::from(c >> i & 1));
+            region.assign_advice(|| "carry", column, row, || bit)?;
+        }
+        Ok(())
+    }
+}
+
+/// The 64 schedule words of `block`, with the carry out of each sum.
+fn message_schedule(block: &[u32; 16]) -> ([u32; 64], [u64; 64]) {
+    let mut words = [0; 64];
+    let mut carries = [0; 64];
+    words[..16].copy_from_slice(block);
+    for t in 16..64 {
+        let s0 =
+            words[t - 15].rotate_right(7) ^ words[t - 15].rotate_right(18) ^ (words[t - 15] >> 3);
+        let s1 =
+            words[t - 2].rotate_right(17) ^ words[t - 2].rotate_right(19) ^ (words[t - 2] >> 10);
+        let sum = [s1, words[t - 7], s0, words[t - 16]]
+            .iter()
+            .map(|&w| u64::from(w))
+            .sum::<u64>();
+        words[t] = sum as u32;
+        carries[t] = sum >> 32;
+    }
+    (words, carries)
+}
+
+/// `(T1 + T2, d + T1)` of one round, before reduction mod `2^32`.
+fn round_sums(state: &[u32; 8], k: u32, w: u32) -> (u64, u64) {
+    let [a, b, c, d, e, f, g, h] = state.map(u64::from);
+    let (a32, e32) = (state[0], state[4]);
+    let sigma1 = u64::from(e32.rotate_right(6) ^ e32.rotate_right(11) ^ e32.rotate_right(25));
+    let ch = (e & f) ^ (!e & 0xffff_ffff & g);
+    let t1 = h + sigma1 + ch + u64::from(k) + u64::from(w);
+    let sigma0 = u64::from(a32.rotate_right(2) ^ a32.rotate_right(13) ^ a32.rotate_right(22));
+    let maj = (a & b) ^ (a & c) ^ (b & c);
+    (t1 + sigma0 + maj, d + t1)
+}
+
+fn next_state(state: &[u32; 8], (a, e): (u64, u64)) -> [u32; 8] {
+    let [old_a, b, c, _, old_e, f, g, _] = *state;
+    [a as u32, old_a, b, c, e as u32, old_e, f, g]
+}
+
+/// Plain-Rust reference: the compression function.
+pub fn compress(state: &[u32; 8], block: &[u32; 16]) -> [u32; 8] {
+    let (words, _) = message_schedule(block);
+    let last = (0..64).fold(*state, |s, t| {
+        next_state(&s, round_sums(&s, ROUND_CONSTANTS[t], words[t]))
+    });
+    std::array::from_fn(|i| state[i].wrapping_add(last[i]))
+}
@@ -0,0 +1,70 @@
+//! SHA-256: [`Sha256Chip`] runs the compression function, and `preimage`
+//! proves knowledge of a preimage of a public digest with it, for any message
+//! length.
+//!
+//! halo2_gadgets has a SHA-256 chip, table16, but it takes and returns words as
+//! bare values rather than assigned cells, so its digest cannot be bound to an
+//! instance column. The chip here keeps every word a
+//! [`crate::word32::AssignedWord`].
+
+mod chip;
+mod preimage;
+
+pub(crate) use chip::{compress, Sha256Chip, Sha256Config, INITIAL_STATE};
+
+use crate::registry::Example;
+
+/// Bytes in a block.
+pub const BLOCK_BYTES: usize = 64;
+
+/// Pads `message` to whole blocks, as big-endian words: a 1 bit, zeros up to 8
+/// bytes short of a block boundary, and the length in bits as a big-endian
+/// 64-bit integer.
+pub fn pad(message: &[u8]) -> Vec<[u32; 16]> {
+    let mut bytes = message.to_vec();
+    bytes.push(0x80);
+    while bytes.len() % BLOCK_BYTES != BLOCK_BYTES - 8 {
+        bytes.push(0);
+    }
+    bytes.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+    bytes
+        .chunks(BLOCK_BYTES)
+        .map(|block| {
+            std::array::from_fn(|i| u32::from_be_bytes(block[4 * i..4 * i + 4].try_into().unwrap()))
+        })
+        .collect()
+}
+
+/// Plain-Rust reference: the SHA-256 digest of `message`, as big-endian words.
+pub fn digest(message: &[u8]) -> [u32; 8] {
+    pad(message)
+        .iter()
+        .fold(INITIAL_STATE, |state, block| compress(&state, block))
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "sha256::preimage",
+    circuit: preimage::example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn digest_matches_sha2() {
+        // Lengths around the padding boundaries: 55 bytes still fit the length
+        // in the same block, 56 do not.
+        for len in [0usize, 3, 55, 56, 63, 64, 100, 128] {
+            let message: Vec<u8> = (0..len).map(|i| (i * 7 + 1) as u8).collect();
+            let expected = Sha256::digest(&message);
+            let words: Vec<_> = digest(&message)
+                .iter()
+                .flat_map(|word| word.to_be_bytes())
+                .collect();
+            assert_eq!(words[..], expected[..], "length {}", len);
+            assert_eq!(pad(&message).len(), (len + 9).div_ceil(BLOCK_BYTES));
+        }
+    }
+}
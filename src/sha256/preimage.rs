@@ -0,0 +1,235 @@
+use super::{digest, pad, Sha256Chip, Sha256Config, BLOCK_BYTES, INITIAL_STATE};
+use crate::{prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// The digest of `message` as public inputs: its eight words, big-endian.
+fn instances(message: &[u8]) -> Vec<Vec<Fp>> {
+    vec![digest(message)
+        .iter()
+        .map(|&word| Fp::from(u64::from(word)))
+        .collect()]
+}
+
+/// Proves knowledge of a `len`-byte message whose SHA-256 digest is public.
+///
+/// The length is part of the circuit, and with it the number of blocks and
+/// where the padding starts. Whole message words are private. So is a last
+/// word the message only partly fills, which
+/// [`Sha256Chip::load_last_word`] completes with the start of the padding.
+/// The remaining padding words only depend on the length, so they are loaded
+/// from constants.
+#[derive(Default)]
+struct MyCircuit {
+    message: Value<Vec<u8>>,
+    len: usize,
+}
+
+impl MyCircuit {
+    fn new(message: &[u8]) -> Self {
+        Self {
+            message: Value::known(message.to_vec()),
+            len: message.len(),
+        }
+    }
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = (Sha256Config, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            message: Value::unknown(),
+            len: self.len,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (Sha256Chip::configure(meta), instance)
+    }
+
+    fn synthesize(
+        &self,
+        (config, instance): Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = Sha256Chip::construct(config);
+        let (full, tail) = (self.len / 4, self.len % 4);
+
+        let values: Vec<_> = (0..full)
+            .map(|i| {
+                self.message.as_ref().map(|message| {
+                    u32::from_be_bytes(message[4 * i..4 * i + 4].try_into().unwrap())
+                })
+            })
+            .collect();
+        let mut words = chip.load(layouter.namespace(|| "message"), &values)?;
+        if tail > 0 {
+            let value = self.message.as_ref().map(|message| {
+                message[4 * full..]
+                    .iter()
+                    .fold(0, |acc, &byte| acc << 8 | u32::from(byte))
+            });
+            words.push(chip.load_last_word(
+                layouter.namespace(|| "last message word"),
+                value,
+                tail,
+            )?);
+        }
+        let padding = pad(&vec![0; self.len]).concat();
+        words.extend(
+            chip.load_constants(layouter.namespace(|| "padding"), &padding[words.len()..])?,
+        );
+
+        let mut state: [_; 8] = chip
+            .load_constants(layouter.namespace(|| "initial state"), &INITIAL_STATE)?
+            .try_into()
+            .unwrap();
+        for (i, block) in words.chunks(16).enumerate() {
+            state = chip.compress(
+                layouter.namespace(|| format!("block {}", i)),
+                &state,
+                &block.to_vec().try_into().unwrap(),
+            )?;
+        }
+        for (row, word) in state.iter().enumerate() {
+            layouter.constrain_instance(word.cell.cell(), instance, row)?;
+        }
+        Ok(())
+    }
+}
+
+/// The circuit on the bytes `0..64`, which pad to two blocks.
+pub fn example_circuit() -> ExampleCircuit {
+    let message: Vec<u8> = (0..BLOCK_BYTES as u8).collect();
+    ExampleCircuit::new(9, MyCircuit::new(&message), instances(&message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+    use rand_core::{OsRng, RngCore};
+    use sha2::{Digest, Sha256};
+
+    fn run(message: &[u8], instances: Vec<Vec<Fp>>) -> MockProver<Fp> {
+        // Three blocks need more than 2^9 rows.
+        let k = 10;
+
+        MockProver::run(k, &MyCircuit::new(message), instances).unwrap()
+    }
+
+    fn words(digest: &[u8]) -> Vec<Fp> {
+        digest
+            .chunks(4)
+            .map(|word| Fp::from(u64::from(u32::from_be_bytes(word.try_into().unwrap()))))
+            .collect()
+    }
+
+    #[test]
+    fn sha256_preimage_known_digests() {
+        // FIPS 180-2 examples, and the digest of the empty message.
+        for (message, expected) in [
+            (
+                &b""[..],
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ] {
+            let expected = words(&hex::decode(expected).unwrap());
+            assert_eq!(instances(message), vec![expected.clone()]);
+            run(message, vec![expected]).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn sha256_preimage_matches_sha2() {
+        // Every offset within a word, both sides of the 55/56-byte boundary
+        // where the length moves to another block, and three blocks.
+        for len in [1, 2, 5, 55, 56, 63, 64, 119, 130] {
+            let mut message = vec![0; len];
+            OsRng.fill_bytes(&mut message);
+            let expected = words(&Sha256::digest(&message));
+            assert_eq!(
+                instances(&message),
+                vec![expected.clone()],
+                "length {}",
+                len
+            );
+            run(&message, vec![expected]).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn sha256_preimage_rejects_wrong_digest() {
+        let message = [0x61; BLOCK_BYTES];
+        let mut columns = instances(&message);
+        columns[0][7] += Fp::ONE;
+        let failures = run(&message, columns).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+
+        // The digest of another message, and of the same bytes one shorter.
+        assert!(run(&message, instances(&[0x62; BLOCK_BYTES]))
+            .verify()
+            .is_err());
+        assert!(run(&message, instances(&message[1..])).verify().is_err());
+    }
+
+    /// Loads `tail` as the last word of a message ending one byte into it.
+    struct LastWordCircuit {
+        tail: u32,
+    }
+
+    impl Circuit<Fp> for LastWordCircuit {
+        type Config = Sha256Config;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { tail: 0 }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            Sha256Chip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            Sha256Chip::construct(config).load_last_word(layouter, Value::known(self.tail), 1)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sha256_last_word_rejects_long_tail() {
+        let k = 4;
+
+        let prover = MockProver::run(k, &LastWordCircuit { tail: 0x61 }, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // Two bytes do not fit in front of the padding of a one-byte tail.
+        let prover = MockProver::run(k, &LastWordCircuit { tail: 0x6162 }, vec![]).unwrap();
+        crate::testing::assert_constraint_fails(&prover, "sha256 pad");
+    }
+
+    #[test]
+    fn sha256_preimage_structure() {
+        let structure = crate::inspect::inspect::<Fp, MyCircuit>();
+        assert_eq!(structure.instance_columns, 1);
+        assert_eq!(structure.advice_columns, 73);
+        assert!(structure.lookups.is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+//! Rearrangement check: proves that one column of cells is a rearrangement of
+//! another, as multisets, by routing the first through a switching network.
+//!
+//! This is not a shuffle argument. Newer halo2 versions have `meta.shuffle`,
+//! and a grand-product argument compares `prod(gamma - a_i)` with
+//! `prod(gamma - b_i)` at a random `gamma` the verifier picks after the
+//! columns are committed. halo2_proofs 0.3 has neither a shuffle API nor
+//! verifier challenges, and a grand product at a fixed `gamma` is unsound: the
+//! prover knows `gamma` before choosing the columns, so it can pick different
+//! multisets with equal products. Instead the second column is checked to be
+//! the output of a [`SortingNetworkChip`] over the first, as in `memory`. That
+//! costs `n` rounds of `n` rows rather than one extra column.
+
+use halo2_proofs::pasta::Fp;
+
+use crate::{
+    common::{sorting_switches, SortingNetworkChip, SortingNetworkConfig},
+    prelude::*,
+    registry::{Example, ExampleCircuit},
+};
+
+/// `[input, output, switch]` of a network round.
+#[derive(Debug, Clone)]
+pub struct ShuffleConfig {
+    pub advice: [Column<Advice>; 3],
+    pub network: SortingNetworkConfig<1>,
+}
+
+/// Checks that two equally long lists of cells hold the same multiset.
+#[derive(Debug, Clone)]
+pub struct ShuffleChip<F: Field> {
+    config: ShuffleConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> ShuffleChip<F> {
+    pub fn construct(config: ShuffleConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> ShuffleConfig {
+        let [input, output, switch] = advice;
+        let network = SortingNetworkChip::configure(meta, [input], [output], switch);
+        ShuffleConfig { advice, network }
+    }
+
+    /// Constrains `b` to be a rearrangement of `a`. The witness switches
+    /// route each value of `a` to an equal one in `b`; if there is none, the
+    /// final copy into `b` fails.
+    pub fn shuffle(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[AssignedCell<F, F>],
+        b: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        if a.len() != b.len() {
+            return Err(Error::Synthesis);
+        }
+
+        let values = |cells: &[AssignedCell<F, F>]| {
+            cells.iter().fold(Value::known(vec![]), |values, cell| {
+                values.zip(cell.value()).map(|(mut values, &value)| {
+                    values.push(value);
+                    values
+                })
+            })
+        };
+        let switches = values(a)
+            .zip(values(b))
+            .map(|(a, b)| sorting_switches(&targets(&a, &b)));
+
+        let rows: Vec<_> = a.iter().map(|cell| [cell.clone()]).collect();
+        let network = SortingNetworkChip::construct(self.config.network.clone());
+        let rows = network.permute(layouter.namespace(|| "network"), &rows, &switches)?;
+
+        layouter.assign_region(
+            || "shuffle output",
+            |mut region| {
+                for ([out], b) in rows.iter().zip(b) {
+                    region.constrain_equal(out.cell(), b.cell())?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// For each value of `a`, the position in `b` it should end up at: the first
+/// unused equal value, or failing that the first unused position.
+fn targets<F: PartialEq>(a: &[F], b: &[F]) -> Vec<usize> {
+    let mut used = vec![false; b.len()];
+    let mut targets: Vec<Option<usize>> = a
+        .iter()
+        .map(|value| {
+            let j = (0..b.len()).find(|&j| !used[j] && b[j] == *value)?;
+            used[j] = true;
+            Some(j)
+        })
+        .collect();
+    for target in targets.iter_mut().filter(|target| target.is_none()) {
+        let j = used.iter().position(|&used| !used).unwrap();
+        used[j] = true;
+        *target = Some(j);
+    }
+    targets.into_iter().flatten().collect()
+}
+
+/// Witnesses two private vectors and proves that `b` is a shuffle of `a`.
+#[derive(Default)]
+struct MyCircuit {
+    a: Vec<u64>,
+    b: Vec<u64>,
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = ShuffleConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            b: self.b.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        ShuffleChip::configure(meta, advice)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let [a, b] =
+            [(&self.a, config.advice[0]), (&self.b, config.advice[1])].map(|(values, column)| {
+                layouter.assign_region(
+                    || "vector",
+                    |mut region| {
+                        values
+                            .iter()
+                            .enumerate()
+                            .map(|(row, &v)| {
+                                region.assign_advice(
+                                    || "value",
+                                    column,
+                                    row,
+                                    || Value::known(Fp::from(v)),
+                                )
+                            })
+                            .collect::<Result<Vec<_>, Error>>()
+                    },
+                )
+            });
+
+        let chip = ShuffleChip::construct(config);
+        chip.shuffle(layouter.namespace(|| "shuffle"), &a?, &b?)
+    }
+}
+
+/// The circuit on a vector with repeated values.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit {
+        a: vec![5, 3, 8, 3, 1, 9, 5, 2],
+        b: vec![9, 5, 1, 3, 2, 5, 8, 3],
+    };
+    ExampleCircuit::new(7, circuit, vec![])
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "shuffle::network",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+
+    fn run(a: &[u64], b: &[u64]) -> MockProver<Fp> {
+        let k = 7;
+        let circuit = MyCircuit {
+            a: a.to_vec(),
+            b: b.to_vec(),
+        };
+
+        MockProver::run(k, &circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn shuffle_accepts_rearrangements() {
+        let a = [4, 4, 7, 0, 1, 7, 7, 2, 9];
+        for mid in 0..a.len() {
+            let mut b = a;
+            b.rotate_left(mid);
+            b[..mid].reverse();
+            run(&a, &b).assert_satisfied();
+        }
+        run(&[], &[]).assert_satisfied();
+        run(&[6], &[6]).assert_satisfied();
+    }
+
+    #[test]
+    fn shuffle_rejects_different_multisets() {
+        for (a, b) in [
+            ([1, 2, 3, 3], [3, 2, 1, 1]),
+            ([1, 2, 3, 4], [1, 2, 3, 5]),
+            ([0, 0, 0, 0], [0, 0, 0, 1]),
+        ] {
+            let failures = run(&a, &b).verify().unwrap_err();
+            assert!(failures
+                .iter()
+                .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+        }
+    }
+
+    #[test]
+    fn shuffle_rejects_different_lengths() {
+        let circuit = MyCircuit {
+            a: vec![1, 2, 3],
+            b: vec![1, 2],
+        };
+        assert!(MockProver::run(7, &circuit, vec![]).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{prelude::*, range_check::table::RangeTableConfig, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Range-checks a signed value against `[-RANGE/2, RANGE/2)`.
+///
+/// A negative value `-v` is the field element `p - v`, so it cannot be looked up
+/// directly. Adding `RANGE/2` maps the signed range onto `[0, RANGE)` without
+/// wrapping, and the shifted value is looked up in the unsigned range table.
+/// Anything outside the signed range lands outside `[0, RANGE)`: values at or
+/// above `RANGE/2` shift past the top of the table, and values below `-RANGE/2`
+/// stay just under `p`.
+#[derive(Debug, Clone)]
+struct SignedRangeConfig<F: PrimeField, const RANGE: usize> {
+    pub advice: Column<Advice>,
+    pub selector: Selector,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+#[derive(Debug, Clone)]
+struct SignedRangeChip<F: PrimeField, const RANGE: usize> {
+    config: SignedRangeConfig<F, RANGE>,
+}
+
+impl<F: PrimeField, const RANGE: usize> SignedRangeChip<F, RANGE> {
+    pub fn construct(config: SignedRangeConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: Column<Advice>,
+    ) -> SignedRangeConfig<F, RANGE> {
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        let table = RangeTableConfig::configure(meta);
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let value = meta.query_advice(advice, Rotation::cur());
+            let offset = Expression::Constant(F::from((RANGE / 2) as u64));
+            vec![(s * (value + offset), table.value)]
+        });
+
+        SignedRangeConfig {
+            advice,
+            selector,
+            table,
+        }
+    }
+
+    pub fn assign_signed(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "signed value",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", self.config.advice, 0, || value)
+            },
+        )
+    }
+}
+
+/// The field element for a signed integer: `v` itself, or `p - |v|` if negative.
+pub fn signed<F: PrimeField>(value: i64) -> F {
+    if value < 0 {
+        -F::from(value.unsigned_abs())
+    } else {
+        F::from(value as u64)
+    }
+}
+
+/// Plain-Rust reference: whether `value` is in `[-range/2, range/2)`.
+#[cfg(test)]
+pub fn reference(value: i64, range: usize) -> bool {
+    let half = (range / 2) as i64;
+    (-half..half).contains(&value)
+}
+
+#[derive(Default)]
+struct MyCircuit<F, const RANGE: usize> {
+    value: Value<F>,
+}
+
+impl<F: PrimeField, const RANGE: usize> Circuit<F> for MyCircuit<F, RANGE> {
+    type Config = SignedRangeConfig<F, RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        SignedRangeChip::configure(meta, advice)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let chip = SignedRangeChip::construct(config);
+
+        chip.assign_signed(layouter.namespace(|| "value"), self.value)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on -5 in the 8-bit signed range.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp, 256> {
+        value: Value::known(signed(-5)),
+    };
+    ExampleCircuit::new(9, circuit, vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+
+    fn run(value: i64) -> MockProver<Fp> {
+        let k = 9;
+
+        let circuit = MyCircuit::<Fp, 256> {
+            value: Value::known(signed(value)),
+        };
+        MockProver::run(k, &circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn signed_range_accepts_in_range() {
+        for value in [-5, 5, 0, -128, 127] {
+            assert!(reference(value, 256));
+            run(value).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn signed_range_rejects_out_of_range() {
+        for value in [128, -129, 1000, -1000] {
+            assert!(!reference(value, 256));
+            let failures = run(value).verify().unwrap_err();
+            assert!(failures
+                .iter()
+                .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use super::{SudokuChip, SudokuConfig, GROUP};
+use crate::{prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// A Sudoku grid by rows. In a puzzle, 0 marks an empty cell.
+pub type Board = [[u64; GROUP]; GROUP];
+
+/// The 27 groups of a board, as lists of `(row, column)` positions: the rows,
+/// then the columns, then the boxes.
+fn groups() -> Vec<[(usize, usize); GROUP]> {
+    let rows = (0..GROUP).map(|r| std::array::from_fn(|i| (r, i)));
+    let columns = (0..GROUP).map(|c| std::array::from_fn(|i| (i, c)));
+    let boxes = (0..GROUP).map(|b| std::array::from_fn(|i| (b / 3 * 3 + i / 3, b % 3 * 3 + i % 3)));
+    rows.chain(columns).chain(boxes).collect()
+}
+
+/// Plain-Rust reference: whether `solution` is a complete grid that agrees with
+/// every clue of `puzzle`.
+#[cfg(test)]
+pub fn solves(puzzle: &Board, solution: &Board) -> bool {
+    let agrees =
+        (0..GROUP).all(|r| (0..GROUP).all(|c| puzzle[r][c] == 0 || puzzle[r][c] == solution[r][c]));
+    agrees
+        && groups()
+            .iter()
+            .all(|group| super::reference(&group.map(|(r, c)| solution[r][c])))
+}
+
+/// The puzzle as public inputs: the 81 clues by rows, 0 for an empty cell.
+pub fn instances(puzzle: &Board) -> Vec<Vec<Fp>> {
+    vec![puzzle
+        .iter()
+        .flatten()
+        .map(|&clue| Fp::from(clue))
+        .collect()]
+}
+
+#[derive(Debug, Clone)]
+struct BoardConfig<F: PrimeField> {
+    sudoku: SudokuConfig<F>,
+    instance: Column<Instance>,
+    selector_clue: Selector,
+}
+
+/// Proves that a private solution solves a public puzzle.
+///
+/// The 81 solution cells are range-checked once, and each of the 27 groups
+/// copies them into the pairwise distinctness check of [`SudokuChip`]. Each
+/// clue is copied from the instance column next to its cell, and the gate
+/// `clue * (value - clue) = 0` holds the cell to the clue unless it is 0.
+struct MyCircuit<F> {
+    solution: [[Value<F>; GROUP]; GROUP],
+}
+
+impl<F: PrimeField> MyCircuit<F> {
+    pub fn new(solution: &Board) -> Self {
+        Self {
+            solution: solution.map(|row| row.map(|value| Value::known(F::from(value)))),
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
+    type Config = BoardConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            solution: [[Value::unknown(); GROUP]; GROUP],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let pair = [meta.advice_column(), meta.advice_column()];
+        let inverse = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let selector_clue = meta.selector();
+
+        let sudoku = SudokuChip::configure(meta, value, pair, inverse);
+
+        meta.create_gate("clue", |meta| {
+            //
+            // col_x | col_y | selector
+            //  clue   value      s
+            //
+            let s = meta.query_selector(selector_clue);
+            let clue = meta.query_advice(pair[0], Rotation::cur());
+            let value = meta.query_advice(pair[1], Rotation::cur());
+            vec![s * clue.clone() * (value - clue)]
+        });
+
+        BoardConfig {
+            sudoku,
+            instance,
+            selector_clue,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.sudoku.table.load(&mut layouter)?;
+        let chip = SudokuChip::construct(config.sudoku.clone());
+
+        let values: Vec<_> = self.solution.iter().flatten().copied().collect();
+        let cells = chip.assign_values(layouter.namespace(|| "solution"), &values)?;
+
+        let [col_clue, col_value] = config.sudoku.pair;
+        layouter.assign_region(
+            || "clues",
+            |mut region| {
+                for (row, cell) in cells.iter().enumerate() {
+                    config.selector_clue.enable(&mut region, row)?;
+                    region.assign_advice_from_instance(
+                        || "clue",
+                        config.instance,
+                        row,
+                        col_clue,
+                        row,
+                    )?;
+                    cell.copy_advice(|| "value", &mut region, col_value, row)?;
+                }
+                Ok(())
+            },
+        )?;
+
+        for (i, group) in groups().iter().enumerate() {
+            let group: Vec<_> = group
+                .iter()
+                .map(|&(r, c)| cells[r * GROUP + c].clone())
+                .collect();
+            chip.distinct(layouter.namespace(|| format!("group {}", i)), &group)?;
+        }
+
+        Ok(())
+    }
+}
+
+const PUZZLE: Board = [
+    [5, 3, 0, 0, 7, 0, 0, 0, 0],
+    [6, 0, 0, 1, 9, 5, 0, 0, 0],
+    [0, 9, 8, 0, 0, 0, 0, 6, 0],
+    [8, 0, 0, 0, 6, 0, 0, 0, 3],
+    [4, 0, 0, 8, 0, 3, 0, 0, 1],
+    [7, 0, 0, 0, 2, 0, 0, 0, 6],
+    [0, 6, 0, 0, 0, 0, 2, 8, 0],
+    [0, 0, 0, 4, 1, 9, 0, 0, 5],
+    [0, 0, 0, 0, 8, 0, 0, 7, 9],
+];
+
+const SOLUTION: Board = [
+    [5, 3, 4, 6, 7, 8, 9, 1, 2],
+    [6, 7, 2, 1, 9, 5, 3, 4, 8],
+    [1, 9, 8, 3, 4, 2, 5, 6, 7],
+    [8, 5, 9, 7, 6, 1, 4, 2, 3],
+    [4, 2, 6, 8, 5, 3, 7, 9, 1],
+    [7, 1, 3, 9, 2, 4, 8, 5, 6],
+    [9, 6, 1, 5, 3, 7, 2, 8, 4],
+    [2, 8, 7, 4, 1, 9, 6, 3, 5],
+    [3, 4, 5, 2, 8, 6, 1, 7, 9],
+];
+
+/// The circuit on a classic puzzle and its solution.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp>::new(&SOLUTION);
+    ExampleCircuit::new(11, circuit, instances(&PUZZLE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn run(puzzle: &Board, solution: &Board) -> MockProver<Fp> {
+        let k = 11;
+
+        MockProver::run(k, &MyCircuit::new(solution), instances(puzzle)).unwrap()
+    }
+
+    #[test]
+    fn sudoku_board_accepts_solution() {
+        assert!(solves(&PUZZLE, &SOLUTION));
+        run(&PUZZLE, &SOLUTION).assert_satisfied();
+    }
+
+    #[test]
+    fn sudoku_board_rejects_solution_of_other_puzzle() {
+        // Relabelling the digits keeps the grid valid but breaks the clues.
+        let solution = SOLUTION.map(|row| row.map(|value| value % 9 + 1));
+        assert!(!solves(&PUZZLE, &solution));
+        assert!(solves(&[[0; GROUP]; GROUP], &solution));
+        assert_constraint_fails(&run(&PUZZLE, &solution), "clue");
+    }
+
+    #[test]
+    fn sudoku_board_rejects_bad_boxes() {
+        // Every row and column is a permutation, but the boxes repeat digits.
+        let latin: Board =
+            std::array::from_fn(|r| std::array::from_fn(|c| ((r + c) % 9 + 1) as u64));
+        let puzzle = [[0; GROUP]; GROUP];
+        assert!(!solves(&puzzle, &latin));
+        assert_constraint_fails(&run(&puzzle, &latin), "distinct");
+    }
+}
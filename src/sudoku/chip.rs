@@ -0,0 +1,153 @@
+use crate::{
+    is_zero::{IsZeroChip, IsZeroConfig},
+    prelude::*,
+    range_check::table::RangeTableConfig,
+};
+
+/// Number of cells in a Sudoku row, column or box.
+pub const GROUP: usize = 9;
+
+/// Checks one Sudoku group (a row, column or box): nine private values, each in
+/// `[1, 9]` and all distinct.
+///
+/// The group sits in one column, nine rows, and each `value - 1` is looked up in
+/// a `0..9` table. Distinctness is checked pairwise: every one of the 36 pairs is
+/// copied into its own row, and the is-zero gadget on `x - y` must report
+/// non-zero. Nine distinct values from `[1, 9]` are exactly a permutation of
+/// `1..=9`.
+#[derive(Debug, Clone)]
+pub struct SudokuConfig<F: PrimeField> {
+    pub value: Column<Advice>,
+    pub pair: [Column<Advice>; 2],
+    pub selector_range: Selector,
+    pub selector_distinct: Selector,
+    pub x_eq_y: IsZeroConfig<F>,
+    pub table: RangeTableConfig<F, GROUP>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SudokuChip<F: PrimeField> {
+    config: SudokuConfig<F>,
+}
+
+impl<F: PrimeField> SudokuChip<F> {
+    pub fn construct(config: SudokuConfig<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        pair: [Column<Advice>; 2],
+        inverse: Column<Advice>,
+    ) -> SudokuConfig<F> {
+        let [col_x, col_y] = pair;
+        // Lookups cannot use simple selectors.
+        let selector_range = meta.complex_selector();
+        let selector_distinct = meta.selector();
+        let table = RangeTableConfig::configure(meta);
+        meta.enable_equality(value);
+        meta.enable_equality(col_x);
+        meta.enable_equality(col_y);
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector_range);
+            let value = meta.query_advice(value, Rotation::cur());
+            vec![(s * (value - Expression::Constant(F::ONE)), table.value)]
+        });
+
+        let x_eq_y = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector_distinct),
+            |meta| {
+                meta.query_advice(col_x, Rotation::cur())
+                    - meta.query_advice(col_y, Rotation::cur())
+            },
+            inverse,
+        );
+
+        meta.create_gate("distinct", |meta| {
+            //
+            // col_x | col_y | inverse     | selector
+            //   x      y     (x - y)^-1        s
+            //
+            let s = meta.query_selector(selector_distinct);
+            vec![s * x_eq_y.expr()]
+        });
+
+        SudokuConfig {
+            value,
+            pair,
+            selector_range,
+            selector_distinct,
+            x_eq_y,
+            table,
+        }
+    }
+
+    /// Assigns the nine values of a group, range-checks them, and constrains
+    /// every pair to differ. Returns the value cells.
+    pub fn assign_group(
+        &self,
+        mut layouter: impl Layouter<F>,
+        group: &[Value<F>; GROUP],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let cells = self.assign_values(layouter.namespace(|| "values"), group)?;
+        self.distinct(layouter.namespace(|| "distinct"), &cells)?;
+        Ok(cells)
+    }
+
+    /// Assigns `values` down the value column, each range-checked to `[1, 9]`.
+    pub fn assign_values(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "group",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(row, value)| {
+                        self.config.selector_range.enable(&mut region, row)?;
+                        region.assign_advice(|| "value", self.config.value, row, || *value)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )
+    }
+
+    /// Constrains every pair of `cells` to differ.
+    pub fn distinct(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        let [col_x, col_y] = self.config.pair;
+        let x_eq_y = IsZeroChip::construct(self.config.x_eq_y.clone());
+        layouter.assign_region(
+            || "distinct",
+            |mut region| {
+                let pairs =
+                    (0..cells.len()).flat_map(|i| (i + 1..cells.len()).map(move |j| (i, j)));
+                for (row, (i, j)) in pairs.enumerate() {
+                    self.config.selector_distinct.enable(&mut region, row)?;
+                    let x = cells[i].copy_advice(|| "x", &mut region, col_x, row)?;
+                    let y = cells[j].copy_advice(|| "y", &mut region, col_y, row)?;
+                    x_eq_y.assign(&mut region, row, x.value().copied() - y.value())?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Plain-Rust reference: whether `group` is a permutation of `1..=9`.
+#[cfg(test)]
+pub fn reference(group: &[u64; GROUP]) -> bool {
+    let mut sorted = *group;
+    sorted.sort_unstable();
+    sorted == [1, 2, 3, 4, 5, 6, 7, 8, 9]
+}
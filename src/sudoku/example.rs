@@ -0,0 +1,95 @@
+use super::{SudokuChip, SudokuConfig, GROUP};
+use crate::{prelude::*, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+struct MyCircuit<F> {
+    group: [Value<F>; GROUP],
+}
+
+impl<F: PrimeField> MyCircuit<F> {
+    pub fn new(group: &[u64; GROUP]) -> Self {
+        Self {
+            group: group.map(|value| Value::known(F::from(value))),
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for MyCircuit<F> {
+    type Config = SudokuConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            group: [Value::unknown(); GROUP],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let pair = [meta.advice_column(), meta.advice_column()];
+        let inverse = meta.advice_column();
+        SudokuChip::configure(meta, value, pair, inverse)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let chip = SudokuChip::construct(config);
+
+        chip.assign_group(layouter.namespace(|| "group"), &self.group)?;
+
+        Ok(())
+    }
+}
+
+/// The circuit on the group `5 3 4 6 7 8 9 1 2`.
+pub fn example_circuit() -> ExampleCircuit {
+    let circuit = MyCircuit::<Fp>::new(&[5, 3, 4, 6, 7, 8, 9, 1, 2]);
+    ExampleCircuit::new(6, circuit, vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::reference;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    fn run(group: &[u64; GROUP]) -> MockProver<Fp> {
+        let k = 6;
+
+        MockProver::run(k, &MyCircuit::new(group), vec![]).unwrap()
+    }
+
+    #[test]
+    fn sudoku_accepts_permutation() {
+        for group in [[5, 3, 4, 6, 7, 8, 9, 1, 2], [1, 2, 3, 4, 5, 6, 7, 8, 9]] {
+            assert!(reference(&group));
+            run(&group).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn sudoku_rejects_duplicate() {
+        let group = [5, 3, 4, 6, 7, 8, 9, 1, 5];
+        assert!(!reference(&group));
+        assert_constraint_fails(&run(&group), "distinct");
+    }
+
+    #[test]
+    fn sudoku_rejects_out_of_range() {
+        // Distinct, but 0 and 10 are not Sudoku digits.
+        for group in [[0, 1, 2, 3, 4, 5, 6, 7, 8], [10, 1, 2, 3, 4, 5, 6, 7, 8]] {
+            assert!(!reference(&group));
+            let failures = run(&group).verify().unwrap_err();
+            assert!(failures
+                .iter()
+                .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Sudoku checks: a single group in `example`, and a whole board against a
+//! public puzzle in `board`, both built on [`SudokuChip`].
+
+mod board;
+mod chip;
+mod example;
+
+#[cfg(test)]
+pub(crate) use chip::reference;
+pub(crate) use chip::{SudokuChip, SudokuConfig, GROUP};
+
+use crate::registry::Example;
+
+pub(crate) const EXAMPLES: &[Example] = &[
+    Example {
+        name: "sudoku",
+        circuit: example::example_circuit,
+    },
+    Example {
+        name: "sudoku::board",
+        circuit: board::example_circuit,
+    },
+];
@@ -0,0 +1,633 @@
+//! Helpers shared by the example tests.
+
+use std::{cell::RefCell, marker::PhantomData};
+
+use ff::PrimeField;
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, Value},
+    dev::{metadata, MockProver, VerifyFailure},
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+        FloorPlanner, Instance, Selector,
+    },
+};
+
+/// Asserts that verification fails, and that at least one of the failures is an
+/// unsatisfied constraint belonging to the gate named `gate`.
+pub fn assert_constraint_fails<F: Field + Ord>(prover: &MockProver<F>, gate: &str) {
+    let failures = match prover.verify() {
+        Ok(()) => panic!(
+            "expected gate '{}' to fail, but the circuit is satisfied",
+            gate
+        ),
+        Err(failures) => failures,
+    };
+
+    let suffix = format!("('{}')", gate);
+    let found = failures.iter().any(|failure| match failure {
+        VerifyFailure::ConstraintNotSatisfied { constraint, .. } => {
+            constraint.to_string().ends_with(&suffix)
+        }
+        _ => false,
+    });
+    assert!(
+        found,
+        "expected a failure in gate '{}', got: {:#?}",
+        gate, failures
+    );
+}
+
+/// What kind of `VerifyFailure` a negative test expects, without the location
+/// details that make the failures themselves awkward to compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyFailureKind {
+    /// An unsatisfied constraint in the gate with this name.
+    Constraint(&'static str),
+    /// A copy constraint, including one into an instance column.
+    Permutation,
+    Lookup,
+    /// A gate queries a cell that was never assigned.
+    CellNotAssigned,
+}
+
+impl VerifyFailureKind {
+    fn matches(self, failure: &VerifyFailure) -> bool {
+        match (self, failure) {
+            (Self::Constraint(gate), VerifyFailure::ConstraintNotSatisfied { constraint, .. }) => {
+                constraint.to_string().ends_with(&format!("('{}')", gate))
+            }
+            (Self::Permutation, VerifyFailure::Permutation { .. }) => true,
+            (Self::Lookup, VerifyFailure::Lookup { .. }) => true,
+            (Self::CellNotAssigned, VerifyFailure::CellNotAssigned { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Runs `MockProver` and asserts that verification fails with exactly the
+/// `expected` kinds of failure: each kind is reported at least once, and
+/// nothing else is.
+pub fn assert_verify_fails_with<F, C>(
+    k: u32,
+    circuit: &C,
+    instances: Vec<Vec<F>>,
+    expected: &[VerifyFailureKind],
+) where
+    F: PrimeField + Ord,
+    C: Circuit<F>,
+{
+    let prover = MockProver::run(k, circuit, instances).unwrap();
+    let failures = match prover.verify() {
+        Ok(()) => panic!("expected {:?}, but the circuit is satisfied", expected),
+        Err(failures) => failures,
+    };
+
+    let missing: Vec<_> = expected
+        .iter()
+        .filter(|kind| !failures.iter().any(|failure| kind.matches(failure)))
+        .collect();
+    let unexpected: Vec<_> = failures
+        .iter()
+        .filter(|failure| !expected.iter().any(|kind| kind.matches(failure)))
+        .collect();
+    assert!(
+        missing.is_empty() && unexpected.is_empty(),
+        "expected {:?}, missing {:?}, unexpected: {:#?}",
+        expected,
+        missing,
+        unexpected
+    );
+}
+
+/// Runs `MockProver` once per instance set, building each circuit from the set
+/// it is checked against, and panics with every failing set if any is rejected.
+///
+/// A set fails if `MockProver::run` returns an error or verification does. Use it
+/// to sweep a whole input domain in one test.
+pub fn assert_all_satisfied<F, C>(
+    k: u32,
+    circuit_builder: impl Fn(&[Vec<F>]) -> C,
+    instance_sets: &[Vec<Vec<F>>],
+) where
+    F: PrimeField + Ord,
+    C: Circuit<F>,
+{
+    let failures: Vec<String> = instance_sets
+        .iter()
+        .enumerate()
+        .filter_map(|(i, instances)| {
+            let circuit = circuit_builder(instances);
+            let outcome = match MockProver::run(k, &circuit, instances.clone()) {
+                Ok(prover) => prover
+                    .verify()
+                    .map_err(|failures| format!("{:?}", failures)),
+                Err(e) => Err(format!("{:?}", e)),
+            };
+            outcome
+                .err()
+                .map(|e| format!("set {} {:?}: {}", i, instances, e))
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} instance sets failed:\n{}",
+        failures.len(),
+        instance_sets.len(),
+        failures.join("\n")
+    );
+}
+
+/// Runs `configure` on a fresh constraint system and returns the highest degree
+/// among the polynomials of its custom gates.
+///
+/// Unlike `ConstraintSystem::degree`, this ignores the permutation and lookup
+/// arguments, which never report less than 3. halo2_proofs 0.3 keeps the gate
+/// polynomials crate-private, so they are read back from the `Debug` output of
+/// the pinned constraint system.
+pub fn max_gate_degree<F: Field>(configure: impl FnOnce(&mut ConstraintSystem<F>)) -> usize {
+    let mut meta = ConstraintSystem::default();
+    configure(&mut meta);
+
+    let pinned = format!("{:?}", meta.pinned());
+    let gates = pinned
+        .split_once("gates: [")
+        .expect("pinned constraint system lists its gates")
+        .1;
+
+    let mut rest = gates;
+    let mut degree = 0;
+    while !rest.starts_with(']') {
+        let (poly, tail) = expression_degree(rest);
+        degree = degree.max(poly);
+        rest = tail.trim_start_matches(", ");
+    }
+    degree
+}
+
+/// Degree of the `Debug`-formatted expression at the start of `s`, and what
+/// follows it.
+fn expression_degree(s: &str) -> (usize, &str) {
+    let name_len = s.find(['(', ' ']).expect("expression has a payload");
+    let (name, rest) = s.split_at(name_len);
+    match name {
+        "Negated" | "Scaled" | "Sum" | "Product" => {
+            let (a, rest) = expression_degree(&rest[1..]);
+            let (degree, rest) = match name {
+                "Negated" => (a, rest),
+                "Scaled" => (a, skip_balanced(rest.trim_start_matches(", "))),
+                _ => {
+                    let (b, rest) = expression_degree(rest.trim_start_matches(", "));
+                    (if name == "Sum" { a.max(b) } else { a + b }, rest)
+                }
+            };
+            (degree, rest.strip_prefix(')').expect("closing parenthesis"))
+        }
+        "Constant" => (0, skip_balanced(rest)),
+        // Selector, Fixed, Advice, Instance
+        _ => (1, skip_balanced(rest.trim_start())),
+    }
+}
+
+/// Skips a scalar, or one parenthesised or braced group, at the start of `s`.
+fn skip_balanced(s: &str) -> &str {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' if depth == 0 => return &s[i..],
+            ')' | '}' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &s[i + 1..];
+                }
+            }
+            ',' if depth == 0 => return &s[i..],
+            _ => {}
+        }
+    }
+    ""
+}
+
+/// One advice assignment made during synthesis, in the order the circuit made it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdviceSite {
+    /// Region the cell was assigned in.
+    pub region: String,
+    /// Annotation the circuit gave the cell.
+    pub name: String,
+    /// The advice column.
+    pub column: metadata::Column,
+    /// Absolute row of the cell.
+    pub row: usize,
+}
+
+/// Outcome of [`fuzz_witness_mutations`].
+#[derive(Debug)]
+pub struct FuzzReport {
+    /// Every advice assignment of an unmodified run.
+    pub sites: Vec<AdviceSite>,
+    /// Number of mutated runs.
+    pub attempts: usize,
+    /// Cells that could be changed without `MockProver` noticing.
+    pub unconstrained: Vec<AdviceSite>,
+}
+
+struct Mutation {
+    /// Index of the advice assignment to perturb.
+    target: usize,
+    /// Value added to the assignment; holds the circuit's field type.
+    delta: Box<dyn std::any::Any>,
+}
+
+thread_local! {
+    static MUTATION: RefCell<Option<Mutation>> = const { RefCell::new(None) };
+    static SITES: RefCell<Vec<AdviceSite>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Wraps a circuit so that its floor planner hands it a [`Mutator`] instead of the
+/// real constraint system.
+struct Mutated<C>(C);
+
+impl<F: Field, C: Circuit<F>> Circuit<F> for Mutated<C> {
+    type Config = C::Config;
+    type FloorPlanner = MutatingPlanner<C::FloorPlanner>;
+
+    fn without_witnesses(&self) -> Self {
+        Mutated(self.0.without_witnesses())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        C::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.0.synthesize(config, layouter)
+    }
+}
+
+struct MutatingPlanner<P>(PhantomData<P>);
+
+impl<P: FloorPlanner> FloorPlanner for MutatingPlanner<P> {
+    fn synthesize<F: Field, CS: Assignment<F>, C: Circuit<F>>(
+        cs: &mut CS,
+        circuit: &C,
+        config: C::Config,
+        constants: Vec<Column<Fixed>>,
+    ) -> Result<(), Error> {
+        let mut mutator = Mutator {
+            inner: cs,
+            region: String::new(),
+            count: 0,
+            _marker: PhantomData,
+        };
+        P::synthesize(&mut mutator, circuit, config, constants)
+    }
+}
+
+/// Forwards everything to the real constraint system, recording each advice
+/// assignment and perturbing the one selected by [`MUTATION`].
+struct Mutator<'a, F: Field, CS: Assignment<F>> {
+    inner: &'a mut CS,
+    region: String,
+    count: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, CS: Assignment<F>> Assignment<F> for Mutator<'_, F, CS> {
+    fn enter_region<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.region = name_fn().into();
+        let region = self.region.clone();
+        self.inner.enter_region(|| region)
+    }
+
+    fn exit_region(&mut self) {
+        self.inner.exit_region()
+    }
+
+    fn enable_selector<A, AR>(
+        &mut self,
+        annotation: A,
+        selector: &Selector,
+        row: usize,
+    ) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.inner.enable_selector(annotation, selector, row)
+    }
+
+    fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<F>, Error> {
+        self.inner.query_instance(column, row)
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        annotation: A,
+        column: Column<Advice>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let name: String = annotation().into();
+        SITES.with(|sites| {
+            sites.borrow_mut().push(AdviceSite {
+                region: self.region.clone(),
+                name: name.clone(),
+                column: Column::<Any>::from(column).into(),
+                row,
+            })
+        });
+
+        let index = self.count;
+        self.count += 1;
+        let delta = MUTATION.with(|mutation| match &*mutation.borrow() {
+            Some(mutation) if mutation.target == index => {
+                mutation.delta.downcast_ref::<F>().copied()
+            }
+            _ => None,
+        });
+
+        self.inner.assign_advice(
+            || name,
+            column,
+            row,
+            || {
+                let value = to().map(|v| v.into());
+                match delta {
+                    Some(delta) => value.map(|v| v + delta),
+                    None => value,
+                }
+            },
+        )
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        annotation: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.inner.assign_fixed(annotation, column, row, to)
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        self.inner
+            .copy(left_column, left_row, right_column, right_row)
+    }
+
+    fn fill_from_row(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        to: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        self.inner.fill_from_row(column, row, to)
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.inner.push_namespace(name_fn)
+    }
+
+    fn pop_namespace(&mut self, gadget_name: Option<String>) {
+        self.inner.pop_namespace(gadget_name)
+    }
+}
+
+/// Perturbs single advice cells of a satisfied circuit and reports which ones
+/// `MockProver` fails to notice.
+///
+/// An unmodified run first records every advice assignment. Mutation `j` then
+/// adds `j / n + 1` to assignment `j % n`, where `n` is the number of
+/// assignments, so `iterations >= n` touches every cell at least once. Copies
+/// of a mutated cell keep their honest value, which makes each run a true
+/// single-cell change.
+///
+/// Panics if the unmodified circuit is not satisfied.
+pub fn fuzz_witness_mutations<F, C>(
+    k: u32,
+    circuit_builder: impl Fn() -> C,
+    instances: Vec<Vec<F>>,
+    iterations: usize,
+) -> FuzzReport
+where
+    F: PrimeField + Ord,
+    C: Circuit<F>,
+{
+    SITES.with(|sites| sites.borrow_mut().clear());
+    MockProver::run(k, &Mutated(circuit_builder()), instances.clone())
+        .unwrap()
+        .assert_satisfied();
+    let sites = SITES.with(|sites| sites.take());
+
+    let mut unconstrained: Vec<AdviceSite> = Vec::new();
+    for j in 0..iterations {
+        let target = j % sites.len();
+        let delta = F::from((j / sites.len() + 1) as u64);
+        MUTATION.with(|mutation| {
+            *mutation.borrow_mut() = Some(Mutation {
+                target,
+                delta: Box::new(delta),
+            })
+        });
+
+        let prover = MockProver::run(k, &Mutated(circuit_builder()), instances.clone()).unwrap();
+        if prover.verify().is_ok() && !unconstrained.contains(&sites[target]) {
+            unconstrained.push(sites[target].clone());
+        }
+    }
+    MUTATION.with(|mutation| mutation.borrow_mut().take());
+
+    FuzzReport {
+        sites,
+        attempts: iterations,
+        unconstrained,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fibonacci::example1;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, pasta::Fp, plonk::Expression, poly::Rotation};
+
+    /// Doubles a public input, and also witnesses a scratch cell that nothing
+    /// constrains.
+    #[derive(Default)]
+    struct LooseCircuit;
+
+    impl Circuit<Fp> for LooseCircuit {
+        type Config = (Column<Advice>, Selector, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = meta.advice_column();
+            let selector = meta.selector();
+            let instance = meta.instance_column();
+            meta.enable_equality(advice);
+            meta.enable_equality(instance);
+            meta.create_gate("double", |meta| {
+                let s = meta.query_selector(selector);
+                let x = meta.query_advice(advice, Rotation::cur());
+                let y = meta.query_advice(advice, Rotation::next());
+                vec![s * (x.clone() + x - y)]
+            });
+            (advice, selector, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (advice, selector, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "double",
+                |mut region| {
+                    selector.enable(&mut region, 0)?;
+                    let x = region.assign_advice_from_instance(|| "x", instance, 0, advice, 0)?;
+                    region.assign_advice(|| "y", advice, 1, || x.value().map(|x| x.double()))?;
+                    region.assign_advice(|| "scratch", advice, 2, || Value::known(Fp::ONE))
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn max_gate_degree_counts_products() {
+        assert_eq!(
+            max_gate_degree::<Fp>(|meta| {
+                LooseCircuit::configure(meta);
+            }),
+            2
+        );
+
+        // s * x * (x - 1) * (x - 2) * 3, next to a plain degree-2 gate.
+        let degree = max_gate_degree::<Fp>(|meta| {
+            let s = meta.selector();
+            let advice = meta.advice_column();
+            meta.create_gate("linear", |meta| {
+                let s = meta.query_selector(s);
+                let x = meta.query_advice(advice, Rotation::cur());
+                vec![s * x]
+            });
+            meta.create_gate("cubic", |meta| {
+                let s = meta.query_selector(s);
+                let x = meta.query_advice(advice, Rotation::cur());
+                let one = Expression::Constant(Fp::ONE);
+                let two = Expression::Constant(Fp::from(2));
+                vec![s * x.clone() * (x.clone() - one) * (-(x - two) * Fp::from(3))]
+            });
+        });
+        assert_eq!(degree, 4);
+    }
+
+    /// The public inputs of the first Fibonacci example, with a wrong output.
+    fn wrong_fibonacci_output() -> Vec<Vec<Fp>> {
+        vec![vec![Fp::from(1), Fp::from(1), Fp::from(56)]]
+    }
+
+    #[test]
+    fn verify_fails_with_matches_kinds() {
+        // The output cell holds 55, so only its copy into the instance fails.
+        assert_verify_fails_with(
+            4,
+            &example1::MyCircuit::default(),
+            wrong_fibonacci_output(),
+            &[VerifyFailureKind::Permutation],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "expected [Constraint(\"double\")], but the circuit is satisfied")]
+    fn verify_fails_with_rejects_satisfied() {
+        assert_verify_fails_with(
+            4,
+            &LooseCircuit,
+            vec![vec![Fp::from(3)]],
+            &[VerifyFailureKind::Constraint("double")],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "missing [Constraint(\"add\")]")]
+    fn verify_fails_with_reports_missing_kinds() {
+        assert_verify_fails_with(
+            4,
+            &example1::MyCircuit::default(),
+            wrong_fibonacci_output(),
+            &[
+                VerifyFailureKind::Permutation,
+                VerifyFailureKind::Constraint("add"),
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "missing [], unexpected")]
+    fn verify_fails_with_reports_unexpected_failures() {
+        assert_verify_fails_with(
+            4,
+            &example1::MyCircuit::default(),
+            wrong_fibonacci_output(),
+            &[],
+        );
+    }
+
+    #[test]
+    fn all_satisfied_accepts_every_set() {
+        let sets: Vec<_> = (0..4).map(|x| vec![vec![Fp::from(x)]]).collect();
+        assert_all_satisfied(4, |_| LooseCircuit, &sets);
+    }
+
+    #[test]
+    #[should_panic(expected = "1 of 3 instance sets failed:\nset 1 []")]
+    fn all_satisfied_lists_failing_sets() {
+        // The middle set is missing the instance column.
+        let sets = vec![vec![vec![Fp::from(1)]], vec![], vec![vec![Fp::from(2)]]];
+        assert_all_satisfied(4, |_| LooseCircuit, &sets);
+    }
+
+    #[test]
+    fn fuzz_finds_unconstrained_cell() {
+        let report = fuzz_witness_mutations(4, || LooseCircuit, vec![vec![Fp::from(3)]], 6);
+
+        assert_eq!(report.sites.len(), 3);
+        assert_eq!(report.attempts, 6);
+        assert_eq!(report.unconstrained.len(), 1);
+        assert_eq!(report.unconstrained[0].name, "scratch");
+        assert_eq!(report.unconstrained[0].row, 2);
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{prelude::*, range_check::table::RangeTableConfig, registry::ExampleCircuit};
+use halo2_proofs::pasta::Fp;
+
+/// Proves a toy balance transfer of a private `amount` between two accounts.
+///
+/// One row holds both balances before and after the transfer:
+///
+/// ```text
+/// sender_after   == sender_before - amount
+/// receiver_after == receiver_before + amount
+/// ```
+///
+/// `amount` and `sender_after` are looked up in a `0..RANGE` table. The second
+/// lookup is what rules out an overdraft: `amount > sender_before` wraps
+/// `sender_after` around to just under `p`. The first stops a "negative" amount
+/// from moving funds the other way. The four balances are public, in the order
+/// sender before, receiver before, sender after, receiver after.
+#[derive(Debug, Clone)]
+struct TransferConfig<F: PrimeField, const RANGE: usize> {
+    pub advice: [Column<Advice>; 5],
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub table: RangeTableConfig<F, RANGE>,
+}
+
+#[derive(Debug, Clone)]
+struct TransferChip<F: PrimeField, const RANGE: usize> {
+    config: TransferConfig<F, RANGE>,
+}
+
+impl<F: PrimeField, const RANGE: usize> TransferChip<F, RANGE> {
+    pub fn construct(config: TransferConfig<F, RANGE>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        instance: Column<Instance>,
+    ) -> TransferConfig<F, RANGE> {
+        let [col_sb, col_rb, col_amount, col_sa, col_ra] = advice;
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        let table = RangeTableConfig::configure(meta);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(instance);
+
+        meta.create_gate("transfer", |meta| {
+            //
+            // col_sb | col_rb | col_amount | col_sa | col_ra | selector
+            //   sb       rb       amount       sa       ra        s
+            //
+            let s = meta.query_selector(selector);
+            let sb = meta.query_advice(col_sb, Rotation::cur());
+            let rb = meta.query_advice(col_rb, Rotation::cur());
+            let amount = meta.query_advice(col_amount, Rotation::cur());
+            let sa = meta.query_advice(col_sa, Rotation::cur());
+            let ra = meta.query_advice(col_ra, Rotation::cur());
+            vec![
+                s.clone() * (sa - (sb - amount.clone())),
+                s * (ra - (rb + amount)),
+            ]
+        });
+
+        // Inactive rows look up 0, which is always in the table.
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let amount = meta.query_advice(col_amount, Rotation::cur());
+            vec![(s * amount, table.value)]
+        });
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let sa = meta.query_advice(col_sa, Rotation::cur());
+            vec![(s * sa, table.value)]
+        });
+
+        TransferConfig {
+            advice,
+            selector,
+            instance,
+            table,
+        }
+    }
+
+    /// Assigns the transfer row and returns the balance cells in public order:
+    /// `[sender_before, receiver_before, sender_after, receiver_after]`.
+    pub fn assign_transfer(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sender_before: Value<F>,
+        receiver_before: Value<F>,
+        amount: Value<F>,
+    ) -> Result<[AssignedCell<F, F>; 4], Error> {
+        let [col_sb, col_rb, col_amount, col_sa, col_ra] = self.config.advice;
+        layouter.assign_region(
+            || "transfer",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                let sb = region.assign_advice(|| "sender before", col_sb, 0, || sender_before)?;
+                let rb =
+                    region.assign_advice(|| "receiver before", col_rb, 0, || receiver_before)?;
+                region.assign_advice(|| "amount", col_amount, 0, || amount)?;
+                let sa = region.assign_advice(
+                    || "sender after",
+                    col_sa,
+                    0,
+                    || sender_before - amount,
+                )?;
+                let ra = region.assign_advice(
+                    || "receiver after",
+                    col_ra,
+                    0,
+                    || receiver_before + amount,
+                )?;
+
+                Ok([sb, rb, sa, ra])
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+/// Plain-Rust reference: the balances `(sender_after, receiver_after)`, or `None`
+/// on an overdraft or an overflowing receiver balance.
+pub fn reference(sender_before: u64, receiver_before: u64, amount: u64) -> Option<(u64, u64)> {
+    Some((
+        sender_before.checked_sub(amount)?,
+        receiver_before.checked_add(amount)?,
+    ))
+}
+
+#[derive(Default)]
+struct MyCircuit<F, const RANGE: usize> {
+    sender_before: Value<F>,
+    receiver_before: Value<F>,
+    amount: Value<F>,
+}
+
+impl<F: PrimeField, const RANGE: usize> MyCircuit<F, RANGE> {
+    pub fn new(sender_before: u64, receiver_before: u64, amount: u64) -> Self {
+        Self {
+            sender_before: Value::known(F::from(sender_before)),
+            receiver_before: Value::known(F::from(receiver_before)),
+            amount: Value::known(F::from(amount)),
+        }
+    }
+}
+
+impl<F: PrimeField, const RANGE: usize> Circuit<F> for MyCircuit<F, RANGE> {
+    type Config = TransferConfig<F, RANGE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let instance = meta.instance_column();
+        TransferChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+        let chip = TransferChip::construct(config);
+
+        let balances = chip.assign_transfer(
+            layouter.namespace(|| "transfer"),
+            self.sender_before,
+            self.receiver_before,
+            self.amount,
+        )?;
+        for (row, cell) in balances.iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| "balance"), cell, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Places the public inputs by name, like [`crate::fibonacci::FibonacciInstances`].
+/// The balances after default to the ones before, as for a zero transfer.
+pub struct TransferInstances<F> {
+    pub sender_before: F,
+    pub receiver_before: F,
+    pub sender_after: F,
+    pub receiver_after: F,
+}
+
+impl<F: Field> TransferInstances<F> {
+    pub fn new(sender_before: F, receiver_before: F) -> Self {
+        Self {
+            sender_before,
+            receiver_before,
+            sender_after: sender_before,
+            receiver_after: receiver_before,
+        }
+    }
+
+    pub fn expect_after(self, sender_after: F, receiver_after: F) -> Self {
+        Self {
+            sender_after,
+            receiver_after,
+            ..self
+        }
+    }
+
+    /// In the order [`TransferChip::assign_transfer`] returns the balances.
+    pub fn build(self) -> Vec<Vec<F>> {
+        vec![vec![
+            self.sender_before,
+            self.receiver_before,
+            self.sender_after,
+            self.receiver_after,
+        ]]
+    }
+}
+
+/// The circuit on a transfer of 30 from 100 to 20.
+pub fn example_circuit() -> ExampleCircuit {
+    let (sender_after, receiver_after) = reference(100, 20, 30).expect("no overdraft");
+    let circuit = MyCircuit::<Fp, 256>::new(100, 20, 30);
+    let instances = TransferInstances::new(Fp::from(100), Fp::from(20))
+        .expect_after(Fp::from(sender_after), Fp::from(receiver_after))
+        .build();
+    ExampleCircuit::new(9, circuit, instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    const RANGE: usize = 256;
+
+    fn run(
+        (sender_before, receiver_before, amount): (u64, u64, u64),
+        instance: [Fp; 4],
+    ) -> MockProver<Fp> {
+        let k = 9;
+
+        let circuit = MyCircuit::<Fp, RANGE>::new(sender_before, receiver_before, amount);
+        MockProver::run(k, &circuit, vec![instance.to_vec()]).unwrap()
+    }
+
+    #[test]
+    fn transfer_example() {
+        let (sender_after, receiver_after) = reference(100, 20, 30).unwrap();
+        assert_eq!((sender_after, receiver_after), (70, 50));
+
+        let instance = [100, 20, 70, 50].map(Fp::from);
+        run((100, 20, 30), instance).assert_satisfied();
+    }
+
+    #[test]
+    fn transfer_whole_balance() {
+        let instance = [30, 0, 0, 30].map(Fp::from);
+        run((30, 0, 30), instance).assert_satisfied();
+    }
+
+    #[test]
+    fn transfer_rejects_overdraft() {
+        assert_eq!(reference(5, 20, 10), None);
+
+        // The public balances match the field arithmetic, so only the range
+        // check on the sender's new balance can catch the overdraft.
+        let instance = [Fp::from(5), Fp::from(20), -Fp::from(5), Fp::from(30)];
+        let failures = run((5, 20, 10), instance).verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn transfer_rejects_conservation_violation() {
+        // The receiver claims one more than the sender paid.
+        let instance = [100, 20, 70, 51].map(Fp::from);
+        assert!(run((100, 20, 30), instance).verify().is_err());
+
+        // The sender claims to keep more than is left.
+        let instance = [100, 20, 71, 50].map(Fp::from);
+        assert!(run((100, 20, 30), instance).verify().is_err());
+    }
+
+    #[test]
+    fn transfer_has_no_unconstrained_cells() {
+        let k = 9;
+
+        let instance = [100, 20, 70, 50].map(Fp::from).to_vec();
+        let report = fuzz_witness_mutations(
+            k,
+            || MyCircuit::<Fp, RANGE>::new(100, 20, 30),
+            vec![instance],
+            5,
+        );
+
+        assert_eq!(report.sites.len(), 5);
+        // The amount is private, but the gate ties it to the public balances.
+        assert!(
+            report.unconstrained.is_empty(),
+            "{:#?}",
+            report.unconstrained
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Browser bindings for proving and verifying Fibonacci terms with
+//! [`example2`], built with the `wasm` feature:
+//!
+//! ```text
+//! wasm-pack build --target web -- --no-default-features --features wasm
+//! ```
+//!
+//! `multicore` has to go: halo2_proofs refuses to build it for `wasm32`
+//! without atomics. `wasm` turns on getrandom's `js` backend, which the prover
+//! needs for its blinding factors in the browser.
+//!
+//! The public inputs cross the boundary as an inputs file in JSON (see
+//! [`crate::io`]), e.g. `{ "a": 1, "b": 1, "n": 9, "out": 55 }`, and the proof as
+//! raw transcript bytes. Both sides size the circuit as the smallest `k` its
+//! rows and public inputs fit in, so no `k` has to travel with the proof, and
+//! take the keys from [`key_cache`], so repeated calls for one `n` share them.
+
+use std::sync::Arc;
+
+use halo2_proofs::pasta::Fp;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    common::{check_rows, MAX_K},
+    fibonacci::{example2::MyCircuit, FibIndex, FibonacciInstances},
+    io::{FibonacciInputs, Inputs},
+    prover::{self, key_cache, Keys},
+    serialize::encode_fp,
+};
+
+/// Proves `f(n)` for the seeds `f(0) = a` and `f(1) = b`, returning the proof.
+#[wasm_bindgen]
+pub fn prove_fibonacci(a: u64, b: u64, n: usize) -> Result<Vec<u8>, JsError> {
+    prove(a, b, n).map_err(|e| JsError::new(&e))
+}
+
+/// The public inputs [`verify`] expects for [`prove_fibonacci`]'s proof.
+#[wasm_bindgen]
+pub fn fibonacci_instances(a: u64, b: u64, n: usize) -> Result<String, JsError> {
+    instances_json(a, b, n).map_err(|e| JsError::new(&e))
+}
+
+/// Checks `proof` against the public inputs in `instances`. Returns `false` for
+/// a rejected proof and throws if `instances` is malformed.
+#[wasm_bindgen]
+pub fn verify(proof: &[u8], instances: &str) -> Result<bool, JsError> {
+    verify_inputs(proof, instances).map_err(|e| JsError::new(&e))
+}
+
+fn prove(a: u64, b: u64, n: usize) -> Result<Vec<u8>, String> {
+    let instances = FibonacciInstances::from_seeds(a, b, FibIndex(n))
+        .ok_or_else(|| format!("f({}) does not fit in a u64", n))?
+        .to_columns();
+    let keys = keys(n)?;
+    prover::prove(&keys, MyCircuit::new(n), &[&instances[0]]).map_err(|e| e.to_string())
+}
+
+fn instances_json(a: u64, b: u64, n: usize) -> Result<String, String> {
+    let instances = FibonacciInstances::from_seeds(a, b, FibIndex(n))
+        .ok_or_else(|| format!("f({}) does not fit in a u64", n))?;
+    Ok(serde_json::json!({
+        "a": a,
+        "b": b,
+        "n": n,
+        "out": encode_fp(&instances.output),
+    })
+    .to_string())
+}
+
+fn verify_inputs(proof: &[u8], instances: &str) -> Result<bool, String> {
+    let inputs = Inputs::from_json(instances)
+        .and_then(|inputs| FibonacciInputs::from_inputs(&inputs))
+        .map_err(|e| e.to_string())?;
+    if inputs.out.is_none() {
+        return Err("missing input \"out\": verify needs the claimed output".to_string());
+    }
+    let instances = inputs.instances().map_err(|e| e.to_string())?.to_columns();
+    let keys = keys(inputs.n.0)?;
+    Ok(prover::verify(&keys, proof, &[&instances[0]]).is_ok())
+}
+
+/// Keys for `f(n)` at the smallest size that holds its rows and its three
+/// public inputs.
+fn keys(n: usize) -> Result<Arc<Keys>, String> {
+    let circuit = MyCircuit::<Fp>::new(n);
+    let rows = circuit.rows().max(3);
+    let k = (1..=MAX_K)
+        .find(|&k| check_rows::<Fp, MyCircuit<Fp>>(k, rows).is_ok())
+        .ok_or_else(|| format!("f({}) does not fit in any circuit size", n))?;
+    key_cache()
+        .get_or_generate(k, &circuit)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prove_and_verify_round_trip() {
+        let proof = prove(1, 1, 9).unwrap();
+        let instances = instances_json(1, 1, 9).unwrap();
+        assert_eq!(verify_inputs(&proof, &instances), Ok(true));
+
+        let wrong = instances.replace("\"n\":9", "\"n\":10");
+        assert!(verify_inputs(&proof, &wrong).is_err(), "{}", wrong);
+        let mut tampered = proof.clone();
+        tampered[0] ^= 1;
+        assert_eq!(verify_inputs(&tampered, &instances), Ok(false));
+    }
+
+    #[test]
+    fn verify_needs_the_claimed_output() {
+        let proof = prove(1, 1, 9).unwrap();
+        assert!(verify_inputs(&proof, r#"{ "a": 1, "b": 1, "n": 9 }"#).is_err());
+        assert_eq!(
+            verify_inputs(&proof, r#"{ "a": 1, "b": 1, "n": 9, "out": 55 }"#),
+            Ok(true)
+        );
+    }
+}
@@ -0,0 +1,505 @@
+//! 32-bit word arithmetic, as hash functions such as SHA-256 and BLAKE2s use it.
+//!
+//! Every [`AssignedWord`] is range-checked to 32 bits with four 8-bit lookups
+//! when it is created, so the gates can treat words as integers. Overflow is
+//! explicit: [`Word32Chip::add`] returns the carry out beside the sum,
+//! [`Word32Chip::wrapping_add`] drops it and [`Word32Chip::checked_add`] makes
+//! the proof fail if there is one.
+
+use crate::{
+    decompose::{DecomposeChip, DecomposeConfig},
+    prelude::*,
+};
+
+/// A cell holding an integer below `2^32`.
+#[derive(Debug, Clone)]
+pub struct AssignedWord<F: Field> {
+    pub cell: AssignedCell<F, F>,
+}
+
+impl<F: PrimeField> AssignedWord<F> {
+    pub fn value(&self) -> Value<u32> {
+        self.cell.value().map(|v| to_u64(v) as u32)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Word32Config<F: PrimeField> {
+    /// `[a, b, sum, carry]` in an addition row, `[a, hi, lo, lo_shifted, out]`
+    /// in a split row.
+    pub advice: [Column<Advice>; 5],
+    /// `2^n` and `2^(32 - n)` in a split row.
+    pub pow: [Column<Fixed>; 2],
+    pub constant: Column<Fixed>,
+    pub q_add: Selector,
+    pub q_split: Selector,
+    pub range: DecomposeConfig<F, 8, 4>,
+}
+
+/// Additions, shifts and rotations of 32-bit words.
+///
+/// An addition row constrains
+///
+/// ```text
+/// a + b == sum + 2^32 * carry,  carry boolean
+/// ```
+///
+/// and, with `sum` range-checked, is the 33-bit sum of two words.
+///
+/// Shifts and rotations by `n` split a word at bit `n`, in one row with
+/// `2^n` and `2^(32 - n)` in fixed columns:
+///
+/// ```text
+/// a          == hi * 2^n + lo
+/// lo_shifted == lo * 2^(32 - n)
+/// out        == lo_shifted + hi
+/// ```
+///
+/// `hi`, `lo` and `lo_shifted` are range-checked to 32 bits, which bounds `lo`
+/// below `2^n`; `hi` is then `a >> n` and `out` is `a` rotated right by `n`.
+/// Splitting at `32 - n` instead gives `a << n` as `lo_shifted`.
+#[derive(Debug, Clone)]
+pub struct Word32Chip<F: PrimeField> {
+    config: Word32Config<F>,
+}
+
+impl<F: PrimeField> Word32Chip<F> {
+    pub fn construct(config: Word32Config<F>) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+    ) -> Word32Config<F> {
+        let q_add = meta.selector();
+        let q_split = meta.selector();
+        let pow = [(); 2].map(|_| meta.fixed_column());
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("word add", |meta| {
+            let s = meta.query_selector(q_add);
+            let [a, b, sum, carry] =
+                [0, 1, 2, 3].map(|i| meta.query_advice(advice[i], Rotation::cur()));
+            let two_pow_32 = Expression::Constant(F::from(1 << 32));
+            vec![
+                s.clone() * (a + b - sum - carry.clone() * two_pow_32),
+                s * carry.clone() * (Expression::Constant(F::ONE) - carry),
+            ]
+        });
+
+        meta.create_gate("word split", |meta| {
+            let s = meta.query_selector(q_split);
+            let [a, hi, lo, lo_shifted, out] =
+                advice.map(|c| meta.query_advice(c, Rotation::cur()));
+            let [pow_n, pow_rest] = pow.map(|c| meta.query_fixed(c));
+            vec![
+                s.clone() * (a - hi.clone() * pow_n - lo.clone()),
+                s.clone() * (lo_shifted.clone() - lo * pow_rest),
+                s * (out - lo_shifted - hi),
+            ]
+        });
+
+        Word32Config {
+            advice,
+            pow,
+            constant,
+            q_add,
+            q_split,
+            range: DecomposeChip::configure(meta, advice[0], advice[1]),
+        }
+    }
+
+    /// Loads the lookup table of the range checks. Call once per circuit.
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        DecomposeChip::construct(self.config.range.clone()).load_table(layouter)
+    }
+
+    /// Witnesses a word.
+    pub fn load(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<u32>,
+    ) -> Result<AssignedWord<F>, Error> {
+        let cell = layouter.assign_region(
+            || "word",
+            |mut region| {
+                let value = value.map(|v| F::from(u64::from(v)));
+                region.assign_advice(|| "word", self.config.advice[0], 0, || value)
+            },
+        )?;
+        self.range_check(layouter.namespace(|| "range"), cell)
+    }
+
+    /// Returns `a + b` as a word and the carry bit out of it.
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedWord<F>,
+        b: &AssignedWord<F>,
+    ) -> Result<(AssignedWord<F>, AssignedCell<F, F>), Error> {
+        let [col_a, col_b, col_sum, col_carry, _] = self.config.advice;
+        let (sum, carry) = layouter.assign_region(
+            || "word add",
+            |mut region| {
+                self.config.q_add.enable(&mut region, 0)?;
+                a.cell.copy_advice(|| "a", &mut region, col_a, 0)?;
+                b.cell.copy_advice(|| "b", &mut region, col_b, 0)?;
+
+                let result = a.value().zip(b.value()).map(|(a, b)| a.overflowing_add(b));
+                let sum = result.map(|(sum, _)| F::from(u64::from(sum)));
+                let carry = result.map(|(_, carry)| F::from(u64::from(carry)));
+                Ok((
+                    region.assign_advice(|| "sum", col_sum, 0, || sum)?,
+                    region.assign_advice(|| "carry", col_carry, 0, || carry)?,
+                ))
+            },
+        )?;
+        Ok((
+            self.range_check(layouter.namespace(|| "sum range"), sum)?,
+            carry,
+        ))
+    }
+
+    /// Returns `a + b mod 2^32`.
+    pub fn wrapping_add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedWord<F>,
+        b: &AssignedWord<F>,
+    ) -> Result<AssignedWord<F>, Error> {
+        Ok(self.add(layouter, a, b)?.0)
+    }
+
+    /// Returns `a + b`, which must not overflow.
+    pub fn checked_add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedWord<F>,
+        b: &AssignedWord<F>,
+    ) -> Result<AssignedWord<F>, Error> {
+        let (sum, carry) = self.add(layouter.namespace(|| "add"), a, b)?;
+        layouter.assign_region(
+            || "no overflow",
+            |mut region| region.constrain_constant(carry.cell(), F::ZERO),
+        )?;
+        Ok(sum)
+    }
+
+    /// Returns `a >> n`.
+    pub fn shr(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedWord<F>,
+        n: u32,
+    ) -> Result<AssignedWord<F>, Error> {
+        Ok(self.split(layouter, a, n)?.hi)
+    }
+
+    /// Returns `a << n`, dropping the bits shifted out.
+    pub fn shl(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedWord<F>,
+        n: u32,
+    ) -> Result<AssignedWord<F>, Error> {
+        Ok(self.split(layouter, a, 32 - n)?.lo_shifted)
+    }
+
+    /// Returns `a` rotated right by `n`.
+    pub fn rotr(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedWord<F>,
+        n: u32,
+    ) -> Result<AssignedWord<F>, Error> {
+        Ok(self.split(layouter, a, n)?.out)
+    }
+
+    /// Returns `a` rotated left by `n`.
+    pub fn rotl(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedWord<F>,
+        n: u32,
+    ) -> Result<AssignedWord<F>, Error> {
+        self.rotr(layouter, a, 32 - n)
+    }
+
+    fn split(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedWord<F>,
+        n: u32,
+    ) -> Result<Split<F>, Error> {
+        assert!(0 < n && n < 32, "bad shift {}", n);
+        let parts = a.value().map(|a| (a >> n, a & ((1 << n) - 1)));
+        self.assign_split(layouter, a, n, parts)
+    }
+
+    /// Lays out the split of `a` at bit `n` into the given `(hi, lo)`.
+    fn assign_split(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedWord<F>,
+        n: u32,
+        parts: Value<(u32, u32)>,
+    ) -> Result<Split<F>, Error> {
+        let [col_a, col_hi, col_lo, col_lo_shifted, col_out] = self.config.advice;
+        let [pow_n, pow_rest] = [n, 32 - n].map(|e| F::from(1 << e));
+
+        let [hi, lo, lo_shifted, out] = layouter.assign_region(
+            || "word split",
+            |mut region| {
+                self.config.q_split.enable(&mut region, 0)?;
+                region.assign_fixed(|| "2^n", self.config.pow[0], 0, || Value::known(pow_n))?;
+                region.assign_fixed(
+                    || "2^(32-n)",
+                    self.config.pow[1],
+                    0,
+                    || Value::known(pow_rest),
+                )?;
+                a.cell.copy_advice(|| "a", &mut region, col_a, 0)?;
+
+                let hi = parts.map(|(hi, _)| F::from(u64::from(hi)));
+                let lo = parts.map(|(_, lo)| F::from(u64::from(lo)));
+                let lo_shifted = lo.map(|lo| lo * pow_rest);
+                let out = lo_shifted + hi;
+                Ok([
+                    region.assign_advice(|| "hi", col_hi, 0, || hi)?,
+                    region.assign_advice(|| "lo", col_lo, 0, || lo)?,
+                    region.assign_advice(|| "lo shifted", col_lo_shifted, 0, || lo_shifted)?,
+                    region.assign_advice(|| "out", col_out, 0, || out)?,
+                ])
+            },
+        )?;
+
+        self.range_check(layouter.namespace(|| "lo range"), lo)?;
+        Ok(Split {
+            hi: self.range_check(layouter.namespace(|| "hi range"), hi)?,
+            lo_shifted: self.range_check(layouter.namespace(|| "lo shifted range"), lo_shifted)?,
+            // Below 2^32 given the other three.
+            out: AssignedWord { cell: out },
+        })
+    }
+
+    fn range_check(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: AssignedCell<F, F>,
+    ) -> Result<AssignedWord<F>, Error> {
+        DecomposeChip::construct(self.config.range.clone()).decompose(layouter, &cell)?;
+        Ok(AssignedWord { cell })
+    }
+}
+
+/// The cells of a split row, all words.
+struct Split<F: Field> {
+    hi: AssignedWord<F>,
+    lo_shifted: AssignedWord<F>,
+    out: AssignedWord<F>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use rand_core::{OsRng, RngCore};
+
+    /// Every operation applied to one pair of words.
+    fn native(a: u32, b: u32, n: u32) -> [u32; 7] {
+        let (sum, carry) = a.overflowing_add(b);
+        [
+            sum,
+            carry as u32,
+            a >> n,
+            a << n,
+            a.rotate_right(n),
+            a.rotate_left(n),
+            a.wrapping_add(b.rotate_right(n)),
+        ]
+    }
+
+    /// Exposes [`native`] of each `(a, b, n)`, computed in the circuit.
+    #[derive(Default)]
+    struct WordCircuit {
+        inputs: Vec<(u32, u32, u32)>,
+    }
+
+    impl Circuit<Fp> for WordCircuit {
+        type Config = (Word32Config<Fp>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                inputs: self.inputs.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 5].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (Word32Chip::configure(meta, advice), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = Word32Chip::construct(config);
+            chip.load_table(&mut layouter)?;
+
+            let mut row = 0;
+            for &(a, b, n) in &self.inputs {
+                let a = chip.load(layouter.namespace(|| "a"), Value::known(a))?;
+                let b = chip.load(layouter.namespace(|| "b"), Value::known(b))?;
+                let (sum, carry) = chip.add(layouter.namespace(|| "a + b"), &a, &b)?;
+                let b_rotr = chip.rotr(layouter.namespace(|| "b rotr"), &b, n)?;
+                let outs = [
+                    sum.cell,
+                    carry,
+                    chip.shr(layouter.namespace(|| "a >> n"), &a, n)?.cell,
+                    chip.shl(layouter.namespace(|| "a << n"), &a, n)?.cell,
+                    chip.rotr(layouter.namespace(|| "a rotr"), &a, n)?.cell,
+                    chip.rotl(layouter.namespace(|| "a rotl"), &a, n)?.cell,
+                    chip.wrapping_add(layouter.namespace(|| "mix"), &a, &b_rotr)?
+                        .cell,
+                ];
+                for out in outs {
+                    layouter.constrain_instance(out.cell(), instance, row)?;
+                    row += 1;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn run(inputs: Vec<(u32, u32, u32)>) -> MockProver<Fp> {
+        let k = 11;
+
+        let instances = inputs
+            .iter()
+            .flat_map(|&(a, b, n)| native(a, b, n))
+            .map(|v| Fp::from(u64::from(v)))
+            .collect();
+        MockProver::run(k, &WordCircuit { inputs }, vec![instances]).unwrap()
+    }
+
+    #[test]
+    fn word32_matches_native_u32() {
+        let mut inputs = vec![
+            (0, 0, 1),
+            (u32::MAX, 1, 31),
+            (u32::MAX, u32::MAX, 16),
+            (0x8000_0000, 0x8000_0000, 7),
+        ];
+        inputs.extend((0..8).map(|_| {
+            let n = 1 + OsRng.next_u32() % 31;
+            (OsRng.next_u32(), OsRng.next_u32(), n)
+        }));
+        run(inputs).assert_satisfied();
+    }
+
+    #[test]
+    fn word32_rejects_wrong_result() {
+        let circuit = WordCircuit {
+            inputs: vec![(u32::MAX, 7, 3)],
+        };
+        let mut instances: Vec<_> = native(u32::MAX, 7, 3)
+            .iter()
+            .map(|&v| Fp::from(u64::from(v)))
+            .collect();
+        // Claim no wrap-around: the sum as a 33-bit integer.
+        instances[0] += Fp::from(1 << 32);
+
+        let prover = MockProver::run(11, &circuit, vec![instances]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    /// Adds `a + b` with `checked_add`, or splits `a` at `n` into a forged
+    /// `(hi, lo)`.
+    #[derive(Clone, Copy)]
+    struct ForgedCircuit {
+        a: u32,
+        b: u32,
+        split: Option<(u32, u32, u32)>,
+    }
+
+    impl Circuit<Fp> for ForgedCircuit {
+        type Config = Word32Config<Fp>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            *self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 5].map(|_| meta.advice_column());
+            Word32Chip::configure(meta, advice)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = Word32Chip::construct(config);
+            chip.load_table(&mut layouter)?;
+
+            let a = chip.load(layouter.namespace(|| "a"), Value::known(self.a))?;
+            let b = chip.load(layouter.namespace(|| "b"), Value::known(self.b))?;
+            match self.split {
+                Some((n, hi, lo)) => {
+                    let parts = Value::known((hi, lo));
+                    chip.assign_split(layouter.namespace(|| "split"), &a, n, parts)?;
+                }
+                None => {
+                    chip.checked_add(layouter.namespace(|| "a + b"), &a, &b)?;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn run_forged(circuit: &ForgedCircuit) -> MockProver<Fp> {
+        MockProver::run(10, circuit, vec![]).unwrap()
+    }
+
+    #[test]
+    fn word32_checked_add_rejects_overflow() {
+        let circuit = ForgedCircuit {
+            a: u32::MAX - 1,
+            b: 1,
+            split: None,
+        };
+        run_forged(&circuit).assert_satisfied();
+
+        let circuit = ForgedCircuit { b: 2, ..circuit };
+        assert!(run_forged(&circuit).verify().is_err());
+    }
+
+    #[test]
+    fn word32_split_rejects_wide_low_part() {
+        let (a, n) = (0xdead_beef, 8);
+        let circuit = ForgedCircuit {
+            a,
+            b: 0,
+            split: Some((n, a >> n, a & 0xff)),
+        };
+        run_forged(&circuit).assert_satisfied();
+
+        // (hi - 1) * 2^n + (lo + 2^n) is still a, and both parts are words,
+        // but lo no longer fits in n bits.
+        let circuit = ForgedCircuit {
+            split: Some((n, (a >> n) - 1, (a & 0xff) + 0x100)),
+            ..circuit
+        };
+        assert!(run_forged(&circuit).verify().is_err());
+    }
+}
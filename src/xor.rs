@@ -0,0 +1,235 @@
+//! XOR of two bytes through a three-column lookup.
+//!
+//! The table holds every triple `(a, b, a ^ b)` for bytes `a` and `b`, 65536
+//! rows, and each active row of the chip looks up its `(a, b, out)` as one
+//! tuple. A single lookup therefore checks that `a` and `b` are bytes and that
+//! `out` is their XOR, with no arithmetic gate at all. The table needs `k >= 17`.
+
+use halo2_proofs::pasta::Fp;
+
+use crate::{
+    common::to_u64,
+    prelude::*,
+    registry::{Example, ExampleCircuit},
+};
+
+/// Number of rows in the XOR table.
+pub const TABLE_ROWS: usize = 1 << 16;
+
+#[derive(Debug, Clone)]
+pub struct XorConfig {
+    pub advice: [Column<Advice>; 3],
+    pub selector: Selector,
+    pub table: [TableColumn; 3],
+}
+
+#[derive(Debug, Clone)]
+pub struct XorChip<F: PrimeField> {
+    config: XorConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> XorChip<F> {
+    pub fn construct(config: XorConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> XorConfig {
+        // Lookups cannot use simple selectors.
+        let selector = meta.complex_selector();
+        let table = [(); 3].map(|_| meta.lookup_table_column());
+        meta.enable_equality(advice[2]);
+
+        // Inactive rows look up (0, 0, 0), which is always in the table.
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            advice
+                .iter()
+                .zip(table)
+                .map(|(&column, table)| {
+                    let value = meta.query_advice(column, Rotation::cur());
+                    (s.clone() * value, table)
+                })
+                .collect()
+        });
+
+        XorConfig {
+            advice,
+            selector,
+            table,
+        }
+    }
+
+    /// Loads every `(a, b, a ^ b)` byte triple. Call once per circuit.
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let [col_a, col_b, col_out] = self.config.table;
+
+        layouter.assign_table(
+            || "xor table",
+            |mut table| {
+                for row in 0..TABLE_ROWS {
+                    let (a, b) = (row as u64 >> 8, row as u64 & 0xff);
+                    for (column, value) in [(col_a, a), (col_b, b), (col_out, a ^ b)] {
+                        table.assign_cell(
+                            || "xor",
+                            column,
+                            row,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assigns `a`, `b` and `a ^ b` in one row, and returns `a ^ b`.
+    pub fn xor(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Value<F>,
+        b: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [col_a, col_b, col_out] = self.config.advice;
+
+        layouter.assign_region(
+            || "xor",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "a", col_a, 0, || a)?;
+                region.assign_advice(|| "b", col_b, 0, || b)?;
+                let out = a.zip(b).map(|(a, b)| F::from(to_u64(&a) ^ to_u64(&b)));
+                region.assign_advice(|| "a ^ b", col_out, 0, || out)
+            },
+        )
+    }
+}
+
+/// XORs each pair and exposes the results. A result in `forged` replaces
+/// the XOR of the pair at the same index.
+#[derive(Default)]
+struct MyCircuit {
+    pairs: Vec<(u8, u8)>,
+    forged: Vec<Option<u8>>,
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = (XorConfig, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            pairs: self.pairs.clone(),
+            forged: self.forged.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (XorChip::configure(meta, advice), instance)
+    }
+
+    fn synthesize(
+        &self,
+        (config, instance): Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = XorChip::construct(config.clone());
+        chip.load_table(&mut layouter)?;
+
+        for (row, &(a, b)) in self.pairs.iter().enumerate() {
+            let (a, b) = (
+                Value::known(Fp::from(a as u64)),
+                Value::known(Fp::from(b as u64)),
+            );
+            let out = match self.forged.get(row).copied().flatten() {
+                None => chip.xor(layouter.namespace(|| "xor"), a, b)?,
+                Some(out) => layouter.assign_region(
+                    || "forged xor",
+                    |mut region| {
+                        let [col_a, col_b, col_out] = config.advice;
+                        config.selector.enable(&mut region, 0)?;
+                        region.assign_advice(|| "a", col_a, 0, || a)?;
+                        region.assign_advice(|| "b", col_b, 0, || b)?;
+                        region.assign_advice(
+                            || "a ^ b",
+                            col_out,
+                            0,
+                            || Value::known(Fp::from(out as u64)),
+                        )
+                    },
+                )?,
+            };
+            layouter.constrain_instance(out.cell(), instance, row)?;
+        }
+        Ok(())
+    }
+}
+
+/// The circuit on a few byte pairs.
+pub fn example_circuit() -> ExampleCircuit {
+    let pairs = vec![(0x0f, 0xf0), (0xa5, 0x5a), (0xff, 0xff), (3, 5)];
+    let outs = pairs
+        .iter()
+        .map(|&(a, b)| Fp::from((a ^ b) as u64))
+        .collect();
+    let circuit = MyCircuit {
+        pairs,
+        forged: vec![],
+    };
+    ExampleCircuit::new(17, circuit, vec![outs])
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "xor",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+    use rand_core::{OsRng, RngCore};
+
+    fn run(circuit: &MyCircuit, outs: Vec<u8>) -> MockProver<Fp> {
+        let k = 17;
+
+        let outs = outs.into_iter().map(|out| Fp::from(out as u64)).collect();
+        MockProver::run(k, circuit, vec![outs]).unwrap()
+    }
+
+    #[test]
+    fn xor_random_bytes() {
+        let mut pairs = vec![(0, 0), (0xff, 0xff), (0xff, 0), (0xa5, 0x5a)];
+        pairs.extend((0..16).map(|_| {
+            let bytes = OsRng.next_u32().to_le_bytes();
+            (bytes[0], bytes[1])
+        }));
+        let outs = pairs.iter().map(|&(a, b)| a ^ b).collect();
+
+        let circuit = MyCircuit {
+            pairs,
+            forged: vec![],
+        };
+        run(&circuit, outs).assert_satisfied();
+    }
+
+    #[test]
+    fn xor_rejects_wrong_result() {
+        // 0x0f ^ 0xf0 is 0xff; claim 0xfe instead, and match the instance to it
+        // so that only the lookup can object.
+        let circuit = MyCircuit {
+            pairs: vec![(0x0f, 0xf0), (3, 5)],
+            forged: vec![Some(0xfe)],
+        };
+        let failures = run(&circuit, vec![0xfe, 6]).verify().unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(failures[0], VerifyFailure::Lookup { .. }));
+    }
+}
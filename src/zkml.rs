@@ -0,0 +1,335 @@
+//! A one-layer perceptron: proves that a private input vector, classified by a
+//! public weight matrix, gets a public label.
+//!
+//! The input and the weights are fixed-point numbers with [`SCALE`] fractional
+//! bits. The circuit composes the gadget library: [`FixedPointChip`] loads the
+//! input and rescales the scores, [`MatMulChip`] computes the scores `W * x`,
+//! [`DotChip`] picks out the score of the label through a one-hot vector, and
+//! [`GeqChip`] checks that no class scores higher.
+
+use halo2_proofs::pasta::Fp;
+
+use crate::{
+    common::{BooleanChip, BooleanConfig},
+    compare::{CompareConfig, GeqChip, Leq},
+    fixed_point::{self, FixedPointChip, FixedPointConfig},
+    linalg::{
+        dot::{DotChip, DotConfig},
+        matmul::{MatMulChip, MatMulConfig, Matrix},
+    },
+    prelude::*,
+    range_check::table::RangeTableConfig,
+    registry::{Example, ExampleCircuit},
+};
+
+/// Input features.
+const FEATURES: usize = 4;
+/// Output classes.
+const CLASSES: usize = 3;
+/// Fractional bits of the input, the weights and the scores.
+const SCALE: usize = 8;
+/// Raw scores must lie in `[-RANGE/2, RANGE/2)` to be compared, i.e. between
+/// -8 and 8 at this scale.
+const RANGE: usize = 1 << 12;
+
+/// Plain-Rust reference: the raw score of every class, rounded down to
+/// `SCALE` fractional bits.
+pub fn scores(input: &[i64; FEATURES], weights: &[[i64; FEATURES]; CLASSES]) -> [i64; CLASSES] {
+    weights.map(|row| row.iter().zip(input).map(|(w, x)| w * x).sum::<i64>() >> SCALE)
+}
+
+/// Plain-Rust reference: the class with the highest score, the first one on a
+/// tie.
+pub fn classify(input: &[i64; FEATURES], weights: &[[i64; FEATURES]; CLASSES]) -> usize {
+    let scores = scores(input, weights);
+    (0..CLASSES).fold(0, |best, i| if scores[i] > scores[best] { i } else { best })
+}
+
+/// The public inputs: the weights row by row, then the label.
+pub fn instances(weights: &[[i64; FEATURES]; CLASSES], label: usize) -> Vec<Vec<Fp>> {
+    let mut column: Vec<Fp> = weights
+        .iter()
+        .flatten()
+        .map(|&w| fixed_point::to_field(w))
+        .collect();
+    column.push(Fp::from(label as u64));
+    vec![column]
+}
+
+#[derive(Debug, Clone)]
+struct ZkmlConfig {
+    advice: [Column<Advice>; 2 * FEATURES + 1],
+    instance: Column<Instance>,
+    fixed_point: FixedPointConfig<Fp>,
+    matmul: MatMulConfig<FEATURES>,
+    dot: DotConfig,
+    bits: BooleanConfig,
+    geq: CompareConfig<Fp, RANGE, Leq>,
+    q_shift: Selector,
+}
+
+/// Classifies the private `input` as the private `label`, which the public
+/// input then pins down.
+///
+/// The scores are `floor(W * x / 2^SCALE)`. A one-hot vector `e` of boolean
+/// cells, summing to 1 and with `sum_i i * e_i == label`, selects the label's
+/// score as `sum_i e_i * score_i`. Both it and every score are shifted by
+/// `RANGE/2` in a "shift" gate, so [`GeqChip`] can compare them as unsigned
+/// values, and every comparison must hold.
+///
+/// The weights come from the verifier and are not range-checked; the scores
+/// are, so an input that scores outside the comparable range fails to prove.
+#[derive(Default)]
+struct MyCircuit {
+    input: [Value<i64>; FEATURES],
+    label: Value<usize>,
+}
+
+impl MyCircuit {
+    pub fn new(input: &[i64; FEATURES], label: usize) -> Self {
+        Self {
+            input: input.map(Value::known),
+            label: Value::known(label),
+        }
+    }
+}
+
+impl Circuit<Fp> for MyCircuit {
+    type Config = ZkmlConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 2 * FEATURES + 1].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let [a0, a1, a2, a3, a4, a5, a6, a7, a8] = advice;
+        let fixed_point = FixedPointChip::<Fp, SCALE>::configure(meta, [a0, a1, a2, a3, a4]);
+        let matmul = MatMulChip::<Fp, CLASSES, FEATURES, 1>::configure(
+            meta,
+            [a0, a1, a2, a3],
+            [a4, a5, a6, a7],
+            a8,
+        );
+        let dot = DotChip::configure(meta, [a0, a1, a2]);
+        let bits = BooleanChip::configure(meta, a0);
+        let table = RangeTableConfig::configure(meta);
+        let geq = GeqChip::configure(meta, [a0, a1, a2, a3], &table);
+
+        let q_shift = meta.selector();
+        meta.create_gate("shift", |meta| {
+            let s = meta.query_selector(q_shift);
+            let value = meta.query_advice(a0, Rotation::cur());
+            let shifted = meta.query_advice(a1, Rotation::cur());
+            let half = Expression::Constant(Fp::from((RANGE / 2) as u64));
+            vec![s * (value + half - shifted)]
+        });
+
+        ZkmlConfig {
+            advice,
+            instance,
+            fixed_point,
+            matmul,
+            dot,
+            bits,
+            geq,
+            q_shift,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let fixed_point = FixedPointChip::<Fp, SCALE>::construct(config.fixed_point.clone());
+        let matmul = MatMulChip::<Fp, CLASSES, FEATURES, 1>::construct(config.matmul.clone());
+        let dot = DotChip::construct(config.dot.clone());
+        let bits = BooleanChip::construct(config.bits.clone());
+        let geq = GeqChip::construct(config.geq.clone());
+        fixed_point.load_table(&mut layouter)?;
+        config.geq.table.load(&mut layouter)?;
+
+        let weights: Matrix<Fp, CLASSES, FEATURES> = layouter.assign_region(
+            || "weights",
+            |mut region| {
+                let targets: Vec<_> = (0..CLASSES * FEATURES)
+                    .map(|i| (config.advice[i % FEATURES], i / FEATURES))
+                    .collect();
+                let mut cells = load_instances(&mut region, config.instance, &targets)?.into_iter();
+                Ok(std::array::from_fn(|_| {
+                    std::array::from_fn(|_| cells.next().unwrap())
+                }))
+            },
+        )?;
+
+        let mut input = vec![];
+        for (i, &x) in self.input.iter().enumerate() {
+            let x = fixed_point.load(layouter.namespace(|| format!("x_{}", i)), x)?;
+            input.push([x.cell]);
+        }
+        let input: Matrix<Fp, FEATURES, 1> = input.try_into().unwrap();
+
+        let products = matmul.mul(layouter.namespace(|| "W * x"), &weights, &input)?;
+        let scores = products
+            .iter()
+            .enumerate()
+            .map(|(i, [product])| {
+                let layouter = layouter.namespace(|| format!("score {}", i));
+                Ok(fixed_point.rescale(layouter, product)?.cell)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // The one-hot vector of the label, with the constants to check it.
+        let one_hot = (0..CLASSES)
+            .map(|i| {
+                let bit = self.label.map(|label| Fp::from(u64::from(label == i)));
+                bits.assign_bool(layouter.namespace(|| format!("e_{}", i)), bit)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (ones, indices) = layouter.assign_region(
+            || "one-hot constants",
+            |mut region| {
+                let mut assign = |row: usize, value: usize| {
+                    let value = Fp::from(value as u64);
+                    region.assign_advice_from_constant(|| "constant", config.advice[0], row, value)
+                };
+                let ones = (0..CLASSES)
+                    .map(|i| assign(i, 1))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let indices = (0..CLASSES)
+                    .map(|i| assign(CLASSES + i, i))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((ones, indices))
+            },
+        )?;
+
+        let count = dot.dot(layouter.namespace(|| "sum e_i"), &one_hot, &ones)?;
+        let label = dot.dot(layouter.namespace(|| "sum i * e_i"), &one_hot, &indices)?;
+        let selected = dot.dot(layouter.namespace(|| "selected score"), &one_hot, &scores)?;
+        layouter.assign_region(
+            || "exactly one class",
+            |mut region| region.constrain_constant(count.cell(), Fp::ONE),
+        )?;
+        layouter.constrain_instance(label.cell(), config.instance, CLASSES * FEATURES)?;
+
+        let selected = config.shift(layouter.namespace(|| "shift selected"), &selected)?;
+        for (i, score) in scores.iter().enumerate() {
+            let score = config.shift(layouter.namespace(|| format!("shift {}", i)), score)?;
+            let geq = geq.assign(
+                layouter.namespace(|| format!("geq {}", i)),
+                &selected,
+                &score,
+            )?;
+            layouter.assign_region(
+                || "label scores highest",
+                |mut region| region.constrain_constant(geq.cell(), Fp::ONE),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ZkmlConfig {
+    /// Returns `value + RANGE/2`.
+    fn shift(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        value: &AssignedCell<Fp, Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        layouter.assign_region(
+            || "shift",
+            |mut region| {
+                self.q_shift.enable(&mut region, 0)?;
+                let value = value.copy_advice(|| "value", &mut region, self.advice[0], 0)?;
+                let shifted = value.value().map(|&v| v + Fp::from((RANGE / 2) as u64));
+                region.assign_advice(|| "shifted", self.advice[1], 0, || shifted)
+            },
+        )
+    }
+}
+
+/// Weights of a toy classifier over four features.
+pub fn example_weights() -> [[i64; FEATURES]; CLASSES] {
+    [
+        [1.0, 0.5, -1.0, -1.0],
+        [0.0, -0.5, 0.5, 0.25],
+        [-1.0, 0.0, 1.0, 1.0],
+    ]
+    .map(|row| row.map(|w| fixed_point::encode(w, SCALE)))
+}
+
+/// The circuit on an input of class 0.
+pub fn example_circuit() -> ExampleCircuit {
+    let weights = example_weights();
+    let input = [5.1, 3.5, 1.4, 0.2].map(|x| fixed_point::encode(x, SCALE));
+    let label = classify(&input, &weights);
+    ExampleCircuit::new(
+        13,
+        MyCircuit::new(&input, label),
+        instances(&weights, label),
+    )
+}
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "zkml",
+    circuit: example_circuit,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    fn run(input: [f64; FEATURES], label: usize) -> MockProver<Fp> {
+        let k = 13;
+
+        let weights = example_weights();
+        let input = input.map(|x| fixed_point::encode(x, SCALE));
+        let circuit = MyCircuit::new(&input, label);
+        MockProver::run(k, &circuit, instances(&weights, label)).unwrap()
+    }
+
+    const INPUTS: [([f64; FEATURES], usize); 3] = [
+        ([5.1, 3.5, 1.4, 0.2], 0),
+        ([6.3, 2.8, 5.1, 1.5], 1),
+        ([5.0, 2.5, 6.0, 3.0], 2),
+    ];
+
+    #[test]
+    fn zkml_proves_the_predicted_label() {
+        for (input, label) in INPUTS {
+            let raw = input.map(|x| fixed_point::encode(x, SCALE));
+            assert_eq!(classify(&raw, &example_weights()), label);
+            run(input, label).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn zkml_rejects_other_labels() {
+        for (input, label) in INPUTS {
+            for other in (0..CLASSES).filter(|&other| other != label) {
+                assert!(run(input, other).verify().is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn zkml_rejects_label_out_of_range() {
+        // No one-hot vector of three classes encodes 3.
+        assert!(run(INPUTS[0].0, CLASSES).verify().is_err());
+    }
+
+    #[test]
+    fn zkml_rejects_scores_out_of_range() {
+        // Class 0 scores 20, which does not fit in the comparison table.
+        let input = [20.0, 0.0, 0.0, 0.0];
+        let raw = input.map(|x| fixed_point::encode(x, SCALE));
+        assert_eq!(classify(&raw, &example_weights()), 0);
+        assert!(run(input, 0).verify().is_err());
+    }
+}
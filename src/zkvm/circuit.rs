@@ -0,0 +1,473 @@
+use halo2_proofs::pasta::Fp;
+
+use super::program::{execute, result, Instr, Step, BASE};
+use crate::{
+    common::sorting_switches,
+    is_zero::{IsZeroChip, IsZeroConfig},
+    memory::{Access, MemoryChip, MemoryConfig},
+    names,
+    prelude::*,
+    range_check::table::RangeTableConfig,
+    registry::ExampleCircuit,
+};
+
+/// Addresses and times in the memory log differ by less than this.
+const RANGE: usize = 256;
+
+/// One trace row per step:
+///
+/// ```text
+/// pc | opcode | imm | sp | a | b | a_minus_b_inv | write_addr | write_value
+///    | read_a_addr | read_b_addr | push | add | mul | eq | halt
+/// ```
+#[derive(Debug, Clone)]
+struct VmConfig {
+    advice: [Column<Advice>; 16],
+    flags: [Column<Advice>; 5],
+    instance: Column<Instance>,
+    q_step: Selector,
+    q_next: Selector,
+    q_first: Selector,
+    q_last: Selector,
+    /// `(pc, opcode, imm)` of every instruction of the program.
+    program: [TableColumn; 3],
+    a_equals_b: IsZeroConfig<Fp>,
+    memory: MemoryConfig<Fp, RANGE>,
+}
+
+/// Proves that running `program` for `STEPS` steps halts with the public value
+/// on top of the stack.
+///
+/// Every step row looks up `(pc, opcode, imm)` in the program table, so it
+/// executes the instruction at its `pc`. Boolean flags, exactly one of them
+/// set, decode the opcode. The "vm step" gate then fixes the addresses of the
+/// three memory accesses of the step and the value it writes:
+///
+/// ```text
+/// read_a_addr = sp - 1
+/// read_b_addr = sp - 2
+/// write_addr  = sp - 2 * (add + mul + eq) - halt
+/// write_value = push * imm + add * (b + a) + mul * b * a + eq * [a == b] + halt * a
+/// ```
+///
+/// and the transition to the next row moves `pc` on unless halted and `sp` by
+/// the net push or pop. The first row starts at `pc = 0`, `sp = BASE`, and the
+/// last row must be halted. The three accesses of every step, reads before the
+/// write, go to [`MemoryChip`], which is what makes `a` and `b` the values on
+/// the stack.
+#[derive(Default)]
+struct MyCircuit<const STEPS: usize> {
+    program: Vec<Instr>,
+    /// Replaces the written value of one step, to play a cheating prover.
+    forged: Option<(usize, Fp)>,
+    /// Replaces the trace, and leaves the memory log unsorted with every
+    /// `same` bit set, to play a prover whose reads return whatever was
+    /// accessed last, at any address.
+    unsorted: Option<Vec<Step>>,
+}
+
+impl<const STEPS: usize> MyCircuit<STEPS> {
+    fn new(program: &[Instr]) -> Self {
+        Self {
+            program: program.to_vec(),
+            forged: None,
+            unsorted: None,
+        }
+    }
+
+    fn trace(&self) -> Vec<Step> {
+        if let Some(trace) = &self.unsorted {
+            return trace.clone();
+        }
+        let mut trace = execute(&self.program, STEPS);
+        if let Some((step, value)) = self.forged {
+            trace[step].write_value = value;
+        }
+        trace
+    }
+}
+
+impl<const STEPS: usize> Circuit<Fp> for MyCircuit<STEPS> {
+    type Config = VmConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(&self.program)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); 16].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let [pc, opcode, imm, sp, a, b, a_minus_b_inv, write_addr, write_value, read_a_addr, read_b_addr, push, add, mul, eq, halt] =
+            advice;
+        let flags = [push, add, mul, eq, halt];
+
+        // Lookups cannot use simple selectors.
+        let q_step = meta.complex_selector();
+        let q_next = meta.selector();
+        let q_first = meta.selector();
+        let q_last = meta.selector();
+        let program = [(); 3].map(|_| meta.lookup_table_column());
+        let one = || Expression::Constant(Fp::ONE);
+
+        let a_equals_b = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_step),
+            |meta| meta.query_advice(a, Rotation::cur()) - meta.query_advice(b, Rotation::cur()),
+            a_minus_b_inv,
+        );
+
+        meta.create_gate("vm flags", |meta| {
+            let q = meta.query_selector(q_step);
+            let opcode = meta.query_advice(opcode, Rotation::cur());
+            let flags = flags.map(|flag| meta.query_advice(flag, Rotation::cur()));
+
+            let mut constraints: Vec<_> = flags
+                .iter()
+                .map(|flag| q.clone() * flag.clone() * (one() - flag.clone()))
+                .collect();
+            let count = flags
+                .iter()
+                .fold(Expression::Constant(Fp::ZERO), |sum, f| sum + f.clone());
+            let decoded = flags.iter().zip(Instr::ALL).fold(
+                Expression::Constant(Fp::ZERO),
+                |sum, (flag, instr)| {
+                    sum + flag.clone() * Expression::Constant(Fp::from(instr.opcode()))
+                },
+            );
+            constraints.push(q.clone() * (count - one()));
+            constraints.push(q * (decoded - opcode));
+            constraints
+        });
+
+        meta.create_gate("vm step", |meta| {
+            let q = meta.query_selector(q_step);
+            let [imm, sp, a, b, write_addr, write_value, read_a_addr, read_b_addr] = [
+                imm,
+                sp,
+                a,
+                b,
+                write_addr,
+                write_value,
+                read_a_addr,
+                read_b_addr,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [push, add, mul, eq, halt] =
+                flags.map(|flag| meta.query_advice(flag, Rotation::cur()));
+            let two = Expression::Constant(Fp::from(2));
+
+            let pops = add.clone() + mul.clone() + eq.clone();
+            let value = push * imm
+                + add * (b.clone() + a.clone())
+                + mul * b.clone() * a.clone()
+                + eq * a_equals_b.expr()
+                + halt.clone() * a;
+            vec![
+                q.clone() * (sp.clone() - one() - read_a_addr),
+                q.clone() * (sp.clone() - two.clone() - read_b_addr),
+                q.clone() * (sp - two * pops - halt - write_addr),
+                q * (value - write_value),
+            ]
+        });
+
+        meta.create_gate(names::STEP, |meta| {
+            let q = meta.query_selector(q_next);
+            let [pc_next, sp_next] =
+                [pc, sp].map(|column| meta.query_advice(column, Rotation::next()));
+            let [pc, sp] = [pc, sp].map(|column| meta.query_advice(column, Rotation::cur()));
+            let [push, add, mul, eq, halt] =
+                flags.map(|flag| meta.query_advice(flag, Rotation::cur()));
+            vec![
+                q.clone() * (pc + one() - halt - pc_next),
+                q * (sp + push - add - mul - eq - sp_next),
+            ]
+        });
+
+        meta.create_gate(names::FIRST, |meta| {
+            let q = meta.query_selector(q_first);
+            let pc = meta.query_advice(pc, Rotation::cur());
+            let sp = meta.query_advice(sp, Rotation::cur());
+            vec![
+                q.clone() * pc,
+                q * (sp - Expression::Constant(Fp::from(BASE))),
+            ]
+        });
+
+        meta.create_gate(names::LAST, |meta| {
+            let q = meta.query_selector(q_last);
+            let halt = meta.query_advice(halt, Rotation::cur());
+            vec![q * (halt - one())]
+        });
+
+        // Inactive rows look up (0, 0, 0), which the table holds besides the
+        // program and which no opcode matches.
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_step);
+            [pc, opcode, imm]
+                .into_iter()
+                .zip(program)
+                .map(|(column, table)| {
+                    (
+                        q.clone() * meta.query_advice(column, Rotation::cur()),
+                        table,
+                    )
+                })
+                .collect()
+        });
+
+        let table = RangeTableConfig::configure(meta);
+        let memory = MemoryChip::configure(meta, advice[..9].try_into().unwrap(), &table);
+
+        VmConfig {
+            advice,
+            flags,
+            instance,
+            q_step,
+            q_next,
+            q_first,
+            q_last,
+            program,
+            a_equals_b,
+            memory,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        config.memory.table.load(&mut layouter)?;
+        layouter.assign_table(
+            || "program",
+            |mut table| {
+                let rows = [(0, 0, 0)].into_iter().chain(
+                    self.program
+                        .iter()
+                        .enumerate()
+                        .map(|(pc, instr)| (pc as u64, instr.opcode(), instr.imm())),
+                );
+                for (row, (pc, opcode, imm)) in rows.enumerate() {
+                    for (column, value) in config.program.into_iter().zip([pc, opcode, imm]) {
+                        table.assign_cell(
+                            || "program",
+                            column,
+                            row,
+                            || Value::known(Fp::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        let trace = self.trace();
+        let a_equals_b = IsZeroChip::construct(config.a_equals_b.clone());
+        let (accesses, top) = layouter.assign_region(
+            || "trace",
+            |mut region| {
+                let [pc, opcode, imm, sp, a, b, _, write_addr, write_value, read_a_addr, read_b_addr, ..] =
+                    config.advice;
+                let (read, write) = (
+                    region.assign_advice_from_constant(|| "read", config.advice[0], STEPS, Fp::ZERO)?,
+                    region.assign_advice_from_constant(|| "write", config.advice[1], STEPS, Fp::ONE)?,
+                );
+
+                let mut accesses = vec![];
+                let mut top = None;
+                for (row, step) in trace.iter().enumerate() {
+                    config.q_step.enable(&mut region, row)?;
+                    if row == 0 {
+                        config.q_first.enable(&mut region, row)?;
+                    }
+                    if row + 1 < STEPS {
+                        config.q_next.enable(&mut region, row)?;
+                    } else {
+                        config.q_last.enable(&mut region, row)?;
+                    }
+
+                    let mut assign = |column, value: Fp| {
+                        region.assign_advice(|| "trace", column, row, || Value::known(value))
+                    };
+                    assign(pc, Fp::from(step.pc))?;
+                    assign(opcode, Fp::from(step.instr.opcode()))?;
+                    assign(imm, Fp::from(step.instr.imm()))?;
+                    assign(sp, Fp::from(step.sp))?;
+                    let a_cell = assign(a, step.a)?;
+                    let b_cell = assign(b, step.b)?;
+                    let read_a = assign(read_a_addr, Fp::from(step.sp - 1))?;
+                    let read_b = assign(read_b_addr, Fp::from(step.sp - 2))?;
+                    let write_addr = assign(write_addr, Fp::from(step.write_addr))?;
+                    let write_value = assign(write_value, step.write_value)?;
+                    for (flag, instr) in config.flags.into_iter().zip(Instr::ALL) {
+                        let set = instr.opcode() == step.instr.opcode();
+                        assign(flag, Fp::from(u64::from(set)))?;
+                    }
+                    a_equals_b.assign(&mut region, row, Value::known(step.a - step.b))?;
+
+                    accesses.push(Access {
+                        addr: read_a,
+                        value: a_cell.clone(),
+                        is_write: read.clone(),
+                    });
+                    accesses.push(Access {
+                        addr: read_b,
+                        value: b_cell,
+                        is_write: read.clone(),
+                    });
+                    accesses.push(Access {
+                        addr: write_addr,
+                        value: write_value,
+                        is_write: write.clone(),
+                    });
+                    top = Some(a_cell);
+                }
+                Ok((accesses, top.expect("no steps")))
+            },
+        )?;
+
+        let memory = MemoryChip::construct(config.memory);
+        let stack = layouter.namespace(|| "stack");
+        match self.unsorted {
+            None => memory.check(stack, &accesses)?,
+            Some(_) => {
+                // Keys already in order need no swaps.
+                let keys: Vec<_> = (0..accesses.len() as u64).map(|time| (0, time)).collect();
+                let switches = Value::known(sorting_switches(&keys));
+                let same = vec![true; accesses.len() - 1];
+                memory.assign(stack, &accesses, switches, Some(&same))?
+            }
+        }
+        layouter.constrain_instance(top.cell(), config.instance, 0)
+    }
+}
+
+/// The public input for `program` run for `steps` steps, if it halts by then.
+pub fn instances(program: &[Instr], steps: usize) -> Option<Vec<Vec<Fp>>> {
+    Some(vec![vec![result(&execute(program, steps))?]])
+}
+
+/// `(3 + 4) * 5`.
+const EXAMPLE_PROGRAM: [Instr; 6] = [
+    Instr::Push(3),
+    Instr::Push(4),
+    Instr::Add,
+    Instr::Push(5),
+    Instr::Mul,
+    Instr::Halt,
+];
+
+/// The circuit on `(3 + 4) * 5`.
+pub fn example_circuit() -> ExampleCircuit {
+    const STEPS: usize = 8;
+    let instances = instances(&EXAMPLE_PROGRAM, STEPS).expect("the program halts");
+    ExampleCircuit::new(10, MyCircuit::<STEPS>::new(&EXAMPLE_PROGRAM), instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::dev::MockProver;
+
+    const STEPS: usize = 8;
+
+    fn run(circuit: &MyCircuit<STEPS>, top: Fp) -> MockProver<Fp> {
+        let k = 10;
+
+        MockProver::run(k, circuit, vec![vec![top]]).unwrap()
+    }
+
+    #[test]
+    fn zkvm_proves_program_results() {
+        use Instr::*;
+
+        for program in [
+            EXAMPLE_PROGRAM.to_vec(),
+            vec![Push(7), Push(7), Eq, Halt],
+            vec![Push(7), Push(8), Eq, Push(2), Add, Halt],
+            vec![Push(1), Push(2), Push(3), Push(4), Mul, Mul, Mul, Halt],
+            vec![Halt],
+        ] {
+            let top = instances(&program, STEPS).unwrap()[0][0];
+            run(&MyCircuit::new(&program), top).assert_satisfied();
+        }
+        assert_eq!(
+            instances(&EXAMPLE_PROGRAM, STEPS),
+            Some(vec![vec![Fp::from(35)]])
+        );
+    }
+
+    #[test]
+    fn zkvm_rejects_wrong_result() {
+        let circuit = MyCircuit::<STEPS>::new(&EXAMPLE_PROGRAM);
+        assert!(run(&circuit, Fp::from(36)).verify().is_err());
+    }
+
+    #[test]
+    fn zkvm_rejects_forged_step() {
+        // Step 2 adds 3 and 4, and writing 8 instead breaks the "vm step"
+        // gate. Later steps still read 7, so the memory check fails too.
+        let circuit = MyCircuit::<STEPS> {
+            forged: Some((2, Fp::from(8))),
+            ..MyCircuit::new(&EXAMPLE_PROGRAM)
+        };
+        assert_constraint_fails(&run(&circuit, Fp::from(35)), "vm step");
+    }
+
+    #[test]
+    fn zkvm_rejects_program_that_does_not_halt() {
+        use Instr::*;
+
+        let program = [
+            Push(1),
+            Push(1),
+            Add,
+            Push(1),
+            Add,
+            Push(1),
+            Add,
+            Push(1),
+            Add,
+            Halt,
+        ];
+        assert_eq!(instances(&program, STEPS), None);
+        let top = result(&execute(&program, 10)).unwrap();
+        assert_constraint_fails(&run(&MyCircuit::new(&program), top), names::LAST);
+    }
+
+    #[test]
+    fn zkvm_rejects_reads_across_slots() {
+        use Instr::*;
+
+        // Every read returns the last access in execution order, so `3 + 4`
+        // reads 4 for both operands and leaves 8: `b` comes from slot 3
+        // instead of slot 2. Only a `same` bit between two addresses lets the
+        // memory check accept that.
+        let program = [Push(3), Push(4), Add, Halt];
+        let mut trace = execute(&program, STEPS);
+        let mut last = Fp::ZERO;
+        for step in trace.iter_mut() {
+            (step.a, step.b) = (last, last);
+            step.write_value = match step.instr {
+                Push(imm) => Fp::from(imm),
+                Add => last + last,
+                Mul => last * last,
+                Eq => Fp::ONE,
+                Halt => last,
+            };
+            last = step.write_value;
+        }
+        assert_eq!(result(&trace), Some(Fp::from(8)));
+
+        let circuit = MyCircuit::<STEPS> {
+            unsorted: Some(trace),
+            ..MyCircuit::new(&program)
+        };
+        assert_constraint_fails(&run(&circuit, Fp::from(8)), "memory order");
+    }
+}
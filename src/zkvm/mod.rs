@@ -0,0 +1,18 @@
+//! A minimal stack machine whose execution is proven: the program is fixed in
+//! the circuit, the prover supplies the trace, and the value left on top of
+//! the stack is public.
+//!
+//! The machine has five instructions, `push`, `add`, `mul`, `eq` and `halt`,
+//! and no jumps, so a program runs straight through to its `halt` and then
+//! stays there. The stack lives in memory, checked with
+//! [`crate::memory::MemoryChip`].
+
+mod circuit;
+pub mod program;
+
+use crate::registry::Example;
+
+pub(crate) const EXAMPLES: &[Example] = &[Example {
+    name: "zkvm",
+    circuit: circuit::example_circuit,
+}];
@@ -0,0 +1,146 @@
+//! The instruction set, and a plain-Rust interpreter producing the trace the
+//! circuit checks.
+
+use std::collections::HashMap;
+
+use ff::Field;
+use halo2_proofs::pasta::Fp;
+
+/// Address of the bottom of the stack. Addresses 0 and 1 are never written,
+/// so reading the two slots under an empty or one-element stack returns 0.
+pub const BASE: u64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instr {
+    Push(u64),
+    /// Pops `a` and `b` and pushes `b + a`.
+    Add,
+    /// Pops `a` and `b` and pushes `b * a`.
+    Mul,
+    /// Pops `a` and `b` and pushes 1 if they are equal, 0 otherwise.
+    Eq,
+    /// Stops; every later step repeats it.
+    Halt,
+}
+
+impl Instr {
+    /// Every instruction, in opcode order.
+    pub const ALL: [Instr; 5] = [
+        Instr::Push(0),
+        Instr::Add,
+        Instr::Mul,
+        Instr::Eq,
+        Instr::Halt,
+    ];
+
+    /// The opcode, from 1 so that an all-zero row matches no instruction.
+    pub fn opcode(&self) -> u64 {
+        match self {
+            Instr::Push(_) => 1,
+            Instr::Add => 2,
+            Instr::Mul => 3,
+            Instr::Eq => 4,
+            Instr::Halt => 5,
+        }
+    }
+
+    /// The immediate, 0 for everything but `push`.
+    pub fn imm(&self) -> u64 {
+        match self {
+            Instr::Push(imm) => *imm,
+            _ => 0,
+        }
+    }
+}
+
+/// One row of the trace: the state before `instr` and the one write it makes.
+///
+/// Every step reads the two top slots, `a` at `sp - 1` and `b` at `sp - 2`,
+/// whatever its instruction, so all rows make the same three accesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub pc: u64,
+    pub instr: Instr,
+    /// The first free slot.
+    pub sp: u64,
+    pub a: Fp,
+    pub b: Fp,
+    pub write_addr: u64,
+    pub write_value: Fp,
+}
+
+/// Runs `program` for exactly `steps` steps.
+///
+/// # Panics
+///
+/// If `pc` runs off the end of the program, or an instruction pops more than
+/// the stack holds. The circuit does not check for underflow; the program is
+/// public, so the verifier can.
+pub fn execute(program: &[Instr], steps: usize) -> Vec<Step> {
+    let mut memory = HashMap::new();
+    let (mut pc, mut sp) = (0, BASE);
+    (0..steps)
+        .map(|_| {
+            let instr = program[pc as usize];
+            let read = |addr: u64| memory.get(&addr).copied().unwrap_or(Fp::ZERO);
+            let (a, b) = (read(sp - 1), read(sp - 2));
+            if matches!(instr, Instr::Add | Instr::Mul | Instr::Eq) {
+                assert!(sp >= BASE + 2, "stack underflow at pc {}", pc);
+            }
+
+            let (write_addr, write_value, next_sp) = match instr {
+                Instr::Push(imm) => (sp, Fp::from(imm), sp + 1),
+                Instr::Add => (sp - 2, b + a, sp - 1),
+                Instr::Mul => (sp - 2, b * a, sp - 1),
+                Instr::Eq => (sp - 2, Fp::from(u64::from(a == b)), sp - 1),
+                Instr::Halt => (sp - 1, a, sp),
+            };
+            let step = Step {
+                pc,
+                instr,
+                sp,
+                a,
+                b,
+                write_addr,
+                write_value,
+            };
+
+            memory.insert(write_addr, write_value);
+            sp = next_sp;
+            if instr != Instr::Halt {
+                pc += 1;
+            }
+            step
+        })
+        .collect()
+}
+
+/// The top of the stack once the trace has run, if it ends halted.
+pub fn result(trace: &[Step]) -> Option<Fp> {
+    let last = trace.last()?;
+    (last.instr == Instr::Halt).then_some(last.a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_runs_to_halt() {
+        use Instr::*;
+
+        let trace = execute(&[Push(3), Push(4), Add, Push(5), Mul, Halt], 8);
+        assert_eq!(result(&trace), Some(Fp::from(35)));
+        assert!(trace[5..]
+            .iter()
+            .all(|step| step.instr == Halt && step.sp == BASE + 1));
+
+        let equal = execute(&[Push(7), Push(7), Eq, Halt], 4);
+        assert_eq!(result(&equal), Some(Fp::ONE));
+        let unequal = execute(&[Push(7), Push(8), Eq, Halt], 4);
+        assert_eq!(result(&unequal), Some(Fp::ZERO));
+
+        // Too few steps to reach the halt.
+        assert_eq!(result(&execute(&[Push(1), Push(2), Add, Halt], 3)), None);
+    }
+}
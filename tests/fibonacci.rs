@@ -0,0 +1,65 @@
+//! The Fibonacci examples, used through the public API only.
+
+use halo2_proofs::{dev::MockProver, pasta::Fp};
+use halo2examples::{
+    fibonacci::{example1, example2, FibIndex, FibonacciInstances},
+    prover,
+};
+
+#[test]
+fn example1_from_outside() {
+    let instances = FibonacciInstances::from_seeds(1, 1, FibIndex(11)).unwrap();
+    let circuit = example1::MyCircuit::<Fp>::new(11);
+    MockProver::run(4, &circuit, instances.to_columns())
+        .unwrap()
+        .assert_satisfied();
+    example1::run(4, 11).unwrap();
+}
+
+#[test]
+fn example2_from_outside() {
+    let instances = FibonacciInstances::from_seeds(2, 3, FibIndex(9)).unwrap();
+    let circuit = example2::MyCircuit::<Fp>::default();
+    MockProver::run(4, &circuit, instances.to_columns())
+        .unwrap()
+        .assert_satisfied();
+    assert_eq!(example2::reference(2, 3, 9), instances.output);
+}
+
+#[test]
+fn example1_real_proof() {
+    let k = 4;
+
+    let columns = FibonacciInstances::from_seeds(1, 1, FibIndex(9))
+        .unwrap()
+        .to_columns();
+    let instance = columns[0].as_slice();
+
+    let keys = prover::Keys::generate(k, &example1::MyCircuit::<Fp>::default()).unwrap();
+    let proof = prover::prove(&keys, example1::MyCircuit::default(), &[instance]).unwrap();
+    prover::verify(&keys, &proof, &[instance]).unwrap();
+
+    let mut wrong = columns[0].clone();
+    wrong[2] += Fp::from(1);
+    assert!(prover::verify(&keys, &proof, &[&wrong]).is_err());
+}
+
+#[test]
+fn example2_real_proof() {
+    let k = 5;
+
+    let columns = FibonacciInstances::from_seeds(2, 5, FibIndex(20))
+        .unwrap()
+        .to_columns();
+    let instance = columns[0].as_slice();
+
+    let keys = prover::Keys::generate(k, &example2::MyCircuit::<Fp>::new(20)).unwrap();
+    let proof = prover::prove(&keys, example2::MyCircuit::new(20), &[instance]).unwrap();
+    prover::verify(&keys, &proof, &[instance]).unwrap();
+
+    // A proof for f(20) says nothing about f(19).
+    let other = FibonacciInstances::from_seeds(2, 5, FibIndex(19))
+        .unwrap()
+        .to_columns();
+    assert!(prover::verify(&keys, &proof, &[&other[0]]).is_err());
+}
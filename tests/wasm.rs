@@ -0,0 +1,30 @@
+//! The browser bindings, run in node with `wasm-bindgen-test-runner` (see
+//! `.cargo/config.toml`):
+//!
+//! ```text
+//! cargo test --release --target wasm32-unknown-unknown --no-default-features --features wasm --test wasm
+//! ```
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use halo2examples::wasm::{fibonacci_instances, prove_fibonacci, verify};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn prove_and_verify() {
+    let proof = prove_fibonacci(1, 1, 9).ok().unwrap();
+    let instances = fibonacci_instances(1, 1, 9).ok().unwrap();
+    assert!(verify(&proof, &instances).ok().unwrap());
+}
+
+#[wasm_bindgen_test]
+fn verify_rejects_a_tampered_proof() {
+    let mut proof = prove_fibonacci(1, 1, 9).ok().unwrap();
+    let instances = fibonacci_instances(1, 1, 9).ok().unwrap();
+    proof[0] ^= 1;
+    assert!(!verify(&proof, &instances).ok().unwrap());
+
+    // A proof of f(9) is not a proof of another output.
+    let proof = prove_fibonacci(1, 1, 9).ok().unwrap();
+    let other = fibonacci_instances(2, 3, 9).ok().unwrap();
+    assert!(!verify(&proof, &other).ok().unwrap());
+}